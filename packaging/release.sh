@@ -23,8 +23,13 @@ cargo build --release
 
 install -Dm755 target/release/yufi "$STAGE/usr/bin/yufi"
 install -Dm644 packaging/com.yufi.app.desktop "$STAGE/usr/share/applications/com.yufi.app.desktop"
+install -Dm644 packaging/com.yufi.app.service "$STAGE/usr/share/dbus-1/services/com.yufi.app.service"
 install -Dm644 packaging/com.yufi.app.svg "$STAGE/usr/share/icons/hicolor/scalable/apps/com.yufi.app.svg"
 install -Dm644 LICENSE "$STAGE/usr/share/licenses/yufi/LICENSE"
+target/release/yufi --man-page | install -Dm644 /dev/stdin "$STAGE/usr/share/man/man1/yufi.1"
+target/release/yufi --completions=bash | install -Dm644 /dev/stdin "$STAGE/usr/share/bash-completion/completions/yufi"
+target/release/yufi --completions=zsh | install -Dm644 /dev/stdin "$STAGE/usr/share/zsh/site-functions/_yufi"
+target/release/yufi --completions=fish | install -Dm644 /dev/stdin "$STAGE/usr/share/fish/vendor_completions.d/yufi.fish"
 
 for size in 32 64 128 256; do
   src="packaging/icons/com.yufi.app-${size}.png"
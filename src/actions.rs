@@ -0,0 +1,63 @@
+//! Application actions exported on the session bus, so a desktop can bind
+//! keys to them without the window being open, e.g.
+//! `gapplication action com.yufi.app toggle-wifi`.
+
+use crate::friendly_error;
+use crate::worker::{Command, Worker};
+use gtk4::gio::SimpleAction;
+use gtk4::prelude::*;
+use gtk4::Application;
+
+/// Registers `app.toggle-wifi`, `app.scan` and `app.show-window`. With
+/// `read_only` toggling Wi‑Fi is disabled, like the switch in the window.
+pub fn install(app: &Application, worker: &Worker, read_only: bool) {
+    let toggle_wifi = SimpleAction::new("toggle-wifi", None);
+    toggle_wifi.set_enabled(!read_only);
+    let app_toggle = app.clone();
+    let worker_toggle = worker.clone();
+    toggle_wifi.connect_activate(move |_, _| toggle(&app_toggle, &worker_toggle));
+    app.add_action(&toggle_wifi);
+
+    let scan = SimpleAction::new("scan", None);
+    let app_scan = app.clone();
+    let worker_scan = worker.clone();
+    scan.connect_activate(move |_, _| run(&app_scan, &worker_scan, Command::Scan));
+    app.add_action(&scan);
+
+    let show_window = SimpleAction::new("show-window", None);
+    let app_show = app.clone();
+    show_window.connect_activate(move |_, _| app_show.activate());
+    app.add_action(&show_window);
+}
+
+/// Reads which way the radio is switched, then flips it.
+fn toggle(app: &Application, worker: &Worker) {
+    let hold = app.hold();
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::WifiEnabled { reply });
+    let app = app.clone();
+    let worker = worker.clone();
+    gtk4::glib::spawn_future_local(async move {
+        match answer.recv().await {
+            Ok(Ok(enabled)) => run(&app, &worker, Command::SetWifi(!enabled)),
+            Ok(Err(err)) => eprintln!("yufi: failed to toggle Wi‑Fi: {}", friendly_error(&err)),
+            Err(_) => {}
+        }
+        drop(hold);
+    });
+}
+
+/// Sends `command` to the worker. The window, if open, reports how it went as
+/// it does for its own; there may be no window, so the application is only
+/// kept alive until the worker has run it.
+fn run(app: &Application, worker: &Worker, command: Command) {
+    // Keeps a D-Bus activated instance alive until the command is done.
+    let hold = app.hold();
+    worker.send(command);
+    let (reply, done) = async_channel::bounded(1);
+    worker.send(Command::Flush { reply });
+    gtk4::glib::spawn_future_local(async move {
+        let _ = done.recv().await;
+        drop(hold);
+    });
+}
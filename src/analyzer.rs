@@ -0,0 +1,209 @@
+use crate::models::{BandLock, Network, VisibleAccessPoint};
+use std::cmp::Reverse;
+
+const NON_OVERLAPPING_24: [u32; 3] = [1, 6, 11];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Band {
+    TwoPointFour,
+    Five,
+    Six,
+}
+
+impl Band {
+    pub const ALL: [Band; 3] = [Band::TwoPointFour, Band::Five, Band::Six];
+
+    /// How the band is written in preferences and `band:` filters.
+    pub fn key(self) -> &'static str {
+        match self {
+            Band::TwoPointFour => "2.4",
+            Band::Five => "5",
+            Band::Six => "6",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Band::TwoPointFour => "2.4 GHz",
+            Band::Five => "5 GHz",
+            Band::Six => "6 GHz",
+        }
+    }
+}
+
+pub fn channel_for_frequency(frequency_mhz: u32) -> Option<(Band, u32)> {
+    match frequency_mhz {
+        2484 => Some((Band::TwoPointFour, 14)),
+        2412..=2472 => Some((Band::TwoPointFour, (frequency_mhz - 2407) / 5)),
+        5955..=7115 => Some((Band::Six, (frequency_mhz - 5950) / 5)),
+        5160..=5885 => Some((Band::Five, (frequency_mhz - 5000) / 5)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelUsage {
+    pub channel: u32,
+    pub networks: Vec<(String, u8)>,
+}
+
+pub fn channel_usage(access_points: &[VisibleAccessPoint], band: Band) -> Vec<ChannelUsage> {
+    let mut usage: Vec<ChannelUsage> = Vec::new();
+    for ap in access_points {
+        let Some((ap_band, channel)) = channel_for_frequency(ap.frequency_mhz) else {
+            continue;
+        };
+        if ap_band != band {
+            continue;
+        }
+        // Hidden networks broadcast no SSID, so fall back to the BSSID as a label.
+        let label = if ap.ssid.is_empty() { &ap.bssid } else { &ap.ssid };
+        let entry = (label.clone(), ap.strength);
+        match usage.iter_mut().find(|slot| slot.channel == channel) {
+            Some(slot) => slot.networks.push(entry),
+            None => usage.push(ChannelUsage {
+                channel,
+                networks: vec![entry],
+            }),
+        }
+    }
+    usage.sort_by_key(|slot| slot.channel);
+    for slot in &mut usage {
+        slot.networks.sort_by_key(|(_, strength)| Reverse(*strength));
+    }
+    usage
+}
+
+/// Picks the 2.4 GHz channel among 1/6/11 that overlaps the least signal energy.
+/// A 20 MHz transmission bleeds into the four neighbouring channels on each side.
+pub fn recommended_24_channel(access_points: &[VisibleAccessPoint]) -> u32 {
+    let usage = channel_usage(access_points, Band::TwoPointFour);
+    NON_OVERLAPPING_24
+        .into_iter()
+        .min_by_key(|candidate| {
+            usage
+                .iter()
+                .filter(|slot| slot.channel.abs_diff(*candidate) < 5)
+                .flat_map(|slot| slot.networks.iter())
+                .map(|(_, strength)| u32::from(*strength))
+                .sum::<u32>()
+        })
+        .unwrap_or(1)
+}
+
+/// One entry per band for a network heard on both 2.4 and 5 GHz, each with
+/// only that band's radios and their best signal. Anything else, including a
+/// network with a 6 GHz radio, stays a single entry without a band.
+pub fn split_by_band(network: &Network) -> Vec<(Network, Option<BandLock>)> {
+    let bands: Vec<Option<BandLock>> = network
+        .access_points
+        .iter()
+        .map(|ap| BandLock::for_frequency(ap.frequency_mhz))
+        .collect();
+    let has = |band| bands.contains(&Some(band));
+    if bands.contains(&None) || !has(BandLock::TwoPointFour) || !has(BandLock::Five) {
+        return vec![(network.clone(), None)];
+    }
+    [BandLock::TwoPointFour, BandLock::Five]
+        .into_iter()
+        .map(|band| {
+            let mut entry = network.clone();
+            entry
+                .access_points
+                .retain(|ap| BandLock::for_frequency(ap.frequency_mhz) == Some(band));
+            entry.strength = entry.access_points.iter().map(|ap| ap.strength).max().unwrap_or(0);
+            (entry, Some(band))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ap(ssid: &str, frequency_mhz: u32, strength: u8) -> VisibleAccessPoint {
+        VisibleAccessPoint {
+            ssid: ssid.to_string(),
+            bssid: String::new(),
+            frequency_mhz,
+            strength,
+        }
+    }
+
+    #[test]
+    fn maps_frequencies_to_channels() {
+        assert_eq!(channel_for_frequency(2412), Some((Band::TwoPointFour, 1)));
+        assert_eq!(channel_for_frequency(2484), Some((Band::TwoPointFour, 14)));
+        assert_eq!(channel_for_frequency(5180), Some((Band::Five, 36)));
+        assert_eq!(channel_for_frequency(5955), Some((Band::Six, 1)));
+        assert_eq!(channel_for_frequency(900), None);
+    }
+
+    #[test]
+    fn recommends_least_congested_channel() {
+        let aps = [
+            ap("A", 2412, 80),
+            ap("B", 2437, 70),
+            ap("C", 2447, 20),
+            ap("D", 2462, 30),
+            ap("E", 5180, 90),
+        ];
+        let usage = channel_usage(&aps, Band::TwoPointFour);
+        assert_eq!(usage.len(), 4);
+        assert_eq!(usage[0].channel, 1);
+        assert_eq!(recommended_24_channel(&aps), 11);
+        assert_eq!(channel_usage(&aps, Band::Five)[0].networks, [("E".to_string(), 90)]);
+
+        let mut hidden = ap("", 2412, 40);
+        hidden.bssid = "02:00:00:00:00:0A".to_string();
+        let usage = channel_usage(&[hidden], Band::TwoPointFour);
+        assert_eq!(usage[0].networks[0].0, "02:00:00:00:00:0A");
+    }
+
+    #[test]
+    fn recommends_channel_from_scanned_access_points() {
+        use crate::backend::Backend;
+        use crate::backend::mock_nm::{MockAccessPoint, MockNetworkManager};
+
+        let Some(mock) = MockNetworkManager::start(vec![
+            MockAccessPoint::secured("Home", 70, "correct horse").on(2437),
+            MockAccessPoint::open("Cafe", 55).on(2412),
+        ]) else {
+            eprintln!("dbus-daemon not available, skipping analyzer scan test");
+            return;
+        };
+        let aps = mock.backend().list_access_points().expect("access points");
+        assert_eq!(recommended_24_channel(&aps), 11);
+    }
+
+    #[test]
+    fn splits_dual_band_networks_only() {
+        use crate::models::{AccessPoint, NetworkAction};
+
+        let radio = |frequency_mhz, strength| AccessPoint {
+            frequency_mhz,
+            strength,
+            ..AccessPoint::default()
+        };
+        let mut network = Network {
+            ssid: "Home".to_string(),
+            signal_icon: "network-wireless-signal-excellent-symbolic",
+            action: NetworkAction::Connect,
+            strength: 80,
+            is_active: false,
+            is_saved: true,
+            is_secure: true,
+            security: "wpa2",
+            access_points: vec![radio(2437, 80), radio(5180, 60), radio(5500, 65)],
+        };
+        let split = split_by_band(&network);
+        let bands: Vec<_> = split.iter().map(|(entry, band)| (*band, entry.strength)).collect();
+        assert_eq!(bands, [(Some(BandLock::TwoPointFour), 80), (Some(BandLock::Five), 65)]);
+        assert_eq!(split[1].0.access_points.len(), 2);
+
+        network.access_points.push(radio(5975, 50));
+        assert_eq!(split_by_band(&network)[0].1, None);
+        network.access_points = vec![radio(2412, 70)];
+        assert_eq!(split_by_band(&network).len(), 1);
+    }
+}
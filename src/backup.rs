@@ -0,0 +1,92 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::fmt;
+
+/// Start of every backup file, with the format version.
+const MAGIC: &[u8] = b"YUFIBAK1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+pub const FILE_NAME: &str = "wifi-profiles.yufibackup";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BackupError {
+    NotABackup,
+    /// The password is wrong or the file was changed since it was written.
+    WrongPassword,
+    Crypto(String),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::NotABackup => write!(f, "This is not a YuFi backup"),
+            BackupError::WrongPassword => write!(f, "Wrong password, or the backup is damaged"),
+            BackupError::Crypto(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Encrypts `plain` with ChaCha20-Poly1305 under a key derived from `password`
+/// with Argon2id. The header, salt and nonce are stored in the clear; the
+/// header is authenticated along with the contents.
+pub fn seal(plain: &[u8], password: &str) -> Result<Vec<u8>, BackupError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::getrandom(&mut salt).map_err(|err| BackupError::Crypto(err.to_string()))?;
+    getrandom::getrandom(&mut nonce).map_err(|err| BackupError::Crypto(err.to_string()))?;
+    let cipher = ChaCha20Poly1305::new(&derive_key(password, &salt)?);
+    let payload = Payload {
+        msg: plain,
+        aad: MAGIC,
+    };
+    let sealed = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|err| BackupError::Crypto(err.to_string()))?;
+    Ok([MAGIC, &salt, &nonce, &sealed].concat())
+}
+
+/// Reverses `seal`.
+pub fn open(archive: &[u8], password: &str) -> Result<Vec<u8>, BackupError> {
+    let rest = archive.strip_prefix(MAGIC).ok_or(BackupError::NotABackup)?;
+    if rest.len() < SALT_LEN + NONCE_LEN {
+        return Err(BackupError::NotABackup);
+    }
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&derive_key(password, salt)?);
+    let payload = Payload {
+        msg: sealed,
+        aad: MAGIC,
+    };
+    cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| BackupError::WrongPassword)
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Key, BackupError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|err| BackupError::Crypto(err.to_string()))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_wrong_password() {
+        let archive = seal(b"profiles", "correct horse").expect("seal");
+        assert!(archive.starts_with(MAGIC));
+        assert_eq!(open(&archive, "correct horse").as_deref(), Ok(&b"profiles"[..]));
+        assert_eq!(open(&archive, "battery staple"), Err(BackupError::WrongPassword));
+
+        let mut tampered = archive.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(open(&tampered, "correct horse"), Err(BackupError::WrongPassword));
+        assert_eq!(open(b"[connection]\nid=Home\n", "correct horse"), Err(BackupError::NotABackup));
+        assert_eq!(open(MAGIC, "correct horse"), Err(BackupError::NotABackup));
+    }
+}
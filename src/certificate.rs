@@ -0,0 +1,194 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Certificates expiring within this many days get a warning.
+const EXPIRY_WARNING_DAYS: u64 = 30;
+const DAY: u64 = 86_400;
+
+/// What the certificate picker shows about a chosen file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// End of validity, in seconds since the Unix epoch.
+    pub not_after: u64,
+}
+
+impl CertificateInfo {
+    /// The common name of the subject, or the whole subject without one.
+    pub fn name(&self) -> &str {
+        common_name(&self.subject).unwrap_or(&self.subject)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.not_after <= now
+    }
+
+    /// One line for under the picker: who the certificate is for and when it
+    /// runs out.
+    pub fn summary(&self, now: u64) -> String {
+        let issuer = common_name(&self.issuer).unwrap_or(&self.issuer);
+        let issued = if self.issuer == self.subject {
+            "self-signed".to_string()
+        } else {
+            format!("issued by {issuer}")
+        };
+        let days = self.not_after.abs_diff(now) / DAY;
+        let validity = if self.is_expired(now) {
+            format!("expired {}", days_ago(days))
+        } else if days < EXPIRY_WARNING_DAYS {
+            format!("expires in {}", plural_days(days))
+        } else {
+            format!("valid for {}", plural_days(days))
+        };
+        format!("{}, {issued}; {validity}", self.name())
+    }
+
+    /// Whether the summary should be shown as a warning.
+    pub fn needs_attention(&self, now: u64) -> bool {
+        self.not_after < now + EXPIRY_WARNING_DAYS * DAY
+    }
+}
+
+fn common_name(name: &str) -> Option<&str> {
+    name.split(", ").find_map(|part| part.strip_prefix("CN = "))
+}
+
+fn plural_days(days: u64) -> String {
+    if days == 1 { "1 day".to_string() } else { format!("{days} days") }
+}
+
+fn days_ago(days: u64) -> String {
+    if days == 0 { "today".to_string() } else { format!("{} ago", plural_days(days)) }
+}
+
+/// Reads a PEM or DER certificate through `openssl`.
+pub fn inspect(path: &Path) -> Result<CertificateInfo, String> {
+    let output = openssl(&["x509", "-noout", "-subject", "-issuer", "-enddate", "-nameopt", "oneline", "-in"], path)?;
+    if !output.status.success() {
+        return Err(format!("{} is not a certificate", file_name(path)));
+    }
+    parse(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("Could not read the certificate in {}", file_name(path)))
+}
+
+/// Whether `path` is a PKCS #12 bundle, which holds the client certificate
+/// and its key together.
+pub fn is_pkcs12(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("p12") || extension.eq_ignore_ascii_case("pfx"))
+}
+
+/// Checks that `path` holds a private key `password` unlocks; an empty
+/// password stands for an unencrypted key.
+pub fn check_private_key(path: &Path, password: &str) -> Result<(), String> {
+    let passin = format!("pass:{password}");
+    let command = if is_pkcs12(path) { "pkcs12" } else { "pkey" };
+    let output = openssl(&[command, "-noout", "-passin", &passin, "-in"], path)?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(if !stderr.contains("bad decrypt") && !stderr.contains("mac verify failure") {
+        format!("{} is not a private key", file_name(path))
+    } else if password.is_empty() {
+        format!("The key in {} needs its password", file_name(path))
+    } else {
+        format!("The password does not unlock the key in {}", file_name(path))
+    })
+}
+
+fn openssl(args: &[&str], path: &Path) -> Result<Output, String> {
+    Command::new("openssl")
+        .args(args)
+        .arg(path)
+        .output()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => "Checking certificates needs openssl, which is not installed".to_string(),
+            _ => format!("Failed to run openssl: {err}"),
+        })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned()
+}
+
+fn parse(output: &str) -> Option<CertificateInfo> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(str::trim)
+    };
+    Some(CertificateInfo {
+        subject: field("subject")?.to_string(),
+        issuer: field("issuer")?.to_string(),
+        not_after: parse_date(field("notAfter")?)?,
+    })
+}
+
+/// `openssl`'s default date format, e.g. `Oct 17 10:45:07 2026 GMT`.
+fn parse_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let mut parts = date.split_whitespace();
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|name| *name == month)? as u64 + 1;
+    let day: u64 = parts.next()?.parse().ok()?;
+    let mut time = parts.next()?.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    let year: u64 = parts.next()?.parse().ok()?;
+    if year < 1970 {
+        return None;
+    }
+    // Days from the civil calendar, counting years from March so the leap day comes last.
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    Some(days * DAY + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_openssl_output_and_summarizes_validity() {
+        let info = parse(
+            "subject=CN = radius.campus.example, O = Campus\n\
+             issuer=CN = Campus Root CA, O = Campus\n\
+             notAfter=Oct 17 10:45:07 2026 GMT\n",
+        )
+        .unwrap();
+        assert_eq!(info.name(), "radius.campus.example");
+        assert_eq!(info.not_after, 1_792_233_907);
+        assert_eq!(parse_date("Feb 29 00:00:00 2024 GMT"), Some(1_709_164_800));
+        assert_eq!(parse_date("Jan  1 00:00:00 1970 GMT"), Some(0));
+        assert_eq!(parse("subject=CN = x\n"), None);
+
+        let now = info.not_after - 90 * DAY;
+        assert_eq!(
+            info.summary(now),
+            "radius.campus.example, issued by Campus Root CA; valid for 90 days"
+        );
+        assert!(!info.needs_attention(now));
+        assert_eq!(
+            info.summary(info.not_after - DAY),
+            "radius.campus.example, issued by Campus Root CA; expires in 1 day"
+        );
+        assert!(info.needs_attention(info.not_after - DAY));
+        assert!(info.summary(info.not_after + 3 * DAY).ends_with("expired 3 days ago"));
+        assert!(info.is_expired(info.not_after));
+
+        let root = CertificateInfo {
+            subject: "O = Campus".to_string(),
+            issuer: "O = Campus".to_string(),
+            not_after: now + 400 * DAY,
+        };
+        assert_eq!(root.summary(now), "O = Campus, self-signed; valid for 400 days");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::backend::Backend;
+use crate::backend::nm::NetworkManagerBackend;
+use crate::connect_flow::{NM_ACTIVE_CONNECTION_STATE_ACTIVATED, NM_ACTIVE_CONNECTION_STATE_DEACTIVATED};
+use crate::friendly_error;
+use crate::models::best_available;
+use std::thread;
+use std::time::Duration;
+
+const ACTIVATION_POLL: Duration = Duration::from_millis(500);
+const ACTIVATION_ATTEMPTS: u32 = 60;
+
+/// A command-line option of `yufi`. Registered with GApplication and used to
+/// write the shell completions and the man page, so the three cannot drift.
+pub struct CliOption {
+    pub name: &'static str,
+    /// Placeholder for the value the option takes, if any.
+    pub value: Option<&'static str>,
+    /// Values completed for `value`.
+    pub choices: &'static [&'static str],
+    pub description: &'static str,
+}
+
+pub const OPTIONS: &[CliOption] = &[
+    CliOption {
+        name: "connect-best",
+        value: None,
+        choices: &[],
+        description: "Connect to the best available saved network and exit",
+    },
+    CliOption {
+        name: "tui",
+        value: None,
+        choices: &[],
+        description: "Manage Wi‑Fi from a terminal interface instead of opening a window",
+    },
+    CliOption {
+        name: "read-only",
+        value: None,
+        choices: &[],
+        description: "Only show the Wi‑Fi status, without connect, forget or edit actions",
+    },
+    CliOption {
+        name: "demo",
+        value: None,
+        choices: &[],
+        description: "Show a fixed set of made-up networks instead of the real ones, for screenshots and development",
+    },
+    CliOption {
+        name: "profile",
+        value: None,
+        choices: &[],
+        description: "Log how long loading the network list, D-Bus calls and CSS loading take, and print a summary on exit",
+    },
+    CliOption {
+        name: "completions",
+        value: Some("SHELL"),
+        choices: &["bash", "zsh", "fish"],
+        description: "Print the completion script for SHELL (bash, zsh or fish) and exit",
+    },
+    CliOption {
+        name: "man-page",
+        value: None,
+        choices: &[],
+        description: "Print the manual page in roff format and exit",
+    },
+];
+
+/// `yufi --connect-best`: activates the best saved network in range without opening
+/// the window and waits for NetworkManager to report the outcome.
+pub fn connect_best() -> i32 {
+    let backend = NetworkManagerBackend::new();
+    let candidates = backend
+        .load_state()
+        .and_then(|state| Ok((state, backend.saved_profiles()?)));
+    let (state, profiles) = match candidates {
+        Ok(candidates) => candidates,
+        Err(err) => {
+            eprintln!("yufi: {}", friendly_error(&err));
+            return 1;
+        }
+    };
+    let Some(network) = best_available(&state, &profiles) else {
+        eprintln!("yufi: no saved networks in range");
+        return 1;
+    };
+    if network.is_active {
+        println!("Already connected to {}", network.ssid);
+        return 0;
+    }
+
+    println!("Connecting to {}...", network.ssid);
+    let active_path = match backend.connect_network(&network.ssid, None, true, None) {
+        Ok(Some(path)) => path,
+        Ok(None) => return 0,
+        Err(err) => {
+            eprintln!("yufi: {}", friendly_error(&err));
+            return 1;
+        }
+    };
+    for _ in 0..ACTIVATION_ATTEMPTS {
+        match backend.active_connection_state(&active_path) {
+            Ok(NM_ACTIVE_CONNECTION_STATE_ACTIVATED) => {
+                println!("Connected to {}", network.ssid);
+                return 0;
+            }
+            Ok(NM_ACTIVE_CONNECTION_STATE_DEACTIVATED) | Err(_) => break,
+            Ok(_) => thread::sleep(ACTIVATION_POLL),
+        }
+    }
+    eprintln!("yufi: failed to connect to {}", network.ssid);
+    1
+}
@@ -0,0 +1,156 @@
+use crate::cli::{CliOption, OPTIONS};
+
+/// GApplication adds `--help` to every program; completing it is still handy.
+const HELP: CliOption = CliOption {
+    name: "help",
+    value: None,
+    choices: &[],
+    description: "Show help options",
+};
+
+fn options() -> impl Iterator<Item = &'static CliOption> {
+    std::iter::once(&HELP).chain(OPTIONS)
+}
+
+/// Completion script for `shell`, or None for a shell YuFi has none for.
+pub fn script(shell: &str) -> Option<String> {
+    match shell {
+        "bash" => Some(bash()),
+        "zsh" => Some(zsh()),
+        "fish" => Some(fish()),
+        _ => None,
+    }
+}
+
+fn bash() -> String {
+    let names: Vec<String> = options().map(|option| format!("--{}", option.name)).collect();
+    let mut values = String::new();
+    for option in options().filter(|option| !option.choices.is_empty()) {
+        values.push_str(&format!(
+            "        --{}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+            option.name,
+            option.choices.join(" ")
+        ));
+    }
+    format!(
+        "_yufi() {{\n    \
+             local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    \
+             local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    \
+             # `--option=value` reaches us as three words.\n    \
+             if [[ $prev == = && $COMP_CWORD -ge 2 ]]; then\n        \
+                 prev=\"${{COMP_WORDS[COMP_CWORD-2]}}\"\n    \
+             fi\n    \
+             case \"$prev\" in\n\
+             {values}    \
+             esac\n    \
+             COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n\
+         }}\n\
+         complete -F _yufi yufi\n",
+        names.join(" ")
+    )
+}
+
+fn zsh() -> String {
+    let mut script = String::from("#compdef yufi\n\n_arguments \\\n");
+    let specs: Vec<String> = options()
+        .map(|option| {
+            let description = option
+                .description
+                .replace('\'', "'\\''")
+                .replace('[', "\\[")
+                .replace(']', "\\]");
+            match option.value {
+                Some(value) => format!(
+                    "  '--{}=[{description}]:{}:({})'",
+                    option.name,
+                    value.to_lowercase(),
+                    option.choices.join(" ")
+                ),
+                None => format!("  '--{}[{description}]'", option.name),
+            }
+        })
+        .collect();
+    script.push_str(&specs.join(" \\\n"));
+    script.push('\n');
+    script
+}
+
+fn fish() -> String {
+    let mut script = String::from("complete -c yufi -f\n");
+    for option in options() {
+        let description = option.description.replace('\'', "\\'");
+        let value = if option.choices.is_empty() {
+            String::new()
+        } else {
+            format!(" -x -a '{}'", option.choices.join(" "))
+        };
+        script.push_str(&format!("complete -c yufi -l {}{value} -d '{description}'\n", option.name));
+    }
+    script
+}
+
+/// `yufi(1)` in roff, for `man -l -` or installing under `man1`.
+pub fn man_page() -> String {
+    let mut page = format!(
+        ".TH YUFI 1 \"\" \"YuFi {}\" \"User Commands\"\n\
+         .SH NAME\n\
+         yufi \\- lightweight GTK4 Wi\\-Fi manager for NetworkManager\n\
+         .SH SYNOPSIS\n\
+         .B yufi\n\
+         [\\fIOPTION\\fR...]\n\
+         .SH DESCRIPTION\n\
+         Without options, YuFi opens a window listing the Wi\\-Fi networks in range and the saved ones. \
+         Connecting, forgetting and editing networks goes through NetworkManager over D\\-Bus, so \
+         changes to system connections may ask a polkit agent for permission.\n\
+         .SH OPTIONS\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for option in options() {
+        page.push_str(".TP\n");
+        match option.value {
+            Some(value) => page.push_str(&format!("\\fB\\-\\-{}\\fR=\\fI{value}\\fR\n", roff(option.name))),
+            None => page.push_str(&format!(".B \\-\\-{}\n", roff(option.name))),
+        }
+        page.push_str(&roff(option.description));
+        page.push('\n');
+    }
+    page.push_str(
+        ".SH FILES\n\
+         .TP\n\
+         .I $XDG_CONFIG_HOME/yufi/preferences.conf\n\
+         Preferences.\n\
+         .TP\n\
+         .I $XDG_CONFIG_HOME/yufi/hooks.conf\n\
+         Commands run when Wi\\-Fi connects or disconnects.\n\
+         .TP\n\
+         .I $XDG_DATA_HOME/yufi/data.json\n\
+         Hidden networks, usage history, favorites and notes.\n",
+    );
+    page
+}
+
+/// Escapes the hyphens and backslashes roff would otherwise interpret.
+fn roff(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_option_reaches_each_shell_and_the_man_page() {
+        let man = man_page();
+        for option in OPTIONS {
+            for shell in ["bash", "zsh", "fish"] {
+                assert!(script(shell).unwrap().contains(option.name), "{} missing for {shell}", option.name);
+            }
+            assert!(man.contains(&roff(option.name)), "{} missing from the man page", option.name);
+        }
+        assert!(script("bash").unwrap().contains("--completions) COMPREPLY=($(compgen -W \"bash zsh fish\""));
+        assert!(script("zsh").unwrap().contains("'--completions=[Print the completion script for SHELL (bash, zsh or fish) and exit]:shell:(bash zsh fish)'"));
+        assert!(script("fish").unwrap().contains("complete -c yufi -l completions -x -a 'bash zsh fish'"));
+        assert!(man.contains("\\fB\\-\\-completions\\fR=\\fISHELL\\fR"));
+        assert_eq!(script("powershell"), None);
+    }
+}
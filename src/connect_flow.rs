@@ -0,0 +1,636 @@
+use crate::models::BandLock;
+use std::collections::HashSet;
+
+pub const NM_ACTIVE_CONNECTION_STATE_ACTIVATED: u32 = 2;
+pub const NM_ACTIVE_CONNECTION_STATE_DEACTIVATED: u32 = 4;
+const NM_ACTIVE_CONNECTION_STATE_REASON_USER_DISCONNECTED: u32 = 2;
+const NM_ACTIVE_CONNECTION_STATE_REASON_CONNECT_TIMEOUT: u32 = 6;
+const NM_ACTIVE_CONNECTION_STATE_REASON_NO_SECRETS: u32 = 9;
+const NM_ACTIVE_CONNECTION_STATE_REASON_LOGIN_FAILED: u32 = 10;
+const NM_ACTIVE_CONNECTION_STATE_REASON_CONNECTION_REMOVED: u32 = 11;
+const NM_ACTIVE_CONNECTION_STATE_REASON_DEVICE_REMOVED: u32 = 14;
+
+const NM_DEVICE_STATE_REASON_CONFIG_FAILED: u32 = 4;
+const NM_DEVICE_STATE_REASON_IP_CONFIG_UNAVAILABLE: u32 = 5;
+const NM_DEVICE_STATE_REASON_IP_CONFIG_EXPIRED: u32 = 6;
+const NM_DEVICE_STATE_REASON_NO_SECRETS: u32 = 7;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_DISCONNECT: u32 = 8;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_CONFIG_FAILED: u32 = 9;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_FAILED: u32 = 10;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_TIMEOUT: u32 = 11;
+const NM_DEVICE_STATE_REASON_DHCP_START_FAILED: u32 = 15;
+const NM_DEVICE_STATE_REASON_DHCP_ERROR: u32 = 16;
+const NM_DEVICE_STATE_REASON_DHCP_FAILED: u32 = 17;
+const NM_DEVICE_STATE_REASON_SSID_NOT_FOUND: u32 = 53;
+
+const NM_DEVICE_STATE_PREPARE: u32 = 40;
+const NM_DEVICE_STATE_CONFIG: u32 = 50;
+const NM_DEVICE_STATE_NEED_AUTH: u32 = 60;
+const NM_DEVICE_STATE_IP_CONFIG: u32 = 70;
+const NM_DEVICE_STATE_IP_CHECK: u32 = 80;
+const NM_DEVICE_STATE_SECONDARIES: u32 = 90;
+const NM_DEVICE_STATE_FAILED: u32 = 120;
+
+pub const INCORRECT_PASSWORD: &str = "Incorrect password. Try again.";
+pub const GENERIC_FAILURE: &str = "Failed to connect. Check signal and try again.";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub ssid: String,
+    pub was_saved: bool,
+    pub from_password: bool,
+    /// Band the user picked from a split list entry, kept for the retry.
+    pub band: Option<BandLock>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ConnectPhase {
+    #[default]
+    Idle,
+    Activating(Attempt),
+    NeedSecrets {
+        attempt: Attempt,
+        error: Option<String>,
+    },
+    Failed {
+        ssid: String,
+        message: String,
+    },
+    Connected(String),
+}
+
+#[derive(Debug, Default)]
+pub struct ConnectFlow {
+    phase: ConnectPhase,
+    /// The activation the current attempt waits on. Forgotten once the
+    /// attempt is replaced or settles, so its late signals are ignored.
+    active_path: Option<String>,
+    stage: Option<&'static str>,
+    device_reason: Option<u32>,
+    failed: HashSet<String>,
+    /// The network seen active at the last `observe_active`.
+    online: Option<String>,
+    last_connected: Option<String>,
+    disconnect_expected: bool,
+    /// The attempt is held until Wi‑Fi is back on and the device sees its
+    /// network, since NetworkManager only activates a network it has seen.
+    awaiting_radio: bool,
+}
+
+/// A network seen connected that no longer is.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Dropped {
+    pub ssid: String,
+    /// It went away without the user disconnecting, switching networks or
+    /// turning Wi‑Fi off.
+    pub unexpected: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Transition {
+    pub changed: bool,
+    pub remove_profile: bool,
+}
+
+impl ConnectFlow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn phase(&self) -> &ConnectPhase {
+        &self.phase
+    }
+
+    pub fn connecting_ssid(&self) -> Option<&str> {
+        match &self.phase {
+            ConnectPhase::Activating(attempt) => Some(attempt.ssid.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn has_error(&self, ssid: &str) -> bool {
+        self.failed.contains(ssid)
+    }
+
+    pub fn begin(&mut self, attempt: Attempt) {
+        self.phase = ConnectPhase::Activating(attempt);
+        self.active_path = None;
+        self.stage = None;
+        self.device_reason = None;
+        self.awaiting_radio = false;
+    }
+
+    /// Begins `attempt` for a network that needs Wi‑Fi turned on first; it is
+    /// held until `radio_ready` hands it over.
+    pub fn begin_after_radio(&mut self, attempt: Attempt) {
+        self.begin(attempt);
+        self.awaiting_radio = true;
+    }
+
+    /// The network whose attempt is held for the radio, if any.
+    pub fn awaiting_radio(&self) -> Option<&str> {
+        self.connecting_ssid().filter(|_| self.awaiting_radio)
+    }
+
+    /// Stops holding the attempt for the radio and returns it, to be sent.
+    pub fn radio_ready(&mut self) -> Option<Attempt> {
+        let ssid = self.awaiting_radio()?.to_string();
+        self.awaiting_radio = false;
+        self.activating(&ssid)
+    }
+
+    pub fn stage(&self) -> Option<&'static str> {
+        self.connecting_ssid()?;
+        self.stage
+    }
+
+    pub fn device_state(&mut self, state: u32, reason: u32) -> bool {
+        if state == NM_DEVICE_STATE_FAILED && self.connecting_ssid().is_some() {
+            self.device_reason = Some(reason);
+        }
+        let stage = stage_label(state);
+        if self.connecting_ssid().is_none() || stage.is_none() || stage == self.stage {
+            return false;
+        }
+        self.stage = stage;
+        true
+    }
+
+    pub fn track(&mut self, ssid: &str, path: String) {
+        if self.activating(ssid).is_some() {
+            self.active_path = Some(path);
+        }
+    }
+
+    pub fn ssid_for_path(&self, path: &str) -> Option<String> {
+        let ssid = self.connecting_ssid()?;
+        (self.active_path.as_deref() == Some(path)).then(|| ssid.to_string())
+    }
+
+    pub fn request_failed(&mut self, ssid: &str, needs_password: bool, message: String) -> Transition {
+        let Some(attempt) = self.activating(ssid) else {
+            return Transition::default();
+        };
+        self.active_path = None;
+        self.phase = if needs_password && !attempt.from_password {
+            ConnectPhase::NeedSecrets {
+                attempt,
+                error: None,
+            }
+        } else if attempt.from_password {
+            ConnectPhase::NeedSecrets {
+                attempt,
+                error: Some(message),
+            }
+        } else {
+            ConnectPhase::Failed {
+                ssid: attempt.ssid,
+                message,
+            }
+        };
+        Transition {
+            changed: true,
+            remove_profile: false,
+        }
+    }
+
+    pub fn activation_state(
+        &mut self,
+        ssid: &str,
+        state: u32,
+        reason: u32,
+        is_secure: bool,
+    ) -> Transition {
+        let Some(attempt) = self.activating(ssid) else {
+            return Transition::default();
+        };
+        match state {
+            NM_ACTIVE_CONNECTION_STATE_ACTIVATED => {
+                self.connected(ssid);
+                Transition {
+                    changed: true,
+                    remove_profile: false,
+                }
+            }
+            NM_ACTIVE_CONNECTION_STATE_DEACTIVATED => {
+                self.active_path = None;
+                let remove_profile = !attempt.was_saved;
+                let secured = attempt.from_password || is_secure;
+                let cause = failure_cause(self.device_reason.take(), reason, secured);
+                match cause {
+                    FailureCause::Secrets => {
+                        self.failed.insert(attempt.ssid.clone());
+                        self.phase = ConnectPhase::NeedSecrets {
+                            attempt,
+                            error: Some(INCORRECT_PASSWORD.to_string()),
+                        };
+                    }
+                    FailureCause::Other(message) => {
+                        self.phase = ConnectPhase::Failed {
+                            ssid: attempt.ssid,
+                            message: message.to_string(),
+                        };
+                    }
+                }
+                Transition {
+                    changed: true,
+                    remove_profile,
+                }
+            }
+            _ => Transition::default(),
+        }
+    }
+
+    pub fn network_active(&mut self, ssid: &str) -> bool {
+        if self.activating(ssid).is_none() {
+            return false;
+        }
+        self.connected(ssid);
+        true
+    }
+
+    pub fn fail(&mut self, ssid: &str, message: String) {
+        self.active_path = None;
+        self.phase = ConnectPhase::Failed {
+            ssid: ssid.to_string(),
+            message,
+        };
+    }
+
+    pub fn clear(&mut self, ssid: &str) {
+        self.failed.remove(ssid);
+        let involved = match &self.phase {
+            ConnectPhase::Idle => false,
+            ConnectPhase::Activating(attempt) | ConnectPhase::NeedSecrets { attempt, .. } => {
+                attempt.ssid == ssid
+            }
+            ConnectPhase::Failed { ssid: current, .. } | ConnectPhase::Connected(current) => {
+                current == ssid
+            }
+        };
+        if involved {
+            self.phase = ConnectPhase::Idle;
+            self.active_path = None;
+        }
+    }
+
+    pub fn dismiss(&mut self) {
+        if !matches!(self.phase, ConnectPhase::Activating(_)) {
+            self.phase = ConnectPhase::Idle;
+        }
+    }
+
+    pub fn last_connected(&self) -> Option<&str> {
+        self.last_connected.as_deref()
+    }
+
+    pub fn expect_disconnect(&mut self) {
+        self.disconnect_expected = true;
+    }
+
+    /// Records the currently active network and returns the one that was
+    /// active before, if it no longer is.
+    pub fn observe_active(&mut self, active: Option<&str>, wifi_enabled: bool) -> Option<Dropped> {
+        let previous = std::mem::replace(&mut self.online, active.map(str::to_string));
+        if let Some(ssid) = active {
+            self.last_connected = Some(ssid.to_string());
+            self.disconnect_expected = false;
+            return previous
+                .filter(|previous| previous != ssid)
+                .map(|ssid| Dropped { ssid, unexpected: false });
+        }
+        let expected = std::mem::take(&mut self.disconnect_expected)
+            || !wifi_enabled
+            || self.connecting_ssid().is_some();
+        previous.map(|ssid| Dropped {
+            ssid,
+            unexpected: !expected,
+        })
+    }
+
+    fn activating(&self, ssid: &str) -> Option<Attempt> {
+        match &self.phase {
+            ConnectPhase::Activating(attempt) if attempt.ssid == ssid => Some(attempt.clone()),
+            _ => None,
+        }
+    }
+
+    fn connected(&mut self, ssid: &str) {
+        self.failed.remove(ssid);
+        self.active_path = None;
+        self.phase = ConnectPhase::Connected(ssid.to_string());
+    }
+}
+
+enum FailureCause {
+    Secrets,
+    Other(&'static str),
+}
+
+fn failure_cause(device_reason: Option<u32>, reason: u32, secured: bool) -> FailureCause {
+    if matches!(
+        reason,
+        NM_ACTIVE_CONNECTION_STATE_REASON_NO_SECRETS | NM_ACTIVE_CONNECTION_STATE_REASON_LOGIN_FAILED
+    ) {
+        return FailureCause::Secrets;
+    }
+    let message = match device_reason {
+        Some(NM_DEVICE_STATE_REASON_NO_SECRETS) => return FailureCause::Secrets,
+        Some(
+            NM_DEVICE_STATE_REASON_SUPPLICANT_DISCONNECT
+            | NM_DEVICE_STATE_REASON_SUPPLICANT_CONFIG_FAILED
+            | NM_DEVICE_STATE_REASON_SUPPLICANT_FAILED,
+        ) if secured => return FailureCause::Secrets,
+        Some(NM_DEVICE_STATE_REASON_SUPPLICANT_TIMEOUT) => {
+            "Authentication timed out. Move closer to the access point and try again."
+        }
+        Some(
+            NM_DEVICE_STATE_REASON_DHCP_START_FAILED
+            | NM_DEVICE_STATE_REASON_DHCP_ERROR
+            | NM_DEVICE_STATE_REASON_DHCP_FAILED
+            | NM_DEVICE_STATE_REASON_IP_CONFIG_UNAVAILABLE
+            | NM_DEVICE_STATE_REASON_IP_CONFIG_EXPIRED,
+        ) => "Connected, but no IP address was assigned (DHCP failed).",
+        Some(NM_DEVICE_STATE_REASON_SSID_NOT_FOUND) => "Network not found. It may be out of range.",
+        Some(NM_DEVICE_STATE_REASON_CONFIG_FAILED) => "The network configuration could not be applied.",
+        _ => match reason {
+            NM_ACTIVE_CONNECTION_STATE_REASON_USER_DISCONNECTED => "Connection attempt was cancelled.",
+            NM_ACTIVE_CONNECTION_STATE_REASON_CONNECT_TIMEOUT => "Connection attempt timed out.",
+            NM_ACTIVE_CONNECTION_STATE_REASON_CONNECTION_REMOVED => "The saved profile was removed.",
+            NM_ACTIVE_CONNECTION_STATE_REASON_DEVICE_REMOVED => "The Wi‑Fi device was removed.",
+            _ if secured => return FailureCause::Secrets,
+            _ => GENERIC_FAILURE,
+        },
+    };
+    FailureCause::Other(message)
+}
+
+fn stage_label(state: u32) -> Option<&'static str> {
+    match state {
+        NM_DEVICE_STATE_PREPARE => Some("Preparing…"),
+        NM_DEVICE_STATE_CONFIG => Some("Associating…"),
+        NM_DEVICE_STATE_NEED_AUTH => Some("Authenticating…"),
+        NM_DEVICE_STATE_IP_CONFIG => Some("Getting IP address…"),
+        NM_DEVICE_STATE_IP_CHECK => Some("Checking connectivity…"),
+        NM_DEVICE_STATE_SECONDARIES => Some("Finishing…"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(ssid: &str, was_saved: bool, from_password: bool) -> Attempt {
+        Attempt {
+            ssid: ssid.to_string(),
+            was_saved,
+            from_password,
+            band: None,
+        }
+    }
+
+    #[test]
+    fn activation_success_connects() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, false));
+        assert_eq!(flow.connecting_ssid(), Some("Home"));
+
+        assert_eq!(flow.activation_state("Home", 1, 0, true), Transition::default());
+        let transition = flow.activation_state("Home", 2, 0, true);
+        assert!(transition.changed);
+        assert!(!transition.remove_profile);
+        assert_eq!(flow.phase(), &ConnectPhase::Connected("Home".to_string()));
+        assert_eq!(flow.connecting_ssid(), None);
+    }
+
+    #[test]
+    fn missing_secrets_prompts_without_error() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, false));
+        flow.request_failed("Home", true, "NoSecrets".to_string());
+        assert_eq!(
+            flow.phase(),
+            &ConnectPhase::NeedSecrets {
+                attempt: attempt("Home", true, false),
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn wrong_password_marks_row_and_reprompts() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Cafe", false, true));
+        let transition = flow.activation_state("Cafe", 4, 0, true);
+        assert!(transition.remove_profile);
+        assert!(flow.has_error("Cafe"));
+        assert!(matches!(
+            flow.phase(),
+            ConnectPhase::NeedSecrets { error: Some(message), .. } if message == INCORRECT_PASSWORD
+        ));
+
+        flow.begin(attempt("Cafe", false, true));
+        flow.activation_state("Cafe", 2, 0, true);
+        assert!(!flow.has_error("Cafe"));
+    }
+
+    #[test]
+    fn open_network_failure_is_terminal() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Cafe", true, false));
+        let transition = flow.activation_state("Cafe", 4, 0, false);
+        assert!(!transition.remove_profile);
+        assert!(!flow.has_error("Cafe"));
+        assert_eq!(
+            flow.phase(),
+            &ConnectPhase::Failed {
+                ssid: "Cafe".to_string(),
+                message: GENERIC_FAILURE.to_string(),
+            }
+        );
+        flow.dismiss();
+        assert_eq!(flow.phase(), &ConnectPhase::Idle);
+    }
+
+    #[test]
+    fn events_for_other_networks_are_ignored() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, false));
+        assert!(!flow.activation_state("Office", 2, 0, true).changed);
+        assert!(!flow.network_active("Office"));
+        assert!(flow.network_active("Home"));
+    }
+
+    #[test]
+    fn tracked_path_maps_back_to_attempt() {
+        let mut flow = ConnectFlow::new();
+        flow.track("Home", "/active/1".to_string());
+        assert_eq!(flow.ssid_for_path("/active/1"), None);
+
+        flow.begin(attempt("Home", true, false));
+        flow.track("Home", "/active/1".to_string());
+        assert_eq!(flow.ssid_for_path("/active/1").as_deref(), Some("Home"));
+        assert_eq!(flow.ssid_for_path("/active/2"), None);
+
+        let transition = flow.activation_state("Home", 4, 9, false);
+        assert!(transition.changed);
+        assert!(matches!(flow.phase(), ConnectPhase::NeedSecrets { .. }));
+        assert_eq!(flow.ssid_for_path("/active/1"), None);
+    }
+
+    #[test]
+    fn replaced_or_settled_attempts_stop_following_their_activation() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, false));
+        flow.track("Home", "/active/1".to_string());
+        flow.begin(attempt("Home", true, false));
+        assert_eq!(flow.ssid_for_path("/active/1"), None);
+
+        flow.track("Home", "/active/2".to_string());
+        flow.request_failed("Home", false, "failed".to_string());
+        flow.begin(attempt("Home", true, false));
+        assert_eq!(flow.ssid_for_path("/active/2"), None);
+
+        flow.track("Home", "/active/3".to_string());
+        flow.fail("Home", "failed".to_string());
+        assert_eq!(flow.ssid_for_path("/active/3"), None);
+        assert!(!flow.activation_state("Home", 2, 0, false).changed);
+    }
+
+    fn unexpected(flow: &mut ConnectFlow, active: Option<&str>, wifi_enabled: bool) -> bool {
+        flow.observe_active(active, wifi_enabled).is_some_and(|dropped| dropped.unexpected)
+    }
+
+    #[test]
+    fn unexpected_drops_offer_reconnect() {
+        let mut flow = ConnectFlow::new();
+        assert!(!unexpected(&mut flow, None, true));
+        assert!(!unexpected(&mut flow, Some("Home"), true));
+        assert!(unexpected(&mut flow, None, true));
+        assert!(!unexpected(&mut flow, None, true));
+        assert_eq!(flow.last_connected(), Some("Home"));
+
+        flow.observe_active(Some("Home"), true);
+        flow.expect_disconnect();
+        assert!(!unexpected(&mut flow, None, true));
+
+        flow.observe_active(Some("Home"), true);
+        assert!(!unexpected(&mut flow, None, false));
+
+        flow.observe_active(Some("Home"), true);
+        flow.begin(attempt("Office", true, false));
+        assert!(!unexpected(&mut flow, None, true));
+    }
+
+    #[test]
+    fn only_a_network_seen_connected_is_dropped() {
+        let mut flow = ConnectFlow::new();
+        // Joining: nothing was connected yet, so nothing ends.
+        flow.begin(attempt("Cafe", false, false));
+        assert_eq!(flow.observe_active(None, true), None);
+        assert!(flow.network_active("Cafe"));
+        assert_eq!(flow.observe_active(Some("Cafe"), true), None);
+        assert_eq!(flow.observe_active(Some("Cafe"), true), None);
+
+        flow.expect_disconnect();
+        assert_eq!(
+            flow.observe_active(None, true),
+            Some(Dropped {
+                ssid: "Cafe".to_string(),
+                unexpected: false,
+            })
+        );
+        assert_eq!(flow.observe_active(None, true), None);
+
+        // Switching networks ends the previous one too.
+        flow.observe_active(Some("Home"), true);
+        assert_eq!(
+            flow.observe_active(Some("Office"), true),
+            Some(Dropped {
+                ssid: "Home".to_string(),
+                unexpected: false,
+            })
+        );
+    }
+
+    #[test]
+    fn device_states_update_stage_while_activating() {
+        let mut flow = ConnectFlow::new();
+        assert!(!flow.device_state(60, 0));
+        assert_eq!(flow.stage(), None);
+
+        flow.begin(attempt("Home", true, false));
+        assert!(flow.device_state(60, 0));
+        assert!(!flow.device_state(60, 0));
+        assert_eq!(flow.stage(), Some("Authenticating…"));
+        assert!(!flow.device_state(100, 0));
+        assert!(flow.device_state(70, 0));
+        assert_eq!(flow.stage(), Some("Getting IP address…"));
+
+        flow.activation_state("Home", 2, 0, true);
+        assert_eq!(flow.stage(), None);
+        flow.begin(attempt("Home", true, false));
+        assert_eq!(flow.stage(), None);
+    }
+
+    #[test]
+    fn failure_reasons_produce_specific_messages() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, false));
+        flow.device_state(120, 17);
+        flow.activation_state("Home", 4, 3, true);
+        assert_eq!(
+            flow.phase(),
+            &ConnectPhase::Failed {
+                ssid: "Home".to_string(),
+                message: "Connected, but no IP address was assigned (DHCP failed).".to_string(),
+            }
+        );
+        assert!(!flow.has_error("Home"));
+
+        flow.begin(attempt("Home", true, false));
+        flow.device_state(120, 7);
+        flow.activation_state("Home", 4, 3, false);
+        assert!(matches!(flow.phase(), ConnectPhase::NeedSecrets { .. }));
+
+        flow.begin(attempt("Cafe", true, false));
+        flow.activation_state("Cafe", 4, 6, false);
+        assert!(matches!(
+            flow.phase(),
+            ConnectPhase::Failed { message, .. } if message == "Connection attempt timed out."
+        ));
+    }
+
+    #[test]
+    fn clear_resets_matching_network() {
+        let mut flow = ConnectFlow::new();
+        flow.begin(attempt("Home", true, true));
+        flow.activation_state("Home", 4, 0, true);
+        flow.clear("Office");
+        assert!(flow.has_error("Home"));
+        flow.clear("Home");
+        assert!(!flow.has_error("Home"));
+        assert_eq!(flow.phase(), &ConnectPhase::Idle);
+    }
+
+    #[test]
+    fn attempt_is_held_until_the_radio_is_ready() {
+        let mut flow = ConnectFlow::new();
+        let held = Attempt {
+            band: Some(BandLock::Five),
+            ..attempt("Home", true, false)
+        };
+        flow.begin_after_radio(held.clone());
+        assert_eq!(flow.awaiting_radio(), Some("Home"));
+        assert_eq!(flow.connecting_ssid(), Some("Home"));
+        assert_eq!(flow.radio_ready(), Some(held));
+        assert_eq!(flow.awaiting_radio(), None);
+        assert_eq!(flow.radio_ready(), None);
+
+        // A newer attempt is sent right away and replaces the held one.
+        flow.begin_after_radio(attempt("Home", true, false));
+        flow.begin(attempt("Cafe", true, false));
+        assert_eq!(flow.awaiting_radio(), None);
+    }
+}
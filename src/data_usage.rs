@@ -0,0 +1,161 @@
+use crate::models::TrafficCounters;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Months of usage kept per network; older ones are dropped as new ones start.
+const MONTHS_KEPT: usize = 12;
+
+/// Bytes moved on one network in one calendar month.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonthTraffic {
+    pub received: u64,
+    pub sent: u64,
+}
+
+impl MonthTraffic {
+    pub fn total(&self) -> u64 {
+        self.received.saturating_add(self.sent)
+    }
+}
+
+/// Traffic of one network, by month (`"2026-10"`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataUsage {
+    pub months: BTreeMap<String, MonthTraffic>,
+    /// Whether the network was metered when last used.
+    pub metered: bool,
+    /// The month the quota warning was last sent for, so it is sent once a month.
+    pub warned: Option<String>,
+}
+
+impl DataUsage {
+    pub fn month(&self, month: &str) -> MonthTraffic {
+        self.months.get(month).copied().unwrap_or_default()
+    }
+
+    /// The bytes used in `month` once a metered network goes over
+    /// `quota_bytes`, at most once per month. A zero quota never warns.
+    pub fn crossed_quota(&mut self, month: &str, quota_bytes: u64) -> Option<u64> {
+        if !self.metered || quota_bytes == 0 || self.warned.as_deref() == Some(month) {
+            return None;
+        }
+        let used = self.month(month).total();
+        if used < quota_bytes {
+            return None;
+        }
+        self.warned = Some(month.to_string());
+        Some(used)
+    }
+}
+
+/// Turns successive device counter readings into per-SSID monthly traffic.
+#[derive(Debug, Default)]
+pub struct TrafficTracker {
+    last: Option<(String, TrafficCounters)>,
+}
+
+impl TrafficTracker {
+    /// Adds what the counters moved since the previous reading to the month of
+    /// the active network and returns true when something changed. The first
+    /// reading on a network, and one after the counters restarted (the adapter
+    /// was unplugged or NetworkManager restarted), only set a new baseline.
+    pub fn observe(
+        &mut self,
+        usage: &mut BTreeMap<String, DataUsage>,
+        active: Option<&str>,
+        counters: TrafficCounters,
+        month: &str,
+    ) -> bool {
+        let previous = self.last.take();
+        let Some(ssid) = active else { return false };
+        self.last = Some((ssid.to_string(), counters));
+        let Some((_, previous)) = previous.filter(|(previous, _)| previous == ssid) else {
+            return false;
+        };
+        let (Some(received), Some(sent)) = (
+            counters.rx_bytes.checked_sub(previous.rx_bytes),
+            counters.tx_bytes.checked_sub(previous.tx_bytes),
+        ) else {
+            return false;
+        };
+        let network = usage.entry(ssid.to_string()).or_default();
+        let changed = received > 0 || sent > 0 || network.metered != counters.metered;
+        network.metered = counters.metered;
+        if received > 0 || sent > 0 {
+            let traffic = network.months.entry(month.to_string()).or_default();
+            traffic.received = traffic.received.saturating_add(received);
+            traffic.sent = traffic.sent.saturating_add(sent);
+            while network.months.len() > MONTHS_KEPT {
+                network.months.pop_first();
+            }
+        }
+        changed
+    }
+}
+
+/// `"512 B"`, `"3.4 MB"`, `"1.2 GB"`, in decimal units like file managers use.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["kB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(rx_bytes: u64, tx_bytes: u64, metered: bool) -> TrafficCounters {
+        TrafficCounters {
+            rx_bytes,
+            tx_bytes,
+            metered,
+        }
+    }
+
+    #[test]
+    fn accumulates_per_network_and_month() {
+        let mut usage = BTreeMap::new();
+        let mut tracker = TrafficTracker::default();
+        assert!(!tracker.observe(&mut usage, Some("Phone"), counters(5_000, 1_000, true), "2026-09"));
+        assert!(tracker.observe(&mut usage, Some("Phone"), counters(8_000, 1_500, true), "2026-09"));
+        assert!(tracker.observe(&mut usage, Some("Phone"), counters(9_000, 1_500, true), "2026-10"));
+        assert!(!tracker.observe(&mut usage, Some("Home"), counters(20_000, 2_000, false), "2026-10"));
+        assert!(!tracker.observe(&mut usage, Some("Home"), counters(100, 10, false), "2026-10"));
+        assert!(tracker.observe(&mut usage, Some("Home"), counters(600, 10, false), "2026-10"));
+        assert!(!tracker.observe(&mut usage, None, counters(900, 10, false), "2026-10"));
+
+        let phone = &usage["Phone"];
+        assert_eq!(phone.month("2026-09"), MonthTraffic { received: 3_000, sent: 500 });
+        assert_eq!(phone.month("2026-10").total(), 1_000);
+        assert_eq!(usage["Home"].month("2026-10").received, 500);
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1_250_000_000), "1.2 GB");
+    }
+
+    #[test]
+    fn warns_once_a_month_over_quota_on_metered_networks() {
+        let mut phone = DataUsage {
+            metered: true,
+            ..DataUsage::default()
+        };
+        phone.months.insert("2026-10".to_string(), MonthTraffic { received: 900, sent: 200 });
+        assert_eq!(phone.crossed_quota("2026-10", 0), None);
+        assert_eq!(phone.crossed_quota("2026-10", 2_000), None);
+        assert_eq!(phone.crossed_quota("2026-10", 1_000), Some(1_100));
+        assert_eq!(phone.crossed_quota("2026-10", 1_000), None);
+
+        phone.metered = false;
+        phone.months.insert("2026-11".to_string(), MonthTraffic { received: 5_000, sent: 0 });
+        assert_eq!(phone.crossed_quota("2026-11", 1_000), None);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::backend::Backend;
+use crate::backend::mock_nm::{MockAccessPoint, MockNetworkManager};
+use crate::backend::nm;
+use crate::friendly_error;
+use crate::storage::AppData;
+use crate::usage::NetworkUsage;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// What the clock reads in the demo: Thursday, 16 October 2025, 10:00 UTC.
+pub const FROZEN_NOW: u64 = 1_760_608_800;
+const HOUR: u64 = 3_600;
+const DAY: u64 = 86_400;
+
+/// Where the demo keeps its preferences and data instead of the user's.
+static ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// `yufi --demo`: the made-up neighbourhood the window shows while it runs.
+/// Dropping it stops the scripted NetworkManager and removes its files.
+pub struct Demo {
+    network_manager: MockNetworkManager,
+    root: PathBuf,
+}
+
+impl Drop for Demo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Whether `yufi --demo` is running, which also freezes the clock at `FROZEN_NOW`.
+pub fn is_active() -> bool {
+    ROOT.get().is_some()
+}
+
+/// `$XDG_CONFIG_HOME` and `$XDG_DATA_HOME` stand-ins while the demo runs.
+pub fn root() -> Option<&'static Path> {
+    ROOT.get().map(PathBuf::as_path)
+}
+
+/// Starts a scripted NetworkManager on a private bus with the same networks
+/// every time and points every backend of this process at it.
+pub fn start() -> Result<Demo, String> {
+    let network_manager = MockNetworkManager::start(neighbourhood())
+        .ok_or("the demo needs dbus-daemon, which is not installed")?;
+    let root = std::env::temp_dir().join(format!("yufi-demo-{}", std::process::id()));
+    fs::create_dir_all(&root).map_err(|err| format!("failed to create {}: {err}", root.display()))?;
+    let demo = Demo {
+        network_manager,
+        root: root.clone(),
+    };
+    ROOT.set(root).map_err(|_| "the demo is already running".to_string())?;
+
+    let network_manager = &demo.network_manager;
+    network_manager.add_saved("Home", Some("correct horse"));
+    network_manager.add_saved("Office", Some("hunter22"));
+    network_manager.add_saved("Corner Cafe", None);
+    network_manager.add_saved("Grandma's", Some("cookies4ever"));
+    network_manager
+        .backend()
+        .connect_network("Home", None, true, None)
+        .map_err(|err| friendly_error(&err))?;
+    nm::use_bus_address(network_manager.bus_address());
+    seed_data().map_err(|err| format!("failed to write the demo data: {err}"))?;
+    Ok(demo)
+}
+
+fn neighbourhood() -> Vec<MockAccessPoint> {
+    vec![
+        MockAccessPoint::secured("Home", 88, "correct horse").on(5180),
+        MockAccessPoint::secured("Home", 64, "correct horse").on(2437),
+        MockAccessPoint::sae("Office", 71, "hunter22").on(5500),
+        MockAccessPoint::open("Corner Cafe", 52).on(2412),
+        MockAccessPoint::owe("Library Guest", 43).on(5240),
+        MockAccessPoint::secured("Neighbour 5G", 37, "not-yours").on(5745),
+        MockAccessPoint::secured("DIRECT-42-Printer", 24, "printer").on(2462),
+    ]
+}
+
+/// History, favorites and notes, so the list and the saved networks look lived in.
+fn seed_data() -> std::io::Result<()> {
+    let mut data = AppData::load();
+    for (ssid, times_connected, ago) in [
+        ("Home", 214, 0),
+        ("Office", 87, 18 * HOUR),
+        ("Corner Cafe", 6, 9 * DAY),
+        ("Grandma's", 3, 140 * DAY),
+    ] {
+        data.history.insert(
+            ssid.to_string(),
+            NetworkUsage {
+                times_connected,
+                last_connected: FROZEN_NOW - ago,
+                last_seen: FROZEN_NOW - ago,
+            },
+        );
+    }
+    data.favorites.push("Home".to_string());
+    data.notes.insert("Office".to_string(), "Ask IT before changing DNS".to_string());
+    data.save()
+}
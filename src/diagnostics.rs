@@ -0,0 +1,198 @@
+use crate::models::NmDiagnostics;
+use crate::scan_mac;
+use std::fs;
+use std::path::Path;
+use zbus::blocking::{Connection, Proxy};
+
+/// Executables known to register a polkit authentication agent.
+const POLKIT_AGENTS: &[&str] = &[
+    "polkit-gnome-authentication-agent-1",
+    "polkit-kde-authentication-agent-1",
+    "polkit-mate-authentication-agent-1",
+    "lxpolkit",
+    "lxqt-policykit-agent",
+    "xfce-polkit",
+    "hyprpolkitagent",
+    "polkit-efl-authentication-agent-1",
+    "gnome-shell",
+    "cinnamon",
+];
+
+/// Executables known to register a NetworkManager secret agent.
+const SECRET_AGENTS: &[&str] = &["nm-applet", "nm-tray", "gnome-shell", "kded5", "kded6", "cinnamon"];
+
+const SECRET_SERVICE: &str = "org.freedesktop.secrets";
+
+/// Facts gathered from the local session rather than from NetworkManager.
+#[derive(Clone, Debug, Default)]
+pub struct SessionDiagnostics {
+    pub polkit_agent: Option<String>,
+    pub secret_agent: Option<String>,
+    pub secret_service: bool,
+    pub scan_mac_randomization: bool,
+}
+
+impl SessionDiagnostics {
+    pub fn collect() -> Self {
+        let processes = running_processes();
+        Self {
+            polkit_agent: find_process(&processes, POLKIT_AGENTS),
+            secret_agent: find_process(&processes, SECRET_AGENTS),
+            secret_service: secret_service_available(),
+            scan_mac_randomization: scan_mac::randomization_enabled(),
+        }
+    }
+}
+
+pub fn connectivity_label(connectivity: u32) -> &'static str {
+    match connectivity {
+        1 => "None",
+        2 => "Captive portal",
+        3 => "Limited",
+        4 => "Full",
+        _ => "Unknown",
+    }
+}
+
+fn rfkill_label(nm: &NmDiagnostics) -> &'static str {
+    match (nm.wireless_hardware_enabled, nm.wireless_enabled) {
+        (false, _) => "Hard blocked (hardware switch or BIOS)",
+        (true, false) => "Soft blocked (Wi‑Fi turned off)",
+        (true, true) => "Unblocked",
+    }
+}
+
+/// Plain-text report suitable for pasting into a bug report.
+pub fn format_report(nm: Result<&NmDiagnostics, &str>, session: &SessionDiagnostics) -> String {
+    let mut lines = vec![format!("YuFi {}", env!("CARGO_PKG_VERSION"))];
+    match nm {
+        Ok(nm) => {
+            lines.push(format!("NetworkManager: {}", nm.version));
+            match &nm.device {
+                Some(device) => {
+                    lines.push(format!("Wi‑Fi device: {}", device.interface));
+                    lines.push(format!("Driver: {} {}", device.driver, device.driver_version));
+                    lines.push(format!("Firmware: {}", device.firmware_version));
+                    let origin = if device.mac_randomized() { "randomized" } else { "hardware address" };
+                    lines.push(format!("Scan MAC: {} ({origin})", device.mac));
+                }
+                None => lines.push("Wi‑Fi device: not found".to_string()),
+            }
+            lines.push(format!("Radio: {}", rfkill_label(nm)));
+            let randomization = if session.scan_mac_randomization { "on" } else { "off" };
+            lines.push(format!("Scan MAC randomization: {randomization}"));
+            let check = if nm.connectivity_check_enabled { "" } else { " (checking disabled)" };
+            lines.push(format!("Connectivity: {}{check}", connectivity_label(nm.connectivity)));
+            lines.push("Devices:".to_string());
+            for device in &nm.devices {
+                lines.push(format!(
+                    "  {} ({}, {}) {}: {}",
+                    device.interface,
+                    device.kind.label(),
+                    device.driver,
+                    device.mac,
+                    device.state_label()
+                ));
+            }
+        }
+        Err(err) => lines.push(format!("NetworkManager: unavailable ({err})")),
+    }
+    let found = |name: &Option<String>| {
+        name.as_ref()
+            .map_or_else(|| "not detected".to_string(), |name| format!("{name} (running)"))
+    };
+    lines.push(format!("Polkit agent: {}", found(&session.polkit_agent)));
+    lines.push(format!("Secret agent: {}", found(&session.secret_agent)));
+    let service = if session.secret_service { "available" } else { "not found" };
+    lines.push(format!("Secret service ({SECRET_SERVICE}): {service}"));
+    lines.join("\n")
+}
+
+fn find_process(processes: &[String], known: &[&str]) -> Option<String> {
+    known
+        .iter()
+        .find(|name| processes.iter().any(|process| process == *name))
+        .map(|name| name.to_string())
+}
+
+/// Executable names of the running processes, taken from argv[0] since
+/// `/proc/<pid>/comm` is truncated to 15 characters.
+fn running_processes() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().chars().all(|ch| ch.is_ascii_digit()))
+        .filter_map(|entry| fs::read(entry.path().join("cmdline")).ok())
+        .filter_map(|cmdline| {
+            let program = cmdline.split(|byte| *byte == 0).next()?;
+            let program = String::from_utf8_lossy(program);
+            Path::new(program.as_ref())
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+fn secret_service_available() -> bool {
+    let Ok(conn) = Connection::session() else {
+        return false;
+    };
+    Proxy::new(&conn, "org.freedesktop.DBus", "/org/freedesktop/DBus", "org.freedesktop.DBus")
+        .and_then(|proxy| proxy.call("NameHasOwner", &(SECRET_SERVICE)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DeviceInfo, DeviceKind, WifiDeviceInfo};
+
+    #[test]
+    fn formats_report_for_bug_reports() {
+        let nm = NmDiagnostics {
+            version: "1.46.0".to_string(),
+            wireless_enabled: false,
+            wireless_hardware_enabled: true,
+            connectivity: 2,
+            connectivity_check_enabled: true,
+            device: Some(WifiDeviceInfo {
+                interface: "wlan0".to_string(),
+                driver: "iwlwifi".to_string(),
+                driver_version: "6.8.0".to_string(),
+                firmware_version: "89.1".to_string(),
+                mac: "02:3F:9A:10:22:7C".to_string(),
+                permanent_mac: "00:1B:21:00:AA:01".to_string(),
+            }),
+            devices: vec![DeviceInfo {
+                path: "/org/freedesktop/NetworkManager/Devices/3".to_string(),
+                interface: "enp3s0".to_string(),
+                kind: DeviceKind::from_nm(1),
+                state: 100,
+                driver: "r8169".to_string(),
+                mac: "AA:BB:CC:DD:EE:FF".to_string(),
+            }],
+        };
+        let processes = ["bash".to_string(), "nm-applet".to_string()];
+        let session = SessionDiagnostics {
+            polkit_agent: find_process(&processes, POLKIT_AGENTS),
+            secret_agent: find_process(&processes, SECRET_AGENTS),
+            secret_service: true,
+            scan_mac_randomization: true,
+        };
+
+        let report = format_report(Ok(&nm), &session);
+        assert!(report.contains("Driver: iwlwifi 6.8.0"));
+        assert!(report.contains("Radio: Soft blocked"));
+        assert!(report.contains("Scan MAC: 02:3F:9A:10:22:7C (randomized)"));
+        assert!(report.contains("Scan MAC randomization: on"));
+        assert!(report.contains("Connectivity: Captive portal"));
+        assert!(report.contains("  enp3s0 (Ethernet, r8169) AA:BB:CC:DD:EE:FF: connected"));
+        assert!(report.contains("Polkit agent: not detected"));
+        assert!(report.contains("Secret agent: nm-applet (running)"));
+
+        let offline = format_report(Err("no bus"), &session);
+        assert!(offline.contains("NetworkManager: unavailable (no bus)"));
+    }
+}
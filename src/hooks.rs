@@ -0,0 +1,274 @@
+use crate::backend::Backend;
+use crate::models::DeviceKind;
+use crate::preferences;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::process::{Command, Stdio};
+use std::thread;
+
+const FILE_NAME: &str = "hooks.conf";
+const NM_DEVICE_STATE_ACTIVATED: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    Connect,
+    Disconnect,
+    /// NetworkManager found a captive portal on the active network.
+    Portal,
+}
+
+impl HookEvent {
+    pub fn key(self) -> &'static str {
+        match self {
+            HookEvent::Connect => "on_connect",
+            HookEvent::Disconnect => "on_disconnect",
+            HookEvent::Portal => "on_portal",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::Connect => "connect",
+            HookEvent::Disconnect => "disconnect",
+            HookEvent::Portal => "portal",
+        }
+    }
+}
+
+/// What a hook is told about the connection, passed as `YUFI_*` variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HookEnv {
+    pub ssid: String,
+    pub interface: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl HookEnv {
+    fn new(ssid: &str) -> Self {
+        Self {
+            ssid: ssid.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Looks up the Wi‑Fi interface and its address for the network that just came up.
+    pub fn lookup(backend: &impl Backend, ssid: &str) -> Self {
+        let interface = backend.list_devices().ok().and_then(|devices| {
+            devices
+                .into_iter()
+                .filter(|device| device.kind == DeviceKind::Wifi)
+                .max_by_key(|device| device.state == NM_DEVICE_STATE_ACTIVATED)
+                .map(|device| device.interface)
+        });
+        let ip = backend.ip_config().ok().flatten().map(|config| config.address);
+        Self {
+            ssid: ssid.to_string(),
+            interface,
+            ip,
+        }
+    }
+}
+
+/// An empty command is kept, so a network's section can turn off a global hook.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct HookCommands {
+    on_connect: Option<String>,
+    on_disconnect: Option<String>,
+    /// Usually a script that signs in to the network's captive portal.
+    on_portal: Option<String>,
+}
+
+impl HookCommands {
+    fn get(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Connect => self.on_connect.as_deref(),
+            HookEvent::Disconnect => self.on_disconnect.as_deref(),
+            HookEvent::Portal => self.on_portal.as_deref(),
+        }
+    }
+}
+
+/// Commands from `$XDG_CONFIG_HOME/yufi/hooks.conf`. Keys before the first
+/// `[SSID]` header apply to every network; keys under a header replace them
+/// for that network only, and an empty one turns the global command off.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hooks {
+    global: HookCommands,
+    per_ssid: BTreeMap<String, HookCommands>,
+}
+
+impl Hooks {
+    pub fn load() -> Self {
+        preferences::config_dir()
+            .and_then(|dir| fs::read_to_string(dir.join(FILE_NAME)).ok())
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut hooks = Self::default();
+        let mut section: Option<String> = None;
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(ssid) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                section = Some(ssid.to_string());
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let commands = match &section {
+                Some(ssid) => hooks.per_ssid.entry(ssid.clone()).or_default(),
+                None => &mut hooks.global,
+            };
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "on_connect" => commands.on_connect = value,
+                "on_disconnect" => commands.on_disconnect = value,
+                "on_portal" => commands.on_portal = value,
+                _ => {}
+            }
+        }
+        hooks
+    }
+
+    pub fn command(&self, event: HookEvent, ssid: &str) -> Option<&str> {
+        self.per_ssid
+            .get(ssid)
+            .and_then(|commands| commands.get(event))
+            .or_else(|| self.global.get(event))
+            .filter(|command| !command.is_empty())
+    }
+}
+
+/// Runs `command` through `sh -c` without waiting for it. A thread reaps the
+/// child and logs a non-zero exit, since nobody is watching its output.
+pub fn run(command: &str, event: HookEvent, env: &HookEnv) -> io::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("YUFI_EVENT", event.name())
+        .env("YUFI_SSID", &env.ssid)
+        .env("YUFI_INTERFACE", env.interface.as_deref().unwrap_or_default())
+        .env("YUFI_IP", env.ip.as_deref().unwrap_or_default())
+        .stdin(Stdio::null())
+        .spawn()?;
+    let key = event.key();
+    thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => eprintln!("yufi: {key} hook exited with {status}"),
+        Ok(_) => {}
+        Err(err) => eprintln!("yufi: {key} hook could not be waited on: {err}"),
+    });
+    Ok(())
+}
+
+/// Turns successive active networks into hook events. As with usage counting,
+/// a network already up the first time the app looks does not fire `on_connect`.
+///
+/// The interface and address of a network that comes up are looked up by the
+/// worker, so `on_connect` fires once `looked_up` hands them in.
+#[derive(Debug, Default)]
+pub struct HookTracker {
+    active: Option<HookEnv>,
+    observed: bool,
+    lookup: Option<String>,
+    connect_pending: bool,
+}
+
+impl HookTracker {
+    /// Returns the `on_disconnect` of a network that went away.
+    pub fn observe(&mut self, active: Option<&str>) -> Vec<(HookEvent, HookEnv)> {
+        let mut events = Vec::new();
+        if self.active.as_ref().map(|env| env.ssid.as_str()) != active {
+            if let Some(previous) = self.active.take() {
+                events.push((HookEvent::Disconnect, previous));
+            }
+            self.active = active.map(HookEnv::new);
+            self.lookup = active.map(str::to_string);
+            self.connect_pending = self.observed && active.is_some();
+        }
+        self.observed = true;
+        events
+    }
+
+    /// The network whose environment to look up, once after it came up.
+    pub fn take_lookup(&mut self) -> Option<String> {
+        self.lookup.take()
+    }
+
+    /// Keeps `env` for the network that is still up, so `on_disconnect` sees
+    /// the interface and address too, and returns its pending `on_connect`.
+    /// An answer for a network gone meanwhile is dropped.
+    pub fn looked_up(&mut self, env: HookEnv) -> Vec<(HookEvent, HookEnv)> {
+        let Some(active) = self.active.as_mut().filter(|active| active.ssid == env.ssid) else {
+            return Vec::new();
+        };
+        *active = env.clone();
+        if std::mem::take(&mut self.connect_pending) {
+            vec![(HookEvent::Connect, env)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The portal event for the network that is up, if any.
+    pub fn portal(&self) -> Option<(HookEvent, HookEnv)> {
+        self.active.clone().map(|env| (HookEvent::Portal, env))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(ssid: &str) -> HookEnv {
+        HookEnv {
+            ssid: ssid.to_string(),
+            interface: Some("wlan0".to_string()),
+            ip: Some("192.168.1.50".to_string()),
+        }
+    }
+
+    #[test]
+    fn per_ssid_commands_override_global_ones() {
+        let hooks = Hooks::parse(
+            "# user hooks\non_connect = notify-send \"$YUFI_SSID\"\non_disconnect = umount /mnt/nas\n\n\
+             [Home = 5G]\non_connect = mount /mnt/nas\n\n[Cafe]\non_disconnect =\nunknown = 1\n\
+             on_portal = ~/bin/cafe-login\n",
+        );
+        assert_eq!(hooks.command(HookEvent::Connect, "Home = 5G"), Some("mount /mnt/nas"));
+        assert_eq!(hooks.command(HookEvent::Disconnect, "Home = 5G"), Some("umount /mnt/nas"));
+        assert_eq!(hooks.command(HookEvent::Connect, "Office"), Some("notify-send \"$YUFI_SSID\""));
+        assert_eq!(hooks.command(HookEvent::Disconnect, "Cafe"), None);
+        assert_eq!(hooks.command(HookEvent::Disconnect, "Office"), Some("umount /mnt/nas"));
+        assert_eq!(hooks.command(HookEvent::Portal, "Cafe"), Some("~/bin/cafe-login"));
+        assert_eq!(hooks.command(HookEvent::Portal, "Office"), None);
+        assert_eq!(Hooks::parse("").command(HookEvent::Connect, "Office"), None);
+    }
+
+    #[test]
+    fn tracker_fires_on_transitions_only() {
+        let mut tracker = HookTracker::default();
+        assert!(tracker.observe(Some("Home")).is_empty());
+        assert_eq!(tracker.take_lookup().as_deref(), Some("Home"));
+        assert!(tracker.looked_up(env("Home")).is_empty());
+        assert!(tracker.observe(Some("Home")).is_empty());
+        assert_eq!(tracker.take_lookup(), None);
+
+        assert_eq!(tracker.observe(Some("Cafe")), vec![(HookEvent::Disconnect, env("Home"))]);
+        assert_eq!(tracker.take_lookup().as_deref(), Some("Cafe"));
+        assert_eq!(tracker.looked_up(env("Cafe")), vec![(HookEvent::Connect, env("Cafe"))]);
+        assert!(tracker.looked_up(env("Cafe")).is_empty());
+        assert_eq!(tracker.portal(), Some((HookEvent::Portal, env("Cafe"))));
+        assert_eq!(tracker.observe(None), vec![(HookEvent::Disconnect, env("Cafe"))]);
+        assert_eq!(tracker.portal(), None);
+        assert_eq!(tracker.observe(None), Vec::new());
+
+        // The lookup for a network that already went away fires nothing.
+        tracker.observe(Some("Lab"));
+        tracker.observe(None);
+        assert!(tracker.looked_up(env("Lab")).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::models::ImportedProfile;
+use std::collections::HashMap;
+
+/// Reads Wi‑Fi profiles out of a dropped file: a NetworkManager keyfile
+/// (`.nmconnection`) or the `network={...}` blocks of a `wpa_supplicant.conf`.
+pub fn parse(contents: &str) -> Result<Vec<ImportedProfile>, String> {
+    let profiles = if contents.contains("network={") || contents.contains("network ={") {
+        parse_wpa_supplicant(contents)?
+    } else if contents.lines().any(|line| line.trim() == "[connection]") {
+        vec![parse_keyfile(contents)?]
+    } else {
+        return Err("Not a NetworkManager keyfile or wpa_supplicant.conf".to_string());
+    };
+    if profiles.is_empty() {
+        return Err("The file contains no Wi‑Fi networks".to_string());
+    }
+    Ok(profiles)
+}
+
+/// Short description of how the profile is secured, for the preview.
+pub fn security_label(profile: &ImportedProfile) -> &'static str {
+    match profile.key_mgmt.as_deref() {
+        None => "Open",
+        Some("none") => "WEP",
+        Some("sae") => "WPA3",
+        Some("owe") => "Enhanced Open",
+        Some(_) => "WPA/WPA2",
+    }
+}
+
+fn parse_keyfile(contents: &str) -> Result<ImportedProfile, String> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = String::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = name.to_string();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    let get = |section: &str, key: &str| sections.get(section).and_then(|values| values.get(key));
+
+    let kind = get("connection", "type").map(String::as_str);
+    if !matches!(kind, Some("wifi" | "802-11-wireless")) {
+        return Err(format!(
+            "Only Wi‑Fi profiles can be imported (this one is {})",
+            kind.unwrap_or("untyped")
+        ));
+    }
+    let ssid = get("wifi", "ssid")
+        .or_else(|| get("802-11-wireless", "ssid"))
+        .map(|ssid| keyfile_ssid(ssid))
+        .filter(|ssid| !ssid.is_empty())
+        .ok_or_else(|| "The profile has no SSID".to_string())?;
+    let security = ["wifi-security", "802-11-wireless-security"]
+        .into_iter()
+        .find_map(|name| sections.get(name));
+    let key_mgmt = security.and_then(|security| security.get("key-mgmt")).cloned();
+    if key_mgmt.as_deref().is_some_and(is_enterprise) {
+        return Err("Enterprise (802.1X) profiles cannot be imported".to_string());
+    }
+    let password = security
+        .and_then(|security| security.get("psk").or_else(|| security.get("wep-key0")))
+        .cloned();
+    let flag = |section: &str, key: &str, default: bool| {
+        get(section, key).map_or(default, |value| value == "true")
+    };
+    Ok(ImportedProfile {
+        id: get("connection", "id").cloned().unwrap_or_else(|| ssid.clone()),
+        hidden: flag("wifi", "hidden", false) || flag("802-11-wireless", "hidden", false),
+        autoconnect: flag("connection", "autoconnect", true),
+        ssid,
+        key_mgmt,
+        password,
+    })
+}
+
+/// Keyfiles written by older NetworkManager versions store the SSID as a
+/// `;`-separated byte list.
+fn keyfile_ssid(value: &str) -> String {
+    let bytes: Option<Vec<u8>> = value
+        .strip_suffix(';')
+        .filter(|list| list.contains(';'))
+        .map(|list| list.split(';').map(|byte| byte.trim().parse().ok()).collect())
+        .unwrap_or(None);
+    match bytes {
+        Some(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+        None => value.to_string(),
+    }
+}
+
+fn parse_wpa_supplicant(contents: &str) -> Result<Vec<ImportedProfile>, String> {
+    let mut profiles = Vec::new();
+    let mut block: Option<HashMap<String, String>> = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("network") && line.ends_with('{') {
+            block = Some(HashMap::new());
+            continue;
+        }
+        if line == "}" {
+            if let Some(fields) = block.take() {
+                profiles.push(wpa_network(&fields)?);
+            }
+            continue;
+        }
+        if let (Some(fields), Some((key, value))) = (block.as_mut(), line.split_once('=')) {
+            fields.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    Ok(profiles)
+}
+
+fn wpa_network(fields: &HashMap<String, String>) -> Result<ImportedProfile, String> {
+    let ssid = fields
+        .get("ssid")
+        .and_then(|ssid| wpa_string(ssid))
+        .filter(|ssid| !ssid.is_empty())
+        .ok_or_else(|| "A network block has no SSID".to_string())?;
+    // Unquoted keys are hex (a raw PSK or WEP key), which NetworkManager takes as-is.
+    let key = |name: &str| {
+        fields.get(name).map(|value| {
+            let quoted = value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"'));
+            quoted.unwrap_or(value).to_string()
+        })
+    };
+    let psk = key("psk");
+    let wep = key("wep_key0");
+    let declared = fields.get("key_mgmt").map(|value| value.to_uppercase());
+    let key_mgmt = match declared.as_deref() {
+        Some(value) if value.split_whitespace().any(is_enterprise) => {
+            return Err(format!("{ssid} uses enterprise (802.1X) login, which cannot be imported"));
+        }
+        Some(value) if value.contains("WPA-PSK") => Some("wpa-psk"),
+        Some(value) if value.contains("SAE") => Some("sae"),
+        Some(value) if value.contains("OWE") => Some("owe"),
+        Some("NONE") if wep.is_some() => Some("none"),
+        Some("NONE") => None,
+        _ if psk.is_some() => Some("wpa-psk"),
+        _ => None,
+    };
+    let password = match key_mgmt {
+        Some("none") => wep,
+        Some("wpa-psk" | "sae") => psk,
+        _ => None,
+    };
+    Ok(ImportedProfile {
+        id: ssid.clone(),
+        hidden: fields.get("scan_ssid").is_some_and(|value| value == "1"),
+        autoconnect: fields.get("disabled").is_none_or(|value| value != "1"),
+        key_mgmt: key_mgmt.map(str::to_string),
+        password,
+        ssid,
+    })
+}
+
+/// wpa_supplicant SSIDs are either quoted text or unquoted hex bytes.
+fn wpa_string(value: &str) -> Option<String> {
+    if let Some(text) = value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        return Some(text.to_string());
+    }
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes: Option<Vec<u8>> = (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect();
+    bytes.map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+}
+
+fn is_enterprise(key_mgmt: &str) -> bool {
+    matches!(
+        key_mgmt.to_lowercase().as_str(),
+        "wpa-eap" | "ieee8021x" | "wpa-eap-suite-b-192" | "wpa-eap-sha256"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keyfiles_and_supplicant_blocks() {
+        let keyfile = "[connection]\nid=Home Wi-Fi\nuuid=4b9f\ntype=wifi\nautoconnect=false\n\n\
+                       [wifi]\nmode=infrastructure\nssid=72;111;109;101;\nhidden=true\n\n\
+                       [wifi-security]\nkey-mgmt=sae\npsk=correct horse\n\n[ipv4]\nmethod=auto\n";
+        let home = parse(keyfile).expect("keyfile");
+        assert_eq!(
+            home,
+            [ImportedProfile {
+                id: "Home Wi-Fi".to_string(),
+                ssid: "Home".to_string(),
+                key_mgmt: Some("sae".to_string()),
+                password: Some("correct horse".to_string()),
+                hidden: true,
+                autoconnect: false,
+            }]
+        );
+        assert!(parse("[connection]\nid=Wired\ntype=ethernet\n").is_err());
+
+        let supplicant = "ctrl_interface=/run/wpa_supplicant\n\
+                          network={\n\tssid=\"Office\"\n\tpsk=\"hunter22\"\n}\n\
+                          network={\n\tssid=436166c3a9\n\tkey_mgmt=NONE\n\tscan_ssid=1\n\tdisabled=1\n}\n\
+                          network={\n\tssid=\"Legacy\"\n\tkey_mgmt=NONE\n\twep_key0=0123456789\n}\n";
+        let networks = parse(supplicant).expect("supplicant");
+        assert_eq!(networks.len(), 3);
+        assert_eq!(networks[0].key_mgmt.as_deref(), Some("wpa-psk"));
+        assert_eq!(networks[0].password.as_deref(), Some("hunter22"));
+        assert_eq!(networks[1].ssid, "Café");
+        assert_eq!(security_label(&networks[1]), "Open");
+        assert!(networks[1].hidden && !networks[1].autoconnect);
+        assert_eq!(security_label(&networks[2]), "WEP");
+        assert_eq!(networks[2].password.as_deref(), Some("0123456789"));
+
+        let eap = "network={\n ssid=\"Campus\"\n key_mgmt=WPA-EAP\n}\n";
+        assert!(parse(eap).is_err());
+        assert!(parse("just some text").is_err());
+    }
+}
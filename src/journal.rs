@@ -0,0 +1,130 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+const UNITS: &[&str] = &["NetworkManager.service", "wpa_supplicant.service"];
+/// Entries shown from before the viewer was opened.
+const BACKLOG_LINES: u32 = 200;
+const FAILURE_HINTS: &[&str] = &["fail", "error", "timeout", "timed out", "denied", "reason="];
+
+/// `journalctl -f` over the NetworkManager and wpa_supplicant units, with
+/// lines that mention a different interface filtered out.
+pub struct JournalStream {
+    child: Child,
+}
+
+impl JournalStream {
+    pub fn spawn(interface: Option<String>) -> io::Result<(Self, async_channel::Receiver<String>)> {
+        let mut child = Command::new("journalctl")
+            .args(journalctl_args())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (tx, rx) = async_channel::unbounded();
+
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            forward_lines(stdout, move |line| {
+                let keep = interface.as_deref().is_none_or(|interface| is_relevant(&line, interface));
+                !keep || tx.send_blocking(line).is_ok()
+            });
+        }
+        // journalctl explains missing permissions on stderr; pass that through as-is.
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, move |line| tx.send_blocking(line).is_ok());
+        }
+        Ok((Self { child }, rx))
+    }
+
+    pub fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for JournalStream {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn forward_lines(source: impl Read + Send + 'static, mut send: impl FnMut(String) -> bool + Send + 'static) {
+    thread::spawn(move || {
+        for line in BufReader::new(source).lines().map_while(Result::ok) {
+            if !send(line) {
+                break;
+            }
+        }
+    });
+}
+
+fn journalctl_args() -> Vec<String> {
+    let mut args = vec![
+        "--follow".to_string(),
+        "--no-pager".to_string(),
+        "--output=short-iso".to_string(),
+        format!("--lines={BACKLOG_LINES}"),
+    ];
+    for unit in UNITS {
+        args.push(format!("--unit={unit}"));
+    }
+    args
+}
+
+/// Keeps lines that name `interface` as a whole word, plus lines that name no
+/// interface-looking token at all (daemon-wide messages like supplicant restarts).
+pub fn is_relevant(line: &str, interface: &str) -> bool {
+    let mut mentions_other = false;
+    for word in line.split(|ch: char| !(ch.is_alphanumeric() || ch == '_')) {
+        if word == interface {
+            return true;
+        }
+        if looks_like_interface(word) {
+            mentions_other = true;
+        }
+    }
+    !mentions_other
+}
+
+fn looks_like_interface(word: &str) -> bool {
+    const NUMBERED: &[&str] = &["wlan", "wlp", "eth", "enp", "eno", "ens"];
+    const BY_MAC: &[&str] = &["wlx", "enx"];
+    NUMBERED.iter().any(|prefix| {
+        word.strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with(|ch: char| ch.is_ascii_digit()))
+    }) || BY_MAC.iter().any(|prefix| {
+        word.strip_prefix(prefix)
+            .is_some_and(|rest| rest.len() == 12 && rest.chars().all(|ch| ch.is_ascii_hexdigit()))
+    })
+}
+
+/// Lines likely to explain a failed association, highlighted in the viewer.
+pub fn is_failure(line: &str) -> bool {
+    let lowered = line.to_lowercase();
+    FAILURE_HINTS.iter().any(|hint| lowered.contains(hint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_lines_to_interface() {
+        let nm = "2024-05-01T10:00:00+0200 host NetworkManager[812]: <info>  [1714550400.1] device (wlan0): state change: config -> need-auth";
+        let other = "2024-05-01T10:00:01+0200 host NetworkManager[812]: <info>  [1714550401.1] device (enp3s0): carrier: link connected";
+        let supplicant = "2024-05-01T10:00:02+0200 host wpa_supplicant[640]: wlan0: CTRL-EVENT-SSID-TEMP-DISABLED id=0 ssid=\"Home\" auth_failures=1 duration=10 reason=WRONG_KEY";
+        let general = "2024-05-01T10:00:03+0200 host NetworkManager[812]: <info>  [1714550403.1] manager: NetworkManager state is now CONNECTING";
+
+        assert!(is_relevant(nm, "wlan0"));
+        assert!(!is_relevant(other, "wlan0"));
+        assert!(is_relevant(supplicant, "wlan0"));
+        assert!(is_relevant(general, "wlan0"));
+        assert!(!is_relevant(nm, "wlp2s0"));
+        assert!(is_relevant("p2p-dev-wlan0: CTRL-EVENT-DISCONNECTED", "wlan0"));
+        assert!(is_relevant("ensure the supplicant is running", "wlan0"));
+
+        assert!(is_failure(supplicant));
+        assert!(!is_failure(general));
+    }
+}
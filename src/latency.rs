@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// Port tried when the host in Preferences names none; HTTPS is open almost everywhere.
+const DEFAULT_PORT: u16 = 443;
+/// A probe slower than this counts as lost.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Probes the loss and jitter figures are taken over.
+const WINDOW: usize = 20;
+
+/// Time to open a TCP connection to `target` (`host` or `host:port`), which
+/// works without the privileges ICMP needs. None when it failed or timed out.
+pub fn probe(target: &str) -> Option<Duration> {
+    let target = target.trim();
+    let address = target
+        .to_socket_addrs()
+        .or_else(|_| (target, DEFAULT_PORT).to_socket_addrs())
+        .ok()?
+        .next()?;
+    let started = Instant::now();
+    TcpStream::connect_timeout(&address, PROBE_TIMEOUT).ok()?;
+    Some(started.elapsed())
+}
+
+/// The last `WINDOW` probes, None for the lost ones.
+#[derive(Debug, Default)]
+pub struct LatencyWindow {
+    samples: VecDeque<Option<Duration>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub last_ms: Option<u64>,
+    pub average_ms: Option<u64>,
+    /// Mean difference between consecutive answered probes.
+    pub jitter_ms: Option<u64>,
+    pub loss_percent: u64,
+    pub probes: usize,
+}
+
+impl LatencyWindow {
+    pub fn push(&mut self, sample: Option<Duration>) {
+        self.samples.push_back(sample);
+        if self.samples.len() > WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn summary(&self) -> Option<LatencySummary> {
+        let last = *self.samples.back()?;
+        let answered: Vec<u64> = self.samples.iter().flatten().map(|rtt| rtt.as_millis() as u64).collect();
+        let lost = self.samples.len() - answered.len();
+        let average_ms = (!answered.is_empty()).then(|| answered.iter().sum::<u64>() / answered.len() as u64);
+        let jitter_ms = (answered.len() > 1).then(|| {
+            answered.windows(2).map(|pair| pair[0].abs_diff(pair[1])).sum::<u64>() / (answered.len() - 1) as u64
+        });
+        Some(LatencySummary {
+            last_ms: last.map(|rtt| rtt.as_millis() as u64),
+            average_ms,
+            jitter_ms,
+            loss_percent: (lost * 100 / self.samples.len()) as u64,
+            probes: self.samples.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_latency_jitter_and_loss() {
+        let mut window = LatencyWindow::default();
+        assert_eq!(window.summary(), None);
+        for ms in [Some(10), Some(30), None, Some(20)] {
+            window.push(ms.map(Duration::from_millis));
+        }
+        assert_eq!(
+            window.summary(),
+            Some(LatencySummary {
+                last_ms: Some(20),
+                average_ms: Some(20),
+                jitter_ms: Some(15),
+                loss_percent: 25,
+                probes: 4,
+            })
+        );
+
+        for _ in 0..WINDOW {
+            window.push(None);
+        }
+        let lost = window.summary().unwrap();
+        assert_eq!((lost.last_ms, lost.average_ms, lost.loss_percent), (None, None, 100));
+        assert_eq!(lost.probes, WINDOW);
+    }
+}
@@ -0,0 +1,59 @@
+use std::process::{Command, Stdio};
+
+/// Transmit power of `interface` in dBm, from `iw`, which asks the kernel over
+/// nl80211. None when `iw` is not installed or the driver does not report it.
+pub fn tx_power_dbm(interface: &str) -> Option<f64> {
+    let output = Command::new("iw")
+        .args(["dev", interface, "info"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_tx_power(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Reads the `txpower 22.00 dBm` line of `iw dev <interface> info`.
+fn parse_tx_power(info: &str) -> Option<f64> {
+    info.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("txpower ")?
+            .strip_suffix("dBm")?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// `"433.3 Mbit/s"`, or `"Not connected"` for a rate of zero.
+pub fn format_bitrate(kbps: u32) -> String {
+    match kbps {
+        0 => "Not connected".to_string(),
+        1..1000 => format!("{kbps} kbit/s"),
+        _ if kbps.is_multiple_of(1000) => format!("{} Mbit/s", kbps / 1000),
+        _ => format!("{:.1} Mbit/s", f64::from(kbps) / 1000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_iw_output_and_formats_rates() {
+        let info = "Interface wlan0\n\
+                    \tifindex 3\n\
+                    \taddr 02:00:00:00:01:00\n\
+                    \ttype managed\n\
+                    \tchannel 36 (5180 MHz), width: 80 MHz, center1: 5210 MHz\n\
+                    \ttxpower 22.00 dBm\n";
+        assert_eq!(parse_tx_power(info), Some(22.0));
+        assert_eq!(parse_tx_power("Interface wlan0\n\ttype managed\n"), None);
+
+        assert_eq!(format_bitrate(0), "Not connected");
+        assert_eq!(format_bitrate(54_000), "54 Mbit/s");
+        assert_eq!(format_bitrate(433_300), "433.3 Mbit/s");
+    }
+}
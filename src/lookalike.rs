@@ -0,0 +1,123 @@
+/// Latin letters that Cyrillic and Greek ones are indistinguishable from in
+/// most fonts.
+const HOMOGLYPHS: &[(char, char)] = &[
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('Н', 'H'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('Ι', 'I'),
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+    ('ο', 'o'),
+    ('ν', 'v'),
+];
+
+/// Characters that take no space on screen: zero-width spaces and joiners,
+/// the byte order mark and the bidirectional controls.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}')
+        || c.is_control()
+}
+
+/// What `ssid` looks like on screen: invisible characters and surrounding
+/// spaces dropped, homoglyphs and fullwidth forms turned into ASCII.
+pub fn skeleton(ssid: &str) -> String {
+    ssid.chars()
+        .filter(|c| !is_invisible(*c))
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' | '\u{00A0}' => ' ',
+            _ => HOMOGLYPHS
+                .iter()
+                .find(|(glyph, _)| *glyph == c)
+                .map_or(c, |(_, latin)| *latin),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The saved network `ssid` passes itself off as: one that looks the same
+/// but is spelled differently, the usual evil-twin trick.
+pub fn imitated(ssid: &str, saved: &[String]) -> Option<String> {
+    if saved.iter().any(|name| name == ssid) {
+        return None;
+    }
+    let looks = skeleton(ssid);
+    saved.iter().find(|name| skeleton(name) == looks).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::Backend;
+    use crate::backend::mock_nm::{MockAccessPoint, MockNetworkManager};
+
+    #[test]
+    fn catches_homoglyphs_invisible_characters_and_spaces() {
+        let saved = vec!["Office".to_string(), "Home".to_string()];
+        assert_eq!(imitated("Оffice", &saved).as_deref(), Some("Office"));
+        assert_eq!(imitated("Home\u{200B}", &saved).as_deref(), Some("Home"));
+        assert_eq!(imitated("Home ", &saved).as_deref(), Some("Home"));
+        assert_eq!(imitated("Ｈｏｍｅ", &saved).as_deref(), Some("Home"));
+        assert_eq!(imitated("Home", &saved), None);
+        assert_eq!(imitated("Homes", &saved), None);
+        assert_eq!(imitated("office", &saved), None);
+        assert_eq!(skeleton("\u{202E}Саfé\u{FEFF}"), "Café");
+    }
+
+    #[test]
+    fn a_trailing_space_is_flagged_as_broadcast() {
+        let Some(mock) = MockNetworkManager::start(vec![
+            MockAccessPoint::open("Home", 70),
+            MockAccessPoint::open("Home ", 80),
+        ]) else {
+            eprintln!("dbus-daemon not available, skipping mocked NetworkManager test");
+            return;
+        };
+        mock.add_saved("Home", None);
+        let state = mock.backend().load_state().expect("state");
+        let saved: Vec<String> = state
+            .networks
+            .iter()
+            .filter(|network| network.is_saved)
+            .map(|network| network.ssid.clone())
+            .collect();
+        assert_eq!(saved, ["Home"]);
+        let impostor = state
+            .networks
+            .iter()
+            .find(|network| network.ssid == "Home ")
+            .expect("both networks listed");
+        assert!(!impostor.is_saved);
+        assert_eq!(imitated(&impostor.ssid, &saved).as_deref(), Some("Home"));
+    }
+}
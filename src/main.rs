@@ -1,44 +1,189 @@
-mod backend;
-mod models;
-
-use backend::{Backend, BackendError};
-use backend::nm::NetworkManagerBackend;
+mod actions;
+mod analyzer;
+mod backup;
+mod certificate;
+mod cli;
+mod completions;
+mod connect_flow;
+mod data_usage;
+mod demo;
+mod diagnostics;
+mod hooks;
+mod import;
+mod journal;
+mod latency;
+mod link;
+mod lookalike;
+#[cfg(feature = "netlink")]
+mod netlink;
+mod oui;
+mod polkit_agent;
+mod preferences;
+mod resume;
+mod roaming;
+mod scan_clock;
+mod schedule;
+mod scan_mac;
+mod search;
+mod speed_test;
+mod storage;
+mod survey;
+mod templates;
+mod tray;
+mod troubleshoot;
+mod trust;
+mod tui;
+mod usage;
+mod worker;
+
+use analyzer::{Band, ChannelUsage};
+use yufi_core::{backend, models, profile};
+use backend::{Backend, BackendError, BackendEvent, BackendResult};
+use backend::nm::{NetworkManagerBackend, polkit};
+use backend::secret_agent::{Credentials, SecretRequest};
+use connect_flow::{Attempt, ConnectFlow, ConnectPhase, INCORRECT_PASSWORD};
+use data_usage::{TrafficTracker, format_bytes};
+use hooks::{HookEnv, HookTracker, Hooks};
 use gtk4::gdk::Display;
 use gtk4::glib::ControlFlow;
 use gtk4::glib::Propagation;
 use gtk4::prelude::*;
 use gtk4::{
-    Align, Application, ApplicationWindow, Box as GtkBox, Button, CssProvider, Dialog, Entry, Image,
-    Label, ListBox, ListBoxRow, MessageDialog, MessageType, Orientation, Overlay, ResponseType,
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, CheckButton, CssProvider, Dialog, Entry, Expander,
+    FileChooserAction, FileChooserNative, Image, Label, ListBox, ListBoxRow, MessageDialog, MessageType, Orientation, Overlay, ResponseType,
     ScrolledWindow, SearchEntry, Spinner, Switch,
 };
-use models::{AppState, Network, NetworkAction, NetworkDetails};
+use preferences::Preferences;
+use roaming::{RoamingAssistant, RoamingMode, Suggestion, WeakSpell, stronger_saved};
+use scan_clock::ScanClock;
+use schedule::{ScheduleTracker, TimeOfDay, WifiSchedule};
+use survey::{NetworkSummary, SiteSurvey};
+use tray::{Tray, TrayAction};
+use storage::AppData;
+use usage::{NetworkUsage, UsageTracker};
+use models::{AccessPointInfo, AppState, AuditFinding, BandLock, DhcpLease, DhcpLeases, EMBEDDED_CERTIFICATE, EnterpriseCertificates, ImportedProfile, RestoredProfiles, Network, VisibleAccessPoint, NetworkAction, NetworkDetails, RawSettings, RawValue, SavedProfile, SettingKind, TrafficCounters, audit_profile, duplicate_profile_groups};
 use std::cell::{Cell, RefCell};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::sync::mpsc;
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use std::thread;
-use zbus::blocking::{Connection, Proxy};
-use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use worker::{BackupFailure, Command, Worker};
 
 fn main() {
     let app = Application::builder()
         .application_id("com.yufi.app")
         .build();
 
-    app.connect_activate(build_ui);
+    for option in cli::OPTIONS {
+        app.add_main_option(
+            option.name,
+            gtk4::glib::Char::from(0),
+            gtk4::glib::OptionFlags::NONE,
+            if option.value.is_some() {
+                gtk4::glib::OptionArg::String
+            } else {
+                gtk4::glib::OptionArg::None
+            },
+            option.description,
+            option.value,
+        );
+    }
+    let read_only = Rc::new(Cell::new(false));
+    let read_only_options = read_only.clone();
+    // Kept until the app exits, which stops the demo's NetworkManager.
+    let demo = Rc::new(RefCell::new(None));
+    let demo_options = demo.clone();
+    app.connect_handle_local_options(move |app, options| {
+        if options.contains("profile") {
+            profile::enable();
+        }
+        if options.contains("connect-best") {
+            return cli::connect_best();
+        }
+        if options.contains("tui") {
+            return tui::run();
+        }
+        if let Ok(Some(shell)) = options.lookup::<String>("completions") {
+            return match completions::script(&shell) {
+                Some(script) => {
+                    print!("{script}");
+                    0
+                }
+                None => {
+                    eprintln!("yufi: no completions for {shell}; use bash, zsh or fish");
+                    1
+                }
+            };
+        }
+        if options.contains("man-page") {
+            print!("{}", completions::man_page());
+            return 0;
+        }
+        if options.contains("demo") {
+            match demo::start() {
+                Ok(started) => {
+                    // A YuFi already running would otherwise be shown instead.
+                    app.set_flags(app.flags() | gtk4::gio::ApplicationFlags::NON_UNIQUE);
+                    *demo_options.borrow_mut() = Some(started);
+                }
+                Err(err) => {
+                    eprintln!("yufi: {err}");
+                    return 1;
+                }
+            }
+        }
+        read_only_options.set(options.contains("read-only"));
+        -1
+    });
+    let read_only_actions = read_only.clone();
+    // One worker for the window and the application actions, which may run
+    // without a window.
+    let backend_worker = Rc::new(RefCell::new(None));
+    let startup_worker = backend_worker.clone();
+    app.connect_startup(move |app| {
+        let (ui_tx, ui_rx) = async_channel::unbounded::<UiEvent>();
+        let worker = Worker::spawn(ui_tx.clone());
+        actions::install(app, &worker, read_only_actions.get());
+        *startup_worker.borrow_mut() = Some((worker, ui_tx, ui_rx));
+    });
+    app.connect_activate(move |app| {
+        if let Some((worker, ui_tx, ui_rx)) = backend_worker.borrow().clone() {
+            build_ui(app, worker, ui_tx, ui_rx, read_only.get());
+        }
+    });
     app.run();
+    demo.borrow_mut().take();
+    if profile::enabled() {
+        eprint!("{}", profile::summary());
+    }
 }
 
-fn build_ui(app: &Application) {
+/// With `read_only`, for shared or managed machines, the window only shows the
+/// status: everything that connects, forgets, edits or toggles Wi‑Fi is left out.
+fn build_ui(
+    app: &Application,
+    worker: Worker,
+    ui_tx: async_channel::Sender<UiEvent>,
+    ui_rx: async_channel::Receiver<UiEvent>,
+    read_only: bool,
+) {
+    // With the tray icon the window is only hidden on close, so re-activation shows it again.
+    if let Some(window) = app.windows().first() {
+        window.present();
+        return;
+    }
     load_css();
 
-    let (ui_tx, ui_rx) = mpsc::channel::<UiEvent>();
-
     let window = ApplicationWindow::builder()
         .application(app)
-        .title("YuFi Network Manager Dashboard")
+        .title(if read_only {
+            "YuFi Network Manager Dashboard (read-only)"
+        } else {
+            "YuFi Network Manager Dashboard"
+        })
         .default_width(360)
         .default_height(720)
         .build();
@@ -60,453 +205,681 @@ fn build_ui(app: &Application) {
 
     let (status_bar, status_label) = build_status();
     let status_handler = build_status_handler(&status_label);
-    let state = load_state_with_backend(&nm_backend, &status_handler);
+    let reconnect = build_reconnect_bar();
+    let initial_state = nm_backend.load_state();
+    let state = initial_state.as_ref().cloned().unwrap_or_else(|_| fallback_state());
     let state_cache = Rc::new(RefCell::new(state.clone()));
+    let tray = Tray::start(ui_tx.clone()).ok();
+    if let Err(err) = resume::watch(ui_tx.clone()) {
+        eprintln!("yufi: not watching for resume from suspend: {err}");
+    }
+    if let Some(tray) = &tray {
+        tray.update(&state);
+        window.connect_close_request(|window| {
+            window.set_visible(false);
+            Propagation::Stop
+        });
+    }
 
     let header = build_header(&state);
     let header_ref = Rc::new(header.clone());
     let search = build_search();
-    let list = build_network_list();
     let list_scroller = ScrolledWindow::new();
     list_scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
     list_scroller.set_vexpand(true);
     list_scroller.set_hexpand(true);
-    list_scroller.set_child(Some(&list));
+    // Pull to refresh: dragging or flicking the list down past its top scans.
+    let refresh_pull = header.refresh.clone();
+    list_scroller.connect_edge_overshot(move |_, position| {
+        if position == gtk4::PositionType::Top && refresh_pull.is_sensitive() {
+            refresh_pull.emit_clicked();
+        }
+    });
     let legend = build_lock_legend();
     let action_handler: Rc<RefCell<Option<ActionHandler>>> = Rc::new(RefCell::new(None));
-    let optimistic_active = Rc::new(RefCell::new(None::<String>));
-    let pending_connect = Rc::new(RefCell::new(None::<PendingConnect>));
-    let failed_connects = Rc::new(RefCell::new(HashSet::<String>::new()));
-    let filtered_state = filter_state(&state, &search.text().to_string());
-    let empty_label = empty_label_for(
-        &state,
-        &search.text().to_string(),
-        filtered_state.networks.len(),
-    );
-    populate_network_list(
-        &list,
-        &filtered_state,
-        &action_handler,
-        optimistic_active.borrow().as_deref(),
-        empty_label,
-        pending_connect
-            .borrow()
-            .as_ref()
-            .map(|pending| pending.ssid.as_str()),
-        &failed_connects.borrow(),
-    );
-    let status_container = Rc::new(StatusContainer {
-        dialog_label: Rc::new(RefCell::new(None)),
-    });
+    let flow = Rc::new(RefCell::new(ConnectFlow::new()));
+    let preferences = Rc::new(RefCell::new(Preferences::load()));
+    let data = Rc::new(RefCell::new(AppData::load()));
+    search.set_text(&preferences.borrow().list_filters);
+    let list_view = NetworkListView::new(&search, &state_cache, &flow, &action_handler, &data, &preferences, read_only);
+    list_scroller.set_child(Some(&list_view.list));
+    list_view.render();
+    let band_chips = build_band_chips(&list_view, &status_handler);
+    let dialog_errors = DialogErrors::default();
     let hidden = build_hidden_button();
+    let cleanup = build_cleanup_button();
+    let saved = build_saved_button();
+    let analyzer = build_analyzer_button();
+    let survey = build_survey_button();
+    let diagnostics = build_diagnostics_button();
+    let audit = build_audit_button();
+    let log = build_log_button();
+    let best = build_best_button();
+    let mut content: Vec<gtk4::Widget> = vec![
+        search.clone().upcast(),
+        band_chips.clone().upcast(),
+        list_scroller.clone().upcast(),
+        legend.clone().upcast(),
+        analyzer.clone().upcast(),
+        survey.clone().upcast(),
+        diagnostics.clone().upcast(),
+        audit.clone().upcast(),
+        log.clone().upcast(),
+    ];
+    if read_only {
+        header.toggle.set_visible(false);
+        header.preferences.set_visible(false);
+    } else {
+        content.extend([
+            hidden.clone().upcast(),
+            saved.clone().upcast(),
+            cleanup.clone().upcast(),
+            best.clone().upcast(),
+        ]);
+    }
+    let unavailable = build_unavailable_view(&worker, &header.toggle, content);
 
     panel.append(&header.container);
+    panel.append(&header.scan_age);
     panel.append(&search);
+    panel.append(&band_chips);
     panel.append(&status_bar);
+    panel.append(&reconnect.container);
     panel.append(&list_scroller);
+    panel.append(&unavailable.page);
     panel.append(&legend);
-    panel.append(&hidden);
+    if !read_only {
+        panel.append(&best);
+        panel.append(&hidden);
+        panel.append(&saved);
+        panel.append(&cleanup);
+    }
+    panel.append(&analyzer);
+    panel.append(&survey);
+    panel.append(&diagnostics);
+    panel.append(&audit);
+    panel.append(&log);
+
+    if let Err(err) = &initial_state {
+        report_load_error(err, &status_handler, &unavailable);
+    }
 
     root.append(&panel);
 
+    let connect = ConnectContext {
+        window: window.clone(),
+        loading: loading.clone(),
+        header: header_ref.clone(),
+        worker: worker.clone(),
+        flow: flow.clone(),
+        list_view: list_view.clone(),
+        preferences: preferences.clone(),
+        scan_clock: Rc::new(RefCell::new(ScanClock::new(backend::SCAN_COOLDOWN))),
+        scan_clock_ticking: Rc::new(Cell::new(false)),
+        read_only,
+    };
+
     wire_actions(
         &header,
-        &list,
-        &nm_backend,
-        &state_cache,
-        &failed_connects,
         &toggle_guard,
-        &window,
         &status_handler,
-        &status_container,
         &loading,
         &header_ref,
-        &ui_tx,
+        &worker,
+        &connect,
     );
 
-    let list_search = list.clone();
-    let handler_search = action_handler.clone();
-    let state_search = state_cache.clone();
-    let optimistic_search = optimistic_active.clone();
-    let pending_search = pending_connect.clone();
-    let failed_search = failed_connects.clone();
-    search.connect_changed(move |entry| {
-        let query = entry.text().to_string();
-        let state = state_search.borrow().clone();
-        let filtered = filter_state(&state, &query);
-        let empty_label = empty_label_for(&state, &query, filtered.networks.len());
-        populate_network_list(
-            &list_search,
-            &filtered,
-            &handler_search,
-            optimistic_search.borrow().as_deref(),
-            empty_label,
-            pending_search
-                .borrow()
-                .as_ref()
-                .map(|pending| pending.ssid.as_str()),
-            &failed_search.borrow(),
-        );
+    let list_view_search = list_view.clone();
+    let status_search = status_handler.clone();
+    search.connect_changed(move |search| {
+        list_view_search.render();
+        // Only the filters are remembered; the free text is a one-off search.
+        let filters = search::filter_terms(&search.text());
+        let mut preferences = list_view_search.preferences.borrow_mut();
+        if preferences.list_filters != filters {
+            preferences.list_filters = filters;
+            if let Err(err) = preferences.save() {
+                status_search(StatusKind::Error, format!("Failed to save preferences: {err}"));
+            }
+        }
     });
 
     let loading_action = loading.clone();
     let header_action = header_ref.clone();
-    let ui_tx_action = ui_tx.clone();
-    let window_action = window.clone();
-    let status_container_connect = status_container.clone();
+    let worker_action = worker.clone();
+    let connect_action = connect.clone();
+    let list_view_action = list_view.clone();
+    let status_action = status_handler.clone();
+    let nm_action = nm_backend.clone();
+    let dialog_errors_open = dialog_errors.clone();
 
     *action_handler.borrow_mut() = Some(Rc::new(move |action| {
+        // Rows offer nothing else in read-only mode, but the tray still asks to connect.
+        if connect_action.read_only && !matches!(action, RowAction::Hide(_) | RowAction::Troubleshoot(_)) {
+            return;
+        }
         match action {
-            RowAction::Connect { ssid, is_saved } => {
+            RowAction::Connect { ssid, is_saved, band } => {
                 if is_saved {
-                    let ssid_clone = ssid.clone();
-                    loading_action.start();
-                    update_loading_ui(header_action.as_ref(), &loading_action);
-                    spawn_connect_task(&ui_tx_action, ssid_clone, None, false, true);
+                    connect_action.connect(ssid, None, true, true, band);
                 } else {
-                    prompt_connect_dialog(
-                        &window_action,
-                        &ssid,
-                        &loading_action,
-                        &header_action,
-                        &ui_tx_action,
-                        &status_container_connect,
-                        false,
-                        None,
-                    );
+                    connect_action.join_new(&ssid, band);
                 }
             }
+            RowAction::EnableWifiAndConnect { ssid, band } => {
+                status_action(StatusKind::Info, format!("Turning on Wi‑Fi to connect to {ssid}…"));
+                connect_action.enable_wifi_and_connect(ssid, band);
+            }
             RowAction::Disconnect(ssid) => {
+                connect_action.flow.borrow_mut().expect_disconnect();
                 let ssid_clone = ssid.clone();
-                loading_action.start();
+                loading_action.start(Busy::Apply);
                 update_loading_ui(header_action.as_ref(), &loading_action);
-                spawn_disconnect_task(&ui_tx_action, ssid_clone);
+                worker_action.send(Command::Disconnect(ssid_clone));
+            }
+            RowAction::Forget(ssid) => {
+                let confirm = MessageDialog::builder()
+                    .transient_for(&connect_action.window)
+                    .modal(true)
+                    .message_type(MessageType::Warning)
+                    .text(format!("Forget {ssid}?"))
+                    .secondary_text("Saved credentials and settings will be removed.")
+                    .build();
+                confirm.add_button("Cancel", ResponseType::Cancel);
+                confirm.add_button("Forget", ResponseType::Accept);
+                confirm.set_default_response(ResponseType::Cancel);
+                if let Some(forget_action) = confirm.widget_for_response(ResponseType::Accept) {
+                    forget_action.add_css_class("destructive-action");
+                }
+                let connect_forget = connect_action.clone();
+                confirm.connect_response(move |dialog, response| {
+                    dialog.close();
+                    if response != ResponseType::Accept {
+                        return;
+                    }
+                    let mut flow = connect_forget.flow.borrow_mut();
+                    if flow.last_connected() == Some(ssid.as_str()) {
+                        flow.expect_disconnect();
+                    }
+                    flow.clear(&ssid);
+                    drop(flow);
+                    connect_forget.worker.send(Command::RemoveProfile(ssid.clone()));
+                    request_state_refresh(&connect_forget.worker);
+                });
+                confirm.present();
+            }
+            RowAction::Hide(ssid) => {
+                let mut data = list_view_action.data.borrow_mut();
+                data.hide(&ssid);
+                if let Err(err) = data.save() {
+                    status_action(StatusKind::Error, format!("Failed to save hidden networks: {err}"));
+                }
+                drop(data);
+                list_view_action.render();
+                status_action(
+                    StatusKind::Info,
+                    format!("Hid {ssid}. Unhide it from Preferences."),
+                );
+            }
+            RowAction::Troubleshoot(ssid) => {
+                show_troubleshoot_dialog(&connect_action.window, ssid);
+            }
+            RowAction::Open { ssid, band } => {
+                let pending_error = connect_action
+                    .flow
+                    .borrow()
+                    .has_error(&ssid)
+                    .then(|| INCORRECT_PASSWORD.to_string());
+                let state = list_view_action.state.borrow();
+                let network = state
+                    .networks
+                    .iter()
+                    .chain(&state.out_of_range)
+                    .find(|network| network.ssid == ssid)
+                    .cloned();
+                drop(state);
+                match network {
+                    Some(network) if network.is_saved && pending_error.is_none() => {
+                        show_network_details_dialog(
+                            &connect_action.window,
+                            &network,
+                            nm_action.clone(),
+                            worker_action.clone(),
+                            status_action.clone(),
+                            dialog_errors_open.clone(),
+                            &list_view_action,
+                        );
+                    }
+                    Some(network) if network.is_saved => {
+                        connect_action.prompt_password(&ssid, pending_error, true, band);
+                    }
+                    _ => {
+                        let context = connect_action.clone();
+                        let ssid_prompt = ssid.clone();
+                        connect_action.check_lookalike(&ssid, move || {
+                            context.prompt_password(&ssid_prompt, pending_error.clone(), false, band);
+                        });
+                    }
+                }
             }
         }
     }));
 
     let hidden_window = window.clone();
-    let loading_hidden = loading.clone();
-    let header_hidden = header_ref.clone();
-    let ui_tx_hidden = ui_tx.clone();
-    let status_container_action = status_container.clone();
+    let connect_hidden = connect.clone();
     hidden.connect_clicked(move |_| {
-        let loading_hidden = loading_hidden.clone();
-        let header_hidden = header_hidden.clone();
-        let status_container_dialog = status_container_action.clone();
-        let ui_tx_hidden = ui_tx_hidden.clone();
+        let connect_hidden = connect_hidden.clone();
+        let autoconnect = connect_hidden.preferences.borrow().autoconnect_new;
         show_hidden_network_dialog(
             &hidden_window,
-            move |ssid, password| {
-                loading_hidden.start();
-                update_loading_ui(header_hidden.as_ref(), &loading_hidden);
-                spawn_hidden_task(&ui_tx_hidden, ssid, password);
+            autoconnect,
+            move |ssid, password, autoconnect| {
+                connect_hidden.connect_hidden(ssid, password, autoconnect);
             },
-            (*status_container_dialog).clone(),
         );
     });
 
-    let list_rx = list.clone();
+    let preferences_window = window.clone();
+    let preferences_dialog = preferences.clone();
+    let preferences_status = status_handler.clone();
+    let preferences_list_view = list_view.clone();
+    header.preferences.connect_clicked(move |_| {
+        show_preferences_dialog(
+            &preferences_window,
+            preferences_dialog.clone(),
+            preferences_status.clone(),
+            preferences_list_view.clone(),
+        );
+    });
+
+    let reconnect_action = reconnect.clone();
+    let reconnect_connect = connect.clone();
+    reconnect.button.connect_clicked(move |_| {
+        let Some(ssid) = reconnect_action.ssid.borrow_mut().take() else {
+            return;
+        };
+        reconnect_action.hide();
+        reconnect_connect.connect(ssid, None, true, true, None);
+    });
+
+    let analyzer_window = window.clone();
+    let analyzer_worker = worker.clone();
+    analyzer.connect_clicked(move |_| {
+        show_analyzer_dialog(&analyzer_window, analyzer_worker.clone());
+    });
+
+    let best_connect = connect.clone();
+    let best_status = status_handler.clone();
+    best.connect_clicked(move |_| {
+        connect_best_available(&best_connect, &best_status);
+    });
+
+    let diagnostics_window = window.clone();
+    let diagnostics_worker = worker.clone();
+    diagnostics.connect_clicked(move |_| {
+        show_diagnostics_dialog(&diagnostics_window, diagnostics_worker.clone());
+    });
+
+    let audit_window = window.clone();
+    let audit_worker = worker.clone();
+    audit.connect_clicked(move |_| {
+        show_security_audit_dialog(&audit_window, &audit_worker);
+    });
+
+    let log_window = window.clone();
+    let log_backend = nm_backend.clone();
+    log.connect_clicked(move |_| {
+        show_journal_dialog(&log_window, &log_backend);
+    });
+
+    let survey_window = window.clone();
+    survey.connect_clicked(move |_| {
+        show_survey_dialog(&survey_window);
+    });
+
+    let saved_window = window.clone();
+    let saved_backend = nm_backend.clone();
+    let saved_data = data.clone();
+    let saved_worker = worker.clone();
+    let saved_status = status_handler.clone();
+    saved.connect_clicked(move |_| {
+        show_saved_networks_dialog(
+            &saved_window,
+            saved_backend.clone(),
+            saved_data.clone(),
+            saved_worker.clone(),
+            saved_status.clone(),
+        );
+    });
+
+    let cleanup_window = window.clone();
+    let cleanup_worker = worker.clone();
+    let cleanup_status = status_handler.clone();
+    cleanup.connect_clicked(move |_| {
+        show_duplicate_profiles_dialog(&cleanup_window, cleanup_worker.clone(), cleanup_status.clone());
+    });
+
     let toggle_rx = header.toggle.clone();
     let guard_rx = toggle_guard.clone();
-    let handler_rx = action_handler.clone();
     let status_rx = status_handler.clone();
-    let status_container_rx = status_container.clone();
     let loading_rx = loading.clone();
     let header_rx = header_ref.clone();
-    let refresh_button_rx = header.refresh.clone();
-    let spinner_rx = header.spinner.clone();
-    let refresh_overlay_rx = header.refresh_overlay.clone();
-    let window_rx = window.clone();
-    let ui_tx_rx = ui_tx.clone();
-    let ui_rx = Rc::new(RefCell::new(ui_rx));
-    let optimistic_active_rx = optimistic_active.clone();
-    let pending_connect_rx = pending_connect.clone();
-    let failed_connects_rx = failed_connects.clone();
+    let worker_rx = worker.clone();
+    let flow_rx = flow.clone();
+    let connect_rx = connect.clone();
+    let list_view_rx = list_view.clone();
     let refresh_guard = Rc::new(Cell::new(false));
     let refresh_guard_rx = refresh_guard.clone();
     let refresh_guard_signal = refresh_guard.clone();
-    let ui_tx_signal = ui_tx.clone();
-    spawn_nm_signal_listeners(&ui_tx_signal);
+    let subscribed = Cell::new(spawn_backend_event_forwarder(&nm_backend, &ui_tx));
+    let nm_backend_rx = nm_backend.clone();
+    let ui_tx_rx = ui_tx.clone();
+    let unavailable_rx = unavailable.clone();
     let state_cache_rx = state_cache.clone();
-    let search_rx = search.clone();
+    let action_handler_rx = action_handler.clone();
+    let reconnect_rx = reconnect.clone();
+    let data_rx = data.clone();
+    let dialog_errors_rx = dialog_errors.clone();
+    let mut usage_tracker = UsageTracker::default();
+    let mut traffic_tracker = TrafficTracker::default();
+    let mut hook_tracker = HookTracker::default();
+    let mut roaming_assistant = RoamingAssistant::default();
+    let mut low_signal = WeakSpell::default();
+    start_signal_poll(&connect);
+    start_traffic_poll(worker.clone());
+    start_scan_clock(&connect);
+    let connect_map = connect.clone();
+    window.connect_map(move |_| apply_scan_clock(&connect_map));
+    if !read_only {
+        start_wifi_schedule(connect.clone());
+        let wifi_off = initial_state.as_ref().is_ok_and(|state| !state.wifi_enabled);
+        if wifi_off && preferences.borrow().enable_wifi_on_start {
+            loading.start(Busy::Radio);
+            update_loading_ui(header_ref.as_ref(), &loading);
+            worker.send(Command::SetWifi(true));
+        }
+    }
 
-    gtk4::glib::timeout_add_local(Duration::from_millis(100), move || {
-        while let Ok(event) = ui_rx.borrow().try_recv() {
+    gtk4::glib::spawn_future_local(async move {
+        while let Ok(event) = ui_rx.recv().await {
             match event {
                 UiEvent::StateLoaded(result) => {
+                    let loaded = result.is_ok();
                     let state = match result {
-                        Ok(state) => state,
+                        Ok(state) => {
+                            unavailable_rx.hide();
+                            if !subscribed.get() {
+                                subscribed.set(spawn_backend_event_forwarder(&nm_backend_rx, &ui_tx_rx));
+                            }
+                            let mut data = data_rx.borrow_mut();
+                            if usage_tracker.observe(&mut data.history, &state, survey::now_secs()) {
+                                data.save()
+                                    .unwrap_or_else(|err| eprintln!("yufi: failed to save usage data: {err}"));
+                            }
+                            drop(data);
+                            let active = state
+                                .networks
+                                .iter()
+                                .find(|network| network.is_active)
+                                .map(|network| network.ssid.as_str());
+                            run_hooks(&hook_tracker.observe(active), &status_rx);
+                            if let Some(ssid) = hook_tracker.take_lookup() {
+                                worker_rx.send(Command::LookupHookEnv(ssid));
+                            }
+                            let preferences = connect_rx.preferences.borrow().clone();
+                            if preferences.roaming != RoamingMode::Off && !connect_rx.read_only {
+                                let suggestion = roaming_assistant.observe(
+                                    &state,
+                                    preferences.roaming_threshold,
+                                    preferences.roaming_delay_secs,
+                                    survey::now_secs(),
+                                );
+                                if let Some(suggestion) = suggestion {
+                                    offer_roam(&connect_rx, preferences.roaming, suggestion);
+                                }
+                            }
+                            let weak = preferences.low_signal_warning.then(|| {
+                                low_signal.observe(
+                                    &state,
+                                    preferences.low_signal_threshold,
+                                    preferences.low_signal_delay_secs,
+                                    survey::now_secs(),
+                                )
+                            });
+                            // Only from the tray; with the window open the signal icon says enough.
+                            if let Some(weak) = weak.flatten().filter(|_| !connect_rx.window.is_visible()) {
+                                let alternative = stronger_saved(&state, weak, preferences.low_signal_threshold);
+                                notify_low_signal(&connect_rx.window, weak, alternative);
+                                low_signal.handle();
+                            }
+                            state
+                        }
                         Err(err) => {
-                            status_rx(StatusKind::Error, format!("NetworkManager error: {err:?}"));
-                            fallback_state(err)
+                            // NetworkManager is up, so listen for an adapter being plugged in.
+                            if matches!(err, BackendError::NoWifiDevice) && !subscribed.get() {
+                                subscribed.set(spawn_backend_event_forwarder(&nm_backend_rx, &ui_tx_rx));
+                            }
+                            report_load_error(&err, &status_rx, &unavailable_rx);
+                            fallback_state()
                         }
                     };
                     guard_rx.set(true);
                     toggle_rx.set_active(state.wifi_enabled);
                     guard_rx.set(false);
-                    if state.networks.iter().any(|n| matches!(n.action, NetworkAction::Disconnect)) {
-                        *optimistic_active_rx.borrow_mut() = None;
-                    }
-                    let pending = pending_connect_rx.borrow().clone();
-                    if let Some(pending) = pending {
-                        let is_active = state.networks.iter().any(|network| {
-                            network.ssid == pending.ssid
-                                && matches!(network.action, NetworkAction::Disconnect)
-                        });
-                        if is_active {
-                            status_rx(StatusKind::Info, String::new());
-                            *pending_connect_rx.borrow_mut() = None;
-                            *optimistic_active_rx.borrow_mut() = None;
-                            failed_connects_rx.borrow_mut().remove(&pending.ssid);
+                    let active_ssid = state
+                        .networks
+                        .iter()
+                        .find(|network| matches!(network.action, NetworkAction::Disconnect))
+                        .map(|network| network.ssid.clone());
+                    let dropped = flow_rx
+                        .borrow_mut()
+                        .observe_active(active_ssid.as_deref(), state.wifi_enabled);
+                    let forgotten = loaded
+                        && !connect_rx.read_only
+                        && dropped
+                            .as_ref()
+                            .is_some_and(|dropped| forget_ended_connection(&connect_rx, &state, &dropped.ssid));
+                    match dropped {
+                        Some(dropped) if dropped.unexpected && !connect_rx.read_only && !forgotten => {
+                            reconnect_rx.show(&dropped.ssid)
                         }
+                        _ if active_ssid.is_some() || !state.wifi_enabled => reconnect_rx.hide(),
+                        _ => {}
                     }
-                    *state_cache_rx.borrow_mut() = state.clone();
-                    let query = search_rx.text().to_string();
-                    let filtered = filter_state(&state, &query);
-                    let empty_label = empty_label_for(&state, &query, filtered.networks.len());
-                    let pending_ssid_owned = pending_connect_rx
-                        .borrow()
-                        .as_ref()
-                        .map(|pending| pending.ssid.clone());
-                    let pending_ssid = pending_ssid_owned.as_deref();
-                    populate_network_list(
-                        &list_rx,
-                        &filtered,
-                        &handler_rx,
-                        optimistic_active_rx.borrow().as_deref(),
-                        empty_label,
-                        pending_ssid,
-                        &failed_connects_rx.borrow(),
-                    );
+                    // Wi‑Fi coming back on may join a held network by itself,
+                    // which then is never sent.
+                    if active_ssid.is_some() && flow_rx.borrow().awaiting_radio() == active_ssid.as_deref() {
+                        loading_rx.stop(Busy::Apply);
+                        update_loading_ui(header_rx.as_ref(), &loading_rx);
+                    }
+                    if active_ssid.is_some_and(|ssid| flow_rx.borrow_mut().network_active(&ssid)) {
+                        status_rx(StatusKind::Info, String::new());
+                    }
+                    connect_rx.release_held_connect(|held| {
+                        state.wifi_enabled && state.networks.iter().any(|network| network.ssid == held)
+                    });
+                    if let Some(tray) = &tray {
+                        tray.update(&state);
+                    }
+                    *state_cache_rx.borrow_mut() = state;
+                    list_view_rx.render();
                 }
                 UiEvent::ScanDone(result) => {
-                    loading_rx.stop();
+                    loading_rx.stop(Busy::Scan);
                     update_loading_ui(header_rx.as_ref(), &loading_rx);
-                    spinner_rx.stop();
-                    spinner_rx.set_visible(false);
-                    refresh_overlay_rx.set_visible(true);
-                    refresh_button_rx.set_sensitive(true);
-                    refresh_button_rx.set_visible(true);
-                    refresh_button_rx.set_opacity(1.0);
                     match result {
-        Ok(_) => status_rx(StatusKind::Info, "Scan complete".to_string()),
+        Ok(_) => {
+            connect_rx.scan_clock.borrow_mut().record(Instant::now());
+            status_rx(StatusKind::Info, "Scan complete".to_string());
+        }
+        Err(BackendError::NotAllowed(_)) => {
+            // NetworkManager scanned on its own since the last LastScan reading;
+            // count the cooldown from now so the queued scan is not refused again.
+            let mut clock = connect_rx.scan_clock.borrow_mut();
+            clock.record(Instant::now());
+            clock.queue();
+            drop(clock);
+            status_rx(StatusKind::Info, "Scan queued until NetworkManager allows the next one".to_string());
+        }
         Err(err) => {
-            status_rx(StatusKind::Error, format!("Scan failed: {}", friendly_error(&err)))
+            status_rx(StatusKind::Error, format!("Scan failed: {}", friendly_error(&err)));
+            let worker = worker_rx.clone();
+            PrivilegedOperation::new(polkit::WIFI_SCAN)
+                .retry(move || worker.send(Command::Scan))
+                .explain(&connect_rx.window, &err);
         }
     }
+                    apply_scan_clock(&connect_rx);
                     // Updates should arrive via D-Bus signals.
                 }
+                UiEvent::LastScan(age) => {
+                    let at = age.and_then(|age| Instant::now().checked_sub(age));
+                    if let Some(at) = at {
+                        connect_rx.scan_clock.borrow_mut().record(at);
+                        apply_scan_clock(&connect_rx);
+                    }
+                }
                 UiEvent::WifiSet { enabled, result } => {
-                    loading_rx.stop();
+                    loading_rx.stop(Busy::Radio);
+                    // A connection held for the radio is dropped once it stays off.
+                    let radio_off = !enabled || (result.is_err() && !matches!(result, Err(BackendError::Superseded)));
+                    let held = if radio_off { flow_rx.borrow_mut().radio_ready() } else { None };
+                    if let Some(attempt) = held {
+                        loading_rx.stop(Busy::Apply);
+                        flow_rx.borrow_mut().clear(&attempt.ssid);
+                        list_view_rx.render();
+                    }
                     update_loading_ui(header_rx.as_ref(), &loading_rx);
+                    apply_scan_clock(&connect_rx);
                     let is_err = result.is_err();
                     match result {
                         Ok(_) => {
                             let label = if enabled { "Wi‑Fi enabled" } else { "Wi‑Fi disabled" };
                             status_rx(StatusKind::Success, label.to_string());
                         }
+                        Err(BackendError::Superseded) => {
+                            status_rx(StatusKind::Info, "Wi‑Fi switch superseded".to_string());
+                        }
                         Err(err) => {
                             status_rx(
                                 StatusKind::Error,
                                 format!("Failed to set Wi‑Fi: {}", friendly_error(&err)),
                             );
+                            let connect = connect_rx.clone();
+                            PrivilegedOperation::new(polkit::ENABLE_DISABLE_WIFI)
+                                .retry(move || {
+                                    connect.loading.start(Busy::Radio);
+                                    update_loading_ui(connect.header.as_ref(), &connect.loading);
+                                    connect.worker.send(Command::SetWifi(enabled));
+                                })
+                                .explain(&connect_rx.window, &err);
                         }
                     }
                     if is_err {
-                        request_state_refresh(&ui_tx_rx);
+                        request_state_refresh(&worker_rx);
                     }
                 }
-                UiEvent::ConnectDone { ssid, result, from_password, was_saved } => {
-                    loading_rx.stop();
+                UiEvent::ConnectDone { ssid, result, from_password } => {
+                    loading_rx.stop(Busy::Apply);
                     update_loading_ui(header_rx.as_ref(), &loading_rx);
+                    apply_scan_clock(&connect_rx);
                     match result {
                         Ok(active_path) => {
-                            *pending_connect_rx.borrow_mut() = Some(PendingConnect {
-                                ssid: ssid.clone(),
-                                was_saved,
-                                from_password,
-                            });
                             status_rx(StatusKind::Info, String::new());
                             if let Some(path) = active_path {
-                                spawn_active_connection_listener(&ui_tx_rx, ssid.clone(), path);
+                                flow_rx.borrow_mut().track(&ssid, path.clone());
+                                worker_rx.send(Command::ProbeActive(path));
                             } else {
-                                request_state_refresh(&ui_tx_rx);
+                                request_state_refresh(&worker_rx);
                             }
                         }
+                        Err(BackendError::Superseded) => superseded_activation(&connect_rx, &status_rx, &ssid),
                         Err(err) => {
-                            *optimistic_active_rx.borrow_mut() = None;
-                            *pending_connect_rx.borrow_mut() = None;
-                            if !from_password && needs_password(&err) {
-                                let loading_retry = loading_rx.clone();
-                                let header_retry = header_rx.clone();
-                                let ui_tx_retry = ui_tx_rx.clone();
-                                let ssid_retry = ssid.clone();
-                                let status_container_retry = status_container_rx.clone();
-                                show_password_dialog(
-                                    &window_rx,
-                                    &ssid,
-                                    None,
-                                    move |password| {
-                                        loading_retry.start();
-                                        update_loading_ui(header_retry.as_ref(), &loading_retry);
-                                        spawn_connect_task(
-                                            &ui_tx_retry,
-                                            ssid_retry.clone(),
-                                            password.clone(),
-                                            password.is_some(),
-                                            true,
-                                        );
-                                    },
-                                    (*status_container_retry).clone(),
-                                );
-                            } else {
-                                let message = connect_error_message(&err, from_password);
-                                status_rx(
-                                    StatusKind::Error,
-                                    format!("Connect failed: {message}"),
-                                );
-                                if from_password {
-                                    let loading_retry = loading_rx.clone();
-                                    let header_retry = header_rx.clone();
-                                    let ui_tx_retry = ui_tx_rx.clone();
-                                    let ssid_retry = ssid.clone();
-                                    let ssid_label = ssid.clone();
-                                    let status_container_retry = status_container_rx.clone();
-                                    show_password_dialog(
-                                        &window_rx,
-                                        &ssid_label,
-                                        Some(message),
-                                        move |password| {
-                                            loading_retry.start();
-                                            update_loading_ui(header_retry.as_ref(), &loading_retry);
-                                            spawn_connect_task(
-                                                &ui_tx_retry,
-                                                ssid_retry.clone(),
-                                                password.clone(),
-                                                password.is_some(),
-                                                true,
-                                            );
-                                        },
-                                        (*status_container_retry).clone(),
-                                    );
-                                }
+                            let message = connect_error_message(&err, from_password);
+                            let transition =
+                                flow_rx
+                                    .borrow_mut()
+                                    .request_failed(&ssid, needs_password(&err), message);
+                            if transition.changed {
+                                render_connect_phase(&connect_rx, &status_rx);
                             }
+                            explain_permission_error(&connect_rx.window, &err, polkit::NETWORK_CONTROL);
                         }
                     }
                 }
                 UiEvent::DisconnectDone { ssid, result } => {
-                    loading_rx.stop();
+                    loading_rx.stop(Busy::Apply);
                     update_loading_ui(header_rx.as_ref(), &loading_rx);
+                    apply_scan_clock(&connect_rx);
                     match result {
                         Ok(_) => status_rx(StatusKind::Success, format!("Disconnected from {ssid}")),
-                        Err(err) => status_rx(
-                            StatusKind::Error,
-                            format!("Disconnect failed: {}", friendly_error(&err)),
-                        ),
+                        Err(err) => {
+                            status_rx(
+                                StatusKind::Error,
+                                format!("Disconnect failed: {}", friendly_error(&err)),
+                            );
+                            let handler = action_handler_rx.clone();
+                            let ssid = ssid.clone();
+                            PrivilegedOperation::new(polkit::NETWORK_CONTROL)
+                                .retry(move || invoke_action(&handler, RowAction::Disconnect(ssid.clone())))
+                                .explain(&connect_rx.window, &err);
+                        }
                     }
-                    *optimistic_active_rx.borrow_mut() = None;
-                    *pending_connect_rx.borrow_mut() = None;
-                    failed_connects_rx.borrow_mut().remove(&ssid);
+                    flow_rx.borrow_mut().clear(&ssid);
+                    list_view_rx.render();
                     // Updates should arrive via D-Bus signals.
                 }
                 UiEvent::HiddenDone { ssid, result } => {
-                    loading_rx.stop();
+                    loading_rx.stop(Busy::Apply);
                     update_loading_ui(header_rx.as_ref(), &loading_rx);
+                    apply_scan_clock(&connect_rx);
                     match result {
                         Ok(active_path) => {
-                            *pending_connect_rx.borrow_mut() = Some(PendingConnect {
-                                ssid: ssid.clone(),
-                                was_saved: false,
-                                from_password: true,
-                            });
                             status_rx(StatusKind::Info, String::new());
                             if let Some(path) = active_path {
-                                spawn_active_connection_listener(&ui_tx_rx, ssid.clone(), path);
+                                flow_rx.borrow_mut().track(&ssid, path.clone());
+                                worker_rx.send(Command::ProbeActive(path));
                             } else {
-                                request_state_refresh(&ui_tx_rx);
+                                request_state_refresh(&worker_rx);
                             }
                         }
+                        Err(BackendError::Superseded) => superseded_activation(&connect_rx, &status_rx, &ssid),
                         Err(err) => {
-                            status_rx(
-                                StatusKind::Error,
-                                format!("Hidden connect failed: {}", friendly_error(&err)),
-                            );
+                            flow_rx.borrow_mut().fail(&ssid, friendly_error(&err));
+                            render_connect_phase(&connect_rx, &status_rx);
+                            explain_permission_error(&connect_rx.window, &err, polkit::NETWORK_CONTROL);
                         }
                     }
                 }
-                UiEvent::ActiveState { ssid, state } => {
-                    let pending = pending_connect_rx.borrow().clone();
-                    if let Some(pending) = pending {
-                        if pending.ssid != ssid {
-                            continue;
-                        }
-                        let is_secure = state_cache_rx
-                            .borrow()
-                            .networks
-                            .iter()
-                            .find(|network| network.ssid == ssid)
-                            .map(|network| network.is_secure)
-                            .unwrap_or(false);
-                        if state == 2 {
-                            status_rx(StatusKind::Info, String::new());
-                            *pending_connect_rx.borrow_mut() = None;
-                            *optimistic_active_rx.borrow_mut() = None;
-                            failed_connects_rx.borrow_mut().remove(&ssid);
-                            request_state_refresh(&ui_tx_rx);
-                        } else if state == 4 {
-                            let message = if pending.from_password || is_secure {
-                                "Incorrect password. Try again.".to_string()
-                            } else {
-                                "Failed to connect. Check signal and try again.".to_string()
-                            };
-                            status_rx(
-                                StatusKind::Error,
-                                format!("Failed to connect to {}. {message}", ssid),
-                            );
-                            *pending_connect_rx.borrow_mut() = None;
-                            *optimistic_active_rx.borrow_mut() = None;
-                            if pending.from_password || is_secure {
-                                failed_connects_rx.borrow_mut().insert(ssid.clone());
-                            }
-                            if !pending.was_saved {
-                                let ssid_cleanup = ssid.clone();
-                                spawn_task(&ui_tx_rx, move || {
-                                    let backend = NetworkManagerBackend::new();
-                                    let result = backend.forget_network(&ssid_cleanup);
-                                    UiEvent::CleanupResult { ssid: ssid_cleanup, result }
-                                });
-                            }
-                            request_state_refresh(&ui_tx_rx);
-                            if pending.from_password || is_secure {
-                                let loading_retry = loading_rx.clone();
-                                let header_retry = header_rx.clone();
-                                let ui_tx_retry = ui_tx_rx.clone();
-                                let status_container_retry = status_container_rx.clone();
-                                let ssid_retry = ssid.clone();
-                                let ssid_label = ssid.clone();
-                                let was_saved = pending.was_saved;
-                                show_password_dialog(
-                                    &window_rx,
-                                    &ssid_label,
-                                    Some("Incorrect password. Try again.".to_string()),
-                                    move |password| {
-                                        loading_retry.start();
-                                        update_loading_ui(header_retry.as_ref(), &loading_retry);
-                                        spawn_connect_task(
-                                            &ui_tx_retry,
-                                            ssid_retry.clone(),
-                                            password.clone(),
-                                            password.is_some(),
-                                            was_saved,
-                                        );
-                                    },
-                                    (*status_container_retry).clone(),
-                                );
-                            }
-                        }
+                UiEvent::ActiveState { path, state, reason }
+                | UiEvent::Backend(BackendEvent::ActiveConnectionStateChanged { path, state, reason }) => {
+                    // Answers for an activation that was replaced or already
+                    // settled find no attempt and are dropped.
+                    let ssid = flow_rx.borrow().ssid_for_path(&path);
+                    if let Some(ssid) = ssid {
+                        apply_activation_state(&connect_rx, &state_cache_rx, &status_rx, &ssid, state, reason);
+                    }
+                }
+                UiEvent::TrafficCounters(result) => {
+                    if let Ok(counters) = result {
+                        record_traffic(&connect_rx, &mut traffic_tracker, counters);
+                    }
+                }
+                UiEvent::HookEnv(env) => {
+                    let fired = hook_tracker.looked_up(env);
+                    run_hooks(&fired, &status_rx);
+                    if !connect_rx.read_only {
+                        activate_vpn_rules(&fired, &worker_rx, &data_rx.borrow());
                     }
                 }
+                UiEvent::VpnActivated { ssid, result } => match result {
+                    Ok(Some(vpn)) => status_rx(StatusKind::Info, format!("Connecting {vpn} for {ssid}")),
+                    Ok(None) => {}
+                    Err(err) => status_rx(
+                        StatusKind::Error,
+                        format!("Failed to start the VPN for {ssid}: {}", friendly_error(&err)),
+                    ),
+                },
                 UiEvent::CleanupResult { ssid, result } => {
                     if let Err(err) = result {
                         status_rx(
@@ -516,63 +889,324 @@ fn build_ui(app: &Application) {
                                 friendly_error(&err)
                             ),
                         );
+                        let worker = worker_rx.clone();
+                        PrivilegedOperation::new(polkit::SETTINGS_MODIFY_SYSTEM)
+                            .retry(move || worker.send(Command::RemoveProfile(ssid.clone())))
+                            .explain(&connect_rx.window, &err);
+                    }
+                }
+                UiEvent::Resumed => {
+                    // The list is from before the machine slept. Reload it now and scan
+                    // once the driver has had a moment to bring the radio back.
+                    request_state_refresh(&worker_rx);
+                    let scan_clock = connect_rx.scan_clock.clone();
+                    gtk4::glib::timeout_add_seconds_local_once(RESUME_SCAN_DELAY_SECS, move || {
+                        scan_clock.borrow_mut().queue();
+                    });
+                }
+                UiEvent::DialogError { dialog, message } => dialog_errors_rx.show(dialog, &message),
+                UiEvent::Tray(TrayAction::ShowWindow) => connect_rx.window.present(),
+                UiEvent::Tray(TrayAction::Connect(ssid)) => {
+                    invoke_action(&action_handler_rx, RowAction::Connect { ssid, is_saved: true, band: None });
+                }
+                UiEvent::Tray(TrayAction::Quit) => {
+                    if let Some(app) = connect_rx.window.application() {
+                        app.quit();
+                    }
+                }
+                UiEvent::Backend(BackendEvent::ConnectivityChanged(connectivity)) => {
+                    if connectivity == NM_CONNECTIVITY_PORTAL {
+                        run_hooks(hook_tracker.portal().as_slice(), &status_rx);
                     }
                 }
-                UiEvent::RefreshRequested => {
+                UiEvent::Backend(BackendEvent::AccessPointAdded(Some(network))) => {
+                    // Pops in mid-scan; the refresh once the scan ends reconciles.
+                    connect_rx.release_held_connect(|held| held == network.ssid);
+                    state_cache_rx.borrow_mut().add_found(network);
+                    list_view_rx.render();
+                }
+                UiEvent::Backend(BackendEvent::DeviceStateChanged { state, reason }) => {
+                    if flow_rx.borrow_mut().device_state(state, reason) {
+                        list_view_rx.render();
+                    }
+                }
+                UiEvent::Backend(event) => {
+                    if let BackendEvent::WifiEnabledChanged(enabled) = event {
+                        guard_rx.set(true);
+                        toggle_rx.set_active(enabled);
+                        guard_rx.set(false);
+                    }
                     if refresh_guard_rx.get() {
                         continue;
                     }
                     refresh_guard_rx.set(true);
-                    let ui_tx = ui_tx_rx.clone();
+                    let worker = worker_rx.clone();
                     let guard = refresh_guard_signal.clone();
                     gtk4::glib::timeout_add_local(Duration::from_millis(150), move || {
-                        request_state_refresh(&ui_tx);
+                        request_state_refresh(&worker);
                         guard.set(false);
                         ControlFlow::Break
                     });
                 }
             }
         }
-        ControlFlow::Continue
     });
 
+    if !read_only {
+        install_import_drop_target(&window, nm_backend.clone(), worker.clone(), status_handler.clone());
+        // Without an agent an 802.1X network missing its sign-in just times out.
+        if let Ok(requests) = nm_backend.register_secret_agent() {
+            let window = window.clone();
+            gtk4::glib::spawn_future_local(async move {
+                while let Ok(request) = requests.recv().await {
+                    show_sign_in_dialog(&window, request);
+                }
+            });
+        }
+    }
+
     window.set_child(Some(&root));
     window.present();
 }
 
+/// Lets `.nmconnection` keyfiles and `wpa_supplicant.conf` files be dropped on the
+/// window; their Wi‑Fi profiles are previewed before anything is saved.
+fn install_import_drop_target(
+    window: &ApplicationWindow,
+    backend: Rc<NetworkManagerBackend>,
+    worker: Worker,
+    status: StatusHandler,
+) {
+    let target = gtk4::DropTarget::new(gtk4::gio::File::static_type(), gtk4::gdk::DragAction::COPY);
+    let parent = window.clone();
+    target.connect_drop(move |_, value, _, _| {
+        let Some(path) = value.get::<gtk4::gio::File>().ok().and_then(|file| file.path()) else {
+            return false;
+        };
+        let name = path
+            .file_name()
+            .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| import::parse(&contents));
+        let profiles = match parsed {
+            Ok(profiles) => profiles,
+            Err(err) => {
+                status(StatusKind::Error, format!("Cannot import {name}: {err}"));
+                return false;
+            }
+        };
+        show_import_dialog(&parent, profiles, backend.clone(), worker.clone(), status.clone());
+        true
+    });
+    window.add_controller(target);
+}
+
+fn show_import_dialog(
+    parent: &ApplicationWindow,
+    profiles: Vec<ImportedProfile>,
+    backend: Rc<NetworkManagerBackend>,
+    worker: Worker,
+    status: StatusHandler,
+) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Import Networks"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some(
+        "These profiles will be saved to NetworkManager. Nothing connects until you choose a network.",
+    ));
+    intro.add_css_class("dim-label");
+    intro.set_wrap(true);
+    intro.set_halign(Align::Start);
+    box_.append(&intro);
+
+    let list = ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    for profile in &profiles {
+        let text_box = GtkBox::new(Orientation::Vertical, 2);
+        text_box.set_margin_top(6);
+        text_box.set_margin_bottom(6);
+        text_box.set_margin_start(6);
+        text_box.set_margin_end(6);
+        let name = Label::new(Some(&profile.ssid));
+        name.set_halign(Align::Start);
+        name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        name.add_css_class("yufi-network-name");
+        let mut facts = vec![import::security_label(profile)];
+        if profile.password.is_some() {
+            facts.push("password included");
+        }
+        if profile.hidden {
+            facts.push("hidden");
+        }
+        facts.push(if profile.autoconnect {
+            "connects automatically"
+        } else {
+            "manual connect"
+        });
+        let detail = Label::new(Some(&facts.join(" · ")));
+        detail.set_halign(Align::Start);
+        detail.add_css_class("yufi-legend-label");
+        text_box.append(&name);
+        text_box.append(&detail);
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&text_box));
+        list.append(&row);
+    }
+    box_.append(&list);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    actions.set_hexpand(true);
+    let cancel_button = Button::with_label("Cancel");
+    cancel_button.set_hexpand(true);
+    cancel_button.set_halign(Align::Fill);
+    let import_button = Button::with_label("Import");
+    import_button.add_css_class("yufi-primary");
+    import_button.add_css_class("suggested-action");
+    import_button.set_hexpand(true);
+    import_button.set_halign(Align::Fill);
+    actions.append(&cancel_button);
+    actions.append(&import_button);
+    box_.append(&actions);
+    content.append(&box_);
+    dialog.set_default_widget(Some(&import_button));
+
+    let dialog_cancel = dialog.clone();
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_import = dialog.clone();
+    let parent_import = parent.clone();
+    import_button.connect_clicked(move |_| {
+        let mut imported = 0;
+        for profile in &profiles {
+            if let Err(err) = backend.add_profile(profile) {
+                status(
+                    StatusKind::Error,
+                    format!("Failed to import {}: {}", profile.ssid, friendly_error(&err)),
+                );
+                explain_permission_error(&parent_import, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                break;
+            }
+            imported += 1;
+        }
+        if imported == profiles.len() {
+            status(StatusKind::Success, format!("Imported {}", count_networks(imported)));
+        }
+        dialog_import.close();
+        request_state_refresh(&worker);
+    });
+    dialog.present();
+}
+
+/// Runs the user's hook for each event; hooks.conf is re-read so edits apply without a restart.
+fn run_hooks(fired: &[(hooks::HookEvent, HookEnv)], status: &StatusHandler) {
+    if fired.is_empty() {
+        return;
+    }
+    let hooks = Hooks::load();
+    for (event, env) in fired {
+        let Some(command) = hooks.command(*event, &env.ssid) else {
+            continue;
+        };
+        if let Err(err) = hooks::run(command, *event, env) {
+            status(StatusKind::Error, format!("Failed to run {} hook: {err}", event.key()));
+        }
+    }
+}
+
+/// Brings up the VPN tied to a network that just connected. This follows the
+/// networks YuFi sees come up rather than NetworkManager's
+/// `secondary-connections`, which would need the Wi‑Fi profile rewritten.
+/// The worker reports back as `UiEvent::VpnActivated`.
+fn activate_vpn_rules(fired: &[(hooks::HookEvent, HookEnv)], worker: &Worker, data: &AppData) {
+    for (_, env) in fired.iter().filter(|(event, _)| *event == hooks::HookEvent::Connect) {
+        let Some(uuid) = data.vpn_rules.get(&env.ssid) else {
+            continue;
+        };
+        worker.send(Command::ActivateVpn {
+            ssid: env.ssid.clone(),
+            uuid: uuid.clone(),
+        });
+    }
+}
+
 #[derive(Clone)]
 struct HeaderWidgets {
     container: GtkBox,
     toggle: Switch,
+    preferences: Button,
     refresh: Button,
     spinner: Spinner,
-    refresh_overlay: Overlay,
+    /// "Updated 12 s ago", from the device's last scan.
+    scan_age: Label,
+}
+
+/// What the header spinner waits on. Each kind locks different controls,
+/// so they are counted apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Busy {
+    /// Turning the Wi‑Fi radio on or off.
+    Radio,
+    Scan,
+    /// Connecting, disconnecting or activating a hidden network.
+    Apply,
+}
+
+impl Busy {
+    /// Most telling first, for when several run at once.
+    const ALL: [Busy; 3] = [Busy::Radio, Busy::Scan, Busy::Apply];
+
+    fn tooltip(self) -> &'static str {
+        match self {
+            Busy::Radio => "Switching Wi‑Fi…",
+            Busy::Scan => "Scanning for networks…",
+            Busy::Apply => "Applying network settings…",
+        }
+    }
 }
 
 #[derive(Clone)]
 struct LoadingTracker {
-    active: Rc<Cell<u32>>,
+    active: Rc<Cell<[u32; 3]>>,
 }
 
 impl LoadingTracker {
     fn new() -> Self {
         Self {
-            active: Rc::new(Cell::new(0)),
+            active: Rc::new(Cell::new([0; 3])),
         }
     }
 
-    fn start(&self) {
-        let count = self.active.get().saturating_add(1);
-        self.active.set(count);
+    fn start(&self, kind: Busy) {
+        let mut counts = self.active.get();
+        counts[kind as usize] = counts[kind as usize].saturating_add(1);
+        self.active.set(counts);
     }
 
-    fn stop(&self) {
-        let count = self.active.get();
-        self.active.set(count.saturating_sub(1));
+    fn stop(&self, kind: Busy) {
+        let mut counts = self.active.get();
+        counts[kind as usize] = counts[kind as usize].saturating_sub(1);
+        self.active.set(counts);
     }
 
-    fn is_active(&self) -> bool {
-        self.active.get() > 0
+    fn is_busy(&self, kind: Busy) -> bool {
+        self.active.get()[kind as usize] > 0
+    }
+
+    /// The operation the header shows, if any runs.
+    fn current(&self) -> Option<Busy> {
+        Busy::ALL.into_iter().find(|kind| self.is_busy(*kind))
     }
 }
 
@@ -587,6 +1221,7 @@ fn build_header(state: &AppState) -> HeaderWidgets {
     title.set_hexpand(true);
 
     let refresh = Button::builder().icon_name("view-refresh").build();
+    refresh.set_tooltip_text(Some("Scan for networks"));
     refresh.add_css_class("yufi-icon-button");
     refresh.add_css_class("flat");
 
@@ -605,34 +1240,100 @@ fn build_header(state: &AppState) -> HeaderWidgets {
 
     let toggle = Switch::builder().active(state.wifi_enabled).build();
 
+    let preferences = Button::builder().icon_name("preferences-system-symbolic").build();
+    preferences.add_css_class("yufi-icon-button");
+    preferences.add_css_class("flat");
+    preferences.set_tooltip_text(Some("Preferences"));
+
     header.append(&title);
+    header.append(&preferences);
     header.append(&refresh_overlay);
     header.append(&toggle);
 
+    let scan_age = Label::new(None);
+    scan_age.add_css_class("yufi-scan-age");
+    scan_age.add_css_class("dim-label");
+    scan_age.set_halign(Align::Start);
+    scan_age.set_visible(false);
+
     HeaderWidgets {
         container: header,
         toggle,
+        preferences,
         refresh,
         spinner,
-        refresh_overlay,
+        scan_age,
     }
 }
 
+/// Puts the spinner over the refresh button while anything runs, saying what
+/// in its tooltip. Scanning during a radio switch or an activation is refused
+/// by NetworkManager, and switching the radio mid-scan drops the scan, so
+/// those controls are locked until the operation ends.
 fn update_loading_ui(header: &HeaderWidgets, loading: &LoadingTracker) {
-    if loading.is_active() {
+    let current = loading.current();
+    header.spinner.set_tooltip_text(current.map(Busy::tooltip));
+    if current.is_some() {
         header.spinner.start();
+        header.spinner.set_visible(true);
+        header.refresh.set_sensitive(false);
+        header.refresh.set_opacity(0.0);
     } else {
         header.spinner.stop();
+        header.spinner.set_visible(false);
+        // apply_scan_clock greys it out again while NetworkManager cools down.
+        header.refresh.set_sensitive(true);
+        header.refresh.set_opacity(1.0);
+    }
+
+    let toggle_locked = loading.is_busy(Busy::Radio) || loading.is_busy(Busy::Scan);
+    if toggle_locked {
+        header.toggle.add_css_class("yufi-busy");
+        header.toggle.set_sensitive(false);
+        header.toggle.set_tooltip_text(current.map(Busy::tooltip));
+    } else if header.toggle.has_css_class("yufi-busy") {
+        // Only undoes its own lock; the unavailable page disables it too.
+        header.toggle.remove_css_class("yufi-busy");
+        header.toggle.set_sensitive(true);
+        header.toggle.set_tooltip_text(None);
     }
 }
 
 fn build_search() -> SearchEntry {
     let search = SearchEntry::new();
     search.set_placeholder_text(Some("Search networks..."));
+    search.set_tooltip_text(Some(
+        "Filter by attribute with bssid:aa:bb, band:2.4, band:5, band:6 or sec:open/wpa2/wpa3/owe/enterprise",
+    ));
     search.add_css_class("yufi-search");
     search
 }
 
+/// Toggles above the list for each band; networks with no radio on a picked
+/// band are hidden. The pick is saved with the preferences.
+fn build_band_chips(list_view: &NetworkListView, status: &StatusHandler) -> GtkBox {
+    let chips = GtkBox::new(Orientation::Horizontal, 6);
+    chips.add_css_class("yufi-band-chips");
+    for band in Band::ALL {
+        let chip = gtk4::ToggleButton::with_label(band.label());
+        chip.add_css_class("yufi-chip");
+        chip.set_active(list_view.preferences.borrow().list_bands.contains(band));
+        let list_view = list_view.clone();
+        let status = status.clone();
+        chip.connect_toggled(move |chip| {
+            let mut preferences = list_view.preferences.borrow_mut();
+            preferences.list_bands.set(band, chip.is_active());
+            if let Err(err) = preferences.save() {
+                status(StatusKind::Error, format!("Failed to save preferences: {err}"));
+            }
+            drop(preferences);
+            list_view.render();
+        });
+        chips.append(&chip);
+    }
+    chips
+}
+
 fn build_status() -> (GtkBox, Label) {
     let status_bar = GtkBox::new(Orientation::Horizontal, 0);
     status_bar.add_css_class("yufi-status-bar");
@@ -649,28 +1350,168 @@ fn build_status() -> (GtkBox, Label) {
     (status_bar, status)
 }
 
-fn build_network_list() -> ListBox {
-    let list = ListBox::new();
-    list.add_css_class("yufi-list");
-    list.set_selection_mode(gtk4::SelectionMode::None);
-    list.set_show_separators(false);
-
-    list
+#[derive(Clone)]
+struct ReconnectBar {
+    container: GtkBox,
+    label: Label,
+    button: Button,
+    ssid: Rc<RefCell<Option<String>>>,
+}
+
+impl ReconnectBar {
+    fn show(&self, ssid: &str) {
+        self.label.set_text(&format!("Lost connection to {ssid}"));
+        *self.ssid.borrow_mut() = Some(ssid.to_string());
+        self.container.set_visible(true);
+    }
+
+    fn hide(&self) {
+        self.ssid.borrow_mut().take();
+        self.container.set_visible(false);
+    }
+}
+
+fn build_reconnect_bar() -> ReconnectBar {
+    let container = GtkBox::new(Orientation::Horizontal, 8);
+    container.add_css_class("yufi-status-bar");
+    container.set_visible(false);
+
+    let label = Label::new(None);
+    label.add_css_class("yufi-status");
+    label.add_css_class("yufi-status-error");
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+
+    let button = Button::with_label("Reconnect");
+    button.add_css_class("yufi-secondary");
+
+    container.append(&label);
+    container.append(&button);
+    ReconnectBar {
+        container,
+        label,
+        button,
+        ssid: Rc::new(RefCell::new(None)),
+    }
+}
+
+#[derive(Clone)]
+struct UnavailableView {
+    page: GtkBox,
+    title: Label,
+    detail: Label,
+    hint: Label,
+    toggle: Switch,
+    content: Rc<Vec<gtk4::Widget>>,
+    worker: Worker,
+    retry_source: Rc<RefCell<Option<gtk4::glib::SourceId>>>,
+}
+
+impl UnavailableView {
+    fn show(&self, title: &str, detail: &str, hint: &str) {
+        self.title.set_text(title);
+        self.detail.set_text(detail);
+        self.hint.set_text(hint);
+        self.page.set_visible(true);
+        self.toggle.set_sensitive(false);
+        for widget in self.content.iter() {
+            widget.set_visible(false);
+        }
+        if self.retry_source.borrow().is_some() {
+            return;
+        }
+        let worker = self.worker.clone();
+        let source = gtk4::glib::timeout_add_seconds_local(NM_RETRY_INTERVAL_SECS, move || {
+            request_state_refresh(&worker);
+            ControlFlow::Continue
+        });
+        *self.retry_source.borrow_mut() = Some(source);
+    }
+
+    fn hide(&self) {
+        if let Some(source) = self.retry_source.borrow_mut().take() {
+            source.remove();
+        }
+        if !self.page.is_visible() {
+            return;
+        }
+        self.page.set_visible(false);
+        self.toggle.set_sensitive(true);
+        for widget in self.content.iter() {
+            widget.set_visible(true);
+        }
+    }
+}
+
+fn build_unavailable_view(
+    worker: &Worker,
+    toggle: &Switch,
+    content: Vec<gtk4::Widget>,
+) -> UnavailableView {
+    let page = GtkBox::new(Orientation::Vertical, 8);
+    page.add_css_class("yufi-unavailable");
+    page.set_vexpand(true);
+    page.set_valign(Align::Center);
+    page.set_visible(false);
+
+    let icon = Image::from_icon_name("network-offline-symbolic");
+    icon.set_pixel_size(48);
+    icon.add_css_class("dim-label");
+
+    let title = Label::new(None);
+    title.add_css_class("yufi-unavailable-title");
+
+    let detail = Label::new(None);
+    detail.add_css_class("yufi-unavailable-detail");
+    detail.add_css_class("dim-label");
+    detail.set_wrap(true);
+    detail.set_justify(gtk4::Justification::Center);
+
+    let hint = Label::new(None);
+    hint.add_css_class("yufi-unavailable-detail");
+    hint.add_css_class("dim-label");
+    hint.set_wrap(true);
+    hint.set_justify(gtk4::Justification::Center);
+
+    let retry = Button::with_label("Retry");
+    retry.add_css_class("yufi-primary");
+    retry.add_css_class("suggested-action");
+    retry.set_halign(Align::Center);
+    let worker_retry = worker.clone();
+    retry.connect_clicked(move |_| {
+        request_state_refresh(&worker_retry);
+    });
+
+    page.append(&icon);
+    page.append(&title);
+    page.append(&detail);
+    page.append(&hint);
+    page.append(&retry);
+
+    UnavailableView {
+        page,
+        title,
+        detail,
+        hint,
+        toggle: toggle.clone(),
+        content: Rc::new(content),
+        worker: worker.clone(),
+        retry_source: Rc::new(RefCell::new(None)),
+    }
 }
 
 fn build_network_row(
-    network: &Network,
+    entry: &NetworkEntry,
     action_handler: &Rc<RefCell<Option<ActionHandler>>>,
-    effective_action: NetworkAction,
-    is_connecting: bool,
-    has_error: bool,
-) -> ListBoxRow {
-    let row = ListBoxRow::new();
+    read_only: bool,
+) -> GtkBox {
+    let network = &entry.network;
+    let row = GtkBox::new(Orientation::Horizontal, 0);
     row.add_css_class("yufi-row");
-    if has_error {
+    if entry.has_error {
         row.add_css_class("yufi-row-error");
     }
-    row.set_activatable(true);
     row.set_widget_name(&format!("ssid:{}", network.ssid));
 
     let container = GtkBox::new(Orientation::Vertical, 8);
@@ -682,13 +1523,16 @@ fn build_network_row(
     let top = GtkBox::new(Orientation::Horizontal, 8);
     top.set_hexpand(true);
 
-    let label = Label::new(Some(&network.ssid));
+    let band_suffix = entry.band.map(|band| format!(" ({})", band.label())).unwrap_or_default();
+    let label = Label::new(Some(&format!("{}{band_suffix}", network.ssid)));
+    if let Some(markup) = &entry.highlight {
+        label.set_markup(&format!("{markup}{band_suffix}"));
+    }
     label.add_css_class("yufi-network-name");
     label.set_halign(Align::Start);
     label.set_hexpand(true);
 
-    let icon = Image::from_icon_name(network.signal_icon);
-    icon.add_css_class("yufi-network-icon");
+    let icon = build_signal_indicator(network.strength, network.is_secure);
     let icon_row = GtkBox::new(Orientation::Horizontal, 6);
     icon_row.set_halign(Align::End);
     if network.is_saved {
@@ -696,18 +1540,23 @@ fn build_network_row(
         saved_dot.add_css_class("yufi-saved-dot");
         icon_row.append(&saved_dot);
     }
-    let lock_icon = if network.is_secure {
-        "changes-prevent-symbolic"
-    } else {
-        "changes-allow-symbolic"
-    };
-    let lock = Image::from_icon_name(lock_icon);
-    lock.add_css_class(if network.is_secure {
-        "yufi-network-lock"
-    } else {
-        "yufi-network-lock-open"
-    });
-    icon_row.append(&lock);
+    if let Some(weak) = network.weak_security() {
+        let badge = Image::from_icon_name("dialog-warning-symbolic");
+        badge.add_css_class("yufi-network-weak");
+        badge.set_tooltip_text(Some(&format!(
+            "Outdated security ({}). Open details for more.",
+            weak.label()
+        )));
+        icon_row.append(&badge);
+    }
+    if let Some(saved) = &entry.imitates {
+        let badge = Image::from_icon_name("dialog-warning-symbolic");
+        badge.add_css_class("yufi-network-weak");
+        badge.set_tooltip_text(Some(&format!(
+            "Looks like your saved network \"{saved}\" but is not it. It may be an impostor."
+        )));
+        icon_row.append(&badge);
+    }
     icon_row.append(&icon);
 
     top.append(&label);
@@ -715,17 +1564,22 @@ fn build_network_row(
 
     container.append(&top);
 
-    match effective_action {
+    let action_slot = GtkBox::new(Orientation::Vertical, 0);
+    match entry.action {
         NetworkAction::Connect => {
-            if is_connecting {
-                let loading = GtkBox::new(Orientation::Horizontal, 0);
+            if let Some(stage) = entry.connecting.as_deref() {
+                let loading = GtkBox::new(Orientation::Horizontal, 6);
                 loading.set_hexpand(true);
                 loading.set_halign(Align::Center);
                 let spinner = Spinner::new();
                 spinner.start();
                 spinner.set_tooltip_text(Some("Connecting…"));
+                let stage_label = Label::new(Some(stage));
+                stage_label.add_css_class("yufi-stage-label");
+                stage_label.add_css_class("dim-label");
                 loading.append(&spinner);
-                container.append(&loading);
+                loading.append(&stage_label);
+                action_slot.append(&loading);
             } else {
                 let button = Button::with_label("Connect");
                 button.add_css_class("yufi-primary");
@@ -734,6 +1588,7 @@ fn build_network_row(
                 button.set_halign(Align::Fill);
                 let ssid = network.ssid.clone();
                 let is_saved = network.is_saved;
+                let band = entry.band;
                 let handler = action_handler.clone();
                 button.connect_clicked(move |_| {
                     invoke_action(
@@ -741,10 +1596,11 @@ fn build_network_row(
                         RowAction::Connect {
                             ssid: ssid.clone(),
                             is_saved,
+                            band,
                         },
                     )
                 });
-                container.append(&button);
+                action_slot.append(&button);
             }
         }
         NetworkAction::Disconnect => {
@@ -758,15 +1614,308 @@ fn build_network_row(
             button.connect_clicked(move |_| {
                 invoke_action(&handler, RowAction::Disconnect(ssid.clone()))
             });
-            container.append(&button);
+            action_slot.append(&button);
+        }
+        NetworkAction::EnableWifiAndConnect => {
+            let button = Button::with_label("Turn On Wi‑Fi and Connect");
+            button.add_css_class("yufi-primary");
+            button.add_css_class("suggested-action");
+            button.set_hexpand(true);
+            button.set_halign(Align::Fill);
+            let ssid = network.ssid.clone();
+            let band = entry.band;
+            let handler = action_handler.clone();
+            button.connect_clicked(move |_| {
+                invoke_action(
+                    &handler,
+                    RowAction::EnableWifiAndConnect {
+                        ssid: ssid.clone(),
+                        band,
+                    },
+                )
+            });
+            action_slot.append(&button);
         }
         NetworkAction::None => {}
     }
+    if entry.animate_action {
+        // The Connect button and the connecting spinner fade into each other.
+        let revealer = gtk4::Revealer::new();
+        revealer.set_transition_type(gtk4::RevealerTransitionType::Crossfade);
+        revealer.set_transition_duration(ROW_TRANSITION_MS);
+        revealer.set_child(Some(&action_slot));
+        container.append(&revealer);
+        reveal_when_idle(&revealer);
+    } else if action_slot.first_child().is_some() {
+        container.append(&action_slot);
+    }
+    if let Some(slide_open) = entry.expanded {
+        container.append(&build_inline_details(entry, action_handler, read_only, slide_open));
+    }
+
+    let menu_gesture = gtk4::GestureClick::new();
+    menu_gesture.set_button(gtk4::gdk::BUTTON_SECONDARY);
+    let menu_row = row.clone();
+    let menu_handler = action_handler.clone();
+    let menu_ssid = network.ssid.clone();
+    menu_gesture.connect_pressed(move |_, _, x, y| {
+        show_row_menu(&menu_row, x, y, &menu_handler, &menu_ssid);
+    });
+    row.add_controller(menu_gesture);
 
-    row.set_child(Some(&container));
+    container.set_hexpand(true);
+    row.append(&container);
+    if network.is_saved && !read_only {
+        row.append(&build_swipe_actions(&row, &network.ssid, action_handler));
+    }
     row
 }
 
+/// What an expanded row shows under its name: signal, security and whether
+/// the network is saved, with buttons for what the details dialog is
+/// usually opened for.
+fn build_inline_details(
+    entry: &NetworkEntry,
+    action_handler: &Rc<RefCell<Option<ActionHandler>>>,
+    read_only: bool,
+    slide_open: bool,
+) -> gtk4::Revealer {
+    let network = &entry.network;
+    let details = GtkBox::new(Orientation::Vertical, 4);
+    details.add_css_class("yufi-inline-details");
+    let signal = if entry.out_of_range {
+        "Signal: not seen in the last scan".to_string()
+    } else if network.access_points.len() > 1 {
+        format!("Signal: {}% from {} access points", network.strength, network.access_points.len())
+    } else {
+        format!("Signal: {}%", network.strength)
+    };
+    let saved = match (network.is_saved, network.is_active) {
+        (true, true) => "Saved, connected",
+        (true, false) => "Saved",
+        (false, _) => "Not saved",
+    };
+    for text in [signal, format!("Security: {}", security_name(network.security)), saved.to_string()] {
+        let line = Label::new(Some(&text));
+        line.add_css_class("dim-label");
+        line.add_css_class("yufi-stage-label");
+        line.set_halign(Align::Start);
+        details.append(&line);
+    }
+
+    let buttons = GtkBox::new(Orientation::Horizontal, 6);
+    buttons.set_margin_top(4);
+    if network.is_saved {
+        let open = Button::with_label("Details");
+        open.add_css_class("yufi-secondary");
+        let handler = action_handler.clone();
+        let ssid = network.ssid.clone();
+        let band = entry.band;
+        open.connect_clicked(move |_| {
+            invoke_action(&handler, RowAction::Open { ssid: ssid.clone(), band });
+        });
+        buttons.append(&open);
+    }
+    let troubleshoot = Button::with_label("Why can't I connect?");
+    troubleshoot.add_css_class("flat");
+    let handler = action_handler.clone();
+    let ssid = network.ssid.clone();
+    troubleshoot.connect_clicked(move |_| invoke_action(&handler, RowAction::Troubleshoot(ssid.clone())));
+    buttons.append(&troubleshoot);
+    if network.is_saved && !read_only {
+        let forget = Button::with_label("Forget");
+        forget.add_css_class("destructive-action");
+        let handler = action_handler.clone();
+        let ssid = network.ssid.clone();
+        forget.connect_clicked(move |_| invoke_action(&handler, RowAction::Forget(ssid.clone())));
+        buttons.append(&forget);
+    }
+    details.append(&buttons);
+
+    let revealer = gtk4::Revealer::new();
+    revealer.set_transition_type(gtk4::RevealerTransitionType::SlideDown);
+    revealer.set_transition_duration(ROW_TRANSITION_MS);
+    revealer.set_child(Some(&details));
+    if slide_open {
+        reveal_when_idle(&revealer);
+    } else {
+        revealer.set_reveal_child(true);
+    }
+    revealer
+}
+
+/// The access point security keyword as people know it.
+fn security_name(keyword: &str) -> &str {
+    match keyword {
+        "enterprise" => "WPA Enterprise (802.1X)",
+        "wpa3" => "WPA3",
+        "owe" => "Enhanced Open (OWE)",
+        "wpa2" => "WPA2",
+        "wpa" => "WPA",
+        "wep" => "WEP",
+        "open" => "None",
+        other => other,
+    }
+}
+
+/// Details and Forget buttons for touch screens, revealed by swiping a saved
+/// network's row to the left and hidden again by swiping it to the right.
+fn build_swipe_actions(
+    row: &GtkBox,
+    ssid: &str,
+    action_handler: &Rc<RefCell<Option<ActionHandler>>>,
+) -> gtk4::Revealer {
+    let revealer = gtk4::Revealer::new();
+    revealer.set_transition_type(gtk4::RevealerTransitionType::SlideLeft);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 6);
+    actions.add_css_class("yufi-swipe-actions");
+    actions.set_valign(Align::Center);
+    actions.set_margin_end(12);
+    let details = Button::with_label("Details");
+    details.add_css_class("yufi-secondary");
+    let handler = action_handler.clone();
+    let ssid = ssid.to_string();
+    let details_ssid = ssid.clone();
+    let details_revealer = revealer.clone();
+    details.connect_clicked(move |_| {
+        details_revealer.set_reveal_child(false);
+        invoke_action(
+            &handler,
+            RowAction::Open {
+                ssid: details_ssid.clone(),
+                band: None,
+            },
+        );
+    });
+    let forget = Button::with_label("Forget");
+    forget.add_css_class("destructive-action");
+    let handler = action_handler.clone();
+    let forget_revealer = revealer.clone();
+    forget.connect_clicked(move |_| {
+        forget_revealer.set_reveal_child(false);
+        invoke_action(&handler, RowAction::Forget(ssid.clone()));
+    });
+    actions.append(&details);
+    actions.append(&forget);
+    revealer.set_child(Some(&actions));
+
+    let swipe = gtk4::GestureDrag::new();
+    swipe.set_touch_only(true);
+    let swipe_revealer = revealer.clone();
+    swipe.connect_drag_end(move |_, dx, dy| {
+        if dx.abs() >= SWIPE_DISTANCE && dx.abs() > dy.abs() {
+            swipe_revealer.set_reveal_child(dx < 0.0);
+        }
+    });
+    row.add_controller(swipe);
+    revealer
+}
+
+/// Signal strength drawn as a Wi‑Fi fan filled to the exact level, with a lock
+/// for secured networks. Drawn rather than taken from the icon theme, since
+/// not every theme ships the `network-wireless-signal-*` symbolic names.
+fn build_signal_indicator(strength: u8, secure: bool) -> gtk4::DrawingArea {
+    let indicator = gtk4::DrawingArea::new();
+    indicator.add_css_class("yufi-network-icon");
+    indicator.set_content_width(28);
+    indicator.set_content_height(18);
+    indicator.set_valign(Align::Center);
+    let security = if secure { "secured" } else { "open" };
+    indicator.set_tooltip_text(Some(&format!("Signal {strength}% · {security}")));
+    indicator.set_draw_func(move |area, cr, _width, height| {
+        let foreground = area.style_context().color();
+        draw_signal_indicator(cr, f64::from(height), strength, secure, &foreground);
+    });
+    indicator
+}
+
+fn draw_signal_indicator(
+    cr: &gtk4::cairo::Context,
+    height: f64,
+    strength: u8,
+    secure: bool,
+    foreground: &gtk4::gdk::RGBA,
+) {
+    let (r, g, b) = (
+        f64::from(foreground.red()),
+        f64::from(foreground.green()),
+        f64::from(foreground.blue()),
+    );
+    let (cx, cy) = (11.0, height - 2.0);
+    let radius = cy - 1.0;
+    let (start, end) = (-3.0 * std::f64::consts::FRAC_PI_4, -std::f64::consts::FRAC_PI_4);
+    let wedge = |radius: f64| {
+        cr.move_to(cx, cy);
+        cr.arc(cx, cy, radius, start, end);
+        cr.close_path();
+    };
+
+    cr.set_source_rgba(r, g, b, 0.2);
+    wedge(radius);
+    let _ = cr.fill();
+
+    // Weak signals are tinted like the theme's warning and error colours.
+    match strength {
+        0..=20 => cr.set_source_rgb(0.88, 0.11, 0.14),
+        21..=40 => cr.set_source_rgb(0.96, 0.6, 0.0),
+        _ => cr.set_source_rgba(r, g, b, 0.9),
+    }
+    wedge(radius * f64::from(strength.min(100)) / 100.0);
+    let _ = cr.fill();
+
+    if secure {
+        cr.set_source_rgba(r, g, b, 0.8);
+        cr.rectangle(21.0, cy - 5.0, 7.0, 5.0);
+        let _ = cr.fill();
+        cr.set_line_width(1.5);
+        cr.arc(24.5, cy - 5.0, 2.2, std::f64::consts::PI, 0.0);
+        let _ = cr.stroke();
+    }
+}
+
+fn show_row_menu(
+    row: &GtkBox,
+    x: f64,
+    y: f64,
+    action_handler: &Rc<RefCell<Option<ActionHandler>>>,
+    ssid: &str,
+) {
+    let popover = gtk4::Popover::new();
+    popover.set_parent(row);
+    popover.set_has_arrow(false);
+    popover.set_pointing_to(Some(&gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+
+    let items = GtkBox::new(Orientation::Vertical, 0);
+    let troubleshoot = Button::with_label("Why can't I connect?");
+    troubleshoot.add_css_class("flat");
+    let handler = action_handler.clone();
+    let ssid_troubleshoot = ssid.to_string();
+    let popover_troubleshoot = popover.clone();
+    troubleshoot.connect_clicked(move |_| {
+        popover_troubleshoot.popdown();
+        invoke_action(&handler, RowAction::Troubleshoot(ssid_troubleshoot.clone()));
+    });
+
+    let hide = Button::with_label("Hide this network");
+    hide.add_css_class("flat");
+    let handler = action_handler.clone();
+    let ssid = ssid.to_string();
+    let popover_hide = popover.clone();
+    hide.connect_clicked(move |_| {
+        popover_hide.popdown();
+        invoke_action(&handler, RowAction::Hide(ssid.clone()));
+    });
+    items.append(&troubleshoot);
+    items.append(&hide);
+    popover.set_child(Some(&items));
+    popover.connect_closed(|popover| {
+        let popover = popover.clone();
+        gtk4::glib::idle_add_local_once(move || popover.unparent());
+    });
+    popover.popup();
+}
+
 fn build_hidden_button() -> Button {
     let hidden = Button::with_label("Connect to Hidden Network...");
     hidden.add_css_class("yufi-footer");
@@ -774,6 +1923,65 @@ fn build_hidden_button() -> Button {
     hidden
 }
 
+fn build_cleanup_button() -> Button {
+    let cleanup = Button::with_label("Clean Up Duplicate Profiles...");
+    cleanup.add_css_class("yufi-footer");
+    cleanup.add_css_class("yufi-secondary");
+    cleanup
+}
+
+fn build_saved_button() -> Button {
+    let saved = Button::with_label("Saved Networks...");
+    saved.add_css_class("yufi-footer");
+    saved.add_css_class("yufi-secondary");
+    saved
+}
+
+fn build_diagnostics_button() -> Button {
+    let diagnostics = Button::with_label("Diagnostics...");
+    diagnostics.add_css_class("yufi-footer");
+    diagnostics.add_css_class("yufi-secondary");
+    diagnostics
+}
+
+fn build_audit_button() -> Button {
+    let audit = Button::with_label("Security Audit...");
+    audit.add_css_class("yufi-footer");
+    audit.add_css_class("yufi-secondary");
+    audit
+}
+
+fn build_log_button() -> Button {
+    let log = Button::with_label("Wi‑Fi Log...");
+    log.add_css_class("yufi-footer");
+    log.add_css_class("yufi-secondary");
+    log
+}
+
+fn build_analyzer_button() -> Button {
+    let analyzer = Button::with_label("Channel Analyzer...");
+    analyzer.add_css_class("yufi-footer");
+    analyzer.add_css_class("yufi-secondary");
+    analyzer
+}
+
+fn build_best_button() -> Button {
+    let best = Button::with_label("Connect to Best Available");
+    best.add_css_class("yufi-footer");
+    best.add_css_class("yufi-secondary");
+    best.set_tooltip_text(Some(
+        "Connect to the strongest saved network in range, honouring autoconnect priority",
+    ));
+    best
+}
+
+fn build_survey_button() -> Button {
+    let survey = Button::with_label("Site Survey...");
+    survey.add_css_class("yufi-footer");
+    survey.add_css_class("yufi-secondary");
+    survey
+}
+
 fn build_lock_legend() -> GtkBox {
     let legend = GtkBox::new(Orientation::Horizontal, 6);
     legend.add_css_class("yufi-legend");
@@ -784,13 +1992,11 @@ fn build_lock_legend() -> GtkBox {
     let saved_label = Label::new(Some("Saved"));
     saved_label.add_css_class("yufi-legend-label");
 
-    let secure_icon = Image::from_icon_name("changes-prevent-symbolic");
-    secure_icon.add_css_class("yufi-network-lock");
+    let secure_icon = build_signal_indicator(100, true);
     let secure_label = Label::new(Some("Secure"));
     secure_label.add_css_class("yufi-legend-label");
 
-    let open_icon = Image::from_icon_name("changes-allow-symbolic");
-    open_icon.add_css_class("yufi-network-lock-open");
+    let open_icon = build_signal_indicator(100, false);
     let open_label = Label::new(Some("Open"));
     open_label.add_css_class("yufi-legend-label");
 
@@ -804,88 +2010,464 @@ fn build_lock_legend() -> GtkBox {
     legend
 }
 
-fn effective_action_for(
-    state: &AppState,
-    network: &Network,
-    optimistic_active: Option<&str>,
-) -> NetworkAction {
+fn effective_action_for(state: &AppState, network: &Network) -> NetworkAction {
     if !state.wifi_enabled {
-        return NetworkAction::None;
+        return if network.is_saved { NetworkAction::EnableWifiAndConnect } else { NetworkAction::None };
+    }
+
+    network.action.clone()
+}
+
+/// One line of the network list. The list only builds widgets for the
+/// entries scrolled into view, so they hold what a row needs to be drawn.
+enum ListEntry {
+    Network(NetworkEntry),
+    Empty(&'static str),
+    OutOfRangeHeader(usize),
+}
+
+struct NetworkEntry {
+    network: Network,
+    action: NetworkAction,
+    connecting: Option<String>,
+    has_error: bool,
+    highlight: Option<String>,
+    out_of_range: bool,
+    /// Set on the per-band entries of a split network.
+    band: Option<BandLock>,
+    /// With animations on, whether the row slides in when it is first shown.
+    slide_in: Option<bool>,
+    animate_action: bool,
+    /// The saved network this unsaved one looks like.
+    imitates: Option<String>,
+    /// Set while the row is expanded in place; true until its details have slid open.
+    expanded: Option<bool>,
+}
+
+#[derive(Clone)]
+struct NetworkListView {
+    list: gtk4::ListView,
+    store: gtk4::gio::ListStore,
+    search: SearchEntry,
+    state: Rc<RefCell<AppState>>,
+    flow: Rc<RefCell<ConnectFlow>>,
+    action_handler: Rc<RefCell<Option<ActionHandler>>>,
+    data: Rc<RefCell<AppData>>,
+    out_of_range_expanded: Rc<Cell<bool>>,
+    preferences: Rc<RefCell<Preferences>>,
+    /// What the previous render showed, to tell which rows to animate.
+    rendered: Rc<RefCell<RenderedRows>>,
+    /// Set while vanished rows slide out, before the list is rebuilt.
+    collapsing: Rc<Cell<bool>>,
+    /// Rows show the networks without any buttons and do not open.
+    read_only: bool,
+    /// The row expanded in place, with `Preferences::inline_details`.
+    expanded: Rc<RefCell<Option<RowKey>>>,
+    /// Set when a row was just expanded, so only that render slides it open.
+    slide_open: Rc<Cell<bool>>,
+}
+
+/// A network row: the SSID, and the band for one entry of a split network.
+type RowKey = (String, Option<BandLock>);
+
+#[derive(Default)]
+struct RenderedRows {
+    ssids: HashSet<String>,
+    connecting: Option<String>,
+}
+
+impl NetworkListView {
+    /// A `ListView` over a store of `ListEntry`s. Its rows are recycled while
+    /// scrolling, which keeps renders cheap with a hundred access points around.
+    fn new(
+        search: &SearchEntry,
+        state: &Rc<RefCell<AppState>>,
+        flow: &Rc<RefCell<ConnectFlow>>,
+        action_handler: &Rc<RefCell<Option<ActionHandler>>>,
+        data: &Rc<RefCell<AppData>>,
+        preferences: &Rc<RefCell<Preferences>>,
+        read_only: bool,
+    ) -> Self {
+        let store = gtk4::gio::ListStore::new::<gtk4::glib::BoxedAnyObject>();
+        let factory = gtk4::SignalListItemFactory::new();
+        let list = gtk4::ListView::new(Some(gtk4::NoSelection::new(Some(store.clone()))), Some(factory.clone()));
+        list.add_css_class("yufi-list");
+        list.set_single_click_activate(true);
+        let view = Self {
+            list,
+            store,
+            search: search.clone(),
+            state: state.clone(),
+            flow: flow.clone(),
+            action_handler: action_handler.clone(),
+            data: data.clone(),
+            out_of_range_expanded: Rc::new(Cell::new(preferences.borrow().show_out_of_range)),
+            preferences: preferences.clone(),
+            rendered: Rc::new(RefCell::new(RenderedRows::default())),
+            collapsing: Rc::new(Cell::new(false)),
+            read_only,
+            expanded: Rc::new(RefCell::new(None)),
+            slide_open: Rc::new(Cell::new(false)),
+        };
+
+        let view_bind = view.clone();
+        factory.connect_bind(move |_, item| {
+            let Some(item) = item.downcast_ref::<gtk4::ListItem>() else { return };
+            let Some(entry) = item.item().and_downcast::<gtk4::glib::BoxedAnyObject>() else { return };
+            let mut entry = entry.borrow_mut::<ListEntry>();
+            item.set_activatable(!view_bind.read_only && matches!(*entry, ListEntry::Network(_)));
+            item.set_child(Some(&view_bind.build_entry(&mut entry)));
+        });
+        factory.connect_unbind(|_, item| {
+            if let Some(item) = item.downcast_ref::<gtk4::ListItem>() {
+                item.set_child(None::<&gtk4::Widget>);
+            }
+        });
+        let view_activate = view.clone();
+        view.list.connect_activate(move |_, position| {
+            let Some(entry) = view_activate.store.item(position).and_downcast::<gtk4::glib::BoxedAnyObject>() else {
+                return;
+            };
+            let (ssid, band) = match &*entry.borrow::<ListEntry>() {
+                ListEntry::Network(entry) => (entry.network.ssid.clone(), entry.band),
+                _ => return,
+            };
+            if view_activate.preferences.borrow().inline_details {
+                view_activate.toggle_expanded(ssid, band);
+            } else {
+                invoke_action(&view_activate.action_handler, RowAction::Open { ssid, band });
+            }
+        });
+        view
     }
 
-    if let Some(active) = optimistic_active {
-        if network.ssid == active {
-            return NetworkAction::Disconnect;
+    fn render(&self) {
+        if !self.collapsing.get() {
+            self.render_rows(true);
         }
-        return NetworkAction::Connect;
     }
 
-    network.action.clone()
+    /// Expands the row of `ssid` in place, collapsing whichever was before,
+    /// or collapses it when it already is.
+    fn toggle_expanded(&self, ssid: String, band: Option<BandLock>) {
+        let row = Some((ssid, band));
+        let mut expanded = self.expanded.borrow_mut();
+        let opening = *expanded != row;
+        *expanded = if opening { row } else { None };
+        drop(expanded);
+        self.slide_open.set(opening);
+        self.render();
+    }
+
+    /// Replaces the entries of the list. With animations on, rows that are
+    /// about to disappear first slide out, new rows slide in and a row that
+    /// starts or stops connecting fades between its button and spinner.
+    fn render_rows(&self, collapse_first: bool) {
+        let _span = profile::span("list population");
+        let query = search::Query::parse(&self.search.text());
+        let state = without_hidden(&self.state.borrow(), &self.data.borrow());
+        let bands = self.preferences.borrow().list_bands.clone();
+        let filtered = filter_state(&state, &query, &bands);
+        let animate = animations_enabled(&self.preferences.borrow());
+        if animate && collapse_first && self.collapse_vanished(&filtered) {
+            return;
+        }
+        let empty_label = empty_label_for(&state, &query, &bands, filtered.networks.len());
+        let flow = self.flow.borrow();
+        let rendered = RenderedRows {
+            ssids: filtered.networks.iter().map(|network| network.ssid.clone()).collect(),
+            connecting: flow.connecting_ssid().map(str::to_string),
+        };
+        let previous = self.rendered.replace(rendered);
+        let split_bands = self.preferences.borrow().split_bands;
+        let saved = saved_ssids(&self.state.borrow());
+        let mut entries = network_entries(
+            &filtered,
+            empty_label,
+            &flow,
+            &query.text,
+            split_bands,
+            animate.then_some(&previous),
+            &saved,
+        );
+        drop(flow);
+        entries.extend(out_of_range_entries(
+            &filtered.out_of_range,
+            self.out_of_range_expanded.get(),
+            state.wifi_enabled,
+        ));
+        let expanded = self.expanded.borrow();
+        let slide_open = self.slide_open.replace(false) && animate;
+        for entry in &mut entries {
+            let ListEntry::Network(entry) = entry else { continue };
+            if self.read_only {
+                entry.action = NetworkAction::None;
+            }
+            if expanded.as_ref().is_some_and(|(ssid, band)| *ssid == entry.network.ssid && *band == entry.band) {
+                entry.expanded = Some(slide_open);
+            }
+        }
+        drop(expanded);
+        let objects: Vec<gtk4::glib::BoxedAnyObject> =
+            entries.into_iter().map(gtk4::glib::BoxedAnyObject::new).collect();
+        self.store.splice(0, self.store.n_items(), &objects);
+    }
+
+    /// Builds the widget of an entry as it scrolls into view. A row animates
+    /// only the first time, not each time it is scrolled back to.
+    fn build_entry(&self, entry: &mut ListEntry) -> gtk4::Widget {
+        let entry = match entry {
+            ListEntry::Network(entry) => entry,
+            ListEntry::Empty(text) => return build_empty_label(text).upcast(),
+            ListEntry::OutOfRangeHeader(count) => return self.build_out_of_range_header(*count).upcast(),
+        };
+        let row = build_network_row(entry, &self.action_handler, self.read_only);
+        entry.animate_action = false;
+        if let Some(slide_open) = &mut entry.expanded {
+            *slide_open = false;
+        }
+        if entry.out_of_range {
+            row.set_tooltip_text(Some("Saved, but not seen in the last scan"));
+        }
+        let Some(slide_in) = entry.slide_in.replace(false) else {
+            return row.upcast();
+        };
+        let revealer = gtk4::Revealer::new();
+        revealer.set_transition_type(gtk4::RevealerTransitionType::SlideDown);
+        revealer.set_transition_duration(ROW_TRANSITION_MS);
+        revealer.set_child(Some(&row));
+        if slide_in {
+            reveal_when_idle(&revealer);
+        } else {
+            revealer.set_reveal_child(true);
+        }
+        revealer.upcast()
+    }
+
+    /// Saved networks not in range go below the list under a header that
+    /// expands them; their rows can be edited or forgotten but not connected.
+    fn build_out_of_range_header(&self, count: usize) -> Button {
+        let toggle = Button::new();
+        toggle.add_css_class("flat");
+        toggle.set_hexpand(true);
+        let header = GtkBox::new(Orientation::Horizontal, 6);
+        let arrow = Image::from_icon_name(if self.out_of_range_expanded.get() {
+            "pan-down-symbolic"
+        } else {
+            "pan-end-symbolic"
+        });
+        let title = Label::new(Some(&format!("Out of range ({count})")));
+        title.add_css_class("yufi-legend-label");
+        header.append(&arrow);
+        header.append(&title);
+        toggle.set_child(Some(&header));
+        let view = self.clone();
+        toggle.connect_clicked(move |_| {
+            let open = !view.out_of_range_expanded.get();
+            view.out_of_range_expanded.set(open);
+            let mut preferences = view.preferences.borrow_mut();
+            preferences.show_out_of_range = open;
+            // Not worth a status message; the section just opens collapsed next time.
+            let _ = preferences.save();
+            drop(preferences);
+            view.render();
+        });
+        toggle
+    }
+
+    /// Slides out the rows of networks missing from `next` and rebuilds the
+    /// list once they are gone. Returns false when no row is leaving. Only
+    /// rows scrolled into view have a widget to slide.
+    fn collapse_vanished(&self, next: &AppState) -> bool {
+        let leaving: Vec<gtk4::Revealer> = std::iter::successors(self.list.first_child(), |child| child.next_sibling())
+            .filter_map(|item| item.first_child()?.downcast::<gtk4::Revealer>().ok())
+            .filter(|revealer| {
+                revealer.child().and_then(|row| ssid_from_row(&row)).is_some_and(|ssid| {
+                    self.rendered.borrow().ssids.contains(&ssid)
+                        && !next.networks.iter().any(|network| network.ssid == ssid)
+                })
+            })
+            .collect();
+        if leaving.is_empty() {
+            return false;
+        }
+        for revealer in &leaving {
+            revealer.set_reveal_child(false);
+        }
+        self.collapsing.set(true);
+        let view = self.clone();
+        gtk4::glib::timeout_add_local_once(Duration::from_millis(ROW_TRANSITION_MS.into()), move || {
+            view.collapsing.set(false);
+            view.render_rows(false);
+        });
+        true
+    }
 }
 
-fn populate_network_list(
-    list: &ListBox,
-    state: &AppState,
-    action_handler: &Rc<RefCell<Option<ActionHandler>>>,
-    optimistic_active: Option<&str>,
-    empty_label: Option<&str>,
-    pending_ssid: Option<&str>,
-    failed_connects: &HashSet<String>,
-) {
-    while let Some(child) = list.first_child() {
-        list.remove(&child);
+/// YuFi's own "Reduce motion" preference, or the desktop's animation setting.
+fn animations_enabled(preferences: &Preferences) -> bool {
+    !preferences.reduce_motion
+        && gtk4::Settings::default().is_some_and(|settings| settings.is_gtk_enable_animations())
+}
+
+fn reveal_when_idle(revealer: &gtk4::Revealer) {
+    let revealer = revealer.clone();
+    gtk4::glib::idle_add_local_once(move || revealer.set_reveal_child(true));
+}
+
+/// The header of the out-of-range section, followed by its rows while expanded.
+/// With Wi‑Fi off every saved network is out of range, and each row offers
+/// to turn it back on.
+fn out_of_range_entries(networks: &[Network], expanded: bool, wifi_enabled: bool) -> Vec<ListEntry> {
+    if networks.is_empty() {
+        return Vec::new();
     }
+    let rows = networks.iter().filter(|_| expanded).map(|network| {
+        ListEntry::Network(NetworkEntry {
+            network: network.clone(),
+            action: if wifi_enabled { NetworkAction::None } else { NetworkAction::EnableWifiAndConnect },
+            connecting: None,
+            has_error: false,
+            highlight: None,
+            out_of_range: true,
+            band: None,
+            slide_in: None,
+            animate_action: false,
+            imitates: None,
+            expanded: None,
+        })
+    });
+    std::iter::once(ListEntry::OutOfRangeHeader(networks.len())).chain(rows).collect()
+}
 
+fn network_entries(
+    state: &AppState,
+    empty_label: Option<&'static str>,
+    flow: &ConnectFlow,
+    query: &str,
+    split_bands: bool,
+    previous: Option<&RenderedRows>,
+    saved: &[String],
+) -> Vec<ListEntry> {
     if state.networks.is_empty() {
-        if let Some(label) = empty_label {
-            list.append(&build_empty_row(label));
-        }
-        return;
+        return empty_label.map(ListEntry::Empty).into_iter().collect();
     }
 
-    for network in &state.networks {
-        let effective_action = effective_action_for(state, network, optimistic_active);
-        let is_connecting = pending_ssid == Some(network.ssid.as_str());
-        let has_error = failed_connects.contains(&network.ssid);
-        list.append(&build_network_row(
-            network,
-            action_handler,
-            effective_action,
-            is_connecting,
-            has_error,
-        ));
+    state
+        .networks
+        .iter()
+        .flat_map(|network| {
+            if split_bands {
+                analyzer::split_by_band(network)
+            } else {
+                vec![(network.clone(), None)]
+            }
+        })
+        .map(|(network, band)| {
+            let connecting = (flow.connecting_ssid() == Some(network.ssid.as_str()))
+                .then(|| flow.stage().unwrap_or("Connecting…").to_string());
+            let highlight = search::fuzzy_match(&network.ssid, query)
+                .map(|found| search::highlight_markup(&network.ssid, &found.positions));
+            let was_connecting =
+                previous.and_then(|previous| previous.connecting.as_deref()) == Some(network.ssid.as_str());
+            ListEntry::Network(NetworkEntry {
+                action: effective_action_for(state, &network),
+                animate_action: previous.is_some() && was_connecting != connecting.is_some(),
+                connecting,
+                has_error: flow.has_error(&network.ssid),
+                highlight,
+                out_of_range: false,
+                band,
+                slide_in: previous.map(|previous| !previous.ssids.contains(&network.ssid)),
+                imitates: (!network.is_saved)
+                    .then(|| lookalike::imitated(&network.ssid, saved))
+                    .flatten(),
+                expanded: None,
+                network,
+            })
+        })
+        .collect()
+}
+
+/// Every saved network, in range or not and hidden or not.
+fn saved_ssids(state: &AppState) -> Vec<String> {
+    state
+        .networks
+        .iter()
+        .chain(&state.out_of_range)
+        .filter(|network| network.is_saved)
+        .map(|network| network.ssid.clone())
+        .collect()
+}
+
+fn without_hidden(state: &AppState, data: &AppData) -> AppState {
+    AppState {
+        wifi_enabled: state.wifi_enabled,
+        networks: state
+            .networks
+            .iter()
+            .filter(|network| network.is_active || !data.is_hidden(&network.ssid))
+            .cloned()
+            .collect(),
+        out_of_range: state
+            .out_of_range
+            .iter()
+            .filter(|network| !data.is_hidden(&network.ssid))
+            .cloned()
+            .collect(),
     }
 }
 
-fn filter_state(state: &AppState, query: &str) -> AppState {
-    let query = query.trim().to_lowercase();
-    if query.is_empty() {
+/// Saved networks out of range have no radios to tell the band from, so the
+/// band chips leave them alone.
+fn filter_state(state: &AppState, query: &search::Query, bands: &search::BandFilter) -> AppState {
+    if query.is_empty() && bands.is_all() {
         return state.clone();
     }
 
-    let networks = state
+    let mut ranked: Vec<(i32, &Network)> = state
         .networks
         .iter()
-        .filter(|network| network.ssid.to_lowercase().contains(&query))
+        .filter(|network| query.matches_filters(network) && bands.matches(network))
+        .filter_map(|network| {
+            search::fuzzy_match(&network.ssid, &query.text).map(|found| (found.score, network))
+        })
+        .collect();
+    ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    let networks = ranked.into_iter().map(|(_, network)| network.clone()).collect();
+    let out_of_range = state
+        .out_of_range
+        .iter()
+        .filter(|network| query.matches_filters(network))
+        .filter(|network| search::fuzzy_match(&network.ssid, &query.text).is_some())
         .cloned()
         .collect();
 
     AppState {
         wifi_enabled: state.wifi_enabled,
         networks,
+        out_of_range,
     }
 }
 
-fn empty_label_for(state: &AppState, query: &str, filtered_len: usize) -> Option<&'static str> {
+fn empty_label_for(
+    state: &AppState,
+    query: &search::Query,
+    bands: &search::BandFilter,
+    filtered_len: usize,
+) -> Option<&'static str> {
     if !state.wifi_enabled {
         return Some("Wi-Fi is disabled");
     }
     if state.networks.is_empty() {
         return Some("No networks found");
     }
-    if !query.trim().is_empty() && filtered_len == 0 {
+    if !query.is_empty() && filtered_len == 0 {
         return Some("No matching networks");
     }
+    if !bands.is_all() && filtered_len == 0 {
+        return Some("No networks on the selected bands");
+    }
     None
 }
 
@@ -894,7 +2476,11 @@ fn build_empty_row(text: &str) -> ListBoxRow {
     row.set_activatable(false);
     row.set_selectable(false);
     row.add_css_class("yufi-empty-row");
+    row.set_child(Some(&build_empty_label(text)));
+    row
+}
 
+fn build_empty_label(text: &str) -> Label {
     let label = Label::new(Some(text));
     label.add_css_class("yufi-empty-label");
     label.add_css_class("dim-label");
@@ -903,106 +2489,48 @@ fn build_empty_row(text: &str) -> ListBoxRow {
     label.set_margin_bottom(6);
     label.set_margin_start(6);
     label.set_margin_end(6);
-
-    row.set_child(Some(&label));
-    row
+    label
 }
 
 fn wire_actions(
     header: &HeaderWidgets,
-    list: &ListBox,
-    nm_backend: &Rc<NetworkManagerBackend>,
-    state_cache: &Rc<RefCell<AppState>>,
-    failed_connects: &Rc<RefCell<HashSet<String>>>,
     toggle_guard: &Rc<Cell<bool>>,
-    parent: &ApplicationWindow,
     status: &StatusHandler,
-    status_container: &Rc<StatusContainer>,
     loading: &LoadingTracker,
     header_ref: &Rc<HeaderWidgets>,
-    ui_tx: &mpsc::Sender<UiEvent>,
+    worker: &Worker,
+    connect: &ConnectContext,
 ) {
     let status_refresh = status.clone();
-    let spinner_refresh = header_ref.spinner.clone();
-    let refresh_button = header_ref.refresh.clone();
-    let refresh_overlay = header_ref.refresh_overlay.clone();
     let loading_refresh = loading.clone();
     let header_refresh = header_ref.clone();
-    let ui_tx_refresh = ui_tx.clone();
+    let worker_refresh = worker.clone();
+    let scan_clock = connect.scan_clock.clone();
     header.refresh.connect_clicked(move |_| {
-        loading_refresh.start();
+        if !scan_clock.borrow_mut().request(Instant::now()) {
+            status_refresh(StatusKind::Info, "Scan queued until NetworkManager allows the next one".to_string());
+            return;
+        }
+        loading_refresh.start(Busy::Scan);
         update_loading_ui(header_refresh.as_ref(), &loading_refresh);
-        spinner_refresh.start();
-        refresh_button.set_sensitive(false);
-        refresh_overlay.set_visible(true);
-        refresh_button.set_opacity(0.0);
-        spinner_refresh.set_visible(true);
         status_refresh(StatusKind::Info, "Scan requested".to_string());
-        spawn_scan_task(&ui_tx_refresh);
+        worker_refresh.send(Command::Scan);
     });
 
     let guard_toggle = toggle_guard.clone();
     let loading_toggle = loading.clone();
     let header_toggle = header_ref.clone();
-    let ui_tx_toggle = ui_tx.clone();
+    let worker_toggle = worker.clone();
     header.toggle.connect_state_set(move |_switch, state| {
         if guard_toggle.get() {
             return Propagation::Proceed;
         }
 
-        loading_toggle.start();
+        loading_toggle.start(Busy::Radio);
         update_loading_ui(header_toggle.as_ref(), &loading_toggle);
-        spawn_toggle_task(&ui_tx_toggle, state);
+        worker_toggle.send(Command::SetWifi(state));
         Propagation::Proceed
     });
-
-    let nm_details = nm_backend.clone();
-    let window_details = parent.clone();
-    let status_details = status.clone();
-    let status_details_container = status_container.clone();
-    let loading_details = loading.clone();
-    let header_details = header_ref.clone();
-    let ui_tx_details = ui_tx.clone();
-    let state_details = state_cache.clone();
-    let failed_details = failed_connects.clone();
-    list.connect_row_activated(move |_list, row| {
-        if let Some(ssid) = ssid_from_row(row) {
-            let pending_error = failed_details
-                .borrow()
-                .get(&ssid)
-                .map(|_| "Incorrect password. Try again.".to_string());
-            let is_saved = state_details
-                .borrow()
-                .networks
-                .iter()
-                .find(|network| network.ssid == ssid)
-                .map(|network| network.is_saved)
-                .unwrap_or(false);
-
-            if is_saved && pending_error.is_none() {
-                show_network_details_dialog(
-                    &window_details,
-                    &ssid,
-                    nm_details.clone(),
-                    ui_tx_details.clone(),
-                    status_details.clone(),
-                    (*status_details_container).clone(),
-                    failed_details.clone(),
-                );
-            } else {
-                prompt_connect_dialog(
-                    &window_details,
-                    &ssid,
-                    &loading_details,
-                    &header_details,
-                    &ui_tx_details,
-                    &status_details_container,
-                    false,
-                    pending_error,
-                );
-            }
-        }
-    });
 }
 
 type ActionHandler = Rc<dyn Fn(RowAction)>;
@@ -1027,7 +2555,6 @@ enum UiEvent {
         ssid: String,
         result: Result<Option<String>, BackendError>,
         from_password: bool,
-        was_saved: bool,
     },
     DisconnectDone {
         ssid: String,
@@ -1037,33 +2564,58 @@ enum UiEvent {
         ssid: String,
         result: Result<Option<String>, BackendError>,
     },
+    /// The answer to `Command::ProbeActive`.
     ActiveState {
-        ssid: String,
+        path: String,
         state: u32,
+        reason: u32,
     },
     CleanupResult {
         ssid: String,
         result: Result<(), BackendError>,
     },
-    RefreshRequested,
+    /// The answer to `Command::LookupHookEnv`.
+    HookEnv(HookEnv),
+    /// The answer to `Command::TrafficCounters`.
+    TrafficCounters(Result<TrafficCounters, BackendError>),
+    /// The answer to `Command::ActivateVpn`: the VPN's name, or None when it
+    /// was already up.
+    VpnActivated {
+        ssid: String,
+        result: Result<Option<String>, BackendError>,
+    },
+    Backend(BackendEvent),
+    Tray(TrayAction),
+    /// How long ago the Wi‑Fi device last scanned, if known.
+    LastScan(Option<Duration>),
+    /// The machine woke up from suspend.
+    Resumed,
+    /// An error for the open dialog `dialog` only.
+    DialogError {
+        dialog: DialogId,
+        message: String,
+    },
 }
 
 enum RowAction {
-    Connect { ssid: String, is_saved: bool },
+    /// `band` is set when the row is one band of a split network.
+    Connect {
+        ssid: String,
+        is_saved: bool,
+        band: Option<BandLock>,
+    },
     Disconnect(String),
+    Forget(String),
+    Hide(String),
+    Troubleshoot(String),
+    EnableWifiAndConnect {
+        ssid: String,
+        band: Option<BandLock>,
+    },
+    /// A click on the row: the details of a saved network, or the password prompt.
+    Open { ssid: String, band: Option<BandLock> },
 }
 
-#[derive(Clone)]
-struct PendingConnect {
-    ssid: String,
-    was_saved: bool,
-    from_password: bool,
-}
-
-const NM_BUS_NAME: &str = "org.freedesktop.NetworkManager";
-const NM_OBJECT_PATH: &str = "/org/freedesktop/NetworkManager";
-const NM_DEVICE_TYPE_WIFI: u32 = 2;
-
 fn invoke_action(action_handler: &Rc<RefCell<Option<ActionHandler>>>, action: RowAction) {
     let handler = action_handler.borrow().clone();
     if let Some(handler) = handler {
@@ -1071,23 +2623,36 @@ fn invoke_action(action_handler: &Rc<RefCell<Option<ActionHandler>>>, action: Ro
     }
 }
 
-#[derive(Clone)]
-struct StatusContainer {
-    dialog_label: Rc<RefCell<Option<Label>>>,
-}
+/// Names an open dialog, so an answer that arrives after a while reaches the
+/// dialog that asked even if others were opened since.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct DialogId(u32);
 
-impl StatusContainer {
-    fn register_dialog_label(&self, label: &Label) {
-        *self.dialog_label.borrow_mut() = Some(label.clone());
-    }
+/// The error line of each open dialog that waits on the worker.
+#[derive(Clone, Default)]
+struct DialogErrors {
+    labels: Rc<RefCell<HashMap<DialogId, Label>>>,
+    next_id: Rc<Cell<u32>>,
+}
 
-    fn clear_dialog_label(&self) {
-        *self.dialog_label.borrow_mut() = None;
+impl DialogErrors {
+    /// Shows errors for `dialog` in `label` until the dialog closes.
+    fn open(&self, dialog: &Dialog, label: &Label) -> DialogId {
+        let id = DialogId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.labels.borrow_mut().insert(id, label.clone());
+        let labels = self.labels.clone();
+        dialog.connect_close_request(move |_| {
+            labels.borrow_mut().remove(&id);
+            Propagation::Proceed
+        });
+        id
     }
 
-    fn show_dialog_error(&self, text: String) {
-        if let Some(label) = self.dialog_label.borrow().clone() {
-            label.set_text(&text);
+    /// Does nothing once the dialog is closed.
+    fn show(&self, dialog: DialogId, text: &str) {
+        if let Some(label) = self.labels.borrow().get(&dialog) {
+            label.set_text(text);
             label.set_visible(true);
         }
     }
@@ -1128,257 +2693,367 @@ fn show_status(label: &Label, kind: StatusKind, text: &str) {
     });
 }
 
-fn spawn_task<F>(ui_tx: &mpsc::Sender<UiEvent>, task: F)
-where
-    F: FnOnce() -> UiEvent + Send + 'static,
-{
-    let tx = ui_tx.clone();
-    thread::spawn(move || {
-        let event = task();
-        let _ = tx.send(event);
-    });
+const NM_RETRY_INTERVAL_SECS: u32 = 5;
+/// Nothing reports signal changes of the active network, so the roaming
+/// assistant and the low-signal warning re-read the state this often while
+/// either needs it.
+const SIGNAL_POLL_SECS: u32 = 5;
+const LOW_SIGNAL_NOTIFICATION: &str = "low-signal";
+/// How often the details dialog re-reads the link rate of the active connection.
+const LINK_POLL_SECS: u32 = 2;
+/// Pause between latency probes while the details dialog is open.
+const LATENCY_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a connection held while Wi‑Fi turns on waits for the device to
+/// see its network.
+const WIFI_ON_TIMEOUT: Duration = Duration::from_secs(15);
+/// How often the device's byte counters are added to the active network's usage.
+const TRAFFIC_POLL_SECS: u32 = 60;
+const DATA_QUOTA_NOTIFICATION: &str = "data-quota";
+const SCHEDULE_CHECK_SECS: u32 = 30;
+const RESUME_SCAN_DELAY_SECS: u32 = 3;
+/// `Connectivity` once NetworkManager's check lands on a sign-in page.
+const NM_CONNECTIVITY_PORTAL: u32 = 2;
+/// How long a freshly started polkit agent gets to register before retrying.
+const POLKIT_AGENT_START_DELAY: Duration = Duration::from_secs(1);
+/// Length of the list's slide and fade animations.
+const ROW_TRANSITION_MS: u32 = 200;
+/// Horizontal travel, in pixels, that makes a drag on a row count as a swipe.
+const SWIPE_DISTANCE: f64 = 60.0;
+/// How long a live IP/DNS change stays before NetworkManager reverts it unconfirmed.
+const CHECKPOINT_ROLLBACK_SECS: u32 = 60;
+/// Months without a connection after which Clean Up Unused first offers a network.
+const UNUSED_CLEANUP_MONTHS: u32 = 6;
+
+fn request_state_refresh(worker: &Worker) {
+    worker.send(Command::LoadState);
+    worker.send(Command::ReadLastScan);
 }
 
-fn request_state_refresh(ui_tx: &mpsc::Sender<UiEvent>) {
-    spawn_task(ui_tx, || {
-        let backend = NetworkManagerBackend::new();
-        UiEvent::StateLoaded(backend.load_state())
+/// Runs `apply_scan_clock` every second while the window is shown or a scan
+/// cooldown is running, and stops until either comes back.
+fn start_scan_clock(connect: &ConnectContext) {
+    if connect.scan_clock_ticking.replace(true) {
+        return;
+    }
+    let connect = connect.clone();
+    gtk4::glib::timeout_add_seconds_local(1, move || {
+        apply_scan_clock(&connect);
+        if scan_clock_needed(&connect) {
+            return ControlFlow::Continue;
+        }
+        connect.scan_clock_ticking.set(false);
+        ControlFlow::Break
     });
 }
 
-fn spawn_scan_task(ui_tx: &mpsc::Sender<UiEvent>) {
-    spawn_task(ui_tx, || {
-        let backend = NetworkManagerBackend::new();
-        UiEvent::ScanDone(backend.request_scan())
-    });
+fn scan_clock_needed(connect: &ConnectContext) -> bool {
+    connect.window.is_mapped() || connect.scan_clock.borrow().remaining(Instant::now()).is_some()
 }
 
-fn spawn_toggle_task(ui_tx: &mpsc::Sender<UiEvent>, enabled: bool) {
-    spawn_task(ui_tx, move || {
-        let backend = NetworkManagerBackend::new();
-        UiEvent::WifiSet {
-            enabled,
-            result: backend.set_wifi_enabled(enabled),
-        }
-    });
+/// The worker dropped the activation of `ssid` for a newer request. Unless
+/// another activation is queued to take its place, e.g. when Wi‑Fi was turned
+/// off meanwhile, the row stops showing it as connecting.
+fn superseded_activation(connect: &ConnectContext, status: &StatusHandler, ssid: &str) {
+    status(StatusKind::Info, format!("Connecting to {ssid} superseded"));
+    if !connect.loading.is_busy(Busy::Apply) {
+        connect.flow.borrow_mut().clear(ssid);
+        connect.list_view.render();
+    }
 }
 
-fn spawn_connect_task(
-    ui_tx: &mpsc::Sender<UiEvent>,
-    ssid: String,
-    password: Option<String>,
-    from_password: bool,
-    was_saved: bool,
-) {
-    spawn_task(ui_tx, move || {
-        let backend = NetworkManagerBackend::new();
-        let result = backend.connect_network(&ssid, password.as_deref());
-        UiEvent::ConnectDone {
-            ssid,
-            result,
-            from_password,
-            was_saved,
+/// Greys out the refresh button while NetworkManager would refuse a scan,
+/// counting down in its tooltip, and runs a queued scan once it would not.
+/// Also keeps the "Updated … ago" line under the header current, restarting
+/// the timer of `start_scan_clock` when a cooldown starts or the window shows.
+fn apply_scan_clock(connect: &ConnectContext) {
+    if scan_clock_needed(connect) {
+        start_scan_clock(connect);
+    }
+    let refresh = &connect.header.refresh;
+    let now = Instant::now();
+    let last_scan = connect.scan_clock.borrow().last_scan();
+    let scan_age = &connect.header.scan_age;
+    scan_age.set_visible(last_scan.is_some());
+    if let Some(last_scan) = last_scan {
+        scan_age.set_text(&scan_clock::updated_ago(now.saturating_duration_since(last_scan)));
+    }
+    // The spinner is up while an operation runs; queued scans wait for it.
+    if connect.header.spinner.is_visible() {
+        return;
+    }
+    let mut clock = connect.scan_clock.borrow_mut();
+    if let Some(left) = clock.remaining(now) {
+        refresh.set_sensitive(false);
+        refresh.set_tooltip_text(Some(&format!("Scan again in {} s", left.as_millis().div_ceil(1000))));
+        return;
+    }
+    refresh.set_sensitive(true);
+    refresh.set_tooltip_text(Some("Scan for networks"));
+    if clock.take_pending(now) {
+        drop(clock);
+        refresh.emit_clicked();
+    }
+}
+
+fn start_signal_poll(connect: &ConnectContext) {
+    let (preferences, worker, window) =
+        (connect.preferences.clone(), connect.worker.clone(), connect.window.clone());
+    gtk4::glib::timeout_add_seconds_local(SIGNAL_POLL_SECS, move || {
+        let preferences = preferences.borrow();
+        let warn = preferences.low_signal_warning && !window.is_visible();
+        if preferences.roaming != RoamingMode::Off || warn {
+            request_state_refresh(&worker);
         }
+        ControlFlow::Continue
     });
 }
 
-fn spawn_disconnect_task(ui_tx: &mpsc::Sender<UiEvent>, ssid: String) {
-    spawn_task(ui_tx, move || {
-        let backend = NetworkManagerBackend::new();
-        let result = backend.disconnect_network(&ssid);
-        UiEvent::DisconnectDone { ssid, result }
-    });
+/// Clicking the notification activates the app, which shows the window again.
+fn notify_low_signal(window: &ApplicationWindow, weak: &Network, alternative: Option<&Network>) {
+    let Some(app) = window.application() else { return };
+    let notification = gtk4::gio::Notification::new(&format!("Weak Wi‑Fi signal on {}", weak.ssid));
+    let body = match alternative {
+        Some(network) => format!(
+            "The signal has been at {}% for a while. {} is in range at {}%; open YuFi to switch.",
+            weak.strength, network.ssid, network.strength
+        ),
+        None => format!(
+            "The signal has been at {}% for a while. Move closer to the access point or open YuFi to pick another network.",
+            weak.strength
+        ),
+    };
+    notification.set_body(Some(&body));
+    app.send_notification(Some(LOW_SIGNAL_NOTIFICATION), &notification);
 }
 
-fn spawn_hidden_task(
-    ui_tx: &mpsc::Sender<UiEvent>,
-    ssid: String,
-    password: Option<String>,
-) {
-    spawn_task(ui_tx, move || {
-        let backend = NetworkManagerBackend::new();
-        let result = backend.connect_hidden(&ssid, "wpa-psk", password.as_deref());
-        UiEvent::HiddenDone { ssid, result }
+/// Has the worker read the Wi‑Fi device's traffic every `TRAFFIC_POLL_SECS`;
+/// the counters come back as `UiEvent::TrafficCounters`.
+fn start_traffic_poll(worker: Worker) {
+    gtk4::glib::timeout_add_seconds_local(TRAFFIC_POLL_SECS, move || {
+        worker.send(Command::TrafficCounters);
+        ControlFlow::Continue
     });
 }
 
-fn spawn_nm_signal_listeners(ui_tx: &mpsc::Sender<UiEvent>) {
-    spawn_nm_properties_listener(ui_tx.clone());
-    spawn_nm_state_listener(ui_tx.clone());
-    spawn_wifi_device_listener(ui_tx.clone());
+/// Adds the traffic since the last reading to the active network's monthly
+/// usage, and notifies once a month when a metered network goes over the
+/// quota set in Preferences.
+fn record_traffic(connect: &ConnectContext, tracker: &mut TrafficTracker, counters: TrafficCounters) {
+    let Some(month) = current_month() else {
+        return;
+    };
+    let active = connect
+        .list_view
+        .state
+        .borrow()
+        .networks
+        .iter()
+        .find(|network| network.is_active)
+        .map(|network| network.ssid.clone());
+    let mut data = connect.list_view.data.borrow_mut();
+    if !tracker.observe(&mut data.data_usage, active.as_deref(), counters, &month) {
+        return;
+    }
+    let quota = connect.preferences.borrow().metered_quota_mb.saturating_mul(1_000_000);
+    let crossed = active
+        .as_deref()
+        .and_then(|ssid| data.data_usage.get_mut(ssid)?.crossed_quota(&month, quota));
+    data.save()
+        .unwrap_or_else(|err| eprintln!("yufi: failed to save usage data: {err}"));
+    if let (Some(ssid), Some(used)) = (&active, crossed) {
+        notify_data_quota(&connect.window, ssid, used, quota);
+    }
 }
 
-fn spawn_nm_properties_listener(ui_tx: mpsc::Sender<UiEvent>) {
-    thread::spawn(move || {
-        let Ok(conn) = Connection::system() else { return };
-        let Ok(props) = Proxy::new(
-            &conn,
-            NM_BUS_NAME,
-            NM_OBJECT_PATH,
-            "org.freedesktop.DBus.Properties",
-        ) else {
-            return;
-        };
-        let Ok(mut stream) = props.receive_signal("PropertiesChanged") else { return };
-        while let Some(signal) = stream.next() {
-            let Ok((iface, changed, _invalidated)) = signal
-                .body()
-                .deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
-            else {
-                continue;
-            };
-            if iface == "org.freedesktop.NetworkManager"
-                && (changed.contains_key("ActiveConnections")
-                    || changed.contains_key("WirelessEnabled")
-                    || changed.contains_key("PrimaryConnection"))
-            {
-                let _ = ui_tx.send(UiEvent::RefreshRequested);
-            }
-        }
-    });
+/// The local month as `"2026-10"`, the key monthly usage is stored under.
+fn current_month() -> Option<String> {
+    let now = gtk4::glib::DateTime::from_unix_local(survey::now_secs() as i64).ok()?;
+    now.format("%Y-%m").ok().map(String::from)
 }
 
-fn spawn_nm_state_listener(ui_tx: mpsc::Sender<UiEvent>) {
-    thread::spawn(move || {
-        let Ok(conn) = Connection::system() else { return };
-        let Ok(proxy) = Proxy::new(
-            &conn,
-            NM_BUS_NAME,
-            NM_OBJECT_PATH,
-            "org.freedesktop.NetworkManager",
-        ) else {
-            return;
-        };
-        let Ok(mut stream) = proxy.receive_signal("StateChanged") else { return };
-        while stream.next().is_some() {
-            let _ = ui_tx.send(UiEvent::RefreshRequested);
-        }
-    });
+fn notify_data_quota(window: &ApplicationWindow, ssid: &str, used: u64, quota: u64) {
+    let Some(app) = window.application() else { return };
+    let notification = gtk4::gio::Notification::new(&format!("{ssid} is over its data quota"));
+    notification.set_body(Some(&format!(
+        "{} used this month on this metered network, over the {} quota set in YuFi.",
+        format_bytes(used),
+        format_bytes(quota)
+    )));
+    app.send_notification(Some(DATA_QUOTA_NOTIFICATION), &notification);
 }
 
-fn spawn_wifi_device_listener(ui_tx: mpsc::Sender<UiEvent>) {
-    thread::spawn(move || {
-        let Ok(conn) = Connection::system() else { return };
-        let Some(device_path) = find_wifi_device_path(&conn) else { return };
-        let Ok(props) = Proxy::new(
-            &conn,
-            NM_BUS_NAME,
-            device_path.as_str(),
-            "org.freedesktop.DBus.Properties",
-        ) else {
-            return;
+/// Checks the clock every `SCHEDULE_CHECK_SECS` for as long as YuFi runs, which
+/// includes sitting in the tray with the window closed.
+fn start_wifi_schedule(connect: ConnectContext) {
+    let mut tracker = ScheduleTracker::default();
+    gtk4::glib::timeout_add_seconds_local(SCHEDULE_CHECK_SECS, move || {
+        let Some(now) = gtk4::glib::DateTime::now_local()
+            .ok()
+            .and_then(|now| TimeOfDay::new(now.hour() as u32, now.minute() as u32))
+        else {
+            return ControlFlow::Continue;
         };
-        let Ok(mut stream) = props.receive_signal("PropertiesChanged") else { return };
-        while let Some(signal) = stream.next() {
-            let Ok((iface, changed, _invalidated)) = signal
-                .body()
-                .deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
-            else {
-                continue;
-            };
-            if iface == "org.freedesktop.NetworkManager.Device.Wireless"
-                || iface == "org.freedesktop.NetworkManager.Device"
-            {
-                if changed.contains_key("ActiveAccessPoint")
-                    || changed.contains_key("ActiveConnection")
-                    || changed.contains_key("LastScan")
-                {
-                    let _ = ui_tx.send(UiEvent::RefreshRequested);
-                }
-            }
+        let preferences = connect.preferences.borrow().clone();
+        let schedule = preferences
+            .wifi_schedule_enabled
+            .then_some(&preferences.wifi_schedule);
+        if let Some(enabled) = tracker.tick(schedule, now) {
+            connect.loading.start(Busy::Radio);
+            update_loading_ui(connect.header.as_ref(), &connect.loading);
+            connect.worker.send(Command::SetWifi(enabled));
         }
+        ControlFlow::Continue
     });
 }
 
-fn find_wifi_device_path(conn: &Connection) -> Option<OwnedObjectPath> {
-    let nm = Proxy::new(
-        conn,
-        NM_BUS_NAME,
-        NM_OBJECT_PATH,
-        "org.freedesktop.NetworkManager",
-    )
-    .ok()?;
-    let devices: Vec<OwnedObjectPath> = nm.call("GetDevices", &()).ok()?;
-    for path in devices {
-        let device = Proxy::new(
-            conn,
-            NM_BUS_NAME,
-            path.as_str(),
-            "org.freedesktop.NetworkManager.Device",
-        )
-        .ok()?;
-        let device_type: u32 = device.get_property("DeviceType").ok()?;
-        if device_type == NM_DEVICE_TYPE_WIFI {
-            drop(device);
-            return Some(path);
+/// Switches to the stronger network right away or asks first, depending on the mode.
+fn offer_roam(connect: &ConnectContext, mode: RoamingMode, suggestion: Suggestion) {
+    let Suggestion {
+        from,
+        from_strength,
+        to,
+        to_strength,
+    } = suggestion;
+    match mode {
+        RoamingMode::Off => {}
+        RoamingMode::Switch => connect.connect(to, None, true, true, None),
+        RoamingMode::Prompt => {
+            let confirm = MessageDialog::builder()
+                .transient_for(&connect.window)
+                .modal(true)
+                .message_type(MessageType::Question)
+                .text(format!("Switch to {to}?"))
+                .secondary_text(format!(
+                    "The signal of {from} has been weak ({from_strength}%) for a while. \
+                     {to} is in range at {to_strength}%."
+                ))
+                .build();
+            confirm.add_button("Stay", ResponseType::Cancel);
+            confirm.add_button("Switch", ResponseType::Accept);
+            confirm.set_default_response(ResponseType::Accept);
+            let connect = connect.clone();
+            confirm.connect_response(move |dialog, response| {
+                if response == ResponseType::Accept {
+                    connect.connect(to.clone(), None, true, true, None);
+                }
+                dialog.close();
+            });
+            confirm.present();
         }
     }
-    None
 }
 
-fn spawn_active_connection_listener(
-    ui_tx: &mpsc::Sender<UiEvent>,
-    ssid: String,
-    path: String,
-) {
-    let tx = ui_tx.clone();
+fn spawn_backend_event_forwarder(
+    backend: &NetworkManagerBackend,
+    ui_tx: &async_channel::Sender<UiEvent>,
+) -> bool {
+    let Ok(events) = backend.subscribe() else { return false };
+    let ui_tx = ui_tx.clone();
     thread::spawn(move || {
-        let Ok(conn) = Connection::system() else { return };
-        let Ok(proxy) = Proxy::new(
-            &conn,
-            NM_BUS_NAME,
-            path.as_str(),
-            "org.freedesktop.NetworkManager.Connection.Active",
-        ) else {
-            return;
-        };
-
-        if let Ok(state) = proxy.get_property::<u32>("State") {
-            let _ = tx.send(UiEvent::ActiveState {
-                ssid: ssid.clone(),
-                state,
-            });
-            if state == 2 || state == 4 {
-                return;
+        for event in events {
+            if ui_tx.send_blocking(UiEvent::Backend(event)).is_err() {
+                break;
             }
         }
+    });
+    true
+}
 
-        let Ok(props) = Proxy::new(
-            &conn,
-            NM_BUS_NAME,
-            path.as_str(),
-            "org.freedesktop.DBus.Properties",
-        ) else {
+/// The worker reads the networks and profiles and picks one, as
+/// `yufi --connect-best` does.
+fn connect_best_available(connect: &ConnectContext, status: &StatusHandler) {
+    let (reply, answer) = async_channel::bounded(1);
+    connect.worker.send(Command::PickBest { reply });
+    let (connect, status) = (connect.clone(), status.clone());
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
             return;
         };
-        let Ok(mut stream) = props.receive_signal("PropertiesChanged") else { return };
-        while let Some(signal) = stream.next() {
-            let Ok((iface, changed, _invalidated)) =
-                signal
-                    .body()
-                    .deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
-            else {
-                continue;
-            };
-            if iface != "org.freedesktop.NetworkManager.Connection.Active" {
-                continue;
+        match result {
+            Ok(Some(network)) if network.is_active => {
+                status(StatusKind::Info, format!("Already connected to {}", network.ssid));
             }
-            let Some(value) = changed.get("State") else { continue };
-            let Some(state) = owned_value_to_u32(value) else { continue };
-            let _ = tx.send(UiEvent::ActiveState {
-                ssid: ssid.clone(),
-                state,
-            });
-            if state == 2 || state == 4 {
-                break;
+            Ok(Some(network)) => connect.connect(network.ssid, None, true, true, None),
+            Ok(None) => status(StatusKind::Info, "No saved networks in range".to_string()),
+            Err(err) => {
+                status(
+                    StatusKind::Error,
+                    format!("Failed to read saved networks: {}", friendly_error(&err)),
+                );
+                explain_permission_error(&connect.window, &err, polkit::SETTINGS_MODIFY_SYSTEM);
             }
         }
     });
 }
 
-fn owned_value_to_u32(value: &OwnedValue) -> Option<u32> {
-    let owned = value.try_clone().ok()?;
-    u32::try_from(owned).ok()
+/// Deletes the profile of `ssid`, seen connected and now dropped, if it was
+/// joined for one visit, and returns whether it did. A tag whose profile is
+/// already gone is dropped quietly.
+fn forget_ended_connection(connect: &ConnectContext, state: &AppState, ssid: &str) -> bool {
+    let mut data = connect.list_view.data.borrow_mut();
+    let reconnecting = connect.flow.borrow().connecting_ssid() == Some(ssid);
+    if reconnecting || !data.forget_on_disconnect.iter().any(|tagged| tagged == ssid) {
+        return false;
+    }
+    data.set_forget_on_disconnect(ssid, false);
+    // Saved networks are not listed while Wi‑Fi is off; a tagged one was
+    // most likely connected until then.
+    let saved = !state.wifi_enabled
+        || state
+            .networks
+            .iter()
+            .chain(&state.out_of_range)
+            .any(|network| network.ssid == ssid && network.is_saved);
+    if saved {
+        connect.worker.send(Command::RemoveProfile(ssid.to_string()));
+        data.forget(ssid);
+    }
+    data.save()
+        .unwrap_or_else(|err| eprintln!("yufi: failed to save forget-on-disconnect tags: {err}"));
+    if saved {
+        request_state_refresh(&connect.worker);
+    }
+    saved
+}
+
+fn apply_activation_state(
+    connect: &ConnectContext,
+    state_cache: &Rc<RefCell<AppState>>,
+    status: &StatusHandler,
+    ssid: &str,
+    state: u32,
+    reason: u32,
+) {
+    let is_secure = state_cache
+        .borrow()
+        .networks
+        .iter()
+        .find(|network| network.ssid == ssid)
+        .map(|network| network.is_secure)
+        .unwrap_or(false);
+    let transition = connect
+        .flow
+        .borrow_mut()
+        .activation_state(ssid, state, reason, is_secure);
+    if transition.remove_profile {
+        connect.worker.send(Command::RemoveProfile(ssid.to_string()));
+    }
+    if transition.changed {
+        render_connect_phase(connect, status);
+        request_state_refresh(&connect.worker);
+    }
+}
+
+fn nm_unavailable(err: &BackendError) -> bool {
+    let BackendError::Unavailable(message) = err else {
+        return false;
+    };
+    let msg = message.to_lowercase();
+    msg.contains("serviceunknown")
+        || msg.contains("namehasnoowner")
+        || msg.contains("not provided by any .service")
+        || msg.contains("no such file or directory")
+        || msg.contains("connection refused")
 }
 
 fn needs_password(err: &BackendError) -> bool {
@@ -1390,6 +3065,10 @@ fn needs_password(err: &BackendError) -> bool {
                 || msg.contains("psk")
                 || msg.contains("wireless-security")
         }
+        BackendError::PermissionDenied(_)
+        | BackendError::NoWifiDevice
+        | BackendError::NotAllowed(_)
+        | BackendError::Superseded => false,
     }
 }
 
@@ -1403,6 +3082,11 @@ fn password_error_message(err: &BackendError) -> String {
             }
             format!("Failed to load password: {err:?}")
         }
+        BackendError::PermissionDenied(_) => {
+            "Password unavailable: permission denied by polkit.".to_string()
+        }
+        BackendError::NoWifiDevice => "Password unavailable: no Wi‑Fi device found.".to_string(),
+        BackendError::NotAllowed(_) | BackendError::Superseded => format!("Failed to load password: {err:?}"),
     }
 }
 
@@ -1413,17 +3097,23 @@ fn friendly_error(err: &BackendError) -> String {
             if msg.contains("nosecrets") || msg.contains("no agents") || msg.contains("no agent") {
                 return "No secrets agent. Start a polkit agent (e.g. polkit-gnome).".to_string();
             }
-            if msg.contains("no wi") && msg.contains("device") {
-                return "No Wi‑Fi device found.".to_string();
+            if nm_unavailable(err) {
+                return "The NetworkManager service is not running.".to_string();
+            }
+            if msg.contains("versionidmismatch") {
+                return "The profile was changed elsewhere meanwhile. Try again.".to_string();
             }
             message.clone()
         }
+        BackendError::PermissionDenied(_) => "Permission denied by polkit.".to_string(),
+        BackendError::NoWifiDevice => "No Wi‑Fi device found.".to_string(),
+        BackendError::NotAllowed(message) => message.clone(),
+        BackendError::Superseded => "Superseded by a newer request.".to_string(),
     }
 }
 
 fn connect_error_message(err: &BackendError, from_password: bool) -> String {
-    if from_password {
-        let BackendError::Unavailable(message) = err;
+    if let (true, BackendError::Unavailable(message)) = (from_password, err) {
         let msg = message.to_lowercase();
         if msg.contains("auth") || msg.contains("password") || msg.contains("psk") {
             return "Incorrect password. Try again.".to_string();
@@ -1432,6 +3122,113 @@ fn connect_error_message(err: &BackendError, from_password: bool) -> String {
     friendly_error(err)
 }
 
+fn explain_permission_error(parent: &impl IsA<gtk4::Window>, err: &BackendError, action: &str) {
+    PrivilegedOperation::new(action).explain(parent, err);
+}
+
+/// An operation NetworkManager may refuse through polkit. When it does and
+/// nothing in the session can ask for a password, `explain` offers to start
+/// an installed polkit agent and then runs the operation again.
+struct PrivilegedOperation {
+    action: String,
+    retry: Option<Rc<dyn Fn()>>,
+}
+
+impl PrivilegedOperation {
+    fn new(action: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            retry: None,
+        }
+    }
+
+    /// What to run again once an agent is up.
+    fn retry(mut self, retry: impl Fn() + 'static) -> Self {
+        self.retry = Some(Rc::new(retry));
+        self
+    }
+
+    fn explain(self, parent: &impl IsA<gtk4::Window>, err: &BackendError) {
+        let BackendError::PermissionDenied(detail) = err else {
+            return;
+        };
+        let action = &self.action;
+        let (secondary, agent) = if polkit_agent::running() {
+            (
+                format!(
+                    "NetworkManager refused the request because the polkit action \
+                     \"{action}\" is not authorized for this session. Grant it with a \
+                     polkit rule such as packaging/polkit/90-yufi.rules.\n\n{detail}"
+                ),
+                None,
+            )
+        } else if let Some((agent, path)) = polkit_agent::installed() {
+            (
+                format!(
+                    "NetworkManager needs a password to allow \"{action}\", but no polkit \
+                     agent is running to ask for it. {} is installed and can be started now.",
+                    agent.name
+                ),
+                Some(path),
+            )
+        } else {
+            (
+                format!(
+                    "NetworkManager needs a password to allow \"{action}\", but no polkit \
+                     agent is running to ask for it. Install one (for example polkit-gnome, \
+                     lxqt-policykit or mate-polkit) and start it with your session, or grant \
+                     the action with a polkit rule such as packaging/polkit/90-yufi.rules.\n\n{detail}"
+                ),
+                None,
+            )
+        };
+        let dialog = MessageDialog::builder()
+            .transient_for(parent)
+            .modal(true)
+            .message_type(MessageType::Error)
+            .text("Permission denied")
+            .secondary_text(secondary)
+            .build();
+        let Some(path) = agent else {
+            dialog.add_button("Close", ResponseType::Close);
+            dialog.connect_response(|dialog, _| dialog.close());
+            dialog.present();
+            return;
+        };
+        dialog.add_button("Cancel", ResponseType::Cancel);
+        dialog.add_button(
+            if self.retry.is_some() { "Start Agent and Retry" } else { "Start Agent" },
+            ResponseType::Accept,
+        );
+        dialog.set_default_response(ResponseType::Accept);
+        let parent = parent.clone().upcast::<gtk4::Window>();
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response != ResponseType::Accept {
+                return;
+            }
+            if let Err(err) = polkit_agent::start(path) {
+                let failed = MessageDialog::builder()
+                    .transient_for(&parent)
+                    .modal(true)
+                    .message_type(MessageType::Error)
+                    .text("Could not start the polkit agent")
+                    .secondary_text(format!("{}: {err}", path.display()))
+                    .build();
+                failed.add_button("Close", ResponseType::Close);
+                failed.connect_response(|dialog, _| dialog.close());
+                failed.present();
+                return;
+            }
+            if let Some(retry) = self.retry.clone() {
+                // The agent needs a moment to register with polkit.
+                gtk4::glib::timeout_add_local_once(POLKIT_AGENT_START_DELAY, move || retry());
+            }
+        });
+        dialog.present();
+    }
+}
+
 struct ParsedNetworkInput {
     ip: Option<String>,
     prefix: Option<u32>,
@@ -1552,7 +3349,7 @@ fn is_ip_or_ipv6(input: &str) -> bool {
     input.contains(':')
 }
 
-fn ssid_from_row(row: &ListBoxRow) -> Option<String> {
+fn ssid_from_row(row: &impl IsA<gtk4::Widget>) -> Option<String> {
     let name = row.widget_name();
     let name = name.as_str();
     name.strip_prefix("ssid:").map(|s| s.to_string())
@@ -1560,15 +3357,16 @@ fn ssid_from_row(row: &ListBoxRow) -> Option<String> {
 
 fn show_network_details_dialog(
     parent: &ApplicationWindow,
-    ssid: &str,
+    network: &Network,
     backend: Rc<NetworkManagerBackend>,
-    ui_tx: mpsc::Sender<UiEvent>,
+    worker: Worker,
     status: StatusHandler,
-    status_container: StatusContainer,
-    failed_connects: Rc<RefCell<HashSet<String>>>,
+    dialog_errors: DialogErrors,
+    list_view: &NetworkListView,
 ) {
     let dialog = Dialog::new();
     dialog.set_title(Some("Network Details"));
+    let ssid = network.ssid.as_str();
     dialog.set_transient_for(Some(parent));
     dialog.set_modal(true);
     dialog.set_default_width(380);
@@ -1584,9 +3382,8 @@ fn show_network_details_dialog(
     let error_label = Label::new(None);
     error_label.add_css_class("yufi-dialog-error");
     error_label.set_halign(Align::Start);
-        error_label.set_text("");
-        error_label.set_visible(true);
-    status_container.register_dialog_label(&error_label);
+    error_label.set_visible(false);
+    let dialog_id = dialog_errors.open(&dialog, &error_label);
 
     let title = Label::new(Some(ssid));
     title.set_halign(Align::Start);
@@ -1610,11 +3407,11 @@ fn show_network_details_dialog(
 
     let reveal_state = Rc::new(Cell::new(false));
     let reveal_state_clone = reveal_state.clone();
-    let backend_clone = backend.clone();
+    let worker_reveal = worker.clone();
     let ssid_clone = ssid.to_string();
     let password_entry_clone = password_entry.clone();
     let status_reveal = status.clone();
-    let status_reveal_container = status_container.clone();
+    let parent_reveal = parent.clone();
     reveal_button.connect_clicked(move |button| {
         if reveal_state_clone.get() {
             password_entry_clone.set_text("");
@@ -1625,25 +3422,50 @@ fn show_network_details_dialog(
             return;
         }
 
-        match backend_clone.get_saved_password(&ssid_clone) {
-            Ok(Some(password)) => {
-                password_entry_clone.set_text(&password);
-                password_entry_clone.set_visibility(true);
-                button.set_icon_name("view-conceal-symbolic");
-                button.set_tooltip_text(Some("Hide password"));
-                reveal_state_clone.set(true);
-            }
-            Ok(None) => {
-                password_entry_clone.set_text("");
-                password_entry_clone.set_visibility(false);
-                status_reveal(StatusKind::Info, "No saved password".to_string());
-            }
-            Err(err) => {
-                let message = password_error_message(&err);
-                status_reveal_container.show_dialog_error(message.clone());
-                status_reveal(StatusKind::Error, message);
+        // GetSecrets may wait on a polkit prompt, so the worker fetches it while
+        // the button spins and ignores further clicks.
+        let busy = Spinner::new();
+        busy.start();
+        button.set_child(Some(&busy));
+        button.set_sensitive(false);
+        button.set_tooltip_text(Some("Reading password…"));
+        let (reply, answer) = async_channel::bounded(1);
+        worker_reveal.send(Command::LoadPassword {
+            ssid: ssid_clone.clone(),
+            dialog: dialog_id,
+            reply,
+        });
+        let button = button.clone();
+        let password_entry = password_entry_clone.clone();
+        let reveal_state = reveal_state_clone.clone();
+        let status_reveal = status_reveal.clone();
+        let parent_reveal = parent_reveal.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = answer.recv().await else {
+                return;
+            };
+            button.set_sensitive(true);
+            button.set_icon_name("view-reveal-symbolic");
+            button.set_tooltip_text(Some("Show password"));
+            match result {
+                Ok(Some(password)) => {
+                    password_entry.set_text(&password);
+                    password_entry.set_visibility(true);
+                    button.set_icon_name("view-conceal-symbolic");
+                    button.set_tooltip_text(Some("Hide password"));
+                    reveal_state.set(true);
+                }
+                Ok(None) => {
+                    password_entry.set_text("");
+                    password_entry.set_visibility(false);
+                    status_reveal(StatusKind::Info, "No saved password".to_string());
+                }
+                Err(err) => {
+                    status_reveal(StatusKind::Error, password_error_message(&err));
+                    explain_permission_error(&parent_reveal, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                }
             }
-        }
+        });
     });
 
     password_row.append(&password_entry);
@@ -1674,6 +3496,17 @@ fn show_network_details_dialog(
     dhcp_row.append(&dhcp_label);
     dhcp_row.append(&dhcp_switch);
 
+    let link_local_row = GtkBox::new(Orientation::Horizontal, 8);
+    link_local_row.set_tooltip_text(Some(
+        "Pick a 169.254.x.x address without a DHCP server, to reach a printer, camera or other device joined directly",
+    ));
+    let link_local_label = Label::new(Some("Link-local only"));
+    link_local_label.set_halign(Align::Start);
+    link_local_label.set_hexpand(true);
+    let link_local_switch = Switch::builder().active(false).build();
+    link_local_row.append(&link_local_label);
+    link_local_row.append(&link_local_switch);
+
     let auto_row = GtkBox::new(Orientation::Horizontal, 8);
     let auto_label = Label::new(Some("Auto‑reconnect"));
     auto_label.set_halign(Align::Start);
@@ -1682,8 +3515,78 @@ fn show_network_details_dialog(
     auto_row.append(&auto_label);
     auto_row.append(&auto_switch);
 
+    let dns_priority_row = GtkBox::new(Orientation::Horizontal, 8);
+    let dns_priority_label = Label::new(Some("DNS Priority"));
+    dns_priority_label.set_halign(Align::Start);
+    dns_priority_label.set_hexpand(true);
+    let dns_priority = gtk4::SpinButton::with_range(-1000.0, 1000.0, 10.0);
+    dns_priority.set_value(0.0);
+    dns_priority_row.append(&dns_priority_label);
+    dns_priority_row.append(&dns_priority);
+    let dns_priority_hint = Label::new(Some(
+        "Decides whose DNS servers win when several connections are up, such as Wi‑Fi and a VPN. \
+         Lower wins; 0 keeps NetworkManager's default (100, or 50 for VPNs). A negative value \
+         uses only the servers of the lowest negative connection and ignores all others.",
+    ));
+    dns_priority_hint.add_css_class("dim-label");
+    dns_priority_hint.set_wrap(true);
+    dns_priority_hint.set_xalign(0.0);
+
     box_.append(&error_label);
     box_.append(&title);
+    if let Some(weak) = network.weak_security() {
+        let warning = Label::new(Some(weak.explanation()));
+        warning.add_css_class("yufi-weak-security");
+        warning.set_halign(Align::Start);
+        warning.set_xalign(0.0);
+        warning.set_wrap(true);
+        box_.append(&warning);
+    }
+    // Filled once the worker has read the access point, along with the details.
+    let access_point_slot = GtkBox::new(Orientation::Vertical, 0);
+    box_.append(&access_point_slot);
+    if network.is_active {
+        box_.append(&build_link_section(worker.clone()));
+        box_.append(&build_latency_section(list_view.preferences.borrow().latency_host.clone()));
+        box_.append(&build_speed_test_section(
+            ssid,
+            list_view.data.clone(),
+            list_view.preferences.clone(),
+        ));
+    }
+    let hardware_label = Label::new(None);
+    hardware_label.set_halign(Align::Start);
+    hardware_label.set_selectable(true);
+    hardware_label.add_css_class("yufi-ap-info");
+    box_.append(&hardware_label);
+    let profile_info = GtkBox::new(Orientation::Vertical, 2);
+    profile_info.add_css_class("yufi-ap-info");
+    box_.append(&profile_info);
+    if network.is_active {
+        // Filled on first expansion so opening the dialog costs no extra D-Bus round trips.
+        let dhcp_expander = Expander::new(Some("DHCP lease"));
+        dhcp_expander.add_css_class("yufi-ap-info");
+        let backend_dhcp = backend.clone();
+        dhcp_expander.connect_expanded_notify(move |expander| {
+            if !expander.is_expanded() || expander.child().is_some() {
+                return;
+            }
+            let section = match backend_dhcp.dhcp_leases() {
+                Ok(leases) => build_dhcp_section(&leases),
+                Err(err) => {
+                    let section = GtkBox::new(Orientation::Vertical, 4);
+                    let label = Label::new(Some(&format!("Could not read the lease: {}", friendly_error(&err))));
+                    label.add_css_class("dim-label");
+                    label.set_halign(Align::Start);
+                    label.set_wrap(true);
+                    section.append(&label);
+                    section
+                }
+            };
+            expander.set_child(Some(&section));
+        });
+        box_.append(&dhcp_expander);
+    }
     manual_fields.append(&ip_label);
     manual_fields.append(&ip_entry);
     manual_fields.append(&gateway_label);
@@ -1695,7 +3598,49 @@ fn show_network_details_dialog(
     box_.append(&password_row);
     box_.append(&dhcp_row);
     box_.append(&manual_fields);
+    box_.append(&link_local_row);
+    let pending_proxy = Rc::new(RefCell::new(None::<String>));
+    let saved_proxy = Rc::new(RefCell::new(String::new()));
+    box_.append(&build_template_row(
+        saved_proxy.clone(),
+        list_view.data.clone(),
+        status.clone(),
+        AddressFields {
+            ip: ip_entry.clone(),
+            gateway: gateway_entry.clone(),
+            dns: dns_entry.clone(),
+            dhcp: dhcp_switch.clone(),
+            link_local: link_local_switch.clone(),
+        },
+        pending_proxy.clone(),
+    ));
+    box_.append(&dns_priority_row);
+    box_.append(&dns_priority_hint);
     box_.append(&auto_row);
+    box_.append(&build_trust_row(ssid, worker.clone(), list_view.data.clone(), status.clone(), parent));
+    box_.append(&build_vpn_rule_row(ssid, &worker, list_view.data.clone(), status.clone()));
+
+    // Everything NetworkManager stored, read on first expansion like the lease.
+    let advanced = Expander::new(Some("Advanced"));
+    advanced.add_css_class("yufi-ap-info");
+    let backend_advanced = backend.clone();
+    let worker_advanced = worker.clone();
+    let ssid_advanced = ssid.to_string();
+    let status_advanced = status.clone();
+    let parent_advanced = parent.clone();
+    advanced.connect_expanded_notify(move |expander| {
+        if expander.is_expanded() && expander.child().is_none() {
+            fill_advanced_section(
+                expander,
+                &backend_advanced,
+                &worker_advanced,
+                &ssid_advanced,
+                &status_advanced,
+                &parent_advanced,
+            );
+        }
+    });
+    box_.append(&advanced);
 
     let actions = GtkBox::new(Orientation::Vertical, 8);
     actions.set_hexpand(true);
@@ -1723,43 +3668,176 @@ fn show_network_details_dialog(
     save_row.append(&save_button);
 
     actions.append(&save_row);
+    if network.is_active {
+        let renew_button = Button::with_label("Renew Lease");
+        renew_button.add_css_class("yufi-secondary");
+        renew_button.set_hexpand(true);
+        renew_button.set_halign(Align::Fill);
+        renew_button.set_tooltip_text(Some("Reconnect to request a fresh IP configuration"));
+        let backend_renew = backend.clone();
+        let ssid_renew = ssid.to_string();
+        let status_renew = status.clone();
+        let parent_renew = parent.clone();
+        renew_button.connect_clicked(move |_| match backend_renew.renew_lease(&ssid_renew) {
+            Ok(_) => status_renew(StatusKind::Info, "Renewing IP configuration...".to_string()),
+            Err(err) => {
+                status_renew(
+                    StatusKind::Error,
+                    format!("Failed to renew lease: {}", friendly_error(&err)),
+                );
+                explain_permission_error(&parent_renew, &err, polkit::NETWORK_CONTROL);
+            }
+        });
+        actions.append(&renew_button);
+    }
     actions.append(&forget_button);
 
     box_.append(&actions);
     content.append(&box_);
     dialog.set_default_widget(Some(&save_button));
 
-    let details = backend
-        .get_network_details(ssid)
-        .unwrap_or_else(|_| NetworkDetails::default());
-
-    let mut has_manual = false;
-    if let Some(ip) = details.ip_address {
-        ip_entry.set_text(&ip);
-        has_manual = true;
-    }
-    if let Some(gateway) = details.gateway {
-        gateway_entry.set_text(&gateway);
-        has_manual = true;
-    }
-    if !details.dns_servers.is_empty() {
-        dns_entry.set_text(&details.dns_servers.join(", "));
-        has_manual = true;
-    }
-    dhcp_switch.set_active(!has_manual);
-    manual_fields.set_visible(!dhcp_switch.is_active());
-    if let Some(auto) = details.auto_reconnect {
-        auto_switch.set_active(auto);
+    // Reading the profile takes a few D-Bus round trips, so it runs on the worker
+    // and Save stays off until the fields hold the stored values.
+    let loading_row = GtkBox::new(Orientation::Horizontal, 8);
+    let loading_spinner = Spinner::new();
+    loading_spinner.start();
+    let loading_label = Label::new(Some("Loading details…"));
+    loading_label.add_css_class("dim-label");
+    loading_row.append(&loading_spinner);
+    loading_row.append(&loading_label);
+    box_.insert_child_after(&loading_row, Some(&title));
+    save_button.set_sensitive(false);
+    manual_fields.set_visible(false);
+    hardware_label.set_visible(false);
+    profile_info.set_visible(false);
+
+    let saved_dns_priority = Rc::new(Cell::new(0));
+    let saved_link_local = Rc::new(Cell::new(false));
+    let (details_tx, details_rx) = async_channel::bounded(1);
+    worker.send(Command::LoadDetails {
+        ssid: ssid.to_string(),
+        reply: details_tx,
+    });
+    {
+        let save_button = save_button.clone();
+        let ip_entry = ip_entry.clone();
+        let gateway_entry = gateway_entry.clone();
+        let dns_entry = dns_entry.clone();
+        let dhcp_switch = dhcp_switch.clone();
+        let manual_fields = manual_fields.clone();
+        let auto_switch = auto_switch.clone();
+        let dns_priority = dns_priority.clone();
+        let hardware_label = hardware_label.clone();
+        let access_point_slot = access_point_slot.clone();
+        let saved_dns_priority = saved_dns_priority.clone();
+        let saved_proxy = saved_proxy.clone();
+        let worker = worker.clone();
+        let (link_local_switch, saved_link_local) = (link_local_switch.clone(), saved_link_local.clone());
+        let (box_, dialog) = (box_.clone(), dialog.clone());
+        let (backend, ssid, status, parent) = (backend.clone(), ssid.to_string(), status.clone(), parent.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok((result, access_point)) = details_rx.recv().await else {
+                return;
+            };
+            loading_spinner.stop();
+            loading_row.set_visible(false);
+            if let Some(info) = access_point {
+                access_point_slot.append(&build_access_point_section(&info));
+            }
+            save_button.set_sensitive(true);
+            let details = result.unwrap_or_else(|_| NetworkDetails::default());
+            let mut has_manual = false;
+            if let Some(ip) = details.ip_address {
+                ip_entry.set_text(&ip);
+                has_manual = true;
+            }
+            if let Some(gateway) = details.gateway {
+                gateway_entry.set_text(&gateway);
+                has_manual = true;
+            }
+            if !details.dns_servers.is_empty() {
+                dns_entry.set_text(&details.dns_servers.join(", "));
+                has_manual = true;
+            }
+            dhcp_switch.set_active(!has_manual);
+            saved_link_local.set(details.link_local);
+            link_local_switch.set_active(details.link_local);
+            manual_fields.set_visible(!details.link_local && !dhcp_switch.is_active());
+            if let Some(auto) = details.auto_reconnect {
+                auto_switch.set_active(auto);
+            }
+            saved_dns_priority.set(details.dns_priority.unwrap_or(0));
+            dns_priority.set_value(saved_dns_priority.get() as f64);
+            saved_proxy.replace(details.proxy_pac_url.clone().unwrap_or_default());
+            let bssid = details
+                .bssid
+                .as_deref()
+                .map(|bssid| format!("{bssid}{}", oui::suffix(bssid)));
+            let hardware_lines: Vec<String> = [
+                ("Device MAC", details.device_mac.as_deref()),
+                ("Access point BSSID", bssid.as_deref()),
+            ]
+            .into_iter()
+            .filter_map(|(label, value)| value.map(|value| format!("{label}: {value}")))
+            .collect();
+            hardware_label.set_text(&hardware_lines.join("\n"));
+            hardware_label.set_visible(!hardware_lines.is_empty());
+            if let Some(uuid) = &details.uuid {
+                profile_info.append(&build_copy_row("UUID", uuid));
+            }
+            if let Some(certificates) = details.certificates {
+                let section = build_certificate_section(
+                    &dialog,
+                    &ssid,
+                    certificates,
+                    worker.clone(),
+                    status.clone(),
+                    &parent,
+                );
+                box_.insert_child_after(&section, Some(&profile_info));
+            }
+            profile_info.append(&build_personal_row(
+                &ssid,
+                !details.permitted_users.is_empty(),
+                worker.clone(),
+                status.clone(),
+                &parent,
+            ));
+            if let Some(interface) = &details.interface_name {
+                let row = build_copy_row("Only on interface", interface);
+                row.set_tooltip_text(Some(
+                    "The profile only connects through this Wi‑Fi device. Clear it if that adapter is gone.",
+                ));
+                let clear = Button::with_label("Clear");
+                clear.add_css_class("flat");
+                let row_clear = row.clone();
+                clear.connect_clicked(move |_| match backend.clear_interface_binding(&ssid) {
+                    Ok(()) => {
+                        row_clear.set_visible(false);
+                        status(StatusKind::Success, format!("{ssid} can now connect through any Wi‑Fi device"));
+                    }
+                    Err(err) => {
+                        status(
+                            StatusKind::Error,
+                            format!("Failed to clear the interface: {}", friendly_error(&err)),
+                        );
+                        explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                    }
+                });
+                row.append(&clear);
+                profile_info.append(&row);
+            }
+            profile_info.set_visible(profile_info.first_child().is_some());
+        });
     }
 
     let backend_forget = backend.clone();
     let ssid_forget = ssid.to_string();
     let status_forget = status.clone();
-    let status_container_forget = status_container.clone();
     let dialog_forget = dialog.clone();
     let parent_forget = parent.clone();
-    let ui_tx_forget = ui_tx.clone();
-    let failed_forget_ref = failed_connects.clone();
+    let worker_forget = worker.clone();
+    let flow_forget = list_view.flow.clone();
     forget_button.connect_clicked(move |_| {
         let confirm = MessageDialog::builder()
             .transient_for(&parent_forget)
@@ -1777,22 +3855,27 @@ fn show_network_details_dialog(
         let backend_confirm = backend_forget.clone();
         let ssid_confirm = ssid_forget.clone();
         let status_confirm = status_forget.clone();
-        let status_container_confirm = status_container_forget.clone();
         let dialog_close = dialog_forget.clone();
-        let ui_tx_confirm = ui_tx_forget.clone();
-        let failed_confirm = failed_forget_ref.clone();
+        let worker_confirm = worker_forget.clone();
+        let flow_confirm = flow_forget.clone();
+        let parent_confirm = parent_forget.clone();
         confirm.connect_response(move |dialog, response| {
             if response == ResponseType::Accept {
                 match backend_confirm.forget_network(&ssid_confirm) {
                     Ok(_) => {
                         status_confirm(StatusKind::Success, "Network forgotten".to_string());
-                        status_container_confirm.clear_dialog_label();
                         dialog_close.close();
-                        failed_confirm.borrow_mut().remove(&ssid_confirm);
-                        request_state_refresh(&ui_tx_confirm);
+                        let mut flow = flow_confirm.borrow_mut();
+                        if flow.last_connected() == Some(ssid_confirm.as_str()) {
+                            flow.expect_disconnect();
+                        }
+                        flow.clear(&ssid_confirm);
+                        drop(flow);
+                        request_state_refresh(&worker_confirm);
                     }
                     Err(err) => {
                         status_confirm(StatusKind::Error, format!("Failed to forget: {err:?}"));
+                        explain_permission_error(&parent_confirm, &err, polkit::SETTINGS_MODIFY_SYSTEM);
                     }
                 }
             }
@@ -1815,16 +3898,26 @@ fn show_network_details_dialog(
         Propagation::Proceed
     });
 
+    // Link-local leaves nothing to configure, so DHCP and the manual fields go.
+    let (dhcp_row_toggle, manual_fields_toggle) = (dhcp_row.clone(), manual_fields.clone());
+    let dhcp_switch_link = dhcp_switch.clone();
+    link_local_switch.connect_state_set(move |_switch, state| {
+        dhcp_row_toggle.set_visible(!state);
+        manual_fields_toggle.set_visible(!state && !dhcp_switch_link.is_active());
+        Propagation::Proceed
+    });
+
     let ip_entry = ip_entry.clone();
     let gateway_entry = gateway_entry.clone();
     let dns_entry = dns_entry.clone();
     let auto_switch = auto_switch.clone();
     let ssid = ssid.to_string();
+    let network_active = network.is_active;
     let status_save = status.clone();
-    let status_container = status_container.clone();
-    let status_container_save = status_container.clone();
+    let error_save = error_label.clone();
     let dialog_save = dialog.clone();
     let backend_save = backend.clone();
+    let parent_save = parent.clone();
     save_button.connect_clicked(move |_| {
         let ip_text = ip_entry.text().to_string();
         let gateway_text = gateway_entry.text().to_string();
@@ -1833,16 +3926,46 @@ fn show_network_details_dialog(
         let parsed = match parse_network_inputs(&ip_text, &gateway_text, &dns_text) {
             Ok(parsed) => parsed,
             Err(message) => {
-                status_container_save.show_dialog_error(message);
+                error_save.set_text(&message);
+                error_save.set_visible(true);
                 return;
             }
         };
 
         let mut failed = false;
-        let use_manual = !dhcp_switch_clone.is_active();
+        let link_local = link_local_switch.is_active();
+        let link_local_changed = link_local != saved_link_local.get();
+        let use_manual = !link_local && !dhcp_switch_clone.is_active();
         let ip = if use_manual { parsed.ip.as_deref() } else { None };
         let gateway = if use_manual { parsed.gateway.as_deref() } else { None };
         let dns = if use_manual { parsed.dns } else { None };
+        let proxy = pending_proxy.borrow().clone();
+        // Manual addressing or a proxy on the live connection can cut off a remote
+        // session, so apply it behind a checkpoint that NetworkManager undoes
+        // unless confirmed.
+        let risky = network_active
+            && (ip.is_some() || gateway.is_some() || dns.is_some() || link_local_changed || proxy.is_some());
+        let mut checkpoint = None;
+        if risky {
+            match backend_save.checkpoint_create(CHECKPOINT_ROLLBACK_SECS) {
+                Ok(path) => checkpoint = Some(path),
+                Err(err) => status_save(
+                    StatusKind::Error,
+                    format!(
+                        "No rollback point available, so the new settings apply on the next connect: {}",
+                        friendly_error(&err)
+                    ),
+                ),
+            }
+        }
+        if let Some(Err(err)) = link_local_changed.then(|| backend_save.set_link_local(&ssid, link_local)) {
+            failed = true;
+            status_save(StatusKind::Error, format!("Failed to set link-local addressing: {}", friendly_error(&err)));
+            explain_permission_error(&parent_save, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            if let Some(checkpoint) = checkpoint.take() {
+                let _ = backend_save.checkpoint_destroy(&checkpoint);
+            }
+        }
         if let Err(err) = backend_save.set_ip_dns(
             &ssid,
             ip,
@@ -1852,146 +3975,4329 @@ fn show_network_details_dialog(
         ) {
             failed = true;
             status_save(StatusKind::Error, format!("Failed to set IP/DNS: {err:?}"));
+            explain_permission_error(&parent_save, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            if let Some(checkpoint) = checkpoint.take() {
+                let _ = backend_save.checkpoint_destroy(&checkpoint);
+            }
+        }
+        if let Some(Err(err)) = proxy.map(|url| backend_save.set_proxy(&ssid, &url)) {
+            failed = true;
+            status_save(StatusKind::Error, format!("Failed to set the proxy: {}", friendly_error(&err)));
+            explain_permission_error(&parent_save, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            if let Some(checkpoint) = checkpoint.take() {
+                let _ = backend_save.checkpoint_destroy(&checkpoint);
+            }
         }
         if let Err(err) = backend_save.set_autoreconnect(&ssid, auto_switch.is_active()) {
             failed = true;
             status_save(StatusKind::Error, format!("Failed to set auto‑reconnect: {err:?}"));
+            explain_permission_error(&parent_save, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+        }
+        let priority = dns_priority.value_as_int();
+        let priority_result = (priority != saved_dns_priority.get())
+            .then(|| backend_save.set_dns_priority(&ssid, priority));
+        if let Some(Err(err)) = priority_result {
+            failed = true;
+            status_save(StatusKind::Error, format!("Failed to set DNS priority: {}", friendly_error(&err)));
+            explain_permission_error(&parent_save, &err, polkit::SETTINGS_MODIFY_SYSTEM);
         }
         if !failed {
             status_save(StatusKind::Success, "Saved network settings".to_string());
         }
-        status_container_save.clear_dialog_label();
+        if let Some(checkpoint) = checkpoint {
+            match backend_save.reapply_connection() {
+                Ok(()) => confirm_network_change(
+                    &parent_save,
+                    backend_save.clone(),
+                    checkpoint,
+                    status_save.clone(),
+                    worker.clone(),
+                ),
+                Err(err) => {
+                    let _ = backend_save.checkpoint_destroy(&checkpoint);
+                    status_save(
+                        StatusKind::Error,
+                        format!("Saved, but reconnect to apply the settings: {}", friendly_error(&err)),
+                    );
+                }
+            }
+        }
         dialog_save.close();
-        request_state_refresh(&ui_tx);
+        request_state_refresh(&worker);
     });
 
     let dialog_cancel = dialog.clone();
-    let status_container_cancel = status_container.clone();
-    cancel_button.connect_clicked(move |_| {
-        status_container_cancel.clear_dialog_label();
-        dialog_cancel.close();
-    });
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
     dialog.present();
 }
 
-fn prompt_connect_dialog(
+/// Asks whether the network still works after a live addressing change. Without an
+/// answer NetworkManager restores the checkpoint by itself when the timeout runs out.
+fn confirm_network_change(
     parent: &ApplicationWindow,
-    ssid: &str,
-    loading: &LoadingTracker,
-    header: &Rc<HeaderWidgets>,
-    ui_tx: &mpsc::Sender<UiEvent>,
-    status_container: &Rc<StatusContainer>,
-    was_saved: bool,
-    initial_error: Option<String>,
+    backend: Rc<NetworkManagerBackend>,
+    checkpoint: String,
+    status: StatusHandler,
+    worker: Worker,
 ) {
-    let ssid = ssid.to_string();
-    let ssid_label = ssid.clone();
-    let ssid_connect = ssid.clone();
-    let loading = loading.clone();
-    let header = header.clone();
-    let ui_tx = ui_tx.clone();
-    let status_container = (**status_container).clone();
-    show_password_dialog(
-        parent,
-        &ssid_label,
-        initial_error,
-        move |password| {
-            loading.start();
-            update_loading_ui(header.as_ref(), &loading);
-            spawn_connect_task(
-                &ui_tx,
-                ssid_connect.clone(),
-                password.clone(),
-                password.is_some(),
-                was_saved,
-            );
-        },
-        status_container,
-    );
-}
+    let countdown_text = |seconds: u32| {
+        format!("The previous settings come back in {seconds} seconds unless you confirm the network still works.")
+    };
+    let confirm = MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .message_type(MessageType::Question)
+        .text("Keep these network settings?")
+        .secondary_text(countdown_text(CHECKPOINT_ROLLBACK_SECS))
+        .build();
+    confirm.add_button("Revert", ResponseType::Reject);
+    confirm.add_button("Keep Settings", ResponseType::Accept);
+    confirm.set_default_response(ResponseType::Reject);
+    if let Some(keep) = confirm.widget_for_response(ResponseType::Accept) {
+        keep.add_css_class("suggested-action");
+    }
 
-fn show_password_dialog<F: Fn(Option<String>) + 'static>(
-    parent: &ApplicationWindow,
-    ssid: &str,
-    initial_error: Option<String>,
-    on_submit: F,
-    status_container: StatusContainer,
-) {
-    let dialog = Dialog::new();
-    dialog.set_title(Some("Connect to network"));
-    dialog.set_transient_for(Some(parent));
-    dialog.set_modal(true);
-    dialog.set_default_width(380);
+    let remaining = Rc::new(Cell::new(CHECKPOINT_ROLLBACK_SECS));
+    let countdown: Rc<RefCell<Option<gtk4::glib::SourceId>>> = Rc::new(RefCell::new(None));
+    let confirm_tick = confirm.clone();
+    let countdown_tick = countdown.clone();
+    let source = gtk4::glib::timeout_add_seconds_local(1, move || {
+        let left = remaining.get().saturating_sub(1);
+        remaining.set(left);
+        if left == 0 {
+            countdown_tick.borrow_mut().take();
+            confirm_tick.response(ResponseType::None);
+            return ControlFlow::Break;
+        }
+        confirm_tick.set_secondary_text(Some(&countdown_text(left)));
+        ControlFlow::Continue
+    });
+    *countdown.borrow_mut() = Some(source);
 
-    let content = dialog.content_area();
-    let box_ = GtkBox::new(Orientation::Vertical, 8);
-    box_.set_margin_top(12);
-    box_.set_margin_bottom(12);
-    box_.set_margin_start(12);
-    box_.set_margin_end(12);
+    confirm.connect_response(move |dialog, response| {
+        if let Some(source) = countdown.borrow_mut().take() {
+            source.remove();
+        }
+        match response {
+            ResponseType::Accept => match backend.checkpoint_destroy(&checkpoint) {
+                Ok(()) => status(StatusKind::Success, "Kept the new network settings".to_string()),
+                Err(err) => status(
+                    StatusKind::Error,
+                    format!("Could not keep the new settings: {}", friendly_error(&err)),
+                ),
+            },
+            // Timed out: NetworkManager has already rolled back on its own.
+            ResponseType::None => status(
+                StatusKind::Info,
+                "No confirmation, so the previous network settings were restored".to_string(),
+            ),
+            _ => match backend.checkpoint_rollback(&checkpoint) {
+                Ok(()) => status(StatusKind::Info, "Restored the previous network settings".to_string()),
+                Err(err) => status(
+                    StatusKind::Error,
+                    format!("Failed to restore the previous settings: {}", friendly_error(&err)),
+                ),
+            },
+        }
+        dialog.close();
+        request_state_refresh(&worker);
+    });
+    confirm.present();
+}
 
-    let label = Label::new(Some(&format!("Password for {ssid}")));
-    label.set_halign(Align::Start);
+#[derive(Clone)]
+struct ConnectContext {
+    window: ApplicationWindow,
+    loading: LoadingTracker,
+    header: Rc<HeaderWidgets>,
+    worker: Worker,
+    flow: Rc<RefCell<ConnectFlow>>,
+    list_view: NetworkListView,
+    preferences: Rc<RefCell<Preferences>>,
+    scan_clock: Rc<RefCell<ScanClock>>,
+    /// The timer of `start_scan_clock` is running.
+    scan_clock_ticking: Rc<Cell<bool>>,
+    read_only: bool,
+}
+
+impl ConnectContext {
+    fn connect(
+        &self,
+        ssid: String,
+        password: Option<String>,
+        was_saved: bool,
+        autoconnect: bool,
+        band: Option<BandLock>,
+    ) {
+        let from_password = password.is_some();
+        self.flow.borrow_mut().begin(Attempt {
+            ssid: ssid.clone(),
+            was_saved,
+            from_password,
+            band,
+        });
+        self.list_view.render();
+        self.loading.start(Busy::Apply);
+        update_loading_ui(self.header.as_ref(), &self.loading);
+        self.worker.send(Command::Connect {
+            ssid,
+            password,
+            from_password,
+            autoconnect,
+            band,
+        });
+    }
+
+    /// Turns Wi‑Fi on and connects to the saved network `ssid` as one step.
+    /// The connection is held until the device sees the network again, or
+    /// for at most `WIFI_ON_TIMEOUT`; see `release_held_connect`.
+    fn enable_wifi_and_connect(&self, ssid: String, band: Option<BandLock>) {
+        self.flow.borrow_mut().begin_after_radio(Attempt {
+            ssid: ssid.clone(),
+            was_saved: true,
+            from_password: false,
+            band,
+        });
+        self.list_view.render();
+        self.loading.start(Busy::Apply);
+        self.loading.start(Busy::Radio);
+        update_loading_ui(self.header.as_ref(), &self.loading);
+        self.worker.send(Command::SetWifi(true));
+        let connect = self.clone();
+        gtk4::glib::timeout_add_local_once(WIFI_ON_TIMEOUT, move || {
+            // Should the network never show up, connecting reports why.
+            connect.release_held_connect(|held| held == ssid);
+        });
+    }
+
+    /// Sends the connection `enable_wifi_and_connect` holds once `in_range`
+    /// says its network turned up, as a saved network's row would.
+    fn release_held_connect(&self, in_range: impl Fn(&str) -> bool) {
+        if !self.flow.borrow().awaiting_radio().is_some_and(in_range) {
+            return;
+        }
+        let Some(attempt) = self.flow.borrow_mut().radio_ready() else {
+            return;
+        };
+        self.worker.send(Command::Connect {
+            ssid: attempt.ssid,
+            password: None,
+            from_password: false,
+            autoconnect: true,
+            band: attempt.band,
+        });
+    }
+
+    fn connect_hidden(&self, ssid: String, password: Option<String>, autoconnect: bool) {
+        self.flow.borrow_mut().begin(Attempt {
+            ssid: ssid.clone(),
+            was_saved: false,
+            from_password: true,
+            band: None,
+        });
+        self.loading.start(Busy::Apply);
+        update_loading_ui(self.header.as_ref(), &self.loading);
+        self.worker.send(Command::ConnectHidden {
+            ssid,
+            password,
+            autoconnect,
+        });
+    }
+
+    /// Joins a network with no saved profile. Lookalikes of saved networks get a
+    /// warning first, and so do unencrypted networks unless it was turned off;
+    /// Enhanced Open (OWE) counts as encrypted.
+    fn join_new(&self, ssid: &str, band: Option<BandLock>) {
+        let context = self.clone();
+        let ssid_join = ssid.to_string();
+        self.check_lookalike(ssid, move || context.join_unsaved(&ssid_join, band));
+    }
+
+    /// Runs `then` right away unless `ssid` imitates a saved network, in which
+    /// case only once the user chose to connect anyway.
+    fn check_lookalike<F: Fn() + 'static>(&self, ssid: &str, then: F) {
+        let saved = saved_ssids(&self.list_view.state.borrow());
+        match lookalike::imitated(ssid, &saved) {
+            Some(imitated) => show_lookalike_warning(&self.window, ssid, &imitated, then),
+            None => then(),
+        }
+    }
+
+    fn join_unsaved(&self, ssid: &str, band: Option<BandLock>) {
+        let is_open = self
+            .list_view
+            .state
+            .borrow()
+            .networks
+            .iter()
+            .any(|network| network.ssid == ssid && network.security == "open");
+        if !is_open || !self.preferences.borrow().warn_open_networks {
+            self.prompt_password(ssid, None, false, band);
+            return;
+        }
+        let context = self.clone();
+        let ssid_connect = ssid.to_string();
+        show_open_network_warning(&self.window, ssid, move |dont_ask_again, forget| {
+            if dont_ask_again {
+                let mut preferences = context.preferences.borrow_mut();
+                preferences.warn_open_networks = false;
+                preferences
+                    .save()
+                    .unwrap_or_else(|err| eprintln!("yufi: failed to save preferences: {err}"));
+            }
+            let autoconnect = context.preferences.borrow().autoconnect_new;
+            context.tag_forget_on_disconnect(&ssid_connect, forget);
+            context.connect(ssid_connect.clone(), None, false, autoconnect, band);
+        });
+    }
+
+    fn prompt_password(&self, ssid: &str, initial_error: Option<String>, was_saved: bool, band: Option<BandLock>) {
+        let context = self.clone();
+        let ssid_connect = ssid.to_string();
+        let autoconnect = (!was_saved).then(|| self.preferences.borrow().autoconnect_new);
+        show_password_dialog(
+            &self.window,
+            ssid,
+            initial_error,
+            autoconnect,
+            move |password, autoconnect, forget| {
+                if !was_saved {
+                    context.tag_forget_on_disconnect(&ssid_connect, forget);
+                }
+                context.connect(ssid_connect.clone(), password, was_saved, autoconnect, band);
+            },
+        );
+    }
+
+    /// Marks a network being joined to be forgotten once it disconnects, or
+    /// clears a mark left from an earlier visit.
+    fn tag_forget_on_disconnect(&self, ssid: &str, forget: bool) {
+        let mut data = self.list_view.data.borrow_mut();
+        data.set_forget_on_disconnect(ssid, forget);
+        data.save()
+            .unwrap_or_else(|err| eprintln!("yufi: failed to save forget-on-disconnect tags: {err}"));
+    }
+}
+
+/// Calls `on_continue` if the user still wants to join `ssid`, which looks
+/// like their saved network `saved` without being it.
+fn show_lookalike_warning<F: Fn() + 'static>(parent: &ApplicationWindow, ssid: &str, saved: &str, on_continue: F) {
+    let confirm = MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .message_type(MessageType::Warning)
+        .text(format!("Join {ssid}?"))
+        .secondary_text(format!(
+            "This network looks like your saved network \"{saved}\", but its name differs in \
+             invisible characters, spaces or lookalike letters. Someone nearby may be imitating it \
+             to intercept your traffic."
+        ))
+        .build();
+    confirm.add_button("Cancel", ResponseType::Cancel);
+    confirm.add_button("Connect Anyway", ResponseType::Accept);
+    confirm.set_default_response(ResponseType::Cancel);
+    if let Some(connect) = confirm.widget_for_response(ResponseType::Accept) {
+        connect.add_css_class("destructive-action");
+    }
+    confirm.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            on_continue();
+        }
+        dialog.close();
+    });
+    confirm.present();
+}
+
+/// Calls `on_continue` with the "don't ask again" and "forget after
+/// disconnecting" choices if the user still wants to join.
+fn show_open_network_warning<F: Fn(bool, bool) + 'static>(parent: &ApplicationWindow, ssid: &str, on_continue: F) {
+    let confirm = MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .message_type(MessageType::Warning)
+        .text(format!("Join {ssid}?"))
+        .secondary_text(
+            "This network is unencrypted; traffic may be visible to others nearby. \
+             Prefer sites and apps that use HTTPS or a VPN while connected.",
+        )
+        .build();
+    let dont_ask = CheckButton::with_label("Don't ask again for open networks");
+    let forget = CheckButton::with_label(FORGET_ON_DISCONNECT_LABEL);
+    if let Ok(area) = confirm.message_area().downcast::<GtkBox>() {
+        area.append(&dont_ask);
+        area.append(&forget);
+    }
+    confirm.add_button("Cancel", ResponseType::Cancel);
+    confirm.add_button("Connect Anyway", ResponseType::Accept);
+    confirm.set_default_response(ResponseType::Cancel);
+    confirm.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            on_continue(dont_ask.is_active(), forget.is_active());
+        }
+        dialog.close();
+    });
+    confirm.present();
+}
+
+fn render_connect_phase(context: &ConnectContext, status: &StatusHandler) {
+    let phase = context.flow.borrow().phase().clone();
+    match phase {
+        ConnectPhase::Idle | ConnectPhase::Activating(_) => {}
+        ConnectPhase::Connected(_) => status(StatusKind::Info, String::new()),
+        ConnectPhase::NeedSecrets { attempt, error } => {
+            if let Some(message) = &error {
+                status(
+                    StatusKind::Error,
+                    format!("Failed to connect to {}. {message}", attempt.ssid),
+                );
+            }
+            context.flow.borrow_mut().dismiss();
+            context.prompt_password(&attempt.ssid, error, attempt.was_saved, attempt.band);
+        }
+        ConnectPhase::Failed { ssid, message } => {
+            status(StatusKind::Error, format!("Failed to connect to {ssid}. {message}"));
+            context.flow.borrow_mut().dismiss();
+        }
+    }
+    context.list_view.render();
+}
+
+/// For hotel and airport networks not worth keeping.
+const FORGET_ON_DISCONNECT_LABEL: &str = "Forget after disconnecting";
+
+/// `autoconnect` is None for saved networks, which hides the choices that
+/// only apply to a new profile.
+fn show_password_dialog<F: Fn(Option<String>, bool, bool) + 'static>(
+    parent: &ApplicationWindow,
+    ssid: &str,
+    initial_error: Option<String>,
+    autoconnect: Option<bool>,
+    on_submit: F,
+) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Connect to network"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let label = Label::new(Some(&format!("Password for {ssid}")));
+    label.set_halign(Align::Start);
+    let error_label = Label::new(initial_error.as_deref());
+    error_label.add_css_class("yufi-dialog-error");
+    error_label.set_halign(Align::Start);
+    error_label.set_wrap(true);
+    error_label.set_visible(initial_error.is_some());
+    let entry = Entry::new();
+    entry.set_visibility(false);
+    entry.set_placeholder_text(Some("Optional (leave empty for open network)"));
+    entry.add_css_class("yufi-entry");
+    if initial_error.is_some() {
+        entry.add_css_class("yufi-entry-error");
+    }
+    entry.grab_focus();
+    entry.select_region(0, -1);
+
+    let autoconnect_check = CheckButton::with_label("Connect automatically");
+    autoconnect_check.set_active(autoconnect.unwrap_or(true));
+    autoconnect_check.set_visible(autoconnect.is_some());
+    let forget_check = CheckButton::with_label(FORGET_ON_DISCONNECT_LABEL);
+    forget_check.set_visible(autoconnect.is_some());
+
+    box_.append(&label);
+    box_.append(&error_label);
+    box_.append(&entry);
+    box_.append(&autoconnect_check);
+    box_.append(&forget_check);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    actions.set_hexpand(true);
+
+    let cancel_button = Button::with_label("Cancel");
+    cancel_button.set_hexpand(true);
+    cancel_button.set_halign(Align::Fill);
+
+    let connect_button = Button::with_label("Connect");
+    connect_button.add_css_class("yufi-primary");
+    connect_button.add_css_class("suggested-action");
+    connect_button.set_hexpand(true);
+    connect_button.set_halign(Align::Fill);
+
+    actions.append(&cancel_button);
+    actions.append(&connect_button);
+    box_.append(&actions);
+    content.append(&box_);
+    dialog.set_default_widget(Some(&connect_button));
+    let connect_activate = connect_button.clone();
+    entry.connect_activate(move |_| {
+        connect_activate.emit_clicked();
+    });
+
+    let entry_clone = entry.clone();
+
+    let dialog_connect = dialog.clone();
+    connect_button.connect_clicked(move |_| {
+        let text = entry_clone.text().to_string();
+        let password = if text.trim().is_empty() { None } else { Some(text) };
+        on_submit(password, autoconnect_check.is_active(), forget_check.is_active());
+        dialog_connect.close();
+    });
+
+    let dialog_cancel = dialog.clone();
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
+    dialog.present();
+}
+
+/// Asks for the username and password NetworkManager needs to sign in to an
+/// 802.1X network. Closes by itself once NetworkManager stops waiting.
+fn show_sign_in_dialog(parent: &ApplicationWindow, request: SecretRequest) {
+    let request = Rc::new(request);
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Sign in to network"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let message = if request.retry {
+        format!("The username or password for {} was not accepted. Try again.", request.ssid)
+    } else {
+        format!("{} needs your username and password.", request.ssid)
+    };
+    let label = Label::new(Some(&message));
+    label.set_halign(Align::Start);
+    label.set_wrap(true);
+    let identity = Entry::new();
+    identity.set_placeholder_text(Some("Username"));
+    identity.set_text(request.identity.as_deref().unwrap_or_default());
+    identity.add_css_class("yufi-entry");
+    let password = Entry::new();
+    password.set_visibility(false);
+    password.set_placeholder_text(Some("Password"));
+    password.add_css_class("yufi-entry");
+    if request.retry {
+        password.add_css_class("yufi-entry-error");
+    }
+    if request.identity.is_some() {
+        password.grab_focus();
+    } else {
+        identity.grab_focus();
+    }
+    box_.append(&label);
+    box_.append(&identity);
+    box_.append(&password);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    actions.set_hexpand(true);
+    let cancel_button = Button::with_label("Cancel");
+    cancel_button.set_hexpand(true);
+    cancel_button.set_halign(Align::Fill);
+    let sign_in_button = Button::with_label("Sign In");
+    sign_in_button.add_css_class("yufi-primary");
+    sign_in_button.add_css_class("suggested-action");
+    sign_in_button.set_hexpand(true);
+    sign_in_button.set_halign(Align::Fill);
+    actions.append(&cancel_button);
+    actions.append(&sign_in_button);
+    box_.append(&actions);
+    dialog.content_area().append(&box_);
+    dialog.set_default_widget(Some(&sign_in_button));
+    let sign_in_activate = sign_in_button.clone();
+    password.connect_activate(move |_| sign_in_activate.emit_clicked());
+
+    let request_sign_in = request.clone();
+    let dialog_sign_in = dialog.clone();
+    sign_in_button.connect_clicked(move |_| {
+        request_sign_in.answer(Some(Credentials {
+            identity: identity.text().trim().to_string(),
+            password: password.text().to_string(),
+        }));
+        dialog_sign_in.close();
+    });
+    let dialog_cancel = dialog.clone();
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
+    // Closing the dialog any other way gives up too; after an answer this is a no-op.
+    let request_close = request.clone();
+    dialog.connect_close_request(move |_| {
+        request_close.answer(None);
+        Propagation::Proceed
+    });
+    let dialog_watch = dialog.downgrade();
+    gtk4::glib::timeout_add_local(Duration::from_millis(500), move || {
+        let Some(dialog) = dialog_watch.upgrade() else {
+            return ControlFlow::Break;
+        };
+        if !request.is_closed() {
+            return ControlFlow::Continue;
+        }
+        dialog.close();
+        ControlFlow::Break
+    });
+    dialog.present();
+}
+
+fn build_access_point_section(info: &AccessPointInfo) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.add_css_class("yufi-ap-info");
+
+    let heading = Label::new(Some("Security & capabilities"));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    section.append(&heading);
+
+    let security = if info.key_mgmt.is_empty() {
+        "Open".to_string()
+    } else {
+        info.key_mgmt.join(", ")
+    };
+    let ciphers = if info.pairwise.is_empty() && info.group.is_empty() {
+        "None".to_string()
+    } else {
+        format!("Pairwise {} · Group {}", info.pairwise.join("/"), info.group.join("/"))
+    };
+    let rows = [
+        ("Security", security),
+        ("Ciphers", ciphers),
+        ("WPS", if info.wps { "Supported" } else { "Not advertised" }.to_string()),
+        ("Frequency", format!("{} MHz", info.frequency_mhz)),
+        ("Max bitrate", format!("{} Mbit/s", info.max_bitrate_kbps / 1000)),
+        ("Standard", info.generation.to_string()),
+    ];
+    for (name, value) in rows {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        let name_label = Label::new(Some(name));
+        name_label.add_css_class("dim-label");
+        name_label.set_halign(Align::Start);
+        name_label.set_hexpand(true);
+        let value_label = Label::new(Some(&value));
+        value_label.set_halign(Align::End);
+        value_label.set_wrap(true);
+        value_label.set_selectable(true);
+        row.append(&name_label);
+        row.append(&value_label);
+        section.append(&row);
+    }
+    section
+}
+
+/// Live link rate, transmit power and station statistics of the active
+/// connection, read by the worker every `LINK_POLL_SECS` while the dialog is
+/// open.
+fn build_link_section(worker: Worker) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.add_css_class("yufi-ap-info");
+
+    let heading = Label::new(Some("Link"));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    section.append(&heading);
+
+    let add_row = |name: &str| {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        let name_label = Label::new(Some(name));
+        name_label.add_css_class("dim-label");
+        name_label.set_halign(Align::Start);
+        name_label.set_hexpand(true);
+        let value_label = Label::new(None);
+        value_label.set_halign(Align::End);
+        row.append(&name_label);
+        row.append(&value_label);
+        section.append(&row);
+        value_label
+    };
+    let rate = add_row("Link rate");
+    let power = add_row("TX power");
+    #[cfg(feature = "netlink")]
+    let station = netlink::rows(None).map(|(name, _)| add_row(name));
+    let hint = Label::new(Some(
+        "The speed of the radio link to the access point, which follows the signal. \
+         Your internet connection can be much slower.",
+    ));
+    hint.add_css_class("dim-label");
+    hint.set_wrap(true);
+    hint.set_xalign(0.0);
+    section.append(&hint);
+
+    let pending = Rc::new(Cell::new(false));
+    let refresh = move || {
+        // The last reading stays shown until the one on its way arrives.
+        if pending.replace(true) {
+            return;
+        }
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::LoadLink { reply });
+        let pending = pending.clone();
+        let (rate, power) = (rate.clone(), power.clone());
+        #[cfg(feature = "netlink")]
+        let station = station.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let result = answer.recv().await;
+            pending.set(false);
+            let Ok(result) = result else {
+                return;
+            };
+            let Ok(reading) = result else {
+                rate.set_text("Unknown");
+                power.set_text("Unknown");
+                #[cfg(feature = "netlink")]
+                station.iter().for_each(|label| label.set_text("Unknown"));
+                return;
+            };
+            #[cfg(feature = "netlink")]
+            for (label, (_, value)) in station.iter().zip(netlink::rows(reading.station.as_ref())) {
+                label.set_text(&value);
+            }
+            rate.set_text(&link::format_bitrate(reading.link.bitrate_kbps));
+            power.set_text(&reading.tx_power_dbm.map_or_else(
+                || "Not reported".to_string(),
+                |dbm| format!("{dbm:.0} dBm"),
+            ));
+        });
+    };
+    refresh();
+    let section_weak = section.downgrade();
+    gtk4::glib::timeout_add_seconds_local(LINK_POLL_SECS, move || {
+        // Stops once the dialog, and with it the section, is gone.
+        if section_weak.upgrade().is_none_or(|section| section.root().is_none()) {
+            return ControlFlow::Break;
+        }
+        refresh();
+        ControlFlow::Continue
+    });
+    section
+}
+
+/// Live round-trip time to the host set in Preferences, with the jitter and
+/// loss over the last probes, so an unsteady link shows while it happens.
+fn build_latency_section(target: String) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.add_css_class("yufi-ap-info");
+
+    let heading = Label::new(Some(&format!("Latency to {target}")));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    heading.set_wrap(true);
+    section.append(&heading);
+
+    let add_row = |name: &str| {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        let name_label = Label::new(Some(name));
+        name_label.add_css_class("dim-label");
+        name_label.set_halign(Align::Start);
+        name_label.set_hexpand(true);
+        let value_label = Label::new(Some("Measuring…"));
+        value_label.set_halign(Align::End);
+        row.append(&name_label);
+        row.append(&value_label);
+        section.append(&row);
+        value_label
+    };
+    let (round_trip, jitter, loss) = (add_row("Round trip"), add_row("Jitter"), add_row("Packet loss"));
+
+    let (tx, rx) = async_channel::bounded(1);
+    thread::spawn(move || {
+        // Ends once the dialog is gone and nobody receives any more.
+        while tx.send_blocking(latency::probe(&target)).is_ok() {
+            thread::sleep(LATENCY_PROBE_INTERVAL);
+        }
+    });
+    let section_weak = section.downgrade();
+    gtk4::glib::spawn_future_local(async move {
+        let mut window = latency::LatencyWindow::default();
+        while let Ok(sample) = rx.recv().await {
+            if section_weak.upgrade().is_none_or(|section| section.root().is_none()) {
+                break;
+            }
+            window.push(sample);
+            let Some(summary) = window.summary() else {
+                continue;
+            };
+            let ms = |value: Option<u64>| value.map_or_else(|| "No answer".to_string(), |ms| format!("{ms} ms"));
+            round_trip.set_text(&match (summary.last_ms, summary.average_ms) {
+                (Some(last), Some(average)) => format!("{last} ms (average {average} ms)"),
+                (last, _) => ms(last),
+            });
+            jitter.set_text(&summary.jitter_ms.map_or_else(|| "Measuring…".to_string(), |ms| format!("{ms} ms")));
+            loss.set_text(&format!("{}% of the last {}", summary.loss_percent, summary.probes));
+        }
+    });
+    section
+}
+
+/// Switch for keeping a profile to the current account on this machine's
+/// Wi‑Fi device, so personal credentials do not connect for other users.
+fn build_personal_row(
+    ssid: &str,
+    personal: bool,
+    worker: Worker,
+    status: StatusHandler,
+    parent: &ApplicationWindow,
+) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let label = Label::new(Some("Connect automatically only for me on this device"));
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_wrap(true);
+    label.set_tooltip_text(Some(
+        "Other accounts on this computer cannot see or use the network, and it only connects \
+         through the current Wi‑Fi adapter",
+    ));
+    let switch = Switch::new();
+    switch.set_valign(Align::Center);
+    switch.set_active(personal);
+    row.append(&label);
+    row.append(&switch);
+
+    let ssid = ssid.to_string();
+    let parent = parent.clone();
+    // The switch only flips once the profile is written; `reverting` keeps the
+    // rollback after a failure from writing it again.
+    let reverting = Rc::new(Cell::new(false));
+    switch.connect_state_set(move |switch, state| {
+        if reverting.get() {
+            return Propagation::Proceed;
+        }
+        switch.set_sensitive(false);
+        let user = gtk4::glib::user_name().to_string_lossy().into_owned();
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::SetPersonal {
+            ssid: ssid.clone(),
+            user: state.then(|| user.clone()),
+            reply,
+        });
+        let (switch, reverting) = (switch.clone(), reverting.clone());
+        let (ssid, status, parent) = (ssid.clone(), status.clone(), parent.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = answer.recv().await else {
+                return;
+            };
+            switch.set_sensitive(true);
+            match result {
+                Ok(()) => {
+                    switch.set_state(state);
+                    let change = if state {
+                        format!("{ssid} now only connects for {user}")
+                    } else {
+                        format!("{ssid} is shared with every account again")
+                    };
+                    status(StatusKind::Success, change);
+                }
+                Err(err) => {
+                    reverting.set(true);
+                    switch.set_active(!state);
+                    reverting.set(false);
+                    status(
+                        StatusKind::Error,
+                        format!("Failed to change who can use {ssid}: {}", friendly_error(&err)),
+                    );
+                    explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                }
+            }
+        });
+        Propagation::Stop
+    });
+    row
+}
+
+/// Tags the network as trusted or untrusted and writes the settings that go
+/// with the tag to its profile. "Not set" only drops the tag.
+fn build_trust_row(
+    ssid: &str,
+    worker: Worker,
+    data: Rc<RefCell<AppData>>,
+    status: StatusHandler,
+    parent: &ApplicationWindow,
+) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let label = Label::new(Some("Trust"));
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_tooltip_text(Some(
+        "Untrusted networks get a random MAC address, the firewall's public zone, no mDNS and are \
+         treated as metered. Trusted ones use the real MAC address, the default zone and mDNS.",
+    ));
+    let mut names = vec!["Not set"];
+    names.extend(trust::NetworkTrust::ALL.map(trust::NetworkTrust::label));
+    let choice = gtk4::DropDown::from_strings(&names);
+    let current = data.borrow().trust.get(ssid).copied();
+    let position = trust::NetworkTrust::ALL.iter().position(|trust| Some(*trust) == current);
+    choice.set_selected(position.map_or(0, |position| position as u32 + 1));
+    row.append(&label);
+    row.append(&choice);
+
+    let ssid = ssid.to_string();
+    let parent = parent.clone();
+    choice.connect_selected_notify(move |choice| {
+        let trust = (choice.selected() as usize)
+            .checked_sub(1)
+            .and_then(|index| trust::NetworkTrust::ALL.get(index).copied());
+        let Some(trust) = trust else {
+            record_trust(&data, &ssid, None, &status);
+            return;
+        };
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::ApplyTrust {
+            ssid: ssid.clone(),
+            trust,
+            reply,
+        });
+        let (ssid, data, status, parent) = (ssid.clone(), data.clone(), status.clone(), parent.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(applied) = answer.recv().await else {
+                return;
+            };
+            match applied {
+                Ok(()) => record_trust(&data, &ssid, Some(trust), &status),
+                Err(err) => {
+                    status(
+                        StatusKind::Error,
+                        format!("Failed to apply the trust settings: {}", friendly_error(&err)),
+                    );
+                    explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                }
+            }
+        });
+    });
+    row
+}
+
+/// Saves the trust tag of `ssid` once its settings are written.
+fn record_trust(data: &RefCell<AppData>, ssid: &str, trust: Option<trust::NetworkTrust>, status: &StatusHandler) {
+    let mut data = data.borrow_mut();
+    match trust {
+        Some(trust) => {
+            data.trust.insert(ssid.to_string(), trust);
+        }
+        None => {
+            data.trust.remove(ssid);
+        }
+    }
+    if let Err(err) = data.save() {
+        status(StatusKind::Error, format!("Failed to save the trust tag: {err}"));
+    } else if let Some(trust) = trust {
+        status(
+            StatusKind::Success,
+            format!("{ssid} is now {}. Reconnect to apply.", trust.label().to_lowercase()),
+        );
+    }
+}
+
+/// The address fields of the details dialog a template fills in.
+struct AddressFields {
+    ip: Entry,
+    gateway: Entry,
+    dns: Entry,
+    dhcp: Switch,
+    link_local: Switch,
+}
+
+/// Picks a saved IP, DNS and proxy template to fill in, or saves the current
+/// fields as one. The proxy of an applied template is left in `pending_proxy`
+/// for Save to write along with the addresses; until one is, a saved template
+/// takes the profile's `saved_proxy`.
+fn build_template_row(
+    saved_proxy: Rc<RefCell<String>>,
+    data: Rc<RefCell<AppData>>,
+    status: StatusHandler,
+    fields: AddressFields,
+    pending_proxy: Rc<RefCell<Option<String>>>,
+) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let label = Label::new(Some("Template"));
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_tooltip_text(Some("Fill in saved IP, DNS and proxy settings instead of retyping them"));
+    let choice = gtk4::DropDown::from_strings(&["No templates"]);
+    let apply_button = Button::with_label("Apply");
+    let save_button = Button::with_label("Save as…");
+    save_button.set_tooltip_text(Some("Save these IP, DNS and proxy settings as a template"));
+    row.append(&label);
+    row.append(&choice);
+    row.append(&apply_button);
+    row.append(&save_button);
+
+    fill_template_choice(&choice, &apply_button, &data.borrow(), None);
+
+    let fields = Rc::new(fields);
+    let (data_apply, fields_apply, status_apply) = (data.clone(), fields.clone(), status.clone());
+    let (choice_apply, pending_apply) = (choice.clone(), pending_proxy.clone());
+    apply_button.connect_clicked(move |_| {
+        let Some(template) = data_apply.borrow().templates.get(choice_apply.selected() as usize).cloned() else {
+            return;
+        };
+        fields_apply.link_local.set_active(false);
+        fields_apply.dhcp.set_active(template.uses_dhcp());
+        fields_apply.ip.set_text(&template.address);
+        fields_apply.gateway.set_text(&template.gateway);
+        fields_apply.dns.set_text(&template.dns);
+        pending_apply.replace(Some(template.proxy_pac_url));
+        status_apply(StatusKind::Info, format!("Filled in {}. Save to apply.", template.name));
+    });
+
+    save_button.connect_clicked(move |button| {
+        let Some(owner) = button.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+        let use_manual = !fields.dhcp.is_active() && !fields.link_local.is_active();
+        let field = |entry: &Entry| if use_manual { entry.text().trim().to_string() } else { String::new() };
+        let proxy_pac_url = pending_proxy.borrow().clone().unwrap_or_else(|| saved_proxy.borrow().clone());
+        let template = templates::ProfileTemplate {
+            name: String::new(),
+            address: field(&fields.ip),
+            gateway: field(&fields.gateway),
+            dns: field(&fields.dns),
+            proxy_pac_url,
+        };
+        let (data, status) = (data.clone(), status.clone());
+        let (choice, apply_button) = (choice.clone(), apply_button.clone());
+        ask_template_name(&owner, move |name| {
+            let mut saved = data.borrow_mut();
+            templates::save(&mut saved.templates, templates::ProfileTemplate { name: name.clone(), ..template.clone() });
+            let result = saved.save();
+            fill_template_choice(&choice, &apply_button, &saved, Some(&name));
+            match result {
+                Ok(()) => status(StatusKind::Success, format!("Saved the template {name}")),
+                Err(err) => status(StatusKind::Error, format!("Failed to save the template: {err}")),
+            }
+        });
+    });
+    row
+}
+
+/// Lists the saved templates in `choice`, selecting `selected` when given.
+fn fill_template_choice(choice: &gtk4::DropDown, apply_button: &Button, data: &AppData, selected: Option<&str>) {
+    let names: Vec<&str> = data.templates.iter().map(|template| template.name.as_str()).collect();
+    let empty = names.is_empty();
+    choice.set_model(Some(&gtk4::StringList::new(if empty { &["No templates"] } else { &names })));
+    let position = selected.and_then(|selected| names.iter().position(|name| *name == selected));
+    choice.set_selected(position.unwrap_or(0) as u32);
+    choice.set_sensitive(!empty);
+    apply_button.set_sensitive(!empty);
+}
+
+/// Asks what to call a new template. Reusing a name replaces that template.
+fn ask_template_name(parent: &impl IsA<gtk4::Window>, on_submit: impl Fn(String) + 'static) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Save as Template"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(340);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let label = Label::new(Some("Name of the template"));
+    label.set_halign(Align::Start);
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("e.g. Lab static"));
+    entry.add_css_class("yufi-entry");
+    box_.append(&label);
+    box_.append(&entry);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let cancel_button = Button::with_label("Cancel");
+    let ok_button = Button::with_label("Save");
+    ok_button.add_css_class("suggested-action");
+    for button in [&cancel_button, &ok_button] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
+    box_.append(&actions);
+    content.append(&box_);
+    dialog.set_default_widget(Some(&ok_button));
+    let ok_activate = ok_button.clone();
+    entry.connect_activate(move |_| ok_activate.emit_clicked());
+
+    let dialog_ok = dialog.clone();
+    ok_button.connect_clicked(move |_| {
+        let name = entry.text().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        dialog_ok.close();
+        on_submit(name);
+    });
+
+    let dialog_cancel = dialog.clone();
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
+    dialog.present();
+}
+
+/// Which VPN to bring up whenever the network connects. The choice is saved
+/// as soon as it changes; the row stays hidden without any VPN profiles.
+fn build_vpn_rule_row(ssid: &str, worker: &Worker, data: Rc<RefCell<AppData>>, status: StatusHandler) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let label = Label::new(Some("VPN on connect"));
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_tooltip_text(Some("Connects this VPN whenever YuFi sees you join the network"));
+    let choice = gtk4::DropDown::from_strings(&["Loading…"]);
+    choice.set_sensitive(false);
+    row.append(&label);
+    row.append(&choice);
+
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::LoadVpns { reply });
+    let ssid = ssid.to_string();
+    let row_vpn = row.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        let vpns = result.unwrap_or_default();
+        let rule = data.borrow().vpn_rules.get(&ssid).cloned();
+        if vpns.is_empty() && rule.is_none() {
+            row_vpn.set_visible(false);
+            return;
+        }
+        let mut names = vec!["None"];
+        let mut uuids = vec![None];
+        for vpn in &vpns {
+            names.push(vpn.id.as_str());
+            uuids.push(Some(vpn.uuid.clone()));
+        }
+        // A rule for a VPN deleted since stays listed so it can be turned off.
+        if rule.is_some() && !uuids.contains(&rule) {
+            names.push("Deleted VPN");
+            uuids.push(rule.clone());
+        }
+        choice.set_model(Some(&gtk4::StringList::new(&names)));
+        choice.set_selected(uuids.iter().position(|uuid| *uuid == rule).unwrap_or(0) as u32);
+        choice.set_sensitive(true);
+        choice.connect_selected_notify(move |choice| {
+            let Some(uuid) = uuids.get(choice.selected() as usize) else {
+                return;
+            };
+            let mut data = data.borrow_mut();
+            match uuid {
+                Some(uuid) => {
+                    data.vpn_rules.insert(ssid.clone(), uuid.clone());
+                }
+                None => {
+                    data.vpn_rules.remove(&ssid);
+                }
+            }
+            if let Err(err) = data.save() {
+                status(StatusKind::Error, format!("Failed to save the VPN choice: {err}"));
+            }
+        });
+    });
+    row
+}
+
+/// The certificates of an 802.1X profile. Chosen files are checked with
+/// openssl, and again when Apply writes them to the profile.
+fn build_certificate_section(
+    dialog: &Dialog,
+    ssid: &str,
+    certificates: EnterpriseCertificates,
+    worker: Worker,
+    status: StatusHandler,
+    parent: &ApplicationWindow,
+) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 6);
+    section.add_css_class("yufi-ap-info");
+    let heading = Label::new(Some("Certificates"));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    section.append(&heading);
+
+    let warning = Label::new(Some(AuditFinding::NoCaCertificate.advice()));
+    warning.add_css_class("yufi-weak-security");
+    warning.set_halign(Align::Start);
+    warning.set_xalign(0.0);
+    warning.set_wrap(true);
+    warning.set_visible(!certificates.verifies_server());
+    section.append(&warning);
+
+    let stored = certificates.clone();
+    let chosen = Rc::new(RefCell::new(certificates));
+    let update_warning = {
+        let (chosen, warning) = (chosen.clone(), warning.clone());
+        Rc::new(move || warning.set_visible(!chosen.borrow().verifies_server()))
+    };
+    let picker = |title: &'static str, current: Option<String>, set: fn(&mut EnterpriseCertificates, Option<String>)| {
+        let (chosen, update_warning) = (chosen.clone(), update_warning.clone());
+        let inspect = title != "Private key";
+        build_certificate_picker(dialog, title, current, inspect, move |path| {
+            set(&mut chosen.borrow_mut(), path);
+            update_warning();
+        })
+    };
+    let current = chosen.borrow().clone();
+    section.append(&picker("CA certificate", current.ca_cert, |certificates, path| {
+        certificates.ca_cert = path;
+    }));
+
+    let system_row = GtkBox::new(Orientation::Horizontal, 8);
+    let system_label = Label::new(Some("Also trust the system's CAs"));
+    system_label.add_css_class("dim-label");
+    system_label.set_halign(Align::Start);
+    system_label.set_hexpand(true);
+    let system_switch = Switch::builder().active(current.system_ca_certs).build();
+    system_switch.set_valign(Align::Center);
+    let (chosen_system, update_system) = (chosen.clone(), update_warning.clone());
+    system_switch.connect_active_notify(move |switch| {
+        chosen_system.borrow_mut().system_ca_certs = switch.is_active();
+        update_system();
+    });
+    system_row.append(&system_label);
+    system_row.append(&system_switch);
+    section.append(&system_row);
+
+    section.append(&picker("Client certificate", current.client_cert, |certificates, path| {
+        certificates.client_cert = path;
+    }));
+    section.append(&picker("Private key", current.private_key, |certificates, path| {
+        certificates.private_key = path;
+    }));
+    let key_password = Entry::new();
+    key_password.set_visibility(false);
+    key_password.set_placeholder_text(Some("Private key password (unchanged)"));
+    section.append(&key_password);
+
+    let apply = Button::with_label("Apply Certificates");
+    apply.set_halign(Align::End);
+    let ssid = ssid.to_string();
+    let parent = parent.clone();
+    apply.connect_clicked(move |button| {
+        let mut certificates = chosen.borrow().clone();
+        let password = key_password.text().to_string();
+        // A PKCS #12 bundle is both the certificate and the key.
+        if certificates.private_key.is_none() {
+            let bundle = certificates
+                .client_cert
+                .as_deref()
+                .and_then(certificate_file)
+                .filter(|path| certificate::is_pkcs12(path));
+            certificates.private_key = bundle.map(|path| path.display().to_string());
+        }
+        if let Err(message) = check_certificates(&certificates, &stored, &password) {
+            status(StatusKind::Error, message);
+            return;
+        }
+        button.set_sensitive(false);
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::SetCertificates {
+            ssid: ssid.clone(),
+            certificates,
+            key_password: (!password.is_empty()).then_some(password),
+            reply,
+        });
+        let (button, key_password) = (button.clone(), key_password.clone());
+        let (ssid, status, parent) = (ssid.clone(), status.clone(), parent.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = answer.recv().await else {
+                return;
+            };
+            button.set_sensitive(true);
+            match result {
+                Ok(()) => {
+                    key_password.set_text("");
+                    status(StatusKind::Success, format!("Saved the certificates of {ssid}"));
+                }
+                Err(err) => {
+                    status(
+                        StatusKind::Error,
+                        format!("Failed to save the certificates: {}", friendly_error(&err)),
+                    );
+                    explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                }
+            }
+        });
+    });
+    section.append(&apply);
+    section
+}
+
+/// A certificate slot: the chosen file, with what openssl makes of it when
+/// `inspect` is set, and buttons to choose another or clear it.
+fn build_certificate_picker(
+    dialog: &Dialog,
+    title: &'static str,
+    current: Option<String>,
+    inspect: bool,
+    on_change: impl Fn(Option<String>) + 'static,
+) -> GtkBox {
+    let picker = GtkBox::new(Orientation::Vertical, 2);
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let name = Label::new(Some(title));
+    name.add_css_class("dim-label");
+    name.set_halign(Align::Start);
+    let value = Label::new(None);
+    value.set_halign(Align::Start);
+    value.set_hexpand(true);
+    value.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+    let choose = Button::with_label("Choose…");
+    choose.add_css_class("flat");
+    let clear = Button::from_icon_name("edit-clear-symbolic");
+    clear.add_css_class("flat");
+    clear.set_tooltip_text(Some("Remove"));
+    row.append(&name);
+    row.append(&value);
+    row.append(&choose);
+    row.append(&clear);
+    let detail = Label::new(None);
+    detail.add_css_class("dim-label");
+    detail.set_halign(Align::Start);
+    detail.set_xalign(0.0);
+    detail.set_wrap(true);
+    picker.append(&row);
+    picker.append(&detail);
+
+    let show = {
+        let (value, detail, clear) = (value.clone(), detail.clone(), clear.clone());
+        Rc::new(move |path: Option<&str>| {
+            let file = path.and_then(certificate_file);
+            value.set_text(match (path, file) {
+                (None, _) => "None",
+                (Some(_), Some(file)) => file.file_name().and_then(|name| name.to_str()).unwrap_or_default(),
+                (Some(other), None) => other,
+            });
+            value.set_tooltip_text(path);
+            clear.set_visible(path.is_some());
+            let (text, attention) = match file {
+                Some(file) if inspect && !certificate::is_pkcs12(file) => {
+                    match certificate::inspect(file) {
+                        Ok(info) => {
+                            let now = survey::now_secs();
+                            (info.summary(now), info.needs_attention(now))
+                        }
+                        Err(message) => (message, true),
+                    }
+                }
+                _ => (String::new(), false),
+            };
+            detail.set_text(&text);
+            detail.set_visible(!text.is_empty());
+            if attention {
+                detail.add_css_class("yufi-weak-security");
+            } else {
+                detail.remove_css_class("yufi-weak-security");
+            }
+        })
+    };
+    show(current.as_deref());
+
+    let on_change = Rc::new(on_change);
+    let (show_clear, on_clear) = (show.clone(), on_change.clone());
+    clear.connect_clicked(move |_| {
+        show_clear(None);
+        on_clear(None);
+    });
+    let dialog = dialog.clone();
+    choose.connect_clicked(move |_| {
+        let (show, on_change) = (show.clone(), on_change.clone());
+        choose_certificate_file(&dialog, title, move |path| {
+            let path = path.display().to_string();
+            show(Some(&path));
+            on_change(Some(path));
+        });
+    });
+    picker
+}
+
+/// The file a stored certificate refers to; None for one kept in the profile
+/// or on a smart card.
+fn certificate_file(value: &str) -> Option<&Path> {
+    (value != EMBEDDED_CERTIFICATE && !value.starts_with("pkcs11:")).then(|| Path::new(value))
+}
+
+/// Refuses certificates NetworkManager would fail to authenticate with:
+/// unreadable or expired ones, and keys the password does not unlock. A key
+/// left as `stored` without a new password keeps the password it has.
+fn check_certificates(
+    certificates: &EnterpriseCertificates,
+    stored: &EnterpriseCertificates,
+    key_password: &str,
+) -> Result<(), String> {
+    if certificates.client_cert.is_some() != certificates.private_key.is_some() {
+        return Err("Choose both a client certificate and its private key, or neither".to_string());
+    }
+    let now = survey::now_secs();
+    for value in [&certificates.ca_cert, &certificates.client_cert].into_iter().flatten() {
+        let Some(file) = certificate_file(value).filter(|file| !certificate::is_pkcs12(file)) else {
+            continue;
+        };
+        let info = certificate::inspect(file)?;
+        if info.is_expired(now) {
+            return Err(format!("{} has expired", info.name()));
+        }
+    }
+    if key_password.is_empty() && certificates.private_key == stored.private_key {
+        return Ok(());
+    }
+    match certificates.private_key.as_deref().and_then(certificate_file) {
+        Some(key) => certificate::check_private_key(key, key_password),
+        None => Ok(()),
+    }
+}
+
+/// Lets the user pick a certificate or key file.
+fn choose_certificate_file(parent: &Dialog, title: &str, on_chosen: impl Fn(PathBuf) + 'static) {
+    let chooser = FileChooserNative::new(
+        Some(&format!("Choose {title}")),
+        Some(parent),
+        FileChooserAction::Open,
+        Some("Choose"),
+        Some("Cancel"),
+    );
+    chooser.set_modal(true);
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Certificates and keys"));
+    for pattern in ["*.pem", "*.crt", "*.cer", "*.der", "*.key", "*.p12", "*.pfx"] {
+        filter.add_pattern(pattern);
+    }
+    chooser.add_filter(&filter);
+    let everything = gtk4::FileFilter::new();
+    everything.set_name(Some("All files"));
+    everything.add_pattern("*");
+    chooser.add_filter(&everything);
+    // Held until answered, like the backup chooser.
+    let holder = Rc::new(RefCell::new(None));
+    let holder_response = holder.clone();
+    chooser.connect_response(move |chooser, response| {
+        let chosen = chooser.file().and_then(|file| file.path());
+        if let Some(path) = chosen.filter(|_| response == ResponseType::Accept) {
+            on_chosen(path);
+        }
+        holder_response.borrow_mut().take();
+    });
+    chooser.show();
+    holder.replace(Some(chooser));
+}
+
+/// Runs the throughput test on the active network and lists its earlier results.
+fn build_speed_test_section(
+    ssid: &str,
+    data: Rc<RefCell<AppData>>,
+    preferences: Rc<RefCell<Preferences>>,
+) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.add_css_class("yufi-ap-info");
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let heading = Label::new(Some("Speed"));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    heading.set_hexpand(true);
+    let spinner = Spinner::new();
+    spinner.set_visible(false);
+    let test_button = Button::with_label("Test Speed");
+    test_button.set_tooltip_text(Some(
+        "Downloads and then uploads for up to 10 seconds each, through the server set in Preferences.",
+    ));
+    header.append(&heading);
+    header.append(&spinner);
+    header.append(&test_button);
+    let results = Label::new(None);
+    results.set_halign(Align::Start);
+    results.set_xalign(0.0);
+    results.set_wrap(true);
+    results.set_selectable(true);
+    section.append(&header);
+    section.append(&results);
+
+    let ssid = ssid.to_string();
+    let render = {
+        let (data, results, ssid) = (data.clone(), results.clone(), ssid.clone());
+        Rc::new(move |problem: Option<String>| {
+            let now = survey::now_secs();
+            let data = data.borrow();
+            let mut lines: Vec<String> = problem.into_iter().collect();
+            lines.extend(data.speed_tests.get(&ssid).into_iter().flatten().rev().map(|result| {
+                format!(
+                    "{} · ↓ {} · ↑ {}",
+                    usage::relative_time(result.tested_at, now),
+                    link::format_bitrate(result.download_kbps),
+                    link::format_bitrate(result.upload_kbps)
+                )
+            }));
+            if lines.is_empty() {
+                lines.push("Not tested on this network yet.".to_string());
+            }
+            results.set_text(&lines.join("\n"));
+        })
+    };
+    render(None);
+
+    test_button.connect_clicked(move |button| {
+        button.set_sensitive(false);
+        spinner.set_visible(true);
+        spinner.start();
+        results.set_text("Testing download and upload…");
+        let server = preferences.borrow().speed_test_server.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        thread::spawn(move || {
+            let _ = tx.send_blocking(speed_test::run(&server, survey::now_secs()));
+        });
+        let (button, spinner, data, ssid, render) =
+            (button.clone(), spinner.clone(), data.clone(), ssid.clone(), render.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = rx.recv().await else {
+                return;
+            };
+            spinner.stop();
+            spinner.set_visible(false);
+            button.set_sensitive(true);
+            match result {
+                Ok(result) => {
+                    let mut data = data.borrow_mut();
+                    speed_test::record(&mut data.speed_tests, &ssid, result);
+                    data.save()
+                        .unwrap_or_else(|err| eprintln!("yufi: failed to save speed test: {err}"));
+                    drop(data);
+                    render(None);
+                }
+                Err(err) => render(Some(format!("Speed test failed: {err}"))),
+            }
+        });
+    });
+    section
+}
+
+fn build_dhcp_section(leases: &DhcpLeases) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.set_margin_top(4);
+    let families = [("IPv4", leases.ipv4.as_ref()), ("IPv6", leases.ipv6.as_ref())];
+    if families.iter().all(|(_, lease)| lease.is_none()) {
+        let label = Label::new(Some("This connection was not configured through DHCP."));
+        label.add_css_class("dim-label");
+        label.set_halign(Align::Start);
+        section.append(&label);
+        return section;
+    }
+    for (family, lease) in families {
+        let Some(lease) = lease else {
+            continue;
+        };
+        let heading = Label::new(Some(family));
+        heading.add_css_class("yufi-network-name");
+        heading.set_halign(Align::Start);
+        section.append(&heading);
+        for (name, value) in dhcp_rows(lease) {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            let name_label = Label::new(Some(name));
+            name_label.add_css_class("dim-label");
+            name_label.set_halign(Align::Start);
+            name_label.set_hexpand(true);
+            let value_label = Label::new(Some(&value));
+            value_label.set_halign(Align::End);
+            value_label.set_wrap(true);
+            value_label.set_selectable(true);
+            row.append(&name_label);
+            row.append(&value_label);
+            section.append(&row);
+        }
+        let raw = lease
+            .options
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let options = Expander::new(Some("All options"));
+        let raw_label = Label::new(Some(&raw));
+        raw_label.set_halign(Align::Start);
+        raw_label.set_xalign(0.0);
+        raw_label.set_wrap(true);
+        raw_label.set_selectable(true);
+        options.set_child(Some(&raw_label));
+        section.append(&options);
+    }
+    section
+}
+
+/// A "Name: value" line with a button that copies the value.
+fn build_copy_row(name: &str, value: &str) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 4);
+    let label = Label::new(Some(&format!("{name}: {value}")));
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_xalign(0.0);
+    label.set_wrap(true);
+    label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+    label.set_selectable(true);
+    let copy = Button::from_icon_name("edit-copy-symbolic");
+    copy.add_css_class("flat");
+    copy.add_css_class("yufi-icon-button");
+    copy.set_valign(Align::Center);
+    copy.set_tooltip_text(Some(&format!("Copy {name}")));
+    let value = value.to_string();
+    copy.connect_clicked(move |button| button.clipboard().set_text(&value));
+    row.append(&label);
+    row.append(&copy);
+    row
+}
+
+/// Has the worker read the profile again and shows its settings above the
+/// editor once they arrive.
+fn fill_advanced_section(
+    expander: &Expander,
+    backend: &Rc<NetworkManagerBackend>,
+    worker: &Worker,
+    ssid: &str,
+    status: &StatusHandler,
+    parent: &ApplicationWindow,
+) {
+    if expander.child().is_none() {
+        let loading = Label::new(Some("Reading the profile…"));
+        loading.add_css_class("dim-label");
+        loading.set_halign(Align::Start);
+        expander.set_child(Some(&loading));
+    }
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::LoadRawSettings {
+        ssid: ssid.to_string(),
+        reply,
+    });
+    let (expander, backend, worker) = (expander.clone(), backend.clone(), worker.clone());
+    let (ssid, status, parent) = (ssid.to_string(), status.clone(), parent.clone());
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        match result {
+            Ok(settings) => show_advanced_section(&expander, &settings, &backend, &worker, &ssid, &status, &parent),
+            Err(err) => {
+                let label = Label::new(Some(&format!("Could not read the profile: {}", friendly_error(&err))));
+                label.add_css_class("dim-label");
+                label.set_halign(Align::Start);
+                label.set_wrap(true);
+                expander.set_child(Some(&label));
+            }
+        }
+    });
+}
+
+fn show_advanced_section(
+    expander: &Expander,
+    settings: &RawSettings,
+    backend: &Rc<NetworkManagerBackend>,
+    worker: &Worker,
+    ssid: &str,
+    status: &StatusHandler,
+    parent: &ApplicationWindow,
+) {
+    let section_entry = Entry::new();
+    section_entry.set_placeholder_text(Some("Section, e.g. ipv4"));
+    section_entry.set_hexpand(true);
+    let key_entry = Entry::new();
+    key_entry.set_placeholder_text(Some("Key"));
+    key_entry.set_hexpand(true);
+    let kinds: Vec<&str> = SettingKind::ALL.iter().map(|kind| kind.label()).collect();
+    let kind_choice = gtk4::DropDown::from_strings(&kinds);
+    kind_choice.set_valign(Align::Center);
+    let value_entry = Entry::new();
+    value_entry.set_placeholder_text(Some(SettingKind::ALL[0].hint()));
+    value_entry.set_hexpand(true);
+    let error_label = Label::new(None);
+    error_label.add_css_class("yufi-dialog-error");
+    error_label.set_halign(Align::Start);
+    error_label.set_wrap(true);
+    error_label.set_visible(false);
+
+    let section = build_raw_settings_section(settings, {
+        let (section_entry, key_entry, kind_choice, value_entry, error_label) = (
+            section_entry.clone(),
+            key_entry.clone(),
+            kind_choice.clone(),
+            value_entry.clone(),
+            error_label.clone(),
+        );
+        move |section, key, value| {
+            section_entry.set_text(section);
+            key_entry.set_text(key);
+            let kind = SettingKind::from_signature(&value.signature);
+            if let Some(index) = SettingKind::ALL.iter().position(|candidate| Some(*candidate) == kind) {
+                kind_choice.set_selected(index as u32);
+            }
+            value_entry.set_text(value.input.as_deref().unwrap_or_default());
+            error_label.set_visible(value.input.is_none());
+            error_label.set_text(&format!("YuFi cannot edit values of D-Bus type {}", value.signature));
+        }
+    });
+
+    let heading = Label::new(Some("Edit a setting"));
+    heading.add_css_class("yufi-network-name");
+    heading.set_halign(Align::Start);
+    heading.set_margin_top(8);
+    let hint = Label::new(Some(
+        "For options YuFi has no controls for. Click a key above to load it; NetworkManager checks the value when it is applied.",
+    ));
+    hint.add_css_class("dim-label");
+    hint.set_halign(Align::Start);
+    hint.set_xalign(0.0);
+    hint.set_wrap(true);
+    let name_row = GtkBox::new(Orientation::Horizontal, 8);
+    name_row.append(&section_entry);
+    name_row.append(&key_entry);
+    let value_row = GtkBox::new(Orientation::Horizontal, 8);
+    value_row.append(&kind_choice);
+    value_row.append(&value_entry);
+    let apply = Button::with_label("Apply");
+    apply.add_css_class("yufi-secondary");
+
+    let value_hint = value_entry.clone();
+    kind_choice.connect_selected_notify(move |choice| {
+        if let Some(kind) = SettingKind::ALL.get(choice.selected() as usize) {
+            value_hint.set_placeholder_text(Some(kind.hint()));
+        }
+    });
+    let error_clear = error_label.clone();
+    value_entry.connect_changed(move |entry| {
+        entry.remove_css_class("yufi-entry-error");
+        error_clear.set_visible(false);
+    });
+    let (expander_apply, backend_apply, worker_apply) = (expander.clone(), backend.clone(), worker.clone());
+    let (ssid_apply, status_apply, parent_apply) = (ssid.to_string(), status.clone(), parent.clone());
+    let error_apply = error_label.clone();
+    apply.connect_clicked(move |_| {
+        let (section, key) = (section_entry.text(), key_entry.text());
+        let (section, key) = (section.trim(), key.trim());
+        if section.is_empty() || key.is_empty() {
+            error_apply.set_text("Enter the section and the key to set");
+            error_apply.set_visible(true);
+            return;
+        }
+        let kind = SettingKind::ALL[(kind_choice.selected() as usize).min(SettingKind::ALL.len() - 1)];
+        let value = match kind.parse(&value_entry.text()) {
+            Ok(value) => value,
+            Err(err) => {
+                value_entry.add_css_class("yufi-entry-error");
+                error_apply.set_text(&err);
+                error_apply.set_visible(true);
+                return;
+            }
+        };
+        match backend_apply.set_raw_setting(&ssid_apply, section, key, &value) {
+            Ok(()) => {
+                status_apply(StatusKind::Success, format!("Set {section}.{key}"));
+                fill_advanced_section(
+                    &expander_apply,
+                    &backend_apply,
+                    &worker_apply,
+                    &ssid_apply,
+                    &status_apply,
+                    &parent_apply,
+                );
+            }
+            Err(err) => {
+                error_apply.set_text(&format!("Failed to set {section}.{key}: {}", friendly_error(&err)));
+                error_apply.set_visible(true);
+                explain_permission_error(&parent_apply, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            }
+        }
+    });
+
+    section.append(&heading);
+    section.append(&hint);
+    section.append(&name_row);
+    section.append(&value_row);
+    section.append(&error_label);
+    section.append(&apply);
+    expander.set_child(Some(&section));
+}
+
+/// The profile's settings map, one expander per section with a key/value
+/// grid. Values are in GVariant text form and can be selected for copying;
+/// clicking a key hands it to `on_pick`.
+fn build_raw_settings_section(
+    settings: &RawSettings,
+    on_pick: impl Fn(&str, &str, &RawValue) + 'static,
+) -> GtkBox {
+    let on_pick = Rc::new(on_pick);
+    let section = GtkBox::new(Orientation::Vertical, 4);
+    section.set_margin_top(4);
+    for (name, values) in settings {
+        let expander = Expander::new(Some(name));
+        let grid = gtk4::Grid::new();
+        grid.set_column_spacing(12);
+        grid.set_row_spacing(2);
+        grid.set_margin_start(12);
+        for (row, (key, value)) in (0..).zip(values) {
+            let key_label = Button::with_label(key);
+            key_label.add_css_class("flat");
+            key_label.add_css_class("dim-label");
+            key_label.set_halign(Align::Start);
+            key_label.set_valign(Align::Start);
+            key_label.set_tooltip_text(Some(&format!("D-Bus type {}", value.signature)));
+            let (on_pick, section, key, picked) = (on_pick.clone(), name.clone(), key.clone(), value.clone());
+            key_label.connect_clicked(move |_| on_pick(&section, &key, &picked));
+            let value_label = Label::new(Some(&value.text));
+            value_label.set_valign(Align::Center);
+            value_label.set_halign(Align::Start);
+            value_label.set_xalign(0.0);
+            value_label.set_hexpand(true);
+            value_label.set_wrap(true);
+            value_label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+            value_label.set_selectable(true);
+            grid.attach(&key_label, 0, row, 1, 1);
+            grid.attach(&value_label, 1, row, 1, 1);
+        }
+        expander.set_child(Some(&grid));
+        section.append(&expander);
+    }
+    section
+}
+
+fn dhcp_rows(lease: &DhcpLease) -> Vec<(&'static str, String)> {
+    let unknown = || "Not provided".to_string();
+    let ntp = lease.ntp_servers();
+    vec![
+        ("Server", lease.server().map_or_else(unknown, str::to_string)),
+        ("Lease time", lease.lease_time_secs().map_or_else(unknown, format_lease_time)),
+        ("Domain", lease.domain().map_or_else(unknown, str::to_string)),
+        ("NTP", if ntp.is_empty() { unknown() } else { ntp.join(", ") }),
+    ]
+}
+
+/// DHCP lease lengths; 0xffffffff is the protocol's "infinite".
+fn format_lease_time(secs: u64) -> String {
+    match secs {
+        secs if secs >= u64::from(u32::MAX) => "Infinite".to_string(),
+        secs if secs >= 86_400 && secs.is_multiple_of(86_400) => format!("{} d", secs / 86_400),
+        secs if secs >= 3_600 => format!("{} h {} min", secs / 3_600, secs % 3_600 / 60),
+        secs if secs >= 60 => format!("{} min", secs / 60),
+        secs => format!("{secs} s"),
+    }
+}
+
+fn show_analyzer_dialog(parent: &ApplicationWindow, worker: Worker) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Channel Analyzer"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(520);
+    dialog.set_resizable(true);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let access_points: Rc<RefCell<Vec<VisibleAccessPoint>>> = Rc::new(RefCell::new(Vec::new()));
+    let summary = Label::new(None);
+    summary.set_halign(Align::Start);
+    summary.set_wrap(true);
+
+    box_.append(&summary);
+    let mut charts = Vec::new();
+    for band in [Band::TwoPointFour, Band::Five] {
+        let heading = Label::new(Some(band.label()));
+        heading.add_css_class("yufi-network-name");
+        heading.set_halign(Align::Start);
+        let chart = gtk4::DrawingArea::new();
+        chart.set_content_height(160);
+        chart.set_hexpand(true);
+        let access_points_draw = access_points.clone();
+        chart.set_draw_func(move |_area, cr, width, height| {
+            let usage = analyzer::channel_usage(&access_points_draw.borrow(), band);
+            draw_channel_chart(cr, width as f64, height as f64, band, &usage);
+        });
+        box_.append(&heading);
+        box_.append(&chart);
+        charts.push(chart);
+    }
+    let ap_list = Label::new(None);
+    ap_list.set_halign(Align::Start);
+    ap_list.set_xalign(0.0);
+    ap_list.set_selectable(true);
+    ap_list.add_css_class("yufi-ap-info");
+    let ap_expander = Expander::new(Some("Access points"));
+    ap_expander.set_child(Some(&ap_list));
+    box_.append(&ap_expander);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let refresh_button = Button::with_label("Refresh");
+    refresh_button.set_hexpand(true);
+    refresh_button.set_halign(Align::Fill);
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+    actions.append(&refresh_button);
+    actions.append(&close_button);
+    box_.append(&actions);
+    content.append(&box_);
+
+    // Reading every access point takes a D-Bus round trip each, so the worker
+    // does it; the charts keep the last reading until the new one arrives.
+    let reload = Rc::new(move || {
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::LoadAccessPoints { reply });
+        let (summary, ap_list) = (summary.clone(), ap_list.clone());
+        let (access_points, charts) = (access_points.clone(), charts.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = answer.recv().await else {
+                return;
+            };
+            match result {
+                Ok(list) => {
+                    let channel = analyzer::recommended_24_channel(&list);
+                    summary.set_text(&format!(
+                        "{} access points visible. Least congested 2.4 GHz channel: {channel}.",
+                        list.len()
+                    ));
+                    ap_list.set_text(&access_point_lines(&list));
+                    *access_points.borrow_mut() = list;
+                }
+                Err(err) => {
+                    summary.set_text(&format!("Failed to read access points: {}", friendly_error(&err)));
+                    ap_list.set_text("");
+                    access_points.borrow_mut().clear();
+                }
+            }
+            for chart in &charts {
+                chart.queue_draw();
+            }
+        });
+    });
+    reload();
+
+    refresh_button.connect_clicked(move |_| reload());
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+/// One line per access point, strongest first, with the vendor so unknown or
+/// rogue radios stand out.
+fn access_point_lines(access_points: &[VisibleAccessPoint]) -> String {
+    let mut sorted: Vec<&VisibleAccessPoint> = access_points.iter().collect();
+    sorted.sort_by_key(|ap| std::cmp::Reverse(ap.strength));
+    sorted
+        .into_iter()
+        .map(|ap| {
+            let ssid = if ap.ssid.is_empty() { "(hidden)" } else { ap.ssid.as_str() };
+            let channel = analyzer::channel_for_frequency(ap.frequency_mhz)
+                .map_or_else(|| format!("{} MHz", ap.frequency_mhz), |(_, channel)| format!("ch {channel}"));
+            format!(
+                "{:>3}%  {}{}  {channel}  {ssid}",
+                ap.strength,
+                ap.bssid,
+                oui::suffix(&ap.bssid)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn draw_channel_chart(
+    cr: &gtk4::cairo::Context,
+    width: f64,
+    height: f64,
+    band: Band,
+    usage: &[ChannelUsage],
+) {
+    let margin = 20.0;
+    let plot_height = height - margin * 2.0;
+    let channels: Vec<u32> = match band {
+        Band::TwoPointFour => (1..=14).collect(),
+        _ => usage.iter().map(|slot| slot.channel).collect(),
+    };
+
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.6);
+    cr.set_line_width(1.0);
+    cr.move_to(margin, height - margin);
+    cr.line_to(width - margin, height - margin);
+    let _ = cr.stroke();
+
+    if channels.is_empty() {
+        cr.move_to(margin, height / 2.0);
+        let _ = cr.show_text("No access points on this band");
+        return;
+    }
+
+    let step = (width - margin * 2.0) / (channels.len() as f64 + 1.0);
+    let x_for = |channel: u32| -> Option<f64> {
+        channels
+            .iter()
+            .position(|candidate| *candidate == channel)
+            .map(|index| margin + step * (index as f64 + 1.0))
+    };
+
+    cr.set_font_size(10.0);
+    for channel in &channels {
+        if let Some(x) = x_for(*channel) {
+            cr.set_source_rgba(0.5, 0.5, 0.5, 0.9);
+            cr.move_to(x - 4.0, height - margin + 12.0);
+            let _ = cr.show_text(&channel.to_string());
+        }
+    }
+
+    // 2.4 GHz transmissions spread over roughly ±2 channels, so draw them as arcs.
+    let spread = if band == Band::TwoPointFour { step * 2.0 } else { step * 0.35 };
+    for slot in usage {
+        let Some(x) = x_for(slot.channel) else { continue };
+        for (ssid, strength) in &slot.networks {
+            let peak = height - margin - plot_height * f64::from(*strength) / 100.0;
+            let (r, g, b) = color_for_ssid(ssid);
+            cr.set_source_rgba(r, g, b, 0.25);
+            cr.move_to(x - spread, height - margin);
+            cr.curve_to(x - spread / 2.0, peak, x + spread / 2.0, peak, x + spread, height - margin);
+            cr.close_path();
+            let _ = cr.fill_preserve();
+            cr.set_source_rgba(r, g, b, 0.9);
+            let _ = cr.stroke();
+            cr.move_to(x - spread / 2.0, peak + 12.0);
+            let _ = cr.show_text(ssid);
+        }
+    }
+}
+
+fn color_for_ssid(ssid: &str) -> (f64, f64, f64) {
+    let hash = ssid
+        .bytes()
+        .fold(5381u32, |hash, byte| hash.wrapping_mul(33) ^ u32::from(byte));
+    let channel = |shift: u32| 0.25 + f64::from((hash >> shift) & 0xff) / 255.0 * 0.6;
+    (channel(0), channel(8), channel(16))
+}
+
+const SURVEY_DEFAULT_INTERVAL_SECS: f64 = 5.0;
+/// Older journal lines are dropped so a long-open viewer stays responsive.
+const JOURNAL_MAX_LINES: i32 = 2000;
+
+/// Flags saved networks with weak security, passwords kept in plain text,
+/// open networks joined automatically and enterprise logins that do not check
+/// the server, each with what to do about it.
+fn show_security_audit_dialog(parent: &ApplicationWindow, worker: &Worker) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Security Audit"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(420);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let summary = Label::new(Some("Checking saved networks…"));
+    summary.add_css_class("dim-label");
+    summary.set_wrap(true);
+    summary.set_halign(Align::Start);
+
+    let list = ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_min_content_height(300);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&list));
+
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+
+    box_.append(&summary);
+    box_.append(&scroller);
+    box_.append(&close_button);
+    content.append(&box_);
+
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| dialog_close.close());
+
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::LoadProfiles { reply });
+    let parent = parent.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        match result {
+            Ok(mut profiles) => {
+                profiles.sort_by_key(|profile| profile.id.to_lowercase());
+                let flagged: Vec<(SavedProfile, Vec<AuditFinding>)> = profiles
+                    .into_iter()
+                    .map(|profile| {
+                        let findings = audit_profile(&profile);
+                        (profile, findings)
+                    })
+                    .filter(|(_, findings)| !findings.is_empty())
+                    .collect();
+                summary.set_text(&if flagged.is_empty() {
+                    "No issues found in your saved networks.".to_string()
+                } else {
+                    format!("{} need attention.", count_networks(flagged.len()))
+                });
+                for (profile, findings) in flagged {
+                    list.append(&build_audit_row(&profile, &findings));
+                }
+            }
+            Err(err) => {
+                summary.set_text(&format!("Failed to load saved profiles: {}", friendly_error(&err)));
+                explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            }
+        }
+    });
+
+    dialog.present();
+}
+
+fn build_audit_row(profile: &SavedProfile, findings: &[AuditFinding]) -> GtkBox {
+    let row = GtkBox::new(Orientation::Vertical, 4);
+    row.set_margin_top(8);
+    row.set_margin_bottom(8);
+    row.set_margin_start(6);
+    row.set_margin_end(6);
+    let name = Label::new(Some(&profile.id));
+    name.set_halign(Align::Start);
+    name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    name.add_css_class("yufi-network-name");
+    row.append(&name);
+    for finding in findings {
+        let line = GtkBox::new(Orientation::Horizontal, 6);
+        let icon = Image::from_icon_name("dialog-warning-symbolic");
+        icon.add_css_class("yufi-network-weak");
+        icon.set_valign(Align::Start);
+        let text = GtkBox::new(Orientation::Vertical, 2);
+        let label = Label::new(Some(finding.label()));
+        label.set_halign(Align::Start);
+        let advice = Label::new(Some(finding.advice()));
+        advice.add_css_class("yufi-legend-label");
+        advice.set_wrap(true);
+        advice.set_xalign(0.0);
+        text.append(&label);
+        text.append(&advice);
+        line.append(&icon);
+        line.append(&text);
+        row.append(&line);
+    }
+    row
+}
+
+fn show_diagnostics_dialog(parent: &ApplicationWindow, worker: Worker) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Diagnostics"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(460);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some("Include this report when filing a bug."));
+    intro.add_css_class("dim-label");
+    intro.set_halign(Align::Start);
+
+    let report_label = Label::new(Some("Collecting…"));
+    report_label.add_css_class("monospace");
+    report_label.add_css_class("yufi-ap-info");
+    report_label.set_halign(Align::Start);
+    report_label.set_xalign(0.0);
+    report_label.set_wrap(true);
+    report_label.set_selectable(true);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let refresh_button = Button::with_label("Refresh");
+    refresh_button.set_hexpand(true);
+    refresh_button.set_halign(Align::Fill);
+    let copy_button = Button::with_label("Copy Report");
+    copy_button.add_css_class("yufi-primary");
+    copy_button.add_css_class("suggested-action");
+    copy_button.set_hexpand(true);
+    copy_button.set_halign(Align::Fill);
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+    actions.append(&refresh_button);
+    actions.append(&copy_button);
+    actions.append(&close_button);
+
+    box_.append(&intro);
+    box_.append(&report_label);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let report = Rc::new(RefCell::new(String::new()));
+    let report_reload = report.clone();
+    let copy_reload = copy_button.clone();
+    let refresh_reload = refresh_button.clone();
+    let reload = Rc::new(move || {
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::LoadDiagnostics { reply });
+        refresh_reload.set_sensitive(false);
+        let report_label = report_label.clone();
+        let report_reload = report_reload.clone();
+        let copy_reload = copy_reload.clone();
+        let refresh_reload = refresh_reload.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(reading) = answer.recv().await else {
+                return;
+            };
+            let nm = reading.nm.map_err(|err| friendly_error(&err));
+            let text = diagnostics::format_report(nm.as_ref().map_err(String::as_str), &reading.session);
+            #[cfg(feature = "netlink")]
+            let text = text + &reading.netlink;
+            report_label.set_text(&text);
+            *report_reload.borrow_mut() = text;
+            copy_reload.set_label("Copy Report");
+            refresh_reload.set_sensitive(true);
+        });
+    });
+    reload();
+
+    refresh_button.connect_clicked(move |_| reload());
+    let dialog_copy = dialog.clone();
+    copy_button.connect_clicked(move |button| {
+        dialog_copy.clipboard().set_text(&report.borrow());
+        button.set_label("Copied");
+    });
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+fn show_troubleshoot_dialog(parent: &ApplicationWindow, ssid: String) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Why can't I connect?"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(440);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some(&format!("Checking {ssid} step by step...")));
+    intro.add_css_class("dim-label");
+    intro.set_halign(Align::Start);
+    intro.set_wrap(true);
+
+    let steps = GtkBox::new(Orientation::Vertical, 6);
+
+    let fix_label = Label::new(None);
+    fix_label.set_halign(Align::Start);
+    fix_label.set_xalign(0.0);
+    fix_label.set_wrap(true);
+    fix_label.set_visible(false);
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let rerun_button = Button::with_label("Run Again");
+    rerun_button.set_hexpand(true);
+    rerun_button.set_halign(Align::Fill);
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+    actions.append(&rerun_button);
+    actions.append(&close_button);
+
+    box_.append(&intro);
+    box_.append(&steps);
+    box_.append(&fix_label);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let run_checks = Rc::new(move |rerun: &Button| {
+        while let Some(child) = steps.first_child() {
+            steps.remove(&child);
+        }
+        fix_label.set_visible(false);
+        rerun.set_sensitive(false);
+
+        let (tx, rx) = async_channel::unbounded();
+        let ssid_thread = ssid.clone();
+        thread::spawn(move || {
+            let backend = NetworkManagerBackend::new();
+            troubleshoot::run(&backend, &ssid_thread, &troubleshoot::SystemProbes, |step| {
+                let _ = tx.send_blocking(step.clone());
+            });
+        });
+
+        let steps = steps.clone();
+        let intro = intro.clone();
+        let fix_label = fix_label.clone();
+        let rerun = rerun.clone();
+        let ssid = ssid.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let mut failed = None;
+            while let Ok(step) = rx.recv().await {
+                steps.append(&build_troubleshoot_step(&step));
+                if !step.passed {
+                    failed = Some(step.check);
+                }
+            }
+            match failed {
+                Some(check) => {
+                    intro.set_text(&format!("{} failed for {ssid}.", check.label()));
+                    fix_label.set_text(check.fix());
+                    fix_label.set_visible(true);
+                }
+                None => intro.set_text(&format!("Every check passed for {ssid}.")),
+            }
+            rerun.set_sensitive(true);
+        });
+    });
+    run_checks(&rerun_button);
+
+    rerun_button.connect_clicked(move |button| run_checks(button));
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+fn build_troubleshoot_step(step: &troubleshoot::StepResult) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let icon_name = if step.passed {
+        "emblem-ok-symbolic"
+    } else {
+        "dialog-error-symbolic"
+    };
+    let icon = Image::from_icon_name(icon_name);
+    icon.add_css_class(if step.passed { "yufi-status-ok" } else { "yufi-status-error" });
+    icon.set_valign(Align::Start);
+
+    let text = GtkBox::new(Orientation::Vertical, 2);
+    let title = Label::new(Some(step.check.label()));
+    title.set_halign(Align::Start);
+    let detail = Label::new(Some(&step.detail));
+    detail.add_css_class("dim-label");
+    detail.set_halign(Align::Start);
+    detail.set_xalign(0.0);
+    detail.set_wrap(true);
+    text.append(&title);
+    text.append(&detail);
+
+    row.append(&icon);
+    row.append(&text);
+    row
+}
+
+fn show_journal_dialog(parent: &ApplicationWindow, backend: &NetworkManagerBackend) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Wi‑Fi Log"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(640);
+    dialog.set_default_height(420);
+    dialog.set_resizable(true);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let interface = backend
+        .diagnostics()
+        .ok()
+        .and_then(|report| report.device)
+        .map(|device| device.interface);
+    let hint = Label::new(Some(&match &interface {
+        Some(interface) => format!("NetworkManager and wpa_supplicant messages for {interface}."),
+        None => "NetworkManager and wpa_supplicant messages.".to_string(),
+    }));
+    hint.set_halign(Align::Start);
+    hint.set_wrap(true);
+    hint.add_css_class("yufi-legend-label");
+
+    let view = gtk4::TextView::new();
+    view.set_editable(false);
+    view.set_cursor_visible(false);
+    view.set_monospace(true);
+    view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    let buffer = view.buffer();
+    let failure_tag = buffer.create_tag(Some("failure"), &[("weight", &700i32)]);
+    let scroller = ScrolledWindow::new();
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&view));
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let copy_button = Button::with_label("Copy");
+    let clear_button = Button::with_label("Clear");
+    let close_button = Button::with_label("Close");
+    for button in [&copy_button, &clear_button, &close_button] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
+
+    box_.append(&hint);
+    box_.append(&scroller);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let stream = match journal::JournalStream::spawn(interface) {
+        Ok((stream, lines)) => {
+            let buffer = buffer.clone();
+            let view = view.clone();
+            gtk4::glib::spawn_future_local(async move {
+                while let Ok(line) = lines.recv().await {
+                    append_journal_line(&buffer, failure_tag.as_ref(), &line);
+                    view.scroll_to_mark(&buffer.get_insert(), 0.0, false, 0.0, 1.0);
+                }
+            });
+            Some(stream)
+        }
+        Err(err) => {
+            buffer.set_text(&format!("Could not start journalctl: {err}"));
+            None
+        }
+    };
+    let stream = Rc::new(RefCell::new(stream));
+
+    let dialog_copy = dialog.clone();
+    let buffer_copy = buffer.clone();
+    copy_button.connect_clicked(move |_| {
+        let (start, end) = buffer_copy.bounds();
+        dialog_copy.clipboard().set_text(&buffer_copy.text(&start, &end, false));
+    });
+    let buffer_clear = buffer.clone();
+    clear_button.connect_clicked(move |_| {
+        buffer_clear.set_text("");
+    });
+    dialog.connect_close_request(move |_| {
+        if let Some(mut stream) = stream.borrow_mut().take() {
+            stream.stop();
+        }
+        Propagation::Proceed
+    });
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+fn append_journal_line(buffer: &gtk4::TextBuffer, failure_tag: Option<&gtk4::TextTag>, line: &str) {
+    if buffer.line_count() > JOURNAL_MAX_LINES {
+        let mut start = buffer.start_iter();
+        let mut cut = buffer.iter_at_line(buffer.line_count() - JOURNAL_MAX_LINES).unwrap_or(start);
+        buffer.delete(&mut start, &mut cut);
+    }
+    let mut end = buffer.end_iter();
+    let text = format!("{line}\n");
+    match failure_tag.filter(|_| journal::is_failure(line)) {
+        Some(tag) => buffer.insert_with_tags(&mut end, &text, &[tag]),
+        None => buffer.insert(&mut end, &text),
+    }
+}
+
+fn show_survey_dialog(parent: &ApplicationWindow) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Site Survey"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(420);
+    dialog.set_default_height(420);
+    dialog.set_resizable(true);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let hint = Label::new(Some(
+        "Walk around while the survey runs. Each scan records the signal of every access point.",
+    ));
+    hint.set_halign(Align::Start);
+    hint.set_wrap(true);
+    hint.add_css_class("yufi-legend-label");
+
+    let interval_row = GtkBox::new(Orientation::Horizontal, 8);
+    let interval_label = Label::new(Some("Scan every (seconds)"));
+    interval_label.set_halign(Align::Start);
+    interval_label.set_hexpand(true);
+    let interval = gtk4::SpinButton::with_range(2.0, 60.0, 1.0);
+    interval.set_value(SURVEY_DEFAULT_INTERVAL_SECS);
+    interval_row.append(&interval_label);
+    interval_row.append(&interval);
+
+    let status = Label::new(Some("Not running."));
+    status.set_halign(Align::Start);
+
+    let results = ListBox::new();
+    results.set_selection_mode(gtk4::SelectionMode::None);
+    let scroller = ScrolledWindow::new();
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&results));
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let toggle_button = Button::with_label("Start");
+    toggle_button.add_css_class("yufi-primary");
+    toggle_button.add_css_class("suggested-action");
+    let clear_button = Button::with_label("Clear");
+    let close_button = Button::with_label("Close");
+    for button in [&toggle_button, &clear_button, &close_button] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
+
+    box_.append(&hint);
+    box_.append(&interval_row);
+    box_.append(&status);
+    box_.append(&scroller);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let recorded = Rc::new(RefCell::new(SiteSurvey::default()));
+    let running: Rc<RefCell<Option<Arc<AtomicBool>>>> = Rc::new(RefCell::new(None));
+    render_survey_results(&results, &recorded.borrow().summaries());
+
+    let stop = {
+        let running = running.clone();
+        let toggle_button = toggle_button.clone();
+        let interval = interval.clone();
+        move || {
+            if let Some(flag) = running.borrow_mut().take() {
+                flag.store(true, Ordering::Relaxed);
+            }
+            toggle_button.set_label("Start");
+            interval.set_sensitive(true);
+        }
+    };
+
+    let recorded_clear = recorded.clone();
+    let results_clear = results.clone();
+    clear_button.connect_clicked(move |_| {
+        recorded_clear.borrow_mut().clear();
+        render_survey_results(&results_clear, &[]);
+    });
+
+    let stop_toggle = stop.clone();
+    toggle_button.connect_clicked(move |button| {
+        if running.borrow().is_some() {
+            stop_toggle();
+            return;
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        let period = Duration::from_secs(interval.value() as u64);
+        let samples = spawn_survey_loop(period, flag.clone());
+        running.replace(Some(flag));
+        button.set_label("Stop");
+        interval.set_sensitive(false);
+        status.set_text("Scanning...");
+
+        let recorded = recorded.clone();
+        let results = results.clone();
+        let status = status.clone();
+        gtk4::glib::spawn_future_local(async move {
+            while let Ok(result) = samples.recv().await {
+                match result {
+                    Ok(access_points) => {
+                        let mut recorded = recorded.borrow_mut();
+                        recorded.record(survey::now_secs(), &access_points);
+                        status.set_text(&format!("{} scans recorded.", recorded.rounds()));
+                        render_survey_results(&results, &recorded.summaries());
+                    }
+                    Err(err) => {
+                        status.set_text(&format!("Scan failed: {}", friendly_error(&err)));
+                    }
+                }
+            }
+        });
+    });
+
+    let stop_close = stop.clone();
+    dialog.connect_close_request(move |_| {
+        stop_close();
+        Propagation::Proceed
+    });
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+/// Runs scans on a dedicated thread so the survey keeps sampling while the
+/// worker stays free for connect/disconnect commands.
+fn spawn_survey_loop(
+    period: Duration,
+    stop: Arc<AtomicBool>,
+) -> async_channel::Receiver<BackendResult<Vec<VisibleAccessPoint>>> {
+    let (tx, rx) = async_channel::unbounded();
+    thread::spawn(move || {
+        let backend = NetworkManagerBackend::new();
+        while !stop.load(Ordering::Relaxed) {
+            // NetworkManager rate-limits scans; the cached list is still worth sampling.
+            let _ = backend.request_scan();
+            thread::sleep(period);
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            if tx.send_blocking(backend.list_access_points()).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn render_survey_results(results: &ListBox, summaries: &[NetworkSummary]) {
+    while let Some(child) = results.first_child() {
+        results.remove(&child);
+    }
+    if summaries.is_empty() {
+        results.append(&build_empty_row("No samples yet"));
+        return;
+    }
+    let now = survey::now_secs();
+    for summary in summaries {
+        let row_box = GtkBox::new(Orientation::Vertical, 2);
+        row_box.set_margin_top(6);
+        row_box.set_margin_bottom(6);
+        row_box.set_margin_start(6);
+        row_box.set_margin_end(6);
+
+        let name = Label::new(Some(&summary.name));
+        name.set_halign(Align::Start);
+        name.add_css_class("yufi-network-name");
+        let access_points = if summary.bssids == 1 {
+            "1 access point".to_string()
+        } else {
+            format!("{} access points", summary.bssids)
+        };
+        let detail = Label::new(Some(&format!(
+            "Min {}% · Avg {}% · Max {}%\n{} samples · {access_points} · last seen {}s ago",
+            summary.min,
+            summary.avg,
+            summary.max,
+            summary.samples,
+            now.saturating_sub(summary.last_seen),
+        )));
+        detail.set_halign(Align::Start);
+        detail.add_css_class("yufi-legend-label");
+
+        row_box.append(&name);
+        row_box.append(&detail);
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&row_box));
+        results.append(&row);
+    }
+}
+
+fn show_preferences_dialog(
+    parent: &ApplicationWindow,
+    preferences: Rc<RefCell<Preferences>>,
+    status: StatusHandler,
+    list_view: NetworkListView,
+) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Preferences"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let autoconnect_row = GtkBox::new(Orientation::Horizontal, 8);
+    let autoconnect_label = Label::new(Some("Connect automatically to new networks"));
+    autoconnect_label.set_halign(Align::Start);
+    autoconnect_label.set_hexpand(true);
+    autoconnect_label.set_wrap(true);
+    let autoconnect_switch = Switch::new();
+    autoconnect_switch.set_valign(Align::Center);
+    autoconnect_switch.set_active(preferences.borrow().autoconnect_new);
+    autoconnect_row.append(&autoconnect_label);
+    autoconnect_row.append(&autoconnect_switch);
+
+    let warn_open_row = GtkBox::new(Orientation::Horizontal, 8);
+    let warn_open_label = Label::new(Some("Warn before joining unencrypted networks"));
+    warn_open_label.set_halign(Align::Start);
+    warn_open_label.set_hexpand(true);
+    warn_open_label.set_wrap(true);
+    let warn_open_switch = Switch::new();
+    warn_open_switch.set_valign(Align::Center);
+    warn_open_switch.set_active(preferences.borrow().warn_open_networks);
+    warn_open_row.append(&warn_open_label);
+    warn_open_row.append(&warn_open_switch);
+
+    let wifi_on_start_row = GtkBox::new(Orientation::Horizontal, 8);
+    let wifi_on_start_label = Label::new(Some("Turn on Wi‑Fi when YuFi starts"));
+    wifi_on_start_label.set_halign(Align::Start);
+    wifi_on_start_label.set_hexpand(true);
+    wifi_on_start_label.set_wrap(true);
+    let wifi_on_start_switch = Switch::new();
+    wifi_on_start_switch.set_valign(Align::Center);
+    wifi_on_start_switch.set_active(preferences.borrow().enable_wifi_on_start);
+    wifi_on_start_row.append(&wifi_on_start_label);
+    wifi_on_start_row.append(&wifi_on_start_switch);
+
+    let reduce_motion_row = GtkBox::new(Orientation::Horizontal, 8);
+    let reduce_motion_label = Label::new(Some("Reduce motion"));
+    reduce_motion_label.set_halign(Align::Start);
+    reduce_motion_label.set_hexpand(true);
+    reduce_motion_label.set_tooltip_text(Some(
+        "List animations also stay off when they are disabled for the whole desktop",
+    ));
+    let reduce_motion_switch = Switch::new();
+    reduce_motion_switch.set_valign(Align::Center);
+    reduce_motion_switch.set_active(preferences.borrow().reduce_motion);
+    reduce_motion_row.append(&reduce_motion_label);
+    reduce_motion_row.append(&reduce_motion_switch);
+
+    let split_bands_row = GtkBox::new(Orientation::Horizontal, 8);
+    let split_bands_label = Label::new(Some("Show each band of a network separately"));
+    split_bands_label.set_halign(Align::Start);
+    split_bands_label.set_hexpand(true);
+    split_bands_label.set_tooltip_text(Some(
+        "Networks heard on 2.4 and 5 GHz get one entry per band, and connecting from one joins a radio on that band",
+    ));
+    let split_bands_switch = Switch::new();
+    split_bands_switch.set_valign(Align::Center);
+    split_bands_switch.set_active(preferences.borrow().split_bands);
+    split_bands_row.append(&split_bands_label);
+    split_bands_row.append(&split_bands_switch);
+
+    let inline_details_row = GtkBox::new(Orientation::Horizontal, 8);
+    let inline_details_label = Label::new(Some("Expand networks in the list instead of opening details"));
+    inline_details_label.set_halign(Align::Start);
+    inline_details_label.set_hexpand(true);
+    inline_details_label.set_wrap(true);
+    inline_details_label.set_tooltip_text(Some(
+        "Clicking a network shows its signal, security and quick actions under it; the full details stay one click away",
+    ));
+    let inline_details_switch = Switch::new();
+    inline_details_switch.set_valign(Align::Center);
+    inline_details_switch.set_active(preferences.borrow().inline_details);
+    inline_details_row.append(&inline_details_label);
+    inline_details_row.append(&inline_details_switch);
+
+    let scan_mac_row = GtkBox::new(Orientation::Horizontal, 8);
+    let scan_mac_label = Label::new(Some("Randomize MAC address while scanning"));
+    scan_mac_label.set_halign(Align::Start);
+    scan_mac_label.set_hexpand(true);
+    scan_mac_label.set_wrap(true);
+    let scan_mac_switch = Switch::new();
+    scan_mac_switch.set_valign(Align::Center);
+    scan_mac_switch.set_active(scan_mac::randomization_enabled());
+    scan_mac_row.append(&scan_mac_label);
+    scan_mac_row.append(&scan_mac_switch);
+    let scan_mac_hint = Label::new(Some(
+        "Keeps the hardware address private while not connected. This is a system-wide NetworkManager setting and asks for an administrator password.",
+    ));
+    scan_mac_hint.add_css_class("dim-label");
+    scan_mac_hint.set_halign(Align::Start);
+    scan_mac_hint.set_xalign(0.0);
+    scan_mac_hint.set_wrap(true);
+
+    let roaming_heading = Label::new(Some("Roaming assistant"));
+    roaming_heading.add_css_class("yufi-network-name");
+    roaming_heading.set_halign(Align::Start);
+    let roaming_modes: Vec<&str> = RoamingMode::ALL.iter().map(|mode| mode.label()).collect();
+    let roaming_mode = gtk4::DropDown::from_strings(&roaming_modes);
+    let current_mode = preferences.borrow().roaming;
+    let mode_index = RoamingMode::ALL.iter().position(|mode| *mode == current_mode);
+    roaming_mode.set_selected(mode_index.unwrap_or_default() as u32);
+    roaming_mode.set_valign(Align::Center);
+    let roaming_mode_row = GtkBox::new(Orientation::Horizontal, 8);
+    let roaming_mode_label = Label::new(Some("Move to a stronger saved network"));
+    roaming_mode_label.set_halign(Align::Start);
+    roaming_mode_label.set_hexpand(true);
+    roaming_mode_label.set_wrap(true);
+    roaming_mode_row.append(&roaming_mode_label);
+    roaming_mode_row.append(&roaming_mode);
+
+    let roaming_threshold_row = GtkBox::new(Orientation::Horizontal, 8);
+    let roaming_threshold_label = Label::new(Some("When the signal stays below (%)"));
+    roaming_threshold_label.set_halign(Align::Start);
+    roaming_threshold_label.set_hexpand(true);
+    let roaming_threshold = gtk4::SpinButton::with_range(5.0, 80.0, 5.0);
+    roaming_threshold.set_value(f64::from(preferences.borrow().roaming_threshold));
+    roaming_threshold_row.append(&roaming_threshold_label);
+    roaming_threshold_row.append(&roaming_threshold);
+
+    let roaming_delay_row = GtkBox::new(Orientation::Horizontal, 8);
+    let roaming_delay_label = Label::new(Some("For at least (seconds)"));
+    roaming_delay_label.set_halign(Align::Start);
+    roaming_delay_label.set_hexpand(true);
+    let roaming_delay = gtk4::SpinButton::with_range(5.0, 300.0, 5.0);
+    roaming_delay.set_value(preferences.borrow().roaming_delay_secs as f64);
+    roaming_delay_row.append(&roaming_delay_label);
+    roaming_delay_row.append(&roaming_delay);
+    let roaming_enabled = current_mode != RoamingMode::Off;
+    roaming_threshold_row.set_sensitive(roaming_enabled);
+    roaming_delay_row.set_sensitive(roaming_enabled);
+
+    let low_signal_row = GtkBox::new(Orientation::Horizontal, 8);
+    let low_signal_label = Label::new(Some("Notify from the tray when the signal stays weak"));
+    low_signal_label.set_halign(Align::Start);
+    low_signal_label.set_hexpand(true);
+    low_signal_label.set_wrap(true);
+    let low_signal_switch = Switch::new();
+    low_signal_switch.set_valign(Align::Center);
+    low_signal_switch.set_active(preferences.borrow().low_signal_warning);
+    low_signal_row.append(&low_signal_label);
+    low_signal_row.append(&low_signal_switch);
+    let low_signal_limits = GtkBox::new(Orientation::Horizontal, 8);
+    let low_signal_threshold = gtk4::SpinButton::with_range(5.0, 60.0, 5.0);
+    low_signal_threshold.set_value(f64::from(preferences.borrow().low_signal_threshold));
+    let low_signal_delay = gtk4::SpinButton::with_range(10.0, 600.0, 10.0);
+    low_signal_delay.set_value(preferences.borrow().low_signal_delay_secs as f64);
+    low_signal_limits.append(&Label::new(Some("Below (%)")));
+    low_signal_limits.append(&low_signal_threshold);
+    low_signal_limits.append(&Label::new(Some("for (seconds)")));
+    low_signal_limits.append(&low_signal_delay);
+    low_signal_limits.set_sensitive(preferences.borrow().low_signal_warning);
+
+    let schedule_row = GtkBox::new(Orientation::Horizontal, 8);
+    let schedule_label = Label::new(Some("Turn Wi‑Fi off on a schedule"));
+    schedule_label.set_halign(Align::Start);
+    schedule_label.set_hexpand(true);
+    schedule_label.set_wrap(true);
+    let schedule_switch = Switch::new();
+    schedule_switch.set_valign(Align::Center);
+    schedule_switch.set_active(preferences.borrow().wifi_schedule_enabled);
+    schedule_row.append(&schedule_label);
+    schedule_row.append(&schedule_switch);
+    let schedule_times = GtkBox::new(Orientation::Horizontal, 8);
+    let saved_schedule = preferences.borrow().wifi_schedule;
+    let off_at = Entry::new();
+    off_at.set_text(&saved_schedule.off_at.to_string());
+    off_at.set_placeholder_text(Some("23:00"));
+    off_at.set_width_chars(6);
+    let on_at = Entry::new();
+    on_at.set_text(&saved_schedule.on_at.to_string());
+    on_at.set_placeholder_text(Some("07:00"));
+    on_at.set_width_chars(6);
+    let off_at_label = Label::new(Some("Off at"));
+    let on_at_label = Label::new(Some("on again at"));
+    schedule_times.append(&off_at_label);
+    schedule_times.append(&off_at);
+    schedule_times.append(&on_at_label);
+    schedule_times.append(&on_at);
+    schedule_times.set_sensitive(preferences.borrow().wifi_schedule_enabled);
+    let schedule_hint = Label::new(Some(
+        "Runs while YuFi is open or in the tray. Turning Wi‑Fi back on by hand lasts until the next scheduled change.",
+    ));
+    schedule_hint.add_css_class("dim-label");
+    schedule_hint.set_halign(Align::Start);
+    schedule_hint.set_xalign(0.0);
+    schedule_hint.set_wrap(true);
+
+    let quota_row = GtkBox::new(Orientation::Horizontal, 8);
+    let quota_label = Label::new(Some("Warn when a metered network uses more than (MB a month)"));
+    quota_label.set_halign(Align::Start);
+    quota_label.set_hexpand(true);
+    quota_label.set_wrap(true);
+    quota_label.set_tooltip_text(Some("0 turns the warning off. Usage is counted while YuFi runs."));
+    let quota = gtk4::SpinButton::with_range(0.0, 1_000_000.0, 500.0);
+    quota.set_valign(Align::Center);
+    quota.set_value(preferences.borrow().metered_quota_mb as f64);
+    quota_row.append(&quota_label);
+    quota_row.append(&quota);
+
+    let speed_server_row = GtkBox::new(Orientation::Horizontal, 8);
+    let speed_server_label = Label::new(Some("Speed test server"));
+    speed_server_label.set_halign(Align::Start);
+    speed_server_label.set_hexpand(true);
+    let speed_server = Entry::new();
+    speed_server.set_placeholder_text(Some("Cloudflare"));
+    speed_server.set_tooltip_text(Some(
+        "Base URL of a LibreSpeed backend, e.g. https://speed.example.org/backend. Leave empty to use Cloudflare.",
+    ));
+    speed_server.add_css_class("yufi-entry");
+    speed_server.set_hexpand(true);
+    speed_server.set_text(&preferences.borrow().speed_test_server);
+    speed_server_row.append(&speed_server_label);
+    speed_server_row.append(&speed_server);
+
+    let latency_host_row = GtkBox::new(Orientation::Horizontal, 8);
+    let latency_host_label = Label::new(Some("Latency host"));
+    latency_host_label.set_halign(Align::Start);
+    latency_host_label.set_hexpand(true);
+    let latency_host = Entry::new();
+    latency_host.set_tooltip_text(Some(
+        "Host the details dialog measures the round trip to, as host or host:port (443 by default).",
+    ));
+    latency_host.add_css_class("yufi-entry");
+    latency_host.set_hexpand(true);
+    latency_host.set_text(&preferences.borrow().latency_host);
+    latency_host_row.append(&latency_host_label);
+    latency_host_row.append(&latency_host);
+
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+
+    let hidden_heading = Label::new(Some("Hidden networks"));
+    hidden_heading.add_css_class("yufi-network-name");
+    hidden_heading.set_halign(Align::Start);
+    let hidden_list = ListBox::new();
+    hidden_list.set_selection_mode(gtk4::SelectionMode::None);
+    render_hidden_networks(&hidden_list, &status, &list_view);
+
+    box_.append(&autoconnect_row);
+    box_.append(&warn_open_row);
+    box_.append(&wifi_on_start_row);
+    box_.append(&reduce_motion_row);
+    box_.append(&split_bands_row);
+    box_.append(&inline_details_row);
+    box_.append(&scan_mac_row);
+    box_.append(&scan_mac_hint);
+    box_.append(&roaming_heading);
+    box_.append(&roaming_mode_row);
+    box_.append(&roaming_threshold_row);
+    box_.append(&roaming_delay_row);
+    box_.append(&low_signal_row);
+    box_.append(&low_signal_limits);
+    box_.append(&schedule_row);
+    box_.append(&schedule_times);
+    box_.append(&schedule_hint);
+    box_.append(&quota_row);
+    box_.append(&speed_server_row);
+    box_.append(&latency_host_row);
+    box_.append(&hidden_heading);
+    box_.append(&hidden_list);
+    box_.append(&close_button);
+    content.append(&box_);
+
+    let preferences_autoconnect = preferences.clone();
+    let status_autoconnect = status.clone();
+    autoconnect_switch.connect_state_set(move |_switch, state| {
+        let mut preferences = preferences_autoconnect.borrow_mut();
+        preferences.autoconnect_new = state;
+        if let Err(err) = preferences.save() {
+            status_autoconnect(StatusKind::Error, format!("Failed to save preferences: {err}"));
+        }
+        Propagation::Proceed
+    });
+
+    let preferences_warn_open = preferences.clone();
+    let status_warn_open = status.clone();
+    warn_open_switch.connect_state_set(move |_switch, state| {
+        let mut preferences = preferences_warn_open.borrow_mut();
+        preferences.warn_open_networks = state;
+        if let Err(err) = preferences.save() {
+            status_warn_open(StatusKind::Error, format!("Failed to save preferences: {err}"));
+        }
+        Propagation::Proceed
+    });
+
+    let status_save = status.clone();
+    let save_preference = Rc::new(move |change: &dyn Fn(&mut Preferences)| {
+        let mut preferences = preferences.borrow_mut();
+        change(&mut preferences);
+        if let Err(err) = preferences.save() {
+            status_save(StatusKind::Error, format!("Failed to save preferences: {err}"));
+        }
+    });
+    let save_wifi_on_start = save_preference.clone();
+    wifi_on_start_switch.connect_state_set(move |_switch, state| {
+        save_wifi_on_start(&|preferences| preferences.enable_wifi_on_start = state);
+        Propagation::Proceed
+    });
+
+    let save_motion = save_preference.clone();
+    reduce_motion_switch.connect_state_set(move |_switch, state| {
+        save_motion(&|preferences| preferences.reduce_motion = state);
+        Propagation::Proceed
+    });
+
+    let save_split = save_preference.clone();
+    let list_view_split = list_view.clone();
+    split_bands_switch.connect_state_set(move |_switch, state| {
+        save_split(&|preferences| preferences.split_bands = state);
+        list_view_split.render();
+        Propagation::Proceed
+    });
+
+    let save_inline = save_preference.clone();
+    let list_view_inline = list_view.clone();
+    inline_details_switch.connect_state_set(move |_switch, state| {
+        save_inline(&|preferences| preferences.inline_details = state);
+        list_view_inline.expanded.replace(None);
+        list_view_inline.render();
+        Propagation::Proceed
+    });
+
+    let save_mode = save_preference.clone();
+    roaming_mode.connect_selected_notify(move |dropdown| {
+        let mode = RoamingMode::ALL
+            .get(dropdown.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+        roaming_threshold_row.set_sensitive(mode != RoamingMode::Off);
+        roaming_delay_row.set_sensitive(mode != RoamingMode::Off);
+        save_mode(&|preferences| preferences.roaming = mode);
+    });
+    let save_threshold = save_preference.clone();
+    roaming_threshold.connect_value_changed(move |spin| {
+        let threshold = spin.value_as_int() as u8;
+        save_threshold(&|preferences| preferences.roaming_threshold = threshold);
+    });
+    let save_delay = save_preference.clone();
+    roaming_delay.connect_value_changed(move |spin| {
+        let delay = spin.value_as_int() as u64;
+        save_delay(&|preferences| preferences.roaming_delay_secs = delay);
+    });
+
+    let save_low_signal = save_preference.clone();
+    let low_signal_limits_switch = low_signal_limits.clone();
+    low_signal_switch.connect_state_set(move |_switch, state| {
+        low_signal_limits_switch.set_sensitive(state);
+        save_low_signal(&|preferences| preferences.low_signal_warning = state);
+        Propagation::Proceed
+    });
+    let save_low_threshold = save_preference.clone();
+    low_signal_threshold.connect_value_changed(move |spin| {
+        let threshold = spin.value_as_int() as u8;
+        save_low_threshold(&|preferences| preferences.low_signal_threshold = threshold);
+    });
+    let save_low_delay = save_preference.clone();
+    low_signal_delay.connect_value_changed(move |spin| {
+        let delay = spin.value_as_int() as u64;
+        save_low_delay(&|preferences| preferences.low_signal_delay_secs = delay);
+    });
+
+    let save_quota = save_preference.clone();
+    quota.connect_value_changed(move |spin| {
+        let quota = spin.value() as u64;
+        save_quota(&|preferences| preferences.metered_quota_mb = quota);
+    });
+
+    let save_speed_server = save_preference.clone();
+    speed_server.connect_changed(move |entry| {
+        let server = entry.text().trim().to_string();
+        save_speed_server(&|preferences| preferences.speed_test_server = server.clone());
+    });
+
+    let save_latency_host = save_preference.clone();
+    latency_host.connect_changed(move |entry| {
+        let host = entry.text().trim().to_string();
+        if !host.is_empty() {
+            save_latency_host(&|preferences| preferences.latency_host = host.clone());
+        }
+    });
+
+    let save_schedule_switch = save_preference.clone();
+    let schedule_times_switch = schedule_times.clone();
+    schedule_switch.connect_state_set(move |_switch, state| {
+        schedule_times_switch.set_sensitive(state);
+        save_schedule_switch(&|preferences| preferences.wifi_schedule_enabled = state);
+        Propagation::Proceed
+    });
+    // Half-typed times are flagged and left unsaved until both parse.
+    let save_times = Rc::new(move |off_at: &Entry, on_at: &Entry| {
+        let off = off_at.text().parse::<TimeOfDay>();
+        let on = on_at.text().parse::<TimeOfDay>();
+        for (entry, valid) in [(off_at, off.is_ok()), (on_at, on.is_ok())] {
+            if valid {
+                entry.remove_css_class("error");
+            } else {
+                entry.add_css_class("error");
+            }
+        }
+        if let (Ok(off_at), Ok(on_at)) = (off, on) {
+            let schedule = WifiSchedule { off_at, on_at };
+            save_preference(&|preferences| preferences.wifi_schedule = schedule);
+        }
+    });
+    for entry in [&off_at, &on_at] {
+        let save_times = save_times.clone();
+        let (off_at, on_at) = (off_at.clone(), on_at.clone());
+        entry.connect_changed(move |_| save_times(&off_at, &on_at));
+    }
+
+    // The switch only flips once the drop-in is written and NetworkManager has
+    // reloaded; `reverting` keeps the rollback after a failure from re-running it.
+    let reverting = Rc::new(Cell::new(false));
+    scan_mac_switch.connect_state_set(move |switch, state| {
+        if reverting.get() {
+            return Propagation::Proceed;
+        }
+        switch.set_sensitive(false);
+        let (tx, rx) = async_channel::bounded(1);
+        thread::spawn(move || {
+            let result = scan_mac::set_randomization(state)
+                .map_err(|err| err.to_string())
+                .and_then(|()| {
+                    NetworkManagerBackend::new()
+                        .reload_config()
+                        .map_err(|err| friendly_error(&err))
+                });
+            let _ = tx.send_blocking(result);
+        });
+        let switch = switch.clone();
+        let reverting = reverting.clone();
+        let status = status.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let result = rx
+                .recv()
+                .await
+                .unwrap_or_else(|_| Err("The settings helper stopped unexpectedly".to_string()));
+            switch.set_sensitive(true);
+            match result {
+                Ok(()) => {
+                    switch.set_state(state);
+                    let change = if state { "on" } else { "off" };
+                    status(
+                        StatusKind::Success,
+                        format!("Scan MAC randomization turned {change}. It applies the next time Wi‑Fi is disconnected."),
+                    );
+                }
+                Err(err) => {
+                    reverting.set(true);
+                    switch.set_active(!state);
+                    reverting.set(false);
+                    status(
+                        StatusKind::Error,
+                        format!("Failed to change scan MAC randomization: {err}"),
+                    );
+                }
+            }
+        });
+        Propagation::Stop
+    });
+
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+    dialog.present();
+}
+
+fn render_hidden_networks(
+    list: &ListBox,
+    status: &StatusHandler,
+    list_view: &NetworkListView,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    let hidden = list_view.data.borrow().hidden_ssids.clone();
+    if hidden.is_empty() {
+        list.append(&build_empty_row("Right-click a network to hide it"));
+        return;
+    }
+    for ssid in hidden {
+        let row_box = GtkBox::new(Orientation::Horizontal, 8);
+        row_box.set_margin_top(4);
+        row_box.set_margin_bottom(4);
+        row_box.set_margin_start(6);
+        row_box.set_margin_end(6);
+        let label = Label::new(Some(&ssid));
+        label.set_halign(Align::Start);
+        label.set_hexpand(true);
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        let unhide = Button::with_label("Unhide");
+        unhide.add_css_class("yufi-secondary");
+        row_box.append(&label);
+        row_box.append(&unhide);
+
+        let list_unhide = list.clone();
+        let status_unhide = status.clone();
+        let list_view_unhide = list_view.clone();
+        unhide.connect_clicked(move |_| {
+            {
+                let mut data = list_view_unhide.data.borrow_mut();
+                data.unhide(&ssid);
+                if let Err(err) = data.save() {
+                    status_unhide(StatusKind::Error, format!("Failed to save hidden networks: {err}"));
+                }
+            }
+            list_view_unhide.render();
+            render_hidden_networks(&list_unhide, &status_unhide, &list_view_unhide);
+        });
+
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&row_box));
+        list.append(&row);
+    }
+}
+
+fn show_duplicate_profiles_dialog(parent: &ApplicationWindow, worker: Worker, status: StatusHandler) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Duplicate Profiles"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some(
+        "Networks with more than one saved profile. Merging keeps the most recently used profile.",
+    ));
+    intro.add_css_class("dim-label");
+    intro.set_wrap(true);
+    intro.set_halign(Align::Start);
+
+    let groups_box = GtkBox::new(Orientation::Vertical, 12);
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_min_content_height(240);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&groups_box));
+
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+
+    box_.append(&intro);
+    box_.append(&scroller);
+    box_.append(&close_button);
+    content.append(&box_);
+
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+
+    let worker_close = worker.clone();
+    dialog.connect_close_request(move |_| {
+        request_state_refresh(&worker_close);
+        Propagation::Proceed
+    });
+
+    let loading = Label::new(Some("Loading saved profiles…"));
+    loading.add_css_class("dim-label");
+    groups_box.append(&loading);
+    render_duplicate_groups(&groups_box, &worker, &status, parent);
+    dialog.present();
+}
+
+/// Lists the profiles in the worker and shows the duplicates once they
+/// arrive; until then the groups shown stay as they are.
+fn render_duplicate_groups(groups_box: &GtkBox, worker: &Worker, status: &StatusHandler, parent: &ApplicationWindow) {
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::LoadProfiles { reply });
+    let (groups_box, worker) = (groups_box.clone(), worker.clone());
+    let (status, parent) = (status.clone(), parent.clone());
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        fill_duplicate_groups(&groups_box, result, &worker, &status, &parent);
+    });
+}
+
+fn fill_duplicate_groups(
+    groups_box: &GtkBox,
+    profiles: Result<Vec<SavedProfile>, BackendError>,
+    worker: &Worker,
+    status: &StatusHandler,
+    parent: &ApplicationWindow,
+) {
+    while let Some(child) = groups_box.first_child() {
+        groups_box.remove(&child);
+    }
+
+    let groups = match profiles {
+        Ok(profiles) => duplicate_profile_groups(profiles),
+        Err(err) => {
+            let label = Label::new(Some(&format!(
+                "Failed to load saved profiles: {}",
+                friendly_error(&err)
+            )));
+            label.add_css_class("yufi-dialog-error");
+            label.set_wrap(true);
+            groups_box.append(&label);
+            explain_permission_error(parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            return;
+        }
+    };
+
+    if groups.is_empty() {
+        let label = Label::new(Some("No duplicate profiles found."));
+        label.add_css_class("yufi-empty-label");
+        label.add_css_class("dim-label");
+        groups_box.append(&label);
+        return;
+    }
+
+    for group in groups {
+        let group_box = GtkBox::new(Orientation::Vertical, 6);
+        let title_row = GtkBox::new(Orientation::Horizontal, 8);
+        let title = Label::new(Some(&format!("{} ({} profiles)", group[0].ssid, group.len())));
+        title.add_css_class("yufi-network-name");
+        title.set_halign(Align::Start);
+        title.set_hexpand(true);
+        let merge = Button::with_label("Merge");
+        merge.add_css_class("yufi-primary");
+        merge.set_tooltip_text(Some("Keep the most recently used profile and delete the rest"));
+        title_row.append(&title);
+        title_row.append(&merge);
+        group_box.append(&title_row);
+
+        for (index, profile) in group.iter().enumerate() {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            let label = Label::new(Some(&profile_summary(profile, index == 0)));
+            label.add_css_class("yufi-legend-label");
+            label.set_halign(Align::Start);
+            label.set_hexpand(true);
+            label.set_wrap(true);
+            row.append(&label);
+            if index > 0 {
+                let delete = Button::with_label("Delete");
+                delete.add_css_class("destructive-action");
+                let paths = vec![profile.path.clone()];
+                let groups_box = groups_box.clone();
+                let worker = worker.clone();
+                let status = status.clone();
+                let parent = parent.clone();
+                delete.connect_clicked(move |_| {
+                    delete_profiles(paths.clone(), &groups_box, &worker, &status, &parent);
+                });
+                row.append(&delete);
+            }
+            group_box.append(&row);
+        }
+
+        let extras: Vec<String> = group.iter().skip(1).map(|profile| profile.path.clone()).collect();
+        let groups_box_merge = groups_box.clone();
+        let worker_merge = worker.clone();
+        let status_merge = status.clone();
+        let parent_merge = parent.clone();
+        merge.connect_clicked(move |_| {
+            delete_profiles(extras.clone(), &groups_box_merge, &worker_merge, &status_merge, &parent_merge);
+        });
+
+        groups_box.append(&group_box);
+    }
+}
+
+fn profile_summary(profile: &SavedProfile, newest: bool) -> String {
+    let last_used = if profile.timestamp == 0 {
+        "never used".to_string()
+    } else {
+        gtk4::glib::DateTime::from_unix_local(profile.timestamp as i64)
+            .and_then(|time| time.format("%Y-%m-%d %H:%M"))
+            .map(|time| format!("last used {time}"))
+            .unwrap_or_else(|_| "last used unknown".to_string())
+    };
+    if newest {
+        format!("{} · {last_used} · kept", profile.id)
+    } else {
+        format!("{} · {last_used}", profile.id)
+    }
+}
+
+/// Has the worker delete the profiles at `paths`, then lists the groups again.
+fn delete_profiles(
+    paths: Vec<String>,
+    groups_box: &GtkBox,
+    worker: &Worker,
+    status: &StatusHandler,
+    parent: &ApplicationWindow,
+) {
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::DeleteProfiles { paths, reply });
+    let (groups_box, worker) = (groups_box.clone(), worker.clone());
+    let (status, parent) = (status.clone(), parent.clone());
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        match result {
+            Ok(()) => status(StatusKind::Success, "Removed duplicate profiles".to_string()),
+            Err(err) => {
+                status(
+                    StatusKind::Error,
+                    format!("Failed to delete profile: {}", friendly_error(&err)),
+                );
+                explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            }
+        }
+        render_duplicate_groups(&groups_box, &worker, &status, &parent);
+    });
+}
+
+fn show_saved_networks_dialog(
+    parent: &ApplicationWindow,
+    backend: Rc<NetworkManagerBackend>,
+    data: Rc<RefCell<AppData>>,
+    worker: Worker,
+    status: StatusHandler,
+) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Saved Networks"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(380);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some(
+        "Saved networks, most recently used first. Tick several to change them at once.",
+    ));
+    intro.add_css_class("dim-label");
+    intro.set_wrap(true);
+    intro.set_halign(Align::Start);
+
+    let networks = ListBox::new();
+    networks.set_selection_mode(gtk4::SelectionMode::None);
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_min_content_height(260);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&networks));
+
+    let batch = GtkBox::new(Orientation::Horizontal, 8);
+    batch.set_sensitive(false);
+    let forget_selected = Button::with_label("Forget");
+    forget_selected.add_css_class("destructive-action");
+    let disable_autoconnect = Button::with_label("No Autoconnect");
+    let mark_metered = Button::with_label("Mark Metered");
+    for button in [&forget_selected, &disable_autoconnect, &mark_metered] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        batch.append(button);
+    }
+
+    let tools = GtkBox::new(Orientation::Horizontal, 8);
+    let priorities = Button::with_label("Set Priority Order...");
+    priorities.set_tooltip_text(Some("Choose which network to join when several are in range"));
+    let unused = Button::with_label("Clean Up Unused...");
+    unused.set_tooltip_text(Some("Forget open networks and networks not used in months"));
+    for button in [&priorities, &unused] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        tools.append(button);
+    }
+
+    let archive = GtkBox::new(Orientation::Horizontal, 8);
+    let back_up = Button::with_label("Back Up Profiles...");
+    back_up.set_tooltip_text(Some("Save every Wi‑Fi profile and its password to an encrypted file"));
+    let restore = Button::with_label("Restore Profiles...");
+    restore.set_tooltip_text(Some("Add the profiles from a backup that are not saved yet"));
+    for button in [&back_up, &restore] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        archive.append(button);
+    }
+
+    let close_button = Button::with_label("Close");
+    close_button.set_hexpand(true);
+    close_button.set_halign(Align::Fill);
+
+    box_.append(&intro);
+    box_.append(&scroller);
+    box_.append(&batch);
+    box_.append(&tools);
+    box_.append(&archive);
+    box_.append(&close_button);
+    content.append(&box_);
+
+    let view = SavedNetworksView {
+        networks: networks.clone(),
+        batch: batch.clone(),
+        selection: Rc::new(RefCell::new(BTreeMap::new())),
+        backend: backend.clone(),
+        worker: worker.clone(),
+        data: data.clone(),
+        status: status.clone(),
+        parent: parent.clone(),
+    };
+
+    let view_forget = view.clone();
+    forget_selected.connect_clicked(move |_| {
+        let selected = view_forget.selection.take();
+        let mut forgotten = 0;
+        for (ssid, paths) in &selected {
+            if !forget_saved_network(ssid, paths, &view_forget) {
+                break;
+            }
+            forgotten += 1;
+        }
+        if forgotten > 0 {
+            (view_forget.status)(StatusKind::Success, format!("Forgot {}", count_networks(forgotten)));
+        }
+        render_saved_networks(&view_forget);
+    });
+
+    let view_autoconnect = view.clone();
+    disable_autoconnect.connect_clicked(move |_| {
+        apply_to_selection(
+            &view_autoconnect,
+            "turn off autoconnect for",
+            "Turned off autoconnect for",
+            |backend, ssid| backend.set_autoreconnect(ssid, false),
+        );
+    });
+
+    let view_metered = view.clone();
+    mark_metered.connect_clicked(move |_| {
+        apply_to_selection(&view_metered, "mark", "Marked as metered:", |backend, ssid| {
+            backend.set_metered(ssid, true)
+        });
+    });
+
+    let dialog_priorities = dialog.clone();
+    let worker_priorities = worker.clone();
+    let status_priorities = status.clone();
+    priorities.connect_clicked(move |_| {
+        show_priority_wizard(&dialog_priorities, worker_priorities.clone(), status_priorities.clone());
+    });
+
+    let dialog_unused = dialog.clone();
+    let view_unused = view.clone();
+    unused.connect_clicked(move |_| show_unused_cleanup_dialog(&dialog_unused, &view_unused));
+
+    let dialog_back_up = dialog.clone();
+    let view_back_up = view.clone();
+    back_up.connect_clicked(move |_| back_up_profiles(&dialog_back_up, &view_back_up));
+
+    let dialog_restore = dialog.clone();
+    let view_restore = view.clone();
+    restore.connect_clicked(move |_| restore_profiles(&dialog_restore, &view_restore));
+
+    let dialog_close = dialog.clone();
+    close_button.connect_clicked(move |_| {
+        dialog_close.close();
+    });
+
+    let worker_close = worker.clone();
+    dialog.connect_close_request(move |_| {
+        request_state_refresh(&worker_close);
+        Propagation::Proceed
+    });
+
+    networks.append(&build_empty_row("Loading saved networks…"));
+    render_saved_networks(&view);
+    dialog.present();
+}
+
+/// Lists every saved network, most preferred first, to be put in the order
+/// they should be joined when several are in range. Saving writes the order
+/// as `autoconnect-priority` to every profile of each network.
+/// The profiles are listed and the priorities written in the worker.
+fn show_priority_wizard(parent: &impl IsA<gtk4::Window>, worker: Worker, status: StatusHandler) {
+    let (reply, answer) = async_channel::bounded(1);
+    worker.send(Command::LoadProfiles { reply });
+    let parent = parent.clone().upcast::<gtk4::Window>();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        match result {
+            Ok(profiles) => build_priority_wizard(&parent, profiles, worker, status),
+            Err(err) => {
+                status(
+                    StatusKind::Error,
+                    format!("Failed to load saved profiles: {}", friendly_error(&err)),
+                );
+                explain_permission_error(&parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            }
+        }
+    });
+}
+
+fn build_priority_wizard(parent: &gtk4::Window, mut profiles: Vec<SavedProfile>, worker: Worker, status: StatusHandler) {
+    // Start from the current order: highest priority, then most recently used.
+    profiles.sort_by_key(|profile| (std::cmp::Reverse(profile.priority), std::cmp::Reverse(profile.timestamp)));
+    let mut order: Vec<(String, Vec<String>)> = Vec::new();
+    for profile in profiles {
+        match order.iter_mut().find(|(ssid, _)| *ssid == profile.ssid) {
+            Some((_, paths)) => paths.push(profile.path),
+            None => order.push((profile.ssid, vec![profile.path])),
+        }
+    }
+    if order.is_empty() {
+        status(StatusKind::Info, "No saved networks to order".to_string());
+        return;
+    }
+    let order = Rc::new(RefCell::new(order));
+
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Priority Order"));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(340);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let intro = Label::new(Some(
+        "Move the network you prefer most to the top. When several are in range, \
+         NetworkManager joins the highest one that connects automatically.",
+    ));
+    intro.add_css_class("dim-label");
+    intro.set_wrap(true);
+    intro.set_halign(Align::Start);
+
+    let list = ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_min_content_height(240);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&list));
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let cancel = Button::with_label("Cancel");
+    let save = Button::with_label("Save Order");
+    save.add_css_class("suggested-action");
+    for button in [&cancel, &save] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
+
+    box_.append(&intro);
+    box_.append(&scroller);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let dialog_cancel = dialog.clone();
+    cancel.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_save = dialog.clone();
+    let order_save = order.clone();
+    save.connect_clicked(move |button| {
+        let order = order_save.borrow();
+        let ranked = models::ranked_priorities(order.len());
+        let priorities = order
+            .iter()
+            .zip(ranked)
+            .map(|((ssid, paths), priority)| (ssid.clone(), paths.clone(), priority))
+            .collect();
+        let count = order.len();
+        let (reply, answer) = async_channel::bounded(1);
+        worker.send(Command::SetPriorities { priorities, reply });
+        button.set_sensitive(false);
+        let (button, dialog, status) = (button.clone(), dialog_save.clone(), status.clone());
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(result) = answer.recv().await else {
+                return;
+            };
+            button.set_sensitive(true);
+            match result {
+                Ok(()) => {
+                    status(StatusKind::Success, format!("Saved the priority order of {}", count_networks(count)));
+                    dialog.close();
+                }
+                Err((ssid, err)) => {
+                    status(
+                        StatusKind::Error,
+                        format!("Failed to set the priority of {ssid}: {}", friendly_error(&err)),
+                    );
+                    explain_permission_error(&dialog, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                }
+            }
+        });
+    });
+
+    render_priority_order(&list, &order);
+    dialog.present();
+}
+
+/// Saved networks, most preferred first, each with every profile path saved for it.
+type PriorityOrder = Rc<RefCell<Vec<(String, Vec<String>)>>>;
+
+/// One row per network with buttons to move it up or down the order.
+fn render_priority_order(list: &ListBox, order: &PriorityOrder) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    let count = order.borrow().len();
+    for (index, (ssid, _)) in order.borrow().iter().enumerate() {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        row.set_margin_top(4);
+        row.set_margin_bottom(4);
+        row.set_margin_start(6);
+        row.set_margin_end(6);
+
+        let rank = Label::new(Some(&format!("{}.", index + 1)));
+        rank.add_css_class("dim-label");
+        let name = Label::new(Some(ssid));
+        name.set_halign(Align::Start);
+        name.set_hexpand(true);
+        name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        name.add_css_class("yufi-network-name");
+        row.append(&rank);
+        row.append(&name);
+
+        let above = index.checked_sub(1);
+        let below = Some(index + 1).filter(|below| *below < count);
+        for (icon, tooltip, target) in [
+            ("go-up-symbolic", "Move up", above),
+            ("go-down-symbolic", "Move down", below),
+        ] {
+            let button = Button::from_icon_name(icon);
+            button.add_css_class("flat");
+            button.set_tooltip_text(Some(tooltip));
+            button.set_sensitive(target.is_some());
+            let list = list.clone();
+            let order = order.clone();
+            button.connect_clicked(move |_| {
+                if let Some(target) = target {
+                    order.borrow_mut().swap(index, target);
+                    render_priority_order(&list, &order);
+                }
+            });
+            row.append(&button);
+        }
+        list.append(&row);
+    }
+}
+
+/// Offers the saved networks that are unencrypted or were not connected to in
+/// a chosen number of months, all ticked, and forgets the ticked ones at once.
+/// The profiles are listed in the worker before the dialog opens.
+fn show_unused_cleanup_dialog(owner: &Dialog, view: &SavedNetworksView) {
+    let (reply, answer) = async_channel::bounded(1);
+    view.worker.send(Command::LoadProfiles { reply });
+    let (owner, view) = (owner.clone(), view.clone());
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        match result {
+            Ok(profiles) => build_unused_cleanup_dialog(&owner, &view, profiles),
+            Err(err) => {
+                (view.status)(
+                    StatusKind::Error,
+                    format!("Failed to load saved profiles: {}", friendly_error(&err)),
+                );
+                explain_permission_error(&owner, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            }
+        }
+    });
+}
+
+fn build_unused_cleanup_dialog(owner: &Dialog, view: &SavedNetworksView, profiles: Vec<SavedProfile>) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some("Clean Up Unused Networks"));
+    dialog.set_transient_for(Some(owner));
+    dialog.set_modal(true);
+    dialog.set_default_width(360);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let months_row = GtkBox::new(Orientation::Horizontal, 8);
+    let months_label = Label::new(Some("Not connected for (months)"));
+    months_label.set_halign(Align::Start);
+    months_label.set_hexpand(true);
+    let months = gtk4::SpinButton::with_range(1.0, 36.0, 1.0);
+    months.set_value(UNUSED_CLEANUP_MONTHS.into());
+    months_row.append(&months_label);
+    months_row.append(&months);
+
+    let hint = Label::new(Some("Open networks are listed however recently they were used."));
+    hint.add_css_class("dim-label");
+    hint.set_wrap(true);
+    hint.set_halign(Align::Start);
+
+    let list = ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_min_content_height(220);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&list));
+
+    let actions = GtkBox::new(Orientation::Horizontal, 8);
+    let cancel = Button::with_label("Cancel");
+    let forget = Button::with_label("Forget Selected");
+    forget.add_css_class("destructive-action");
+    for button in [&cancel, &forget] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
+
+    box_.append(&months_row);
+    box_.append(&hint);
+    box_.append(&scroller);
+    box_.append(&actions);
+    content.append(&box_);
+
+    let selection: Rc<RefCell<BTreeMap<String, Vec<String>>>> = Rc::new(RefCell::new(BTreeMap::new()));
+    let render = {
+        let list = list.clone();
+        let forget = forget.clone();
+        let selection = selection.clone();
+        let data = view.data.clone();
+        Rc::new(move |months: u32| {
+            while let Some(child) = list.first_child() {
+                list.remove(&child);
+            }
+            let now = survey::now_secs();
+            let candidates = usage::cleanup_candidates(&profiles, &data.borrow().history, now, months);
+            *selection.borrow_mut() = candidates
+                .iter()
+                .map(|candidate| (candidate.ssid.clone(), candidate.paths.clone()))
+                .collect();
+            forget.set_sensitive(!candidates.is_empty());
+            if candidates.is_empty() {
+                list.append(&build_empty_row("Nothing to clean up"));
+            }
+            for candidate in candidates {
+                let row = GtkBox::new(Orientation::Horizontal, 8);
+                row.set_margin_top(4);
+                row.set_margin_bottom(4);
+                row.set_margin_start(6);
+                row.set_margin_end(6);
+                let check = CheckButton::new();
+                check.set_active(true);
+                check.set_valign(Align::Center);
+                let text_box = GtkBox::new(Orientation::Vertical, 2);
+                text_box.set_hexpand(true);
+                let name = Label::new(Some(&candidate.ssid));
+                name.set_halign(Align::Start);
+                name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+                name.add_css_class("yufi-network-name");
+                let last = if candidate.last_connected == 0 {
+                    "Never connected".to_string()
+                } else {
+                    format!("Last connected {}", usage::relative_time(candidate.last_connected, now))
+                };
+                let detail = if candidate.is_open {
+                    format!("Open network · {last}")
+                } else {
+                    last
+                };
+                let detail = Label::new(Some(&detail));
+                detail.set_halign(Align::Start);
+                detail.add_css_class("yufi-legend-label");
+                text_box.append(&name);
+                text_box.append(&detail);
+                row.append(&check);
+                row.append(&text_box);
+                list.append(&row);
+
+                let selection = selection.clone();
+                let forget = forget.clone();
+                check.connect_toggled(move |check| {
+                    let mut selection = selection.borrow_mut();
+                    if check.is_active() {
+                        selection.insert(candidate.ssid.clone(), candidate.paths.clone());
+                    } else {
+                        selection.remove(&candidate.ssid);
+                    }
+                    forget.set_sensitive(!selection.is_empty());
+                });
+            }
+        })
+    };
+    render(UNUSED_CLEANUP_MONTHS);
+    let render_months = render.clone();
+    months.connect_value_changed(move |months| render_months(months.value_as_int() as u32));
+
+    let dialog_cancel = dialog.clone();
+    cancel.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_forget = dialog.clone();
+    let view_forget = view.clone();
+    forget.connect_clicked(move |_| {
+        let selected = selection.take();
+        let mut forgotten = 0;
+        for (ssid, paths) in &selected {
+            if !forget_saved_network(ssid, paths, &view_forget) {
+                break;
+            }
+            forgotten += 1;
+        }
+        if forgotten > 0 {
+            (view_forget.status)(StatusKind::Success, format!("Forgot {}", count_networks(forgotten)));
+        }
+        render_saved_networks(&view_forget);
+        dialog_forget.close();
+    });
+
+    dialog.present();
+}
+
+/// Writes every Wi‑Fi profile, with the secrets NetworkManager hands out, to a
+/// file encrypted with a password the user picks. The worker does the writing.
+fn back_up_profiles(owner: &Dialog, view: &SavedNetworksView) {
+    let owner_chosen = owner.clone();
+    let view = view.clone();
+    ask_backup_password(owner, "Back Up Profiles", true, move |password| {
+        let view = view.clone();
+        choose_backup_file(&owner_chosen, FileChooserAction::Save, move |path| {
+            (view.status)(StatusKind::Info, "Backing up saved networks…".to_string());
+            let (reply, answer) = async_channel::bounded(1);
+            view.worker.send(Command::BackUpProfiles {
+                path: path.clone(),
+                password: password.clone(),
+                reply,
+            });
+            let view = view.clone();
+            gtk4::glib::spawn_future_local(async move {
+                let Ok(written) = answer.recv().await else {
+                    return;
+                };
+                match written {
+                    Ok(()) => (view.status)(
+                        StatusKind::Success,
+                        format!("Backed up saved networks to {}", path.display()),
+                    ),
+                    Err(failure) => (view.status)(
+                        StatusKind::Error,
+                        format!("Failed to back up: {}", backup_failure_message(&failure)),
+                    ),
+                }
+            });
+        });
+    });
+}
+
+/// Adds the profiles of a backup file that are not saved yet, through the worker.
+fn restore_profiles(owner: &Dialog, view: &SavedNetworksView) {
+    let owner_chosen = owner.clone();
+    let view = view.clone();
+    choose_backup_file(owner, FileChooserAction::Open, move |path| {
+        let view = view.clone();
+        ask_backup_password(&owner_chosen, "Restore Profiles", false, move |password| {
+            (view.status)(StatusKind::Info, "Restoring saved networks…".to_string());
+            let (reply, answer) = async_channel::bounded(1);
+            view.worker.send(Command::RestoreProfiles {
+                path: path.clone(),
+                password,
+                reply,
+            });
+            let view = view.clone();
+            gtk4::glib::spawn_future_local(async move {
+                let Ok(restored) = answer.recv().await else {
+                    return;
+                };
+                match restored {
+                    Ok(restored) => (view.status)(StatusKind::Success, restore_summary(&restored)),
+                    Err(failure) => {
+                        (view.status)(
+                            StatusKind::Error,
+                            format!("Failed to restore: {}", backup_failure_message(&failure)),
+                        );
+                        if let BackupFailure::Backend(err) = &failure {
+                            explain_permission_error(&view.parent, err, polkit::SETTINGS_MODIFY_SYSTEM);
+                        }
+                    }
+                }
+                render_saved_networks(&view);
+            });
+        });
+    });
+}
+
+fn backup_failure_message(failure: &BackupFailure) -> String {
+    match failure {
+        BackupFailure::Backend(err) => friendly_error(err),
+        BackupFailure::Archive(message) => message.clone(),
+    }
+}
+
+fn restore_summary(restored: &RestoredProfiles) -> String {
+    let mut summary = format!("Restored {}", count_networks(restored.added.len()));
+    if !restored.skipped.is_empty() {
+        summary.push_str(&format!(", skipped {} already saved", restored.skipped.len()));
+    }
+    summary
+}
+
+/// Asks for the password of a backup, twice when `confirm` is set so a typo
+/// cannot make a new backup unreadable.
+fn ask_backup_password(
+    parent: &impl IsA<gtk4::Window>,
+    title: &str,
+    confirm: bool,
+    on_submit: impl Fn(String) + 'static,
+) {
+    let dialog = Dialog::new();
+    dialog.set_title(Some(title));
+    dialog.set_transient_for(Some(parent));
+    dialog.set_modal(true);
+    dialog.set_default_width(340);
+
+    let content = dialog.content_area();
+    let box_ = GtkBox::new(Orientation::Vertical, 8);
+    box_.set_margin_top(12);
+    box_.set_margin_bottom(12);
+    box_.set_margin_start(12);
+    box_.set_margin_end(12);
+
+    let label = Label::new(Some(if confirm {
+        "Choose a password for the backup. It is needed to restore it."
+    } else {
+        "Password of the backup"
+    }));
+    label.set_wrap(true);
+    label.set_halign(Align::Start);
     let entry = Entry::new();
     entry.set_visibility(false);
-    entry.set_placeholder_text(Some("Optional (leave empty for open network)"));
+    entry.set_placeholder_text(Some("Password"));
     entry.add_css_class("yufi-entry");
-    if initial_error.is_some() {
-        entry.add_css_class("yufi-entry-error");
-    }
-    entry.grab_focus();
-    entry.select_region(0, -1);
+    let repeat = Entry::new();
+    repeat.set_visibility(false);
+    repeat.set_placeholder_text(Some("Repeat password"));
+    repeat.add_css_class("yufi-entry");
+    repeat.set_visible(confirm);
+    let error = Label::new(None);
+    error.add_css_class("error");
+    error.set_halign(Align::Start);
+    error.set_visible(false);
 
     box_.append(&label);
     box_.append(&entry);
+    box_.append(&repeat);
+    box_.append(&error);
 
     let actions = GtkBox::new(Orientation::Horizontal, 8);
-    actions.set_hexpand(true);
-
     let cancel_button = Button::with_label("Cancel");
-    cancel_button.set_hexpand(true);
-    cancel_button.set_halign(Align::Fill);
-
-    let connect_button = Button::with_label("Connect");
-    connect_button.add_css_class("yufi-primary");
-    connect_button.add_css_class("suggested-action");
-    connect_button.set_hexpand(true);
-    connect_button.set_halign(Align::Fill);
-
-    actions.append(&cancel_button);
-    actions.append(&connect_button);
+    let ok_button = Button::with_label("Continue");
+    ok_button.add_css_class("suggested-action");
+    for button in [&cancel_button, &ok_button] {
+        button.set_hexpand(true);
+        button.set_halign(Align::Fill);
+        actions.append(button);
+    }
     box_.append(&actions);
     content.append(&box_);
-    dialog.set_default_widget(Some(&connect_button));
-    let connect_activate = connect_button.clone();
-    entry.connect_activate(move |_| {
-        connect_activate.emit_clicked();
-    });
-
-    let entry_clone = entry.clone();
+    dialog.set_default_widget(Some(&ok_button));
+    for field in [&entry, &repeat] {
+        let ok_activate = ok_button.clone();
+        field.connect_activate(move |_| ok_activate.emit_clicked());
+    }
 
-    let dialog_connect = dialog.clone();
-    let status_connect = status_container.clone();
-    connect_button.connect_clicked(move |_| {
-        let text = entry_clone.text().to_string();
-        let password = if text.trim().is_empty() { None } else { Some(text) };
+    let dialog_ok = dialog.clone();
+    ok_button.connect_clicked(move |_| {
+        let password = entry.text().to_string();
+        let problem = if password.is_empty() {
+            Some("Enter a password")
+        } else if confirm && repeat.text() != password {
+            Some("The passwords do not match")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            error.set_text(problem);
+            error.set_visible(true);
+            return;
+        }
+        dialog_ok.close();
         on_submit(password);
-        status_connect.clear_dialog_label();
-        dialog_connect.close();
     });
 
     let dialog_cancel = dialog.clone();
-    cancel_button.connect_clicked(move |_| {
-        status_container.clear_dialog_label();
-        dialog_cancel.close();
-    });
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
     dialog.present();
 }
 
-fn show_hidden_network_dialog<F: Fn(String, Option<String>) + 'static>(
+/// Lets the user pick where to save a backup, or which one to restore.
+fn choose_backup_file(parent: &Dialog, action: FileChooserAction, on_chosen: impl Fn(PathBuf) + 'static) {
+    let (title, accept) = if action == FileChooserAction::Save {
+        ("Back Up Profiles", "Save")
+    } else {
+        ("Restore Profiles", "Open")
+    };
+    let chooser = FileChooserNative::new(Some(title), Some(parent), action, Some(accept), Some("Cancel"));
+    chooser.set_modal(true);
+    if action == FileChooserAction::Save {
+        chooser.set_current_name(backup::FILE_NAME);
+    }
+    // GTK does not keep native dialogs alive, so this one holds itself until
+    // it is answered.
+    let holder = Rc::new(RefCell::new(None));
+    let holder_response = holder.clone();
+    chooser.connect_response(move |chooser, response| {
+        let chosen = chooser.file().and_then(|file| file.path());
+        if let Some(path) = chosen.filter(|_| response == ResponseType::Accept) {
+            on_chosen(path);
+        }
+        holder_response.borrow_mut().take();
+    });
+    chooser.show();
+    holder.replace(Some(chooser));
+}
+
+/// The saved-networks dialog's list, batch buttons and ticked rows. The selection
+/// maps each ticked SSID to every profile path saved for it.
+#[derive(Clone)]
+struct SavedNetworksView {
+    networks: ListBox,
+    batch: GtkBox,
+    selection: Rc<RefCell<BTreeMap<String, Vec<String>>>>,
+    backend: Rc<NetworkManagerBackend>,
+    worker: Worker,
+    data: Rc<RefCell<AppData>>,
+    status: StatusHandler,
+    parent: ApplicationWindow,
+}
+
+fn count_networks(count: usize) -> String {
+    if count == 1 {
+        "1 network".to_string()
+    } else {
+        format!("{count} networks")
+    }
+}
+
+/// Runs `change` on every ticked network, stopping at the first failure.
+fn apply_to_selection(
+    view: &SavedNetworksView,
+    action: &str,
+    done: &str,
+    change: impl Fn(&NetworkManagerBackend, &str) -> BackendResult<()>,
+) {
+    let ssids: Vec<String> = view.selection.borrow().keys().cloned().collect();
+    for ssid in &ssids {
+        if let Err(err) = change(&view.backend, ssid) {
+            (view.status)(
+                StatusKind::Error,
+                format!("Failed to {action} {ssid}: {}", friendly_error(&err)),
+            );
+            explain_permission_error(&view.parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            return;
+        }
+    }
+    (view.status)(StatusKind::Success, format!("{done} {}", count_networks(ssids.len())));
+}
+
+/// Deletes every profile saved for `ssid` along with its usage history.
+fn forget_saved_network(ssid: &str, paths: &[String], view: &SavedNetworksView) -> bool {
+    for path in paths {
+        if let Err(err) = view.backend.delete_profile(path) {
+            (view.status)(StatusKind::Error, format!("Failed to forget: {}", friendly_error(&err)));
+            explain_permission_error(&view.parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            return false;
+        }
+    }
+    let mut store = view.data.borrow_mut();
+    store.forget(ssid);
+    if let Err(err) = store.save() {
+        (view.status)(StatusKind::Error, format!("Failed to save usage data: {err}"));
+    }
+    true
+}
+
+/// Lists the profiles in the worker and shows them once they arrive; until
+/// then the rows shown stay as they are.
+fn render_saved_networks(view: &SavedNetworksView) {
+    let (reply, answer) = async_channel::bounded(1);
+    view.worker.send(Command::LoadProfiles { reply });
+    let view = view.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(result) = answer.recv().await else {
+            return;
+        };
+        fill_saved_networks(&view, result);
+    });
+}
+
+fn fill_saved_networks(view: &SavedNetworksView, profiles: Result<Vec<SavedProfile>, BackendError>) {
+    let SavedNetworksView {
+        networks,
+        data,
+        parent,
+        ..
+    } = view;
+    while let Some(child) = networks.first_child() {
+        networks.remove(&child);
+    }
+    view.selection.borrow_mut().clear();
+    view.batch.set_sensitive(false);
+
+    let mut profiles = match profiles {
+        Ok(profiles) => profiles,
+        Err(err) => {
+            networks.append(&build_empty_row(&format!(
+                "Failed to load saved profiles: {}",
+                friendly_error(&err)
+            )));
+            explain_permission_error(parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+            return;
+        }
+    };
+
+    // One entry per SSID, keeping every profile path so Forget removes duplicates too.
+    // Newest first, so Duplicate copies the profile that was used last.
+    profiles.sort_by_key(|profile| std::cmp::Reverse(profile.timestamp));
+    let mut saved: Vec<(String, u64, Vec<String>)> = Vec::new();
+    for profile in profiles.iter().cloned() {
+        let recorded = data
+            .borrow()
+            .history
+            .get(&profile.ssid)
+            .map_or(0, |usage| usage.last_connected);
+        let last_connected = recorded.max(profile.timestamp);
+        match saved.iter_mut().find(|(ssid, _, _)| *ssid == profile.ssid) {
+            Some((_, last, paths)) => {
+                *last = (*last).max(last_connected);
+                paths.push(profile.path);
+            }
+            None => saved.push((profile.ssid, last_connected, vec![profile.path])),
+        }
+    }
+    saved.sort_by_key(|(ssid, last_connected, _)| (std::cmp::Reverse(*last_connected), ssid.clone()));
+
+    if saved.is_empty() {
+        networks.append(&build_empty_row("No saved networks"));
+        return;
+    }
+
+    let now = survey::now_secs();
+    let month = current_month().unwrap_or_default();
+    for (ssid, last_connected, paths) in saved {
+        let row_box = GtkBox::new(Orientation::Horizontal, 8);
+        row_box.set_margin_top(6);
+        row_box.set_margin_bottom(6);
+        row_box.set_margin_start(6);
+        row_box.set_margin_end(6);
+
+        let select = CheckButton::new();
+        select.set_valign(Align::Center);
+        let selection = view.selection.clone();
+        let batch = view.batch.clone();
+        let ssid_select = ssid.clone();
+        let paths_select = paths.clone();
+        select.connect_toggled(move |check| {
+            let mut selection = selection.borrow_mut();
+            if check.is_active() {
+                selection.insert(ssid_select.clone(), paths_select.clone());
+            } else {
+                selection.remove(&ssid_select);
+            }
+            batch.set_sensitive(!selection.is_empty());
+        });
+
+        let text_box = GtkBox::new(Orientation::Vertical, 2);
+        text_box.set_hexpand(true);
+        let name = Label::new(Some(&ssid));
+        name.set_halign(Align::Start);
+        name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        name.add_css_class("yufi-network-name");
+        let mut summary = saved_network_summary(data.borrow().history.get(&ssid), last_connected, now);
+        let used = data.borrow().data_usage.get(&ssid).map_or(0, |usage| usage.month(&month).total());
+        if used > 0 {
+            summary.push_str(&format!(" · {} this month", format_bytes(used)));
+        }
+        let detail = Label::new(Some(&summary));
+        detail.set_halign(Align::Start);
+        detail.add_css_class("yufi-legend-label");
+        text_box.append(&name);
+        text_box.append(&detail);
+
+        let duplicate = Button::with_label("Duplicate");
+        duplicate.set_valign(Align::Center);
+        duplicate.set_tooltip_text(Some("Save a copy to try other settings, such as a static IP, on"));
+        let source = profiles.iter().find(|profile| profile.path == paths[0]).map_or(&ssid, |profile| &profile.id);
+        let copy = models::copy_name(source, &profiles);
+        let source_path = paths[0].clone();
+        let ssid_duplicate = ssid.clone();
+        let view_duplicate = view.clone();
+        duplicate.connect_clicked(move |_| {
+            let (reply, answer) = async_channel::bounded(1);
+            view_duplicate.worker.send(Command::DuplicateProfile {
+                path: source_path.clone(),
+                id: copy.clone(),
+                reply,
+            });
+            let (view, ssid, copy) = (view_duplicate.clone(), ssid_duplicate.clone(), copy.clone());
+            gtk4::glib::spawn_future_local(async move {
+                let Ok(result) = answer.recv().await else {
+                    return;
+                };
+                match result {
+                    Ok(_) => (view.status)(
+                        StatusKind::Success,
+                        format!("Saved {copy}, a copy of {ssid} that does not connect automatically"),
+                    ),
+                    Err(err) => {
+                        (view.status)(
+                            StatusKind::Error,
+                            format!("Failed to duplicate {ssid}: {}", friendly_error(&err)),
+                        );
+                        explain_permission_error(&view.parent, &err, polkit::SETTINGS_MODIFY_SYSTEM);
+                    }
+                }
+                render_saved_networks(&view);
+            });
+        });
+
+        let forget = Button::with_label("Forget");
+        forget.add_css_class("destructive-action");
+        forget.set_valign(Align::Center);
+        let view_forget = view.clone();
+        forget.connect_clicked(move |_| {
+            if forget_saved_network(&ssid, &paths, &view_forget) {
+                (view_forget.status)(StatusKind::Success, format!("Forgot {ssid}"));
+            }
+            render_saved_networks(&view_forget);
+        });
+
+        row_box.append(&select);
+        row_box.append(&text_box);
+        row_box.append(&duplicate);
+        row_box.append(&forget);
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&row_box));
+        networks.append(&row);
+    }
+}
+
+fn saved_network_summary(usage: Option<&NetworkUsage>, last_connected: u64, now: u64) -> String {
+    let last = if last_connected == 0 {
+        "Never connected".to_string()
+    } else {
+        format!("Last connected {}", usage::relative_time(last_connected, now))
+    };
+    match usage {
+        Some(usage) if usage.times_connected == 1 => format!("{last} · 1 connection"),
+        Some(usage) if usage.times_connected > 1 => {
+            format!("{last} · {} connections", usage.times_connected)
+        }
+        _ => last,
+    }
+}
+
+fn show_hidden_network_dialog<F: Fn(String, Option<String>, bool) + 'static>(
     parent: &ApplicationWindow,
+    autoconnect: bool,
     on_submit: F,
-    status_container: StatusContainer,
 ) {
     let dialog = Dialog::new();
     dialog.set_title(Some("Hidden Network"));
@@ -2009,9 +8315,7 @@ fn show_hidden_network_dialog<F: Fn(String, Option<String>) + 'static>(
     let error_label = Label::new(None);
     error_label.add_css_class("yufi-dialog-error");
     error_label.set_halign(Align::Start);
-    error_label.set_text("");
-    error_label.set_visible(true);
-    status_container.register_dialog_label(&error_label);
+    error_label.set_visible(false);
 
     let ssid_label = Label::new(Some("Network Name (SSID)"));
     ssid_label.set_halign(Align::Start);
@@ -2024,11 +8328,15 @@ fn show_hidden_network_dialog<F: Fn(String, Option<String>) + 'static>(
     pass_entry.set_visibility(false);
     pass_entry.set_placeholder_text(Some("Optional"));
 
+    let autoconnect_check = CheckButton::with_label("Connect automatically");
+    autoconnect_check.set_active(autoconnect);
+
     box_.append(&error_label);
     box_.append(&ssid_label);
     box_.append(&ssid_entry);
     box_.append(&pass_label);
     box_.append(&pass_entry);
+    box_.append(&autoconnect_check);
     content.append(&box_);
 
     let actions = GtkBox::new(Orientation::Horizontal, 8);
@@ -2057,7 +8365,6 @@ fn show_hidden_network_dialog<F: Fn(String, Option<String>) + 'static>(
     });
 
     let dialog_connect = dialog.clone();
-    let status_connect = status_container.clone();
     connect_button.connect_clicked(move |_| {
         let ssid = ssid_entry.text().to_string();
         if ssid.trim().is_empty() {
@@ -2067,40 +8374,43 @@ fn show_hidden_network_dialog<F: Fn(String, Option<String>) + 'static>(
         }
         let password = pass_entry.text().to_string();
         let pw = if password.is_empty() { None } else { Some(password) };
-        on_submit(ssid, pw);
-        status_connect.clear_dialog_label();
+        on_submit(ssid, pw, autoconnect_check.is_active());
         dialog_connect.close();
     });
 
     let dialog_cancel = dialog.clone();
-    cancel_button.connect_clicked(move |_| {
-        status_container.clear_dialog_label();
-        dialog_cancel.close();
-    });
+    cancel_button.connect_clicked(move |_| dialog_cancel.close());
     dialog.present();
 }
 
-fn load_state_with_backend(
-    nm_backend: &NetworkManagerBackend,
-    status: &StatusHandler,
-) -> AppState {
-    match nm_backend.load_state() {
-        Ok(state) => state,
-        Err(err) => {
-            status(StatusKind::Error, format!("NetworkManager error: {err:?}"));
-            fallback_state(err)
-        }
+fn report_load_error(err: &BackendError, status: &StatusHandler, unavailable: &UnavailableView) {
+    if nm_unavailable(err) {
+        unavailable.show(
+            "NetworkManager unavailable",
+            &friendly_error(err),
+            "YuFi will reconnect automatically once it is running.",
+        );
+    } else if matches!(err, BackendError::NoWifiDevice) {
+        unavailable.show(
+            "No Wi‑Fi device",
+            "NetworkManager does not see a Wi‑Fi adapter.",
+            "Plug in a USB adapter and its networks will appear here.",
+        );
+    } else {
+        status(StatusKind::Error, format!("NetworkManager error: {err:?}"));
     }
 }
 
-fn fallback_state(_error: BackendError) -> AppState {
+fn fallback_state() -> AppState {
     AppState {
         wifi_enabled: false,
         networks: Vec::new(),
+        out_of_range: Vec::new(),
     }
 }
 
 fn load_css() {
+    let _span = profile::span("CSS loading");
     let css = r#"
     .yufi-panel {
         border-radius: 18px;
@@ -2121,25 +8431,36 @@ fn load_css() {
         padding: 6px 10px;
     }
 
+    .yufi-chip {
+        border-radius: 999px;
+        padding: 2px 12px;
+        font-size: 12px;
+    }
+
     .yufi-list {
         background: transparent;
     }
 
-    .yufi-row {
+    .yufi-list > row {
         border-radius: 12px;
         margin-bottom: 8px;
+        padding: 0;
+    }
+
+    .yufi-row {
+        border-radius: 12px;
     }
 
     .yufi-network-name {
         font-weight: 600;
     }
 
-    .yufi-network-lock {
-        opacity: 0.65;
+    .yufi-network-weak {
+        color: @warning_color;
     }
 
-    .yufi-network-lock-open {
-        opacity: 0.35;
+    .yufi-weak-security {
+        color: @warning_color;
     }
 
     .yufi-legend {
@@ -2227,6 +8548,33 @@ fn load_css() {
     .yufi-empty-label {
         font-size: 12px;
     }
+
+    .yufi-stage-label {
+        font-size: 12px;
+    }
+
+    .yufi-scan-age {
+        font-size: 11px;
+        margin-top: -4px;
+    }
+
+    .yufi-ap-info {
+        font-size: 12px;
+        padding: 4px 0;
+    }
+
+    .yufi-unavailable {
+        padding: 24px 12px;
+    }
+
+    .yufi-unavailable-title {
+        font-weight: 700;
+        font-size: 15px;
+    }
+
+    .yufi-unavailable-detail {
+        font-size: 12px;
+    }
     "#;
 
     let provider = CssProvider::new();
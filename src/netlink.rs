@@ -0,0 +1,216 @@
+use crate::link;
+use neli::consts::nl::{NlmF, NlmFFlags};
+use neli::consts::socket::NlFamily;
+use neli::genl::{Genlmsghdr, Nlattr};
+use neli::nl::{NlPayload, Nlmsghdr};
+use neli::socket::NlSocketHandle;
+use neli::types::GenlBuffer;
+use std::fs;
+use std::io;
+
+#[neli::neli_enum(serialized_type = "u8")]
+enum Command {
+    GetStation = 17,
+}
+impl neli::consts::genl::Cmd for Command {}
+
+#[neli::neli_enum(serialized_type = "u16")]
+enum Attribute {
+    Ifindex = 3,
+    Mac = 6,
+    StaInfo = 21,
+}
+impl neli::consts::genl::NlAttrType for Attribute {}
+
+/// Nested in `Attribute::StaInfo`.
+#[neli::neli_enum(serialized_type = "u16")]
+enum StationInfo {
+    TxBitrate = 8,
+    TxPackets = 10,
+    TxRetries = 11,
+    TxFailed = 12,
+    SignalAvg = 13,
+    RxBitrate = 14,
+}
+impl neli::consts::genl::NlAttrType for StationInfo {}
+
+/// Nested in `StationInfo::TxBitrate` and `StationInfo::RxBitrate`.
+#[neli::neli_enum(serialized_type = "u16")]
+enum RateInfo {
+    /// In units of 100 kbit/s; drivers leave it out above 6.5 Gbit/s.
+    Bitrate = 1,
+    Bitrate32 = 5,
+}
+impl neli::consts::genl::NlAttrType for RateInfo {}
+
+/// What the kernel knows about the access point the interface is associated
+/// with. NetworkManager only exposes the transmit rate; the rest comes from
+/// the nl80211 station dump (`iw dev <interface> station dump`). Counters run
+/// from the moment of association.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StationStats {
+    pub bssid: String,
+    pub rx_bitrate_kbps: Option<u32>,
+    pub tx_bitrate_kbps: Option<u32>,
+    pub tx_packets: Option<u32>,
+    pub tx_retries: Option<u32>,
+    pub tx_failed: Option<u32>,
+    pub signal_avg_dbm: Option<i8>,
+}
+
+/// Station statistics of the access point `interface` is associated with, or
+/// None when it is not associated.
+pub fn station_stats(interface: &str) -> io::Result<Option<StationStats>> {
+    let ifindex: u32 = fs::read_to_string(format!("/sys/class/net/{interface}/ifindex"))?
+        .trim()
+        .parse()
+        .map_err(|_| io::Error::other(format!("{interface} has no interface index")))?;
+
+    let mut socket = NlSocketHandle::connect(NlFamily::Generic, None, &[])?;
+    let family = socket.resolve_genl_family("nl80211").map_err(io::Error::other)?;
+    let attrs: GenlBuffer<Attribute, _> = std::iter::once(
+        Nlattr::new(false, false, Attribute::Ifindex, ifindex).map_err(io::Error::other)?,
+    )
+    .collect();
+    let request = Nlmsghdr::new(
+        None,
+        family,
+        NlmFFlags::new(&[NlmF::Request, NlmF::Dump]),
+        None,
+        None,
+        NlPayload::Payload(Genlmsghdr::new(Command::GetStation, 1, attrs)),
+    );
+    socket.send(request).map_err(io::Error::other)?;
+
+    let mut stations = Vec::new();
+    for message in socket.iter::<u16, Genlmsghdr<Command, Attribute>>(false) {
+        let message = message.map_err(io::Error::other)?;
+        if let Some(station) = message.nl_payload.get_payload() {
+            stations.push(parse_station(station).map_err(io::Error::other)?);
+        }
+    }
+    // A managed-mode interface has one station: its access point.
+    Ok(stations.into_iter().next())
+}
+
+fn parse_station(station: &Genlmsghdr<Command, Attribute>) -> Result<StationStats, neli::err::DeError> {
+    let attrs = station.get_attr_handle();
+    let bssid = attrs
+        .get_attribute(Attribute::Mac)
+        .map(|mac| format_mac(mac.nla_payload.as_ref()))
+        .unwrap_or_default();
+    let Some(info) = attrs.get_attribute(Attribute::StaInfo) else {
+        return Ok(StationStats {
+            bssid,
+            ..StationStats::default()
+        });
+    };
+    let info = info.get_attr_handle::<StationInfo>()?;
+    let counter = |kind| info.get_attr_payload_as::<u32>(kind).ok();
+    let rate = |kind| {
+        let rate = info.get_attribute(kind)?.get_attr_handle::<RateInfo>().ok()?;
+        bitrate_kbps(
+            rate.get_attr_payload_as::<u32>(RateInfo::Bitrate32).ok(),
+            rate.get_attr_payload_as::<u16>(RateInfo::Bitrate).ok(),
+        )
+    };
+    Ok(StationStats {
+        bssid,
+        rx_bitrate_kbps: rate(StationInfo::RxBitrate),
+        tx_bitrate_kbps: rate(StationInfo::TxBitrate),
+        tx_packets: counter(StationInfo::TxPackets),
+        tx_retries: counter(StationInfo::TxRetries),
+        tx_failed: counter(StationInfo::TxFailed),
+        signal_avg_dbm: info
+            .get_attr_payload_as::<u8>(StationInfo::SignalAvg)
+            .ok()
+            .map(|signal| signal as i8),
+    })
+}
+
+/// Rates come in units of 100 kbit/s, as a u32 or, from older drivers, a u16.
+fn bitrate_kbps(bitrate32: Option<u32>, bitrate: Option<u16>) -> Option<u32> {
+    bitrate32
+        .or(bitrate.map(u32::from))
+        .filter(|rate| *rate > 0)
+        .map(|rate| rate.saturating_mul(100))
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Rows for the connection card and the diagnostics report. `None` gives the
+/// row names with every value "Not reported".
+pub fn rows(stats: Option<&StationStats>) -> [(&'static str, String); 4] {
+    let stats = stats.cloned().unwrap_or_default();
+    let not_reported = || "Not reported".to_string();
+    let of_sent = |count: Option<u32>| {
+        count.map_or_else(not_reported, |count| match stats.tx_packets.filter(|sent| *sent > 0) {
+            Some(sent) => format!("{count} ({:.1}% of sent frames)", f64::from(count) * 100.0 / f64::from(sent)),
+            None => count.to_string(),
+        })
+    };
+    [
+        (
+            "Receive rate",
+            stats.rx_bitrate_kbps.map_or_else(not_reported, link::format_bitrate),
+        ),
+        ("Retries", of_sent(stats.tx_retries)),
+        ("Failed frames", of_sent(stats.tx_failed)),
+        (
+            "Average signal",
+            stats.signal_avg_dbm.map_or_else(not_reported, |dbm| format!("{dbm} dBm")),
+        ),
+    ]
+}
+
+/// Lines appended to the diagnostics report, starting with a newline.
+pub fn report(interface: &str) -> String {
+    let stats = match station_stats(interface) {
+        Ok(Some(stats)) => stats,
+        Ok(None) => return "\nStation: not associated".to_string(),
+        Err(err) => return format!("\nStation: unavailable ({err})"),
+    };
+    let mut report = format!("\nStation: {}", stats.bssid);
+    if let Some(rate) = stats.tx_bitrate_kbps {
+        report.push_str(&format!("\n  Transmit rate: {}", link::format_bitrate(rate)));
+    }
+    for (name, value) in rows(Some(&stats)) {
+        report.push_str(&format!("\n  {name}: {value}"));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_rates_and_formats_rows() {
+        assert_eq!(bitrate_kbps(Some(8667), Some(8667)), Some(866_700));
+        assert_eq!(bitrate_kbps(None, Some(540)), Some(54_000));
+        assert_eq!(bitrate_kbps(None, Some(0)), None);
+        assert_eq!(format_mac(&[0x50, 0xc7, 0xbf, 0, 0x1a, 0xff]), "50:C7:BF:00:1A:FF");
+
+        let stats = StationStats {
+            bssid: "50:C7:BF:00:1A:FF".to_string(),
+            rx_bitrate_kbps: Some(866_700),
+            tx_packets: Some(2_000),
+            tx_retries: Some(50),
+            tx_failed: Some(4),
+            signal_avg_dbm: Some(-61),
+            ..StationStats::default()
+        };
+        let [rx, retries, failed, signal] = rows(Some(&stats));
+        assert_eq!(rx, ("Receive rate", "866.7 Mbit/s".to_string()));
+        assert_eq!(retries.1, "50 (2.5% of sent frames)");
+        assert_eq!(failed.1, "4 (0.2% of sent frames)");
+        assert_eq!(signal.1, "-61 dBm");
+        assert!(rows(None).iter().all(|(_, value)| value == "Not reported"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+
+/// IEEE registries shipped by distributions (hwdata, ieee-data), tried in order
+/// when a prefix is not in the bundled table.
+const SYSTEM_TABLES: &[&str] = &[
+    "/usr/share/hwdata/oui.txt",
+    "/usr/share/ieee-data/oui.txt",
+    "/usr/share/misc/oui.txt",
+];
+
+/// Short names for common access point and router makers. The IEEE registry
+/// spells them out in full ("TP-LINK TECHNOLOGIES CO.,LTD."), so these win.
+const BUNDLED: &[(&str, &str)] = &[
+    ("000393", "Apple"),
+    ("00040E", "AVM"),
+    ("00055D", "D-Link"),
+    ("00095B", "Netgear"),
+    ("000B86", "Aruba"),
+    ("000C41", "Linksys"),
+    ("000C42", "MikroTik"),
+    ("000C6E", "ASUS"),
+    ("000D88", "D-Link"),
+    ("00112F", "ASUS"),
+    ("001195", "D-Link"),
+    ("001217", "Linksys"),
+    ("00146C", "Netgear"),
+    ("00156D", "Ubiquiti"),
+    ("0015F2", "ASUS"),
+    ("00180A", "Cisco Meraki"),
+    ("001882", "Huawei"),
+    ("001A1E", "Aruba"),
+    ("001A70", "Linksys"),
+    ("001B2F", "Netgear"),
+    ("001E10", "Huawei"),
+    ("001E2A", "Netgear"),
+    ("001F33", "Netgear"),
+    ("001F41", "Ruckus"),
+    ("00223F", "Netgear"),
+    ("00259E", "Huawei"),
+    ("002722", "Ubiquiti"),
+    ("00E0FC", "Huawei"),
+    ("0418D6", "Ubiquiti"),
+    ("18FE34", "Espressif"),
+    ("14CC20", "TP-Link"),
+    ("204E7F", "Netgear"),
+    ("240AC4", "Espressif"),
+    ("246511", "AVM"),
+    ("24A43C", "Ubiquiti"),
+    ("24DEC6", "Aruba"),
+    ("28C68E", "Netgear"),
+    ("2CCF67", "Raspberry Pi"),
+    ("2CE6CC", "Ruckus"),
+    ("30AEA4", "Espressif"),
+    ("3810D5", "AVM"),
+    ("3C5AB4", "Google"),
+    ("3CA62F", "AVM"),
+    ("44D9E7", "Ubiquiti"),
+    ("4C5E0C", "MikroTik"),
+    ("50C7BF", "TP-Link"),
+    ("58B633", "Ruckus"),
+    ("5CCF7F", "Espressif"),
+    ("60E327", "TP-Link"),
+    ("64D154", "MikroTik"),
+    ("687251", "Ubiquiti"),
+    ("6C3B6B", "MikroTik"),
+    ("6CF37F", "Aruba"),
+    ("74ACB9", "Ubiquiti"),
+    ("7483C2", "Ubiquiti"),
+    ("788A20", "Ubiquiti"),
+    ("7CFF4D", "AVM"),
+    ("802AA8", "Ubiquiti"),
+    ("84F3EB", "Espressif"),
+    ("881544", "Cisco Meraki"),
+    ("94B40F", "Aruba"),
+    ("A040A0", "Netgear"),
+    ("A0F3C1", "TP-Link"),
+    ("A4CF12", "Espressif"),
+    ("AC17C8", "Cisco Meraki"),
+    ("B4FBE4", "Ubiquiti"),
+    ("B827EB", "Raspberry Pi"),
+    ("B869F4", "MikroTik"),
+    ("C02506", "AVM"),
+    ("C03F0E", "Netgear"),
+    ("C04A00", "TP-Link"),
+    ("C8BE19", "D-Link"),
+    ("CC2DE0", "MikroTik"),
+    ("D4CA6D", "MikroTik"),
+    ("D83ADD", "Raspberry Pi"),
+    ("D8C7C8", "Aruba"),
+    ("DCA632", "Raspberry Pi"),
+    ("DC9FDB", "Ubiquiti"),
+    ("E0286D", "AVM"),
+    ("E0553D", "Cisco Meraki"),
+    ("E063DA", "Ubiquiti"),
+    ("E091F5", "Netgear"),
+    ("E45F01", "Raspberry Pi"),
+    ("E48D8C", "MikroTik"),
+    ("F09FC2", "Ubiquiti"),
+    ("F0F61C", "Apple"),
+    ("F4F26D", "TP-Link"),
+    ("F4F5D8", "Google"),
+    ("F4F5E8", "Google"),
+    ("FCECDA", "Ubiquiti"),
+];
+
+/// Hardware vendor of a BSSID, from the bundled table or the system's IEEE
+/// registry. Locally administered addresses have no vendor: routers use them
+/// for extra SSIDs and phones for hotspots.
+pub fn vendor(bssid: &str) -> Option<String> {
+    let prefix = prefix(bssid)?;
+    if u8::from_str_radix(&prefix[..2], 16).ok()? & 0x02 != 0 {
+        return Some("Locally administered".to_string());
+    }
+    BUNDLED
+        .iter()
+        .find(|(oui, _)| *oui == prefix)
+        .map(|(_, name)| name.to_string())
+        .or_else(|| system_table().get(&prefix).cloned())
+}
+
+/// Like `vendor`, but formatted to follow a BSSID: `" (TP-Link)"` or nothing.
+pub fn suffix(bssid: &str) -> String {
+    vendor(bssid).map(|name| format!(" ({name})")).unwrap_or_default()
+}
+
+/// First three octets as six upper-case hex digits, e.g. `"50C7BF"`.
+fn prefix(mac: &str) -> Option<String> {
+    let digits: String = mac
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .take(6)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    (digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit())).then_some(digits)
+}
+
+/// The registry is a few megabytes, so it is read once and only when a
+/// prefix misses the bundled table.
+fn system_table() -> &'static HashMap<String, String> {
+    static TABLE: OnceLock<HashMap<String, String>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        SYSTEM_TABLES
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .map(|contents| parse_registry(&contents))
+            .unwrap_or_default()
+    })
+}
+
+/// Reads the `00-1B-21   (hex)\t\tIntel Corporate` lines of `oui.txt`.
+fn parse_registry(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (oui, name) = line.split_once("(hex)")?;
+            let name = name.trim();
+            Some((prefix(oui.trim())?, name.to_string())).filter(|_| !name.is_empty())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_bundled_and_registry_vendors() {
+        assert_eq!(vendor("50:c7:bf:12:34:56").as_deref(), Some("TP-Link"));
+        assert_eq!(vendor("F0:9F:C2:00:00:01").as_deref(), Some("Ubiquiti"));
+        assert_eq!(vendor("02:00:00:00:AA:01").as_deref(), Some("Locally administered"));
+        assert_eq!(suffix("not a mac"), "");
+
+        let registry = "OUI/MA-L            Organization\n\
+                        00-1B-21   (hex)\t\tIntel Corporate\n\
+                        001B21     (base 16)\t\tIntel Corporate\n\
+                        \t\t\t\tPO Box 1\n";
+        let table = parse_registry(registry);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.get("001B21").map(String::as_str), Some("Intel Corporate"));
+    }
+}
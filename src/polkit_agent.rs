@@ -0,0 +1,117 @@
+//! Finding and starting a polkit authentication agent. Full desktops start one
+//! themselves; bare window managers often do not, and then NetworkManager
+//! refuses anything that needs a password without asking for it.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+/// An agent YuFi can start, with where distributions install it.
+pub struct Agent {
+    pub name: &'static str,
+    paths: &'static [&'static str],
+}
+
+const AGENTS: &[Agent] = &[
+    Agent {
+        name: "polkit-gnome",
+        paths: &[
+            "/usr/lib/polkit-gnome/polkit-gnome-authentication-agent-1",
+            "/usr/libexec/polkit-gnome-authentication-agent-1",
+            "/usr/lib/x86_64-linux-gnu/polkit-gnome/polkit-gnome-authentication-agent-1",
+        ],
+    },
+    Agent {
+        name: "polkit-kde",
+        paths: &[
+            "/usr/lib/polkit-kde-authentication-agent-1",
+            "/usr/libexec/polkit-kde-authentication-agent-1",
+            "/usr/lib/x86_64-linux-gnu/libexec/polkit-kde-authentication-agent-1",
+        ],
+    },
+    Agent {
+        name: "lxqt-policykit",
+        paths: &["/usr/bin/lxqt-policykit-agent"],
+    },
+    Agent {
+        name: "mate-polkit",
+        paths: &[
+            "/usr/lib/mate-polkit/polkit-mate-authentication-agent-1",
+            "/usr/libexec/polkit-mate-authentication-agent-1",
+        ],
+    },
+    Agent {
+        name: "xfce-polkit",
+        paths: &["/usr/lib/xfce-polkit/xfce-polkit", "/usr/libexec/xfce-polkit"],
+    },
+    Agent {
+        name: "hyprpolkitagent",
+        paths: &["/usr/lib/hyprpolkitagent/hyprpolkitagent", "/usr/libexec/hyprpolkitagent"],
+    },
+];
+
+/// Shells with an agent built in.
+const SHELLS: &[&str] = &["gnome-shell", "cinnamon", "plasmashell"];
+
+/// Whether a known agent, or a shell with one built in, is running. Processes
+/// are only visible through `/proc`; without it this answers no.
+pub fn running() -> bool {
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return false;
+    };
+    processes
+        .flatten()
+        .any(|process| fs::read(process.path().join("cmdline")).is_ok_and(|cmdline| is_agent(&cmdline)))
+}
+
+/// Whether the NUL-separated `cmdline` of a process starts an agent.
+fn is_agent(cmdline: &[u8]) -> bool {
+    let program = cmdline.split(|byte| *byte == 0).next().unwrap_or_default();
+    let Some(name) = Path::new(OsStr::from_bytes(program)).file_name() else {
+        return false;
+    };
+    SHELLS.iter().any(|shell| OsStr::new(shell) == name)
+        || AGENTS
+            .iter()
+            .flat_map(|agent| agent.paths)
+            .any(|path| Path::new(path).file_name() == Some(name))
+}
+
+/// The first known agent installed here, with the path to start it from.
+pub fn installed() -> Option<(&'static Agent, &'static Path)> {
+    AGENTS.iter().find_map(|agent| {
+        agent
+            .paths
+            .iter()
+            .map(Path::new)
+            .find(|path| path.is_file())
+            .map(|path| (agent, path))
+    })
+}
+
+/// Starts the agent at `path` for the rest of the session.
+pub fn start(path: &Path) -> io::Result<()> {
+    let mut child = Command::new(path).spawn()?;
+    // Reaped if it ever exits, so it does not linger as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_agents_and_shells_by_program_name() {
+        assert!(is_agent(b"/usr/lib/polkit-gnome/polkit-gnome-authentication-agent-1\0"));
+        assert!(is_agent(b"/opt/odd/place/lxqt-policykit-agent\0--debug\0"));
+        assert!(is_agent(b"/usr/bin/gnome-shell\0"));
+        assert!(!is_agent(b"/usr/bin/bash\0/usr/lib/polkit-gnome/polkit-gnome-authentication-agent-1\0"));
+        assert!(!is_agent(b"yufi\0"));
+        assert!(!is_agent(b""));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::demo;
+use crate::roaming::RoamingMode;
+use crate::schedule::WifiSchedule;
+use crate::search::BandFilter;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const FILE_NAME: &str = "preferences.conf";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preferences {
+    pub autoconnect_new: bool,
+    /// Ask before joining a network that does not encrypt traffic.
+    pub warn_open_networks: bool,
+    /// Turn Wi‑Fi on when YuFi starts and finds it switched off.
+    pub enable_wifi_on_start: bool,
+    pub roaming: RoamingMode,
+    /// Signal strength (percent) below which the roaming assistant starts counting.
+    pub roaming_threshold: u8,
+    /// How long the signal has to stay below the threshold before roaming.
+    pub roaming_delay_secs: u64,
+    /// Notify from the tray when the active network stays below
+    /// `low_signal_threshold` percent for `low_signal_delay_secs`.
+    pub low_signal_warning: bool,
+    pub low_signal_threshold: u8,
+    pub low_signal_delay_secs: u64,
+    /// Turn Wi‑Fi off and back on at the times in `wifi_schedule`.
+    pub wifi_schedule_enabled: bool,
+    pub wifi_schedule: WifiSchedule,
+    /// Skip list animations even when the desktop has them on.
+    pub reduce_motion: bool,
+    /// Attribute filters from the search box (`band:5 sec:wpa3`), put back on launch.
+    pub list_filters: String,
+    /// Bands picked with the chips above the list.
+    pub list_bands: BandFilter,
+    /// Whether the "Out of range" section of the list is expanded.
+    pub show_out_of_range: bool,
+    /// List each band of a dual-band network as its own entry.
+    pub split_bands: bool,
+    /// Clicking a network expands its row instead of opening a dialog.
+    pub inline_details: bool,
+    /// Monthly data quota for metered networks in megabytes; 0 turns the warning off.
+    pub metered_quota_mb: u64,
+    /// Base URL of a LibreSpeed backend for the speed test; empty uses Cloudflare.
+    pub speed_test_server: String,
+    /// `host` or `host:port` the details dialog measures latency to.
+    pub latency_host: String,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            autoconnect_new: true,
+            warn_open_networks: true,
+            enable_wifi_on_start: false,
+            roaming: RoamingMode::Off,
+            roaming_threshold: 30,
+            roaming_delay_secs: 20,
+            low_signal_warning: true,
+            low_signal_threshold: 20,
+            low_signal_delay_secs: 60,
+            wifi_schedule_enabled: false,
+            wifi_schedule: WifiSchedule::default(),
+            reduce_motion: false,
+            list_filters: String::new(),
+            list_bands: BandFilter::default(),
+            show_out_of_range: false,
+            split_bands: false,
+            inline_details: false,
+            metered_quota_mb: 0,
+            speed_test_server: String::new(),
+            latency_host: "1.1.1.1".to_string(),
+        }
+    }
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = config_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut preferences = Self::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let value = value.trim();
+            match key.trim() {
+                "autoconnect_new" => set_parsed(&mut preferences.autoconnect_new, value),
+                "warn_open_networks" => set_parsed(&mut preferences.warn_open_networks, value),
+                "enable_wifi_on_start" => set_parsed(&mut preferences.enable_wifi_on_start, value),
+                "roaming" => set_parsed(&mut preferences.roaming, value),
+                "roaming_threshold" => set_parsed(&mut preferences.roaming_threshold, value),
+                "roaming_delay_secs" => set_parsed(&mut preferences.roaming_delay_secs, value),
+                "low_signal_warning" => set_parsed(&mut preferences.low_signal_warning, value),
+                "low_signal_threshold" => set_parsed(&mut preferences.low_signal_threshold, value),
+                "low_signal_delay_secs" => set_parsed(&mut preferences.low_signal_delay_secs, value),
+                "wifi_schedule_enabled" => set_parsed(&mut preferences.wifi_schedule_enabled, value),
+                "wifi_schedule" => set_parsed(&mut preferences.wifi_schedule, value),
+                "reduce_motion" => set_parsed(&mut preferences.reduce_motion, value),
+                "list_filters" => preferences.list_filters = value.to_string(),
+                "list_bands" => set_parsed(&mut preferences.list_bands, value),
+                "show_out_of_range" => set_parsed(&mut preferences.show_out_of_range, value),
+                "split_bands" => set_parsed(&mut preferences.split_bands, value),
+                "inline_details" => set_parsed(&mut preferences.inline_details, value),
+                "metered_quota_mb" => set_parsed(&mut preferences.metered_quota_mb, value),
+                "speed_test_server" => preferences.speed_test_server = value.to_string(),
+                "latency_host" if !value.is_empty() => preferences.latency_host = value.to_string(),
+                _ => {}
+            }
+        }
+        preferences
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "autoconnect_new={}\nwarn_open_networks={}\nenable_wifi_on_start={}\nroaming={}\nroaming_threshold={}\nroaming_delay_secs={}\n\
+             low_signal_warning={}\nlow_signal_threshold={}\nlow_signal_delay_secs={}\n\
+             wifi_schedule_enabled={}\nwifi_schedule={}\nreduce_motion={}\n\
+             list_filters={}\nlist_bands={}\nshow_out_of_range={}\nsplit_bands={}\n\
+             inline_details={}\nmetered_quota_mb={}\nspeed_test_server={}\nlatency_host={}\n",
+            self.autoconnect_new,
+            self.warn_open_networks,
+            self.enable_wifi_on_start,
+            self.roaming.key(),
+            self.roaming_threshold,
+            self.roaming_delay_secs,
+            self.low_signal_warning,
+            self.low_signal_threshold,
+            self.low_signal_delay_secs,
+            self.wifi_schedule_enabled,
+            self.wifi_schedule,
+            self.reduce_motion,
+            self.list_filters,
+            self.list_bands,
+            self.show_out_of_range,
+            self.split_bands,
+            self.inline_details,
+            self.metered_quota_mb,
+            self.speed_test_server,
+            self.latency_host
+        )
+    }
+}
+
+/// Leaves `target` at its default when `value` does not parse.
+fn set_parsed<T: FromStr>(target: &mut T, value: &str) {
+    if let Ok(parsed) = value.parse() {
+        *target = parsed;
+    }
+}
+
+/// Hidden networks stored here before they moved to the app data file.
+pub fn legacy_hidden_ssids() -> Vec<String> {
+    config_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| parse_hidden_ssids(&contents))
+        .unwrap_or_default()
+}
+
+fn parse_hidden_ssids(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .filter(|(key, _)| key.trim() == "hidden_ssid")
+        .map(|(_, value)| value.trim().to_string())
+        .collect()
+}
+
+fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+/// `$XDG_CONFIG_HOME/yufi`, falling back to `~/.config/yufi`.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(root) = demo::root() {
+        return Some(root.join("config").join("yufi"));
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("yufi"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_ignores_unknown_keys() {
+        let preferences = Preferences {
+            autoconnect_new: false,
+            warn_open_networks: false,
+            enable_wifi_on_start: true,
+            roaming: RoamingMode::Prompt,
+            roaming_threshold: 25,
+            roaming_delay_secs: 45,
+            low_signal_warning: false,
+            low_signal_threshold: 35,
+            low_signal_delay_secs: 120,
+            wifi_schedule_enabled: true,
+            wifi_schedule: "22:30-06:45".parse().unwrap(),
+            reduce_motion: true,
+            list_filters: "band:5 sec:wpa3".to_string(),
+            list_bands: "5".parse().unwrap(),
+            show_out_of_range: true,
+            split_bands: true,
+            inline_details: true,
+            metered_quota_mb: 2_000,
+            speed_test_server: "https://speed.example.org/backend".to_string(),
+            latency_host: "router.lan:80".to_string(),
+        };
+        assert_eq!(Preferences::parse(&preferences.serialize()), preferences);
+
+        let parsed = Preferences::parse(
+            "# comment\nfuture_key = 1\nautoconnect_new = nope\nroaming = sometimes\nroaming_threshold = -5\n",
+        );
+        assert_eq!(parsed, Preferences::default());
+    }
+
+    #[test]
+    fn reads_legacy_hidden_ssids() {
+        let contents = "autoconnect_new=true\nhidden_ssid=HP-Print-42\nhidden_ssid=Neighbour = WiFi\n";
+        assert_eq!(parse_hidden_ssids(contents), ["HP-Print-42", "Neighbour = WiFi"]);
+        assert_eq!(Preferences::parse(contents), Preferences::default());
+    }
+}
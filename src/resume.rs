@@ -0,0 +1,27 @@
+use crate::UiEvent;
+use std::thread;
+use zbus::blocking::{Connection, Proxy};
+
+const LOGIND_BUS_NAME: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+/// Sends `UiEvent::Resumed` whenever logind reports that the machine woke up
+/// (`PrepareForSleep(false)`). Fails without logind, e.g. on systems without
+/// systemd, where the list simply stays as it was until the next refresh.
+pub fn watch(ui_tx: async_channel::Sender<UiEvent>) -> zbus::Result<()> {
+    let conn = Connection::system()?;
+    let manager = Proxy::new(&conn, LOGIND_BUS_NAME, LOGIND_PATH, LOGIND_MANAGER)?;
+    let signals = manager.receive_signal("PrepareForSleep")?;
+    thread::spawn(move || {
+        for message in signals {
+            let Ok(going_to_sleep) = message.body().deserialize::<bool>() else {
+                continue;
+            };
+            if !going_to_sleep && ui_tx.send_blocking(UiEvent::Resumed).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use crate::models::{AppState, Network};
+use std::str::FromStr;
+
+/// How much stronger (in percentage points) a saved network must be than the
+/// current one, so two similar access points do not bounce the user back and forth.
+const MIN_GAIN: u8 = 15;
+
+/// What the roaming assistant does once a stronger saved network is found.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoamingMode {
+    #[default]
+    Off,
+    Prompt,
+    Switch,
+}
+
+impl RoamingMode {
+    pub const ALL: [RoamingMode; 3] = [RoamingMode::Off, RoamingMode::Prompt, RoamingMode::Switch];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            RoamingMode::Off => "off",
+            RoamingMode::Prompt => "prompt",
+            RoamingMode::Switch => "switch",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RoamingMode::Off => "Off",
+            RoamingMode::Prompt => "Ask first",
+            RoamingMode::Switch => "Switch automatically",
+        }
+    }
+}
+
+impl FromStr for RoamingMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.key() == value)
+            .ok_or(())
+    }
+}
+
+/// A move the assistant suggests: from the weak active network to a saved one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub from: String,
+    pub from_strength: u8,
+    pub to: String,
+    pub to_strength: u8,
+}
+
+/// Tracks how long the active network has been below a signal threshold.
+#[derive(Debug, Default)]
+pub struct WeakSpell {
+    /// Active SSID and when its signal first dropped below the threshold.
+    since: Option<(String, u64)>,
+    /// Set once the spell was acted on, so each one is acted on at most once.
+    handled: bool,
+}
+
+impl WeakSpell {
+    /// Returns the active network once it has stayed below `threshold` for
+    /// `delay_secs`, until `handle` is called. The spell ends when the signal
+    /// recovers or another network becomes active.
+    pub fn observe<'a>(
+        &mut self,
+        state: &'a AppState,
+        threshold: u8,
+        delay_secs: u64,
+        now: u64,
+    ) -> Option<&'a Network> {
+        let Some(active) = state
+            .networks
+            .iter()
+            .find(|network| network.is_active && network.strength < threshold)
+        else {
+            self.since = None;
+            return None;
+        };
+        let since = match &self.since {
+            Some((ssid, since)) if *ssid == active.ssid => *since,
+            _ => {
+                self.since = Some((active.ssid.clone(), now));
+                self.handled = false;
+                now
+            }
+        };
+        (!self.handled && now.saturating_sub(since) >= delay_secs).then_some(active)
+    }
+
+    pub fn handle(&mut self) {
+        self.handled = true;
+    }
+}
+
+/// The strongest saved network in range that clearly beats `current` and is
+/// itself above `threshold`.
+pub fn stronger_saved<'a>(state: &'a AppState, current: &Network, threshold: u8) -> Option<&'a Network> {
+    let needed = threshold.max(current.strength.saturating_add(MIN_GAIN));
+    state
+        .networks
+        .iter()
+        .filter(|network| network.is_saved && !network.is_active && network.strength >= needed)
+        .max_by_key(|network| network.strength)
+}
+
+/// Watches successive states for an active network that stays weak.
+#[derive(Debug, Default)]
+pub struct RoamingAssistant {
+    spell: WeakSpell,
+}
+
+impl RoamingAssistant {
+    /// Returns a suggestion once the active network has been below `threshold`
+    /// for `delay_secs` and a saved network in range is clearly stronger. Each
+    /// weak spell yields at most one suggestion.
+    pub fn observe(
+        &mut self,
+        state: &AppState,
+        threshold: u8,
+        delay_secs: u64,
+        now: u64,
+    ) -> Option<Suggestion> {
+        let active = self.spell.observe(state, threshold, delay_secs, now)?;
+        let target = stronger_saved(state, active, threshold)?;
+        self.spell.handle();
+        Some(Suggestion {
+            from: active.ssid.clone(),
+            from_strength: active.strength,
+            to: target.ssid.clone(),
+            to_strength: target.strength,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NetworkAction;
+
+    fn network(ssid: &str, strength: u8, is_saved: bool, is_active: bool) -> Network {
+        Network {
+            ssid: ssid.to_string(),
+            signal_icon: "network-wireless-signal-weak-symbolic",
+            action: NetworkAction::None,
+            strength,
+            is_active,
+            is_saved,
+            is_secure: true,
+            security: "wpa2",
+            access_points: Vec::new(),
+        }
+    }
+
+    fn state(networks: Vec<Network>) -> AppState {
+        AppState {
+            wifi_enabled: true,
+            networks,
+            out_of_range: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn suggests_stronger_saved_network_after_delay() {
+        let mut assistant = RoamingAssistant::default();
+        let weak = state(vec![
+            network("Upstairs", 20, true, true),
+            network("Downstairs", 70, true, false),
+            network("Neighbour", 95, false, false),
+            network("Garage", 30, true, false),
+        ]);
+        assert_eq!(assistant.observe(&weak, 30, 20, 100), None);
+        assert_eq!(assistant.observe(&weak, 30, 20, 110), None);
+        let suggestion = assistant.observe(&weak, 30, 20, 120).expect("suggestion");
+        assert_eq!((suggestion.from.as_str(), suggestion.to.as_str()), ("Upstairs", "Downstairs"));
+        assert_eq!(assistant.observe(&weak, 30, 20, 200), None);
+
+        let recovered = state(vec![network("Upstairs", 45, true, true)]);
+        assert_eq!(assistant.observe(&recovered, 30, 20, 210), None);
+        assert_eq!(assistant.observe(&weak, 30, 20, 220), None);
+        assert!(assistant.observe(&weak, 30, 20, 240).is_some());
+
+        let close = state(vec![
+            network("Upstairs", 25, true, true),
+            network("Downstairs", 35, true, false),
+        ]);
+        let mut assistant = RoamingAssistant::default();
+        assistant.observe(&close, 30, 0, 0);
+        assert_eq!(assistant.observe(&close, 30, 0, 10), None);
+    }
+
+    #[test]
+    fn weak_spell_is_reported_until_handled() {
+        let mut spell = WeakSpell::default();
+        let weak = state(vec![network("Upstairs", 15, true, true)]);
+        assert!(spell.observe(&weak, 20, 60, 0).is_none());
+        assert_eq!(spell.observe(&weak, 20, 60, 60).map(|network| network.strength), Some(15));
+        assert!(spell.observe(&weak, 20, 60, 90).is_some());
+        spell.handle();
+        assert!(spell.observe(&weak, 20, 60, 120).is_none());
+
+        let other = state(vec![network("Downstairs", 10, true, true)]);
+        assert!(spell.observe(&other, 20, 60, 130).is_none());
+        assert!(spell.observe(&other, 20, 60, 190).is_some());
+    }
+}
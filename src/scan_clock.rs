@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+/// When the device last scanned, and whether the user asked for a scan that
+/// NetworkManager would refuse until its cooldown is over.
+#[derive(Debug)]
+pub struct ScanClock {
+    cooldown: Duration,
+    last_scan: Option<Instant>,
+    pending: bool,
+}
+
+impl ScanClock {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            last_scan: None,
+            pending: false,
+        }
+    }
+
+    /// Notes a scan that happened at `at`; older reports are ignored.
+    pub fn record(&mut self, at: Instant) {
+        if self.last_scan.is_none_or(|last| at > last) {
+            self.last_scan = Some(at);
+        }
+    }
+
+    /// Time left before a scan request would be accepted.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.last_scan?);
+        self.cooldown.checked_sub(elapsed).filter(|left| !left.is_zero())
+    }
+
+    pub fn last_scan(&self) -> Option<Instant> {
+        self.last_scan
+    }
+
+    /// Returns whether to scan right away; otherwise the scan is queued for
+    /// when the cooldown ends.
+    pub fn request(&mut self, now: Instant) -> bool {
+        let ready = self.remaining(now).is_none();
+        self.pending = !ready;
+        ready
+    }
+
+    /// Queues a scan regardless of the clock, e.g. after NetworkManager
+    /// refused one that YuFi thought was allowed.
+    pub fn queue(&mut self) {
+        self.pending = true;
+    }
+
+    /// Whether a queued scan is due now; a due scan is handed out only once.
+    pub fn take_pending(&mut self, now: Instant) -> bool {
+        let due = self.pending && self.remaining(now).is_none();
+        if due {
+            self.pending = false;
+        }
+        due
+    }
+}
+
+/// "Updated 12 s ago" for a scan `age` old, rounded down to the largest unit.
+pub fn updated_ago(age: Duration) -> String {
+    match age.as_secs() {
+        0..5 => "Updated just now".to_string(),
+        secs @ 5..60 => format!("Updated {secs} s ago"),
+        secs @ 60..3_600 => format!("Updated {} min ago", secs / 60),
+        secs => format!("Updated {} h ago", secs / 3_600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queues_scans_requested_during_cooldown() {
+        let start = Instant::now();
+        let secs = |secs| start + Duration::from_secs(secs);
+        let mut clock = ScanClock::new(Duration::from_secs(10));
+        assert!(clock.request(start));
+
+        clock.record(start);
+        assert_eq!(clock.remaining(secs(4)), Some(Duration::from_secs(6)));
+        assert!(!clock.request(secs(4)));
+        assert!(!clock.take_pending(secs(9)));
+        assert!(clock.take_pending(secs(10)));
+        assert!(!clock.take_pending(secs(11)));
+
+        clock.record(secs(20));
+        clock.record(secs(15));
+        assert_eq!(clock.remaining(secs(25)), Some(Duration::from_secs(5)));
+        assert!(clock.request(secs(30)));
+    }
+
+    #[test]
+    fn describes_scan_age() {
+        assert_eq!(updated_ago(Duration::from_millis(4_900)), "Updated just now");
+        assert_eq!(updated_ago(Duration::from_secs(12)), "Updated 12 s ago");
+        assert_eq!(updated_ago(Duration::from_secs(150)), "Updated 2 min ago");
+        assert_eq!(updated_ago(Duration::from_secs(7_300)), "Updated 2 h ago");
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const KEY: &str = "wifi.scan-rand-mac-address";
+const MAIN_CONFIG: &str = "/etc/NetworkManager/NetworkManager.conf";
+/// Drop-in directories, lowest precedence first: a file in a later directory
+/// shadows one with the same name in an earlier directory.
+const CONF_DIRS: &[&str] = &[
+    "/usr/lib/NetworkManager/conf.d",
+    "/run/NetworkManager/conf.d",
+    "/etc/NetworkManager/conf.d",
+];
+const DROP_IN: &str = "/etc/NetworkManager/conf.d/90-yufi-scan-mac.conf";
+/// pkexec's exit code when the authentication dialog is dismissed.
+const PKEXEC_DISMISSED: i32 = 126;
+
+/// Whether NetworkManager sends probe requests from a random MAC address while
+/// disconnected. It does unless a configuration file turns it off.
+pub fn randomization_enabled() -> bool {
+    config_files()
+        .into_iter()
+        .rev()
+        .find_map(|path| parse(&fs::read_to_string(path).ok()?))
+        .unwrap_or(true)
+}
+
+/// Writes YuFi's drop-in under `/etc/NetworkManager/conf.d`, which needs
+/// administrator rights and asks for them through pkexec. NetworkManager only
+/// picks the change up after a configuration reload.
+pub fn set_randomization(enabled: bool) -> io::Result<()> {
+    let mut child = Command::new("pkexec")
+        .args(["tee", DROP_IN])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(drop_in(enabled).as_bytes())?;
+    }
+    let status = child.wait()?;
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(PKEXEC_DISMISSED) => Err(io::Error::other("Authorization was cancelled")),
+        _ => Err(io::Error::other(format!("Could not write {DROP_IN}"))),
+    }
+}
+
+fn drop_in(enabled: bool) -> String {
+    let value = if enabled { "yes" } else { "no" };
+    format!("# Written by YuFi (Preferences > Randomize MAC address while scanning)\n[device-yufi-scan-mac]\n{KEY}={value}\n")
+}
+
+/// NetworkManager.conf first, then the drop-ins in file-name order.
+fn config_files() -> Vec<PathBuf> {
+    let mut drop_ins = BTreeMap::new();
+    for dir in CONF_DIRS {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if name.to_string_lossy().ends_with(".conf") {
+                drop_ins.insert(name, entry.path());
+            }
+        }
+    }
+    let mut files = vec![PathBuf::from(MAIN_CONFIG)];
+    files.extend(drop_ins.into_values());
+    files
+}
+
+/// The last value of the setting in a `[device]` or `[device-*]` section.
+fn parse(contents: &str) -> Option<bool> {
+    let mut in_device = false;
+    let mut value = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(section) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            in_device = section == "device" || section.starts_with("device-");
+            continue;
+        }
+        let Some((key, setting)) = line.split_once('=').filter(|_| in_device) else {
+            continue;
+        };
+        if key.trim() == KEY {
+            value = match setting.trim().to_lowercase().as_str() {
+                "yes" | "true" | "on" | "1" => Some(true),
+                "no" | "false" | "off" | "0" => Some(false),
+                _ => value,
+            };
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_setting_from_device_sections_only() {
+        assert_eq!(parse("[main]\nplugins=keyfile\n"), None);
+        assert_eq!(parse("[main]\nwifi.scan-rand-mac-address=no\n"), None);
+        assert_eq!(
+            parse("[device]\nwifi.scan-rand-mac-address=no\n[device-wlan]\nmatch-device=interface-name:wlan0\nwifi.scan-rand-mac-address = yes\n"),
+            Some(true)
+        );
+        assert_eq!(parse(&drop_in(false)), Some(false));
+        assert_eq!(parse(&drop_in(true)), Some(true));
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Local wall-clock time, in minutes since midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay(u16);
+
+impl TimeOfDay {
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        (hour < 24 && minute < 60).then(|| Self((hour * 60 + minute) as u16))
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = ();
+
+    /// Accepts `H:MM` or `HH:MM` in 24-hour time.
+    fn from_str(value: &str) -> Result<Self, ()> {
+        let (hour, minute) = value.trim().split_once(':').ok_or(())?;
+        if minute.len() != 2 {
+            return Err(());
+        }
+        let hour = hour.parse().map_err(|_| ())?;
+        let minute = minute.parse().map_err(|_| ())?;
+        Self::new(hour, minute).ok_or(())
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
+}
+
+/// Daily window during which Wi‑Fi is turned off, e.g. 23:00–07:00.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WifiSchedule {
+    pub off_at: TimeOfDay,
+    pub on_at: TimeOfDay,
+}
+
+impl Default for WifiSchedule {
+    fn default() -> Self {
+        Self {
+            off_at: TimeOfDay(23 * 60),
+            on_at: TimeOfDay(7 * 60),
+        }
+    }
+}
+
+impl WifiSchedule {
+    /// Whether `now` falls inside the off window, which may wrap past midnight.
+    /// Equal start and end times make an empty window.
+    pub fn wifi_off(&self, now: TimeOfDay) -> bool {
+        let (off, on) = (self.off_at, self.on_at);
+        if off <= on {
+            off <= now && now < on
+        } else {
+            now >= off || now < on
+        }
+    }
+}
+
+impl FromStr for WifiSchedule {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        let (off_at, on_at) = value.split_once('-').ok_or(())?;
+        Ok(Self {
+            off_at: off_at.parse()?,
+            on_at: on_at.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for WifiSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.off_at, self.on_at)
+    }
+}
+
+/// Turns periodic clock readings into Wi‑Fi on/off requests.
+#[derive(Debug, Default)]
+pub struct ScheduleTracker {
+    was_off: Option<bool>,
+}
+
+impl ScheduleTracker {
+    /// Returns the radio state to apply when `now` crosses a boundary of the
+    /// schedule since the previous tick. The first tick and ticks without a
+    /// schedule only record, so starting YuFi or toggling Wi‑Fi by hand inside
+    /// the window is left alone until the next boundary.
+    pub fn tick(&mut self, schedule: Option<&WifiSchedule>, now: TimeOfDay) -> Option<bool> {
+        let Some(schedule) = schedule else {
+            self.was_off = None;
+            return None;
+        };
+        let off = schedule.wifi_off(now);
+        let crossed = self.was_off.is_some_and(|was_off| was_off != off);
+        self.was_off = Some(off);
+        crossed.then_some(!off)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> TimeOfDay {
+        value.parse().expect("time")
+    }
+
+    #[test]
+    fn overnight_window_wraps_midnight() {
+        let schedule: WifiSchedule = "23:00-7:00".parse().expect("schedule");
+        assert_eq!(schedule.to_string(), "23:00-07:00");
+        assert!(schedule.wifi_off(at("23:00")) && schedule.wifi_off(at("03:15")));
+        assert!(!schedule.wifi_off(at("07:00")) && !schedule.wifi_off(at("22:59")));
+
+        let daytime: WifiSchedule = "09:30-17:00".parse().expect("schedule");
+        assert!(daytime.wifi_off(at("12:00")) && !daytime.wifi_off(at("18:00")));
+        assert!("25:00-07:00".parse::<WifiSchedule>().is_err());
+        assert!("23:0-07:00".parse::<WifiSchedule>().is_err());
+    }
+
+    #[test]
+    fn tracker_acts_only_on_boundaries() {
+        let schedule = WifiSchedule::default();
+        let mut tracker = ScheduleTracker::default();
+        assert_eq!(tracker.tick(Some(&schedule), at("23:30")), None);
+        assert_eq!(tracker.tick(Some(&schedule), at("06:59")), None);
+        assert_eq!(tracker.tick(Some(&schedule), at("07:00")), Some(true));
+        assert_eq!(tracker.tick(Some(&schedule), at("22:59")), None);
+        assert_eq!(tracker.tick(Some(&schedule), at("23:00")), Some(false));
+        assert_eq!(tracker.tick(None, at("07:00")), None);
+        assert_eq!(tracker.tick(Some(&schedule), at("07:01")), None);
+    }
+}
@@ -0,0 +1,370 @@
+use crate::analyzer::{self, Band};
+use crate::models::Network;
+use std::fmt;
+use std::str::FromStr;
+
+const MATCH_SCORE: i32 = 16;
+const CONSECUTIVE_BONUS: i32 = 8;
+const FIRST_CHAR_BONUS: i32 = 8;
+const WORD_START_BONUS: i32 = 6;
+const CAMEL_CASE_BONUS: i32 = 4;
+const GAP_PENALTY: i32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Char indices into the candidate that matched the query.
+    pub positions: Vec<usize>,
+}
+
+fn position_bonus(chars: &[char], index: usize) -> i32 {
+    let Some(previous) = index.checked_sub(1).map(|prev| chars[prev]) else {
+        return FIRST_CHAR_BONUS;
+    };
+    let current = chars[index];
+    if !previous.is_alphanumeric() {
+        WORD_START_BONUS
+    } else if previous.is_lowercase() && current.is_uppercase()
+        || previous.is_alphabetic() && current.is_numeric()
+    {
+        CAMEL_CASE_BONUS
+    } else {
+        0
+    }
+}
+
+/// Scores `query` as a case-insensitive subsequence of `candidate`, rewarding
+/// consecutive runs and matches at word boundaries (skim/fzf style). Returns the
+/// best-scoring alignment so the matched characters can be highlighted.
+pub fn fuzzy_match(candidate: &str, query: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let lowered: Vec<char> = chars
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    let width = chars.len();
+    if width < query.len() {
+        return None;
+    }
+
+    // scores[i][j]: best score with query[i] matched at candidate[j].
+    let mut scores = vec![vec![None::<i32>; width]; query.len()];
+    let mut from = vec![vec![0usize; width]; query.len()];
+    for (i, wanted) in query.iter().enumerate() {
+        // Best predecessor seen so far, stored as score + k * GAP so the gap to j is cheap to apply.
+        let mut best_previous: Option<(i32, usize)> = None;
+        for j in 0..width {
+            // Only predecessors at least two chars back count as gapped; j - 1 is consecutive.
+            let gapped_candidate = (i > 0 && j > 1)
+                .then(|| scores[i - 1][j - 2])
+                .flatten()
+                .map(|score| (score + (j - 2) as i32 * GAP_PENALTY, j - 2))
+                .filter(|(adjusted, _)| best_previous.is_none_or(|(best, _)| *adjusted > best));
+            if gapped_candidate.is_some() {
+                best_previous = gapped_candidate;
+            }
+            if lowered[j] != *wanted {
+                continue;
+            }
+            let base = MATCH_SCORE + position_bonus(&chars, j);
+            if i == 0 {
+                scores[i][j] = Some(base - j as i32 * GAP_PENALTY);
+                continue;
+            }
+            let consecutive = j
+                .checked_sub(1)
+                .and_then(|prev| scores[i - 1][prev])
+                .map(|score| (score + CONSECUTIVE_BONUS, j - 1));
+            let gapped = best_previous.map(|(adjusted, k)| (adjusted - (j - 1) as i32 * GAP_PENALTY, k));
+            let best = match (consecutive, gapped) {
+                (Some(a), Some(b)) => Some(if a.0 >= b.0 { a } else { b }),
+                (a, b) => a.or(b),
+            };
+            if let Some((score, k)) = best {
+                scores[i][j] = Some(score + base);
+                from[i][j] = k;
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|(j, score)| (*score, std::cmp::Reverse(*j)))?;
+    let mut positions = vec![j];
+    for i in (1..=last).rev() {
+        j = from[i][j];
+        positions.push(j);
+    }
+    positions.reverse();
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Pango markup for `text` with the characters at `positions` in bold.
+pub fn highlight_markup(text: &str, positions: &[usize]) -> String {
+    let mut markup = String::new();
+    let mut bold = false;
+    for (index, ch) in text.chars().enumerate() {
+        let matched = positions.contains(&index);
+        if matched != bold {
+            markup.push_str(if matched { "<b>" } else { "</b>" });
+            bold = matched;
+        }
+        match ch {
+            '&' => markup.push_str("&amp;"),
+            '<' => markup.push_str("&lt;"),
+            '>' => markup.push_str("&gt;"),
+            '"' => markup.push_str("&quot;"),
+            '\'' => markup.push_str("&apos;"),
+            _ => markup.push(ch),
+        }
+    }
+    if bold {
+        markup.push_str("</b>");
+    }
+    markup
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Filter {
+    Bssid(String),
+    Band(Band),
+    Security(String),
+}
+
+/// A search box query: free text for fuzzy SSID matching plus attribute filters
+/// written as `bssid:aa:bb`, `band:5` or `sec:open`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub text: String,
+    filters: Vec<Filter>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Query::default();
+        let mut words = Vec::new();
+        for word in input.split_whitespace() {
+            match parse_filter(word) {
+                Some(filter) => query.filters.push(filter),
+                None => words.push(word),
+            }
+        }
+        query.text = words.join(" ");
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.filters.is_empty()
+    }
+
+    pub fn matches_filters(&self, network: &Network) -> bool {
+        self.filters.iter().all(|filter| match filter {
+            Filter::Bssid(prefix) => network
+                .access_points
+                .iter()
+                .any(|ap| ap.bssid.to_lowercase().contains(prefix.as_str())),
+            Filter::Band(band) => network.access_points.iter().any(|ap| {
+                analyzer::channel_for_frequency(ap.frequency_mhz).is_some_and(|(found, _)| found == *band)
+            }),
+            Filter::Security(keyword) => network.security.starts_with(keyword.as_str()),
+        })
+    }
+}
+
+/// The bands picked with the chips above the list. A network shows while any
+/// of its radios is on a picked band; with every band picked nothing is hidden.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BandFilter(Vec<Band>);
+
+impl Default for BandFilter {
+    fn default() -> Self {
+        Self(Band::ALL.to_vec())
+    }
+}
+
+impl BandFilter {
+    pub fn contains(&self, band: Band) -> bool {
+        self.0.contains(&band)
+    }
+
+    pub fn set(&mut self, band: Band, picked: bool) {
+        let mut bands = self.0.clone();
+        bands.retain(|found| *found != band);
+        if picked {
+            bands.push(band);
+        }
+        *self = Self::from_bands(&bands);
+    }
+
+    /// Kept in `Band::ALL` order, so equal picks compare and save the same.
+    fn from_bands(bands: &[Band]) -> Self {
+        Self(Band::ALL.into_iter().filter(|band| bands.contains(band)).collect())
+    }
+
+    pub fn is_all(&self) -> bool {
+        Band::ALL.iter().all(|band| self.contains(*band))
+    }
+
+    pub fn matches(&self, network: &Network) -> bool {
+        self.is_all()
+            || network.access_points.iter().any(|ap| {
+                analyzer::channel_for_frequency(ap.frequency_mhz).is_some_and(|(band, _)| self.contains(band))
+            })
+    }
+}
+
+impl FromStr for BandFilter {
+    type Err = ();
+
+    /// Reads `2.4,5`; an empty value picks no band.
+    fn from_str(value: &str) -> Result<Self, ()> {
+        value
+            .split(',')
+            .filter(|key| !key.trim().is_empty())
+            .map(|key| Band::ALL.into_iter().find(|band| band.key() == key.trim()).ok_or(()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|bands| Self::from_bands(&bands))
+    }
+}
+
+impl fmt::Display for BandFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<&str> = self.0.iter().map(|band| band.key()).collect();
+        f.write_str(&keys.join(","))
+    }
+}
+
+/// The attribute filters of a search box input, without the free text, e.g.
+/// `"band:5 sec:wpa3"` for `"band:5 office sec:wpa3"`.
+pub fn filter_terms(input: &str) -> String {
+    input
+        .split_whitespace()
+        .filter(|word| parse_filter(word).is_some())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_filter(word: &str) -> Option<Filter> {
+    let (key, value) = word.split_once(':')?;
+    let value = value.to_lowercase();
+    if value.is_empty() {
+        return None;
+    }
+    match key.to_lowercase().as_str() {
+        "bssid" => Some(Filter::Bssid(value)),
+        "band" => match value.trim_end_matches("ghz") {
+            "2" | "2.4" | "24" => Some(Filter::Band(Band::TwoPointFour)),
+            "5" => Some(Filter::Band(Band::Five)),
+            "6" => Some(Filter::Band(Band::Six)),
+            _ => None,
+        },
+        "sec" => Some(Filter::Security(match value.as_str() {
+            "eap" | "802.1x" => "enterprise".to_string(),
+            "sae" => "wpa3".to_string(),
+            _ => value,
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccessPoint, NetworkAction};
+
+    fn network(ssid: &str, security: &'static str, bssid: &str, frequency: u32) -> Network {
+        Network {
+            ssid: ssid.to_string(),
+            signal_icon: "network-wireless-signal-good-symbolic",
+            action: NetworkAction::Connect,
+            strength: 50,
+            is_active: false,
+            is_saved: false,
+            is_secure: security != "open",
+            security,
+            access_points: vec![AccessPoint {
+                bssid: bssid.to_string(),
+                frequency_mhz: frequency,
+                ..AccessPoint::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn parses_attribute_filters() {
+        let cafe = network("Cafe", "open", "AA:BB:CC:00:00:01", 2412);
+        let office = network("Office", "wpa3", "DE:AD:BE:EF:00:02", 5180);
+
+        let query = Query::parse("band:5 off");
+        assert_eq!(query.text, "off");
+        assert!(query.matches_filters(&office));
+        assert!(!query.matches_filters(&cafe));
+
+        assert!(Query::parse("bssid:aa:bb").matches_filters(&cafe));
+        assert!(!Query::parse("bssid:aa:bb").matches_filters(&office));
+        assert!(Query::parse("sec:open").matches_filters(&cafe));
+        assert!(Query::parse("sec:wpa").matches_filters(&office));
+        assert!(Query::parse("band:2.4 sec:open").matches_filters(&cafe));
+
+        let unknown = Query::parse("team:blue band:");
+        assert_eq!(unknown.text, "team:blue band:");
+        assert!(unknown.matches_filters(&cafe));
+
+        assert_eq!(filter_terms("band:5 office  sec:wpa3 team:blue"), "band:5 sec:wpa3");
+    }
+
+    #[test]
+    fn band_chips_hide_networks_on_other_bands() {
+        let cafe = network("Cafe", "open", "AA:BB:CC:00:00:01", 2412);
+        let office = network("Office", "wpa3", "DE:AD:BE:EF:00:02", 5180);
+
+        let mut bands = BandFilter::default();
+        assert!(bands.is_all() && bands.matches(&cafe));
+        bands.set(Band::TwoPointFour, false);
+        assert!(!bands.matches(&cafe) && bands.matches(&office));
+        assert_eq!(bands.to_string(), "5,6");
+        assert_eq!("6,5".parse::<BandFilter>(), Ok(bands));
+
+        let none: BandFilter = "".parse().expect("empty filter");
+        assert!(!none.matches(&office));
+        assert!("5,7".parse::<BandFilter>().is_err());
+    }
+
+    #[test]
+    fn matches_subsequences_case_insensitively() {
+        let found = fuzzy_match("Corp-Guest-5G", "cg5").expect("match");
+        assert_eq!(found.positions, [0, 5, 11]);
+        assert!(fuzzy_match("Corp-Guest-5G", "gc").is_none());
+        assert!(fuzzy_match("Home", "homes").is_none());
+    }
+
+    #[test]
+    fn prefers_word_starts_and_consecutive_runs() {
+        let boundary = fuzzy_match("Acme Guest", "gu").unwrap();
+        let inner = fuzzy_match("Argument", "gu").unwrap();
+        assert!(boundary.score > inner.score);
+
+        let run = fuzzy_match("office-net", "net").unwrap();
+        assert_eq!(run.positions, [7, 8, 9]);
+    }
+
+    #[test]
+    fn highlights_matched_characters() {
+        assert_eq!(highlight_markup("a&b", &[0, 1]), "<b>a&amp;</b>b");
+        assert_eq!(highlight_markup("Home", &[1, 3]), "H<b>o</b>m<b>e</b>");
+    }
+}
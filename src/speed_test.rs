@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Cloudflare's endpoints, used when no server is set in Preferences.
+const DEFAULT_DOWNLOAD: &str = "https://speed.cloudflare.com/__down?bytes=250000000";
+const DEFAULT_UPLOAD: &str = "https://speed.cloudflare.com/__up";
+/// Each direction stops after this long; the average up to then is the result.
+const PHASE_SECS: &str = "10";
+/// Most bytes sent for the upload. Fast links hit the time limit first.
+const UPLOAD_BYTES: usize = 250_000_000;
+/// curl's exit code when `--max-time` ran out, which is how a phase normally ends.
+const CURL_TIMED_OUT: i32 = 28;
+/// Results kept per network; older ones are dropped.
+const RESULTS_KEPT: usize = 10;
+
+/// One run of the throughput test.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedResult {
+    pub tested_at: u64,
+    pub download_kbps: u32,
+    pub upload_kbps: u32,
+}
+
+/// Download and upload URLs for `server`, the base URL of a LibreSpeed
+/// backend (`https://example.org/backend`). Empty uses Cloudflare.
+pub fn endpoints(server: &str) -> (String, String) {
+    let server = server.trim().trim_end_matches('/');
+    if server.is_empty() {
+        return (DEFAULT_DOWNLOAD.to_string(), DEFAULT_UPLOAD.to_string());
+    }
+    (format!("{server}/garbage.php?ckSize=100"), format!("{server}/empty.php"))
+}
+
+/// Measures download and then upload throughput through `curl`. Blocks for
+/// up to twice `PHASE_SECS`, so run it off the main thread.
+pub fn run(server: &str, now: u64) -> io::Result<SpeedResult> {
+    let (download, upload) = endpoints(server);
+    let download_kbps = measure(&download, "%{speed_download}", &[], false)?;
+    let upload_kbps = measure(
+        &upload,
+        "%{speed_upload}",
+        &["-X", "POST", "-H", "Content-Type: application/octet-stream", "--upload-file", "-"],
+        true,
+    )?;
+    Ok(SpeedResult {
+        tested_at: now,
+        download_kbps,
+        upload_kbps,
+    })
+}
+
+fn measure(url: &str, write_out: &str, extra: &[&str], upload: bool) -> io::Result<u32> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--output", "/dev/null", "--max-time", PHASE_SECS])
+        .args(["--write-out", write_out])
+        .args(extra)
+        .arg(url)
+        .stdin(if upload { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => io::Error::other("the speed test needs curl, which is not installed"),
+            _ => err,
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Stops once curl hangs up at the time limit.
+        thread::spawn(move || {
+            let zeros = [0u8; 65_536];
+            let mut sent = 0;
+            while sent < UPLOAD_BYTES && stdin.write_all(&zeros).is_ok() {
+                sent += zeros.len();
+            }
+        });
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() && output.status.code() != Some(CURL_TIMED_OUT) {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(io::Error::other(if message.is_empty() {
+            format!("curl failed ({})", output.status)
+        } else {
+            message
+        }));
+    }
+    parse_speed(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| io::Error::other("no data got through to the test server"))
+}
+
+/// Turns curl's bytes per second into kbit/s; None when nothing moved.
+fn parse_speed(write_out: &str) -> Option<u32> {
+    let bytes_per_sec: f64 = write_out.trim().parse().ok()?;
+    let kbps = (bytes_per_sec * 8.0 / 1000.0).round();
+    (kbps >= 1.0).then(|| kbps.min(f64::from(u32::MAX)) as u32)
+}
+
+/// Adds `result` to the results of `ssid`, newest last.
+pub fn record(results: &mut BTreeMap<String, Vec<SpeedResult>>, ssid: &str, result: SpeedResult) {
+    let network = results.entry(ssid.to_string()).or_default();
+    network.push(result);
+    if network.len() > RESULTS_KEPT {
+        network.drain(..network.len() - RESULTS_KEPT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_endpoints_parses_curl_and_keeps_recent_results() {
+        assert_eq!(endpoints(" ").0, DEFAULT_DOWNLOAD);
+        assert_eq!(
+            endpoints("https://speed.example.org/backend/"),
+            (
+                "https://speed.example.org/backend/garbage.php?ckSize=100".to_string(),
+                "https://speed.example.org/backend/empty.php".to_string()
+            )
+        );
+
+        assert_eq!(parse_speed("11712500"), Some(93_700));
+        assert_eq!(parse_speed("2500000.000"), Some(20_000));
+        assert_eq!(parse_speed("0"), None);
+        assert_eq!(parse_speed(""), None);
+
+        let mut results = BTreeMap::new();
+        for tested_at in 0..12 {
+            record(
+                &mut results,
+                "Home",
+                SpeedResult {
+                    tested_at,
+                    ..SpeedResult::default()
+                },
+            );
+        }
+        let home = &results["Home"];
+        assert_eq!(home.len(), RESULTS_KEPT);
+        assert_eq!(home.first().map(|result| result.tested_at), Some(2));
+    }
+}
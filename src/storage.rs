@@ -0,0 +1,246 @@
+use crate::data_usage::DataUsage;
+use crate::preferences;
+use crate::speed_test::SpeedResult;
+use crate::templates::ProfileTemplate;
+use crate::trust::NetworkTrust;
+use crate::usage::{self, NetworkUsage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "data.json";
+/// Where an unreadable data file is moved, so it can be recovered by hand.
+const BAD_EXTENSION: &str = "json.bad";
+pub const CURRENT_VERSION: u32 = 1;
+
+/// YuFi-specific data NetworkManager has no place for, kept as JSON under the
+/// XDG data dir. Keys this version does not know about are carried through
+/// untouched, and a file written by a newer YuFi is never overwritten.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppData {
+    pub version: u32,
+    pub favorites: Vec<String>,
+    pub hidden_ssids: Vec<String>,
+    pub history: BTreeMap<String, NetworkUsage>,
+    pub notes: BTreeMap<String, String>,
+    pub data_usage: BTreeMap<String, DataUsage>,
+    pub speed_tests: BTreeMap<String, Vec<SpeedResult>>,
+    /// UUID of the VPN profile to bring up whenever the network connects.
+    pub vpn_rules: BTreeMap<String, String>,
+    pub trust: BTreeMap<String, NetworkTrust>,
+    /// Networks joined for one visit, whose profile is deleted once the
+    /// connection ends.
+    pub forget_on_disconnect: Vec<String>,
+    /// Saved IP, DNS and proxy presets, sorted by name.
+    pub templates: Vec<ProfileTemplate>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl AppData {
+    pub fn load() -> Self {
+        let path = data_path();
+        let contents = path
+            .as_ref()
+            .and_then(|path| Some((path, fs::read(path).ok()?)));
+        match contents {
+            Some((path, contents)) => String::from_utf8(contents)
+                .ok()
+                .and_then(|contents| Self::parse(&contents))
+                .unwrap_or_else(|| Self::set_aside(path)),
+            None => {
+                let data = Self::import_legacy();
+                if data != Self::new() && data.save().is_ok() {
+                    usage::remove_legacy();
+                }
+                data
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if self.read_only {
+            return Err(io::Error::other(
+                "data file is unreadable or was written by a newer version of YuFi",
+            ));
+        }
+        let path = data_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        // Write then rename so a crash never leaves a truncated file behind.
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(temp, path)
+    }
+
+    fn new() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            ..Self::default()
+        }
+    }
+
+    /// None when the contents are not JSON or a field has the wrong type.
+    fn parse(contents: &str) -> Option<Self> {
+        let mut value = serde_json::from_str::<Value>(contents).ok()?;
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+        migrate(&mut value, version);
+        let mut data: Self = serde_json::from_value(value).ok()?;
+        data.read_only = version > CURRENT_VERSION;
+        Some(data)
+    }
+
+    /// Moves the unreadable file at `path` out of the way and starts afresh.
+    /// Should that fail, the file is left alone and nothing is saved over it.
+    fn set_aside(path: &Path) -> Self {
+        let bad = path.with_extension(BAD_EXTENSION);
+        match fs::rename(path, &bad) {
+            Ok(()) => {
+                eprintln!("yufi: {} was unreadable and was moved to {}", path.display(), bad.display());
+                Self::new()
+            }
+            Err(err) => {
+                eprintln!("yufi: {} is unreadable and will not be changed: {err}", path.display());
+                Self {
+                    read_only: true,
+                    ..Self::new()
+                }
+            }
+        }
+    }
+
+    /// Builds the initial data from the files earlier releases wrote.
+    fn import_legacy() -> Self {
+        Self {
+            hidden_ssids: preferences::legacy_hidden_ssids(),
+            history: usage::load_legacy(),
+            ..Self::new()
+        }
+    }
+
+    pub fn is_hidden(&self, ssid: &str) -> bool {
+        self.hidden_ssids.iter().any(|hidden| hidden == ssid)
+    }
+
+    pub fn hide(&mut self, ssid: &str) {
+        if !self.is_hidden(ssid) {
+            self.hidden_ssids.push(ssid.to_string());
+        }
+    }
+
+    pub fn unhide(&mut self, ssid: &str) {
+        self.hidden_ssids.retain(|hidden| hidden != ssid);
+    }
+
+    pub fn set_forget_on_disconnect(&mut self, ssid: &str, forget: bool) {
+        self.forget_on_disconnect.retain(|tagged| tagged != ssid);
+        if forget {
+            self.forget_on_disconnect.push(ssid.to_string());
+        }
+    }
+
+    /// Drops everything remembered about a network once its profile is gone.
+    pub fn forget(&mut self, ssid: &str) {
+        self.history.remove(ssid);
+        self.notes.remove(ssid);
+        self.data_usage.remove(ssid);
+        self.speed_tests.remove(ssid);
+        self.vpn_rules.remove(ssid);
+        self.trust.remove(ssid);
+        self.forget_on_disconnect.retain(|tagged| tagged != ssid);
+        self.favorites.retain(|favorite| favorite != ssid);
+    }
+}
+
+/// Upgrades older layouts in place. Each format change bumps `CURRENT_VERSION`
+/// and adds a `from < N` step here, applied in order.
+fn migrate(value: &mut Value, from: u32) {
+    if from < CURRENT_VERSION && value.is_object() {
+        value["version"] = Value::from(CURRENT_VERSION);
+    }
+}
+
+fn data_path() -> Option<PathBuf> {
+    usage::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_keeps_unknown_keys() {
+        let mut data = AppData::new();
+        data.hide("HP-Print-42");
+        data.hide("HP-Print-42");
+        data.hide("Neighbour = WiFi");
+        data.notes.insert("Home".to_string(), "Router in the hallway".to_string());
+        data.vpn_rules.insert("Home".to_string(), "6c1f4a1e-1d2b-4c8e-9a3f-0e5b7d9c2a11".to_string());
+        data.trust.insert("Home".to_string(), NetworkTrust::Trusted);
+        data.set_forget_on_disconnect("Home", true);
+        data.set_forget_on_disconnect("Home", true);
+        data.extra.insert("from_the_future".to_string(), Value::from(true));
+        assert_eq!(data.hidden_ssids.len(), 2);
+        assert_eq!(data.forget_on_disconnect, ["Home"]);
+
+        let restored = AppData::parse(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert_eq!(restored, data);
+        assert!(restored.is_hidden("Neighbour = WiFi"));
+
+        let mut restored = restored;
+        restored.unhide("HP-Print-42");
+        restored.forget("Home");
+        assert!(!restored.is_hidden("HP-Print-42"));
+        assert!(restored.notes.is_empty());
+        assert!(restored.vpn_rules.is_empty());
+        assert!(restored.trust.is_empty());
+        assert!(restored.forget_on_disconnect.is_empty());
+    }
+
+    #[test]
+    fn migrates_old_versions_and_protects_newer_files() {
+        let old = AppData::parse(r#"{"history": {"Cafe": {"times_connected": 2}}}"#).unwrap();
+        assert_eq!(old.version, CURRENT_VERSION);
+        assert_eq!(old.history["Cafe"].times_connected, 2);
+        assert!(!old.read_only);
+
+        let newer = AppData::parse(r#"{"version": 99, "hidden_ssids": ["Lab"]}"#).unwrap();
+        assert!(newer.is_hidden("Lab"));
+        assert!(newer.read_only);
+        assert!(newer.save().is_err());
+
+        assert_eq!(AppData::parse("not json"), None);
+        assert_eq!(AppData::parse(r#"{"history": ["Cafe"]}"#), None);
+    }
+
+    #[test]
+    fn corrupt_files_are_kept_rather_than_overwritten() {
+        let dir = std::env::temp_dir().join(format!("yufi-storage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        fs::write(&path, "{\"notes\": {\"Home\": 1").unwrap();
+
+        let data = AppData::set_aside(&path);
+        assert_eq!(data, AppData::new());
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(path.with_extension(BAD_EXTENSION)).unwrap(), "{\"notes\": {\"Home\": 1");
+
+        // A file that cannot be moved aside is never saved over.
+        let stuck = AppData::set_aside(&path);
+        assert!(stuck.read_only);
+        assert!(stuck.save().is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use crate::demo;
+use crate::models::VisibleAccessPoint;
+use std::cmp::Reverse;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub timestamp: u64,
+    pub strength: u8,
+}
+
+#[derive(Clone, Debug)]
+struct BssidTrack {
+    ssid: String,
+    bssid: String,
+    samples: Vec<Sample>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkSummary {
+    pub name: String,
+    pub bssids: usize,
+    pub samples: usize,
+    pub min: u8,
+    pub avg: u8,
+    pub max: u8,
+    pub last_seen: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SiteSurvey {
+    tracks: Vec<BssidTrack>,
+    rounds: usize,
+}
+
+impl SiteSurvey {
+    pub fn record(&mut self, timestamp: u64, access_points: &[VisibleAccessPoint]) {
+        self.rounds += 1;
+        for ap in access_points {
+            let sample = Sample {
+                timestamp,
+                strength: ap.strength,
+            };
+            match self.tracks.iter_mut().find(|track| track.bssid == ap.bssid) {
+                Some(track) => track.samples.push(sample),
+                None => self.tracks.push(BssidTrack {
+                    ssid: ap.ssid.clone(),
+                    bssid: ap.bssid.clone(),
+                    samples: vec![sample],
+                }),
+            }
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+        self.rounds = 0;
+    }
+
+    /// Aggregates samples per network, strongest average first. Hidden networks are
+    /// listed per BSSID since there is no SSID to group them by.
+    pub fn summaries(&self) -> Vec<NetworkSummary> {
+        let mut groups: Vec<(String, Vec<&BssidTrack>)> = Vec::new();
+        for track in &self.tracks {
+            let name = if track.ssid.is_empty() { &track.bssid } else { &track.ssid };
+            match groups.iter_mut().find(|(existing, _)| existing == name) {
+                Some((_, tracks)) => tracks.push(track),
+                None => groups.push((name.clone(), vec![track])),
+            }
+        }
+
+        let mut summaries: Vec<NetworkSummary> = groups
+            .into_iter()
+            .map(|(name, tracks)| {
+                let samples: Vec<Sample> = tracks
+                    .iter()
+                    .flat_map(|track| track.samples.iter().copied())
+                    .collect();
+                let total: u32 = samples.iter().map(|sample| u32::from(sample.strength)).sum();
+                NetworkSummary {
+                    name,
+                    bssids: tracks.len(),
+                    samples: samples.len(),
+                    min: samples.iter().map(|sample| sample.strength).min().unwrap_or(0),
+                    avg: (total / samples.len().max(1) as u32) as u8,
+                    max: samples.iter().map(|sample| sample.strength).max().unwrap_or(0),
+                    last_seen: samples.iter().map(|sample| sample.timestamp).max().unwrap_or(0),
+                }
+            })
+            .collect();
+        summaries.sort_by_key(|summary| (Reverse(summary.avg), summary.name.clone()));
+        summaries
+    }
+}
+
+pub fn now_secs() -> u64 {
+    if demo::is_active() {
+        return demo::FROZEN_NOW;
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ap(ssid: &str, bssid: &str, strength: u8) -> VisibleAccessPoint {
+        VisibleAccessPoint {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            frequency_mhz: 2412,
+            strength,
+        }
+    }
+
+    #[test]
+    fn aggregates_samples_per_network() {
+        let mut survey = SiteSurvey::default();
+        survey.record(10, &[ap("Home", "aa", 80), ap("Home", "bb", 40), ap("", "cc", 30)]);
+        survey.record(20, &[ap("Home", "aa", 60), ap("Cafe", "dd", 90)]);
+
+        assert_eq!(survey.rounds(), 2);
+        let summaries = survey.summaries();
+        let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Cafe", "Home", "cc"]);
+
+        let home = &summaries[1];
+        assert_eq!((home.bssids, home.samples), (2, 3));
+        assert_eq!((home.min, home.avg, home.max), (40, 60, 80));
+        assert_eq!(home.last_seen, 20);
+
+        survey.clear();
+        assert!(survey.summaries().is_empty());
+        assert_eq!(survey.rounds(), 0);
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// A named set of IP, DNS and proxy settings ("Lab static", "Home server
+/// VLAN") to fill in for any network. The addressing fields hold the text as
+/// typed in the details dialog.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileTemplate {
+    pub name: String,
+    /// `10.0.0.5/24`; empty along with the gateway and DNS for DHCP.
+    pub address: String,
+    pub gateway: String,
+    /// Comma-separated.
+    pub dns: String,
+    /// URL of a proxy auto-config script; empty for no proxy.
+    pub proxy_pac_url: String,
+}
+
+impl ProfileTemplate {
+    pub fn uses_dhcp(&self) -> bool {
+        self.address.is_empty() && self.gateway.is_empty() && self.dns.is_empty()
+    }
+}
+
+/// Adds `template`, replacing one of the same name, and keeps them by name.
+pub fn save(templates: &mut Vec<ProfileTemplate>, template: ProfileTemplate) {
+    templates.retain(|saved| saved.name != template.name);
+    templates.push(template);
+    templates.sort_by_key(|template| template.name.to_lowercase());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(name: &str) -> ProfileTemplate {
+        ProfileTemplate {
+            name: name.to_string(),
+            ..ProfileTemplate::default()
+        }
+    }
+
+    #[test]
+    fn saving_replaces_a_template_of_the_same_name() {
+        let mut templates = Vec::new();
+        save(&mut templates, template("lab static"));
+        save(&mut templates, template("Home server VLAN"));
+        save(
+            &mut templates,
+            ProfileTemplate {
+                address: "10.0.0.5/24".to_string(),
+                ..template("lab static")
+            },
+        );
+        let names: Vec<_> = templates.iter().map(|template| template.name.as_str()).collect();
+        assert_eq!(names, ["Home server VLAN", "lab static"]);
+        assert!(!templates[1].uses_dhcp());
+        assert!(templates[0].uses_dhcp());
+    }
+}
@@ -0,0 +1,415 @@
+use crate::UiEvent;
+use crate::models::{AppState, NetworkAction};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use zbus::blocking::{Connection, Proxy, connection};
+use zbus::interface;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Str, Value};
+use zbus::SignalContext;
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+
+pub const QUICK_CONNECT_LIMIT: usize = 5;
+
+const ROOT_ID: i32 = 0;
+const SHOW_ID: i32 = 1;
+const QUIT_ID: i32 = 2;
+const SEPARATOR_ID: i32 = 3;
+const FOOTER_SEPARATOR_ID: i32 = 4;
+const NETWORK_ID_BASE: i32 = 100;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TrayAction {
+    ShowWindow,
+    Connect(String),
+    Quit,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickNetwork {
+    pub ssid: String,
+    pub strength: u8,
+    pub active: bool,
+}
+
+/// The strongest saved networks in range, in the order the tray menu lists them.
+pub fn quick_connect_networks(state: &AppState) -> Vec<QuickNetwork> {
+    let mut saved: Vec<QuickNetwork> = state
+        .networks
+        .iter()
+        .filter(|network| network.is_saved)
+        .map(|network| QuickNetwork {
+            ssid: network.ssid.clone(),
+            strength: network.strength,
+            active: matches!(network.action, NetworkAction::Disconnect),
+        })
+        .collect();
+    saved.sort_by(|a, b| b.strength.cmp(&a.strength).then_with(|| a.ssid.cmp(&b.ssid)));
+    saved.truncate(QUICK_CONNECT_LIMIT);
+    saved
+}
+
+#[derive(Default)]
+struct MenuState {
+    revision: u32,
+    networks: Vec<QuickNetwork>,
+}
+
+type SharedMenu = Arc<Mutex<MenuState>>;
+
+fn lock(menu: &SharedMenu) -> MutexGuard<'_, MenuState> {
+    menu.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A StatusNotifierItem with a DBusMenu, served on its own session bus connection.
+pub struct Tray {
+    connection: Connection,
+    menu: SharedMenu,
+}
+
+impl Tray {
+    /// Registers the tray icon with the desktop's StatusNotifierWatcher. Fails when
+    /// the desktop has no tray host, in which case YuFi behaves as a plain window.
+    pub fn start(ui_tx: async_channel::Sender<UiEvent>) -> zbus::Result<Self> {
+        let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+        let tray = Self::serve(connection::Builder::session()?.name(name.as_str())?, ui_tx)?;
+        let watcher = Proxy::new(&tray.connection, WATCHER_NAME, WATCHER_PATH, WATCHER_NAME)?;
+        let _: () = watcher.call("RegisterStatusNotifierItem", &(name.as_str()))?;
+        Ok(tray)
+    }
+
+    fn serve(builder: connection::Builder<'_>, ui_tx: async_channel::Sender<UiEvent>) -> zbus::Result<Self> {
+        let menu = SharedMenu::default();
+        let connection = builder
+            .serve_at(ITEM_PATH, StatusNotifierItem { ui_tx: ui_tx.clone() })?
+            .serve_at(
+                MENU_PATH,
+                DbusMenu {
+                    menu: menu.clone(),
+                    ui_tx,
+                },
+            )?
+            .build()?;
+        Ok(Self { connection, menu })
+    }
+
+    pub fn update(&self, state: &AppState) {
+        let networks = quick_connect_networks(state);
+        let revision = {
+            let mut menu = lock(&self.menu);
+            if menu.networks == networks {
+                return;
+            }
+            menu.networks = networks;
+            menu.revision += 1;
+            menu.revision
+        };
+        let Ok(iface) = self
+            .connection
+            .object_server()
+            .interface::<_, DbusMenu>(MENU_PATH)
+        else {
+            return;
+        };
+        let _ = zbus::block_on(DbusMenu::layout_updated(iface.signal_context(), revision, ROOT_ID));
+    }
+}
+
+struct StatusNotifierItem {
+    ui_tx: async_channel::Sender<UiEvent>,
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    fn activate(&self, _x: i32, _y: i32) {
+        let _ = self.ui_tx.try_send(UiEvent::Tray(TrayAction::ShowWindow));
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        let _ = self.ui_tx.try_send(UiEvent::Tray(TrayAction::ShowWindow));
+    }
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: String) {}
+
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "Hardware"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "yufi"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> &str {
+        "YuFi"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        "network-wireless-symbolic"
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(MENU_PATH).expect("valid menu path")
+    }
+}
+
+type Properties = HashMap<String, OwnedValue>;
+type Layout = (i32, Properties, Vec<OwnedValue>);
+
+struct DbusMenu {
+    menu: SharedMenu,
+    ui_tx: async_channel::Sender<UiEvent>,
+}
+
+impl DbusMenu {
+    fn items(&self) -> Vec<(i32, Properties)> {
+        let menu = lock(&self.menu);
+        let mut items = vec![(SHOW_ID, label_properties("Open YuFi"))];
+        if !menu.networks.is_empty() {
+            items.push((SEPARATOR_ID, separator_properties()));
+        }
+        for (index, network) in menu.networks.iter().enumerate() {
+            let mut properties =
+                label_properties(&format!("{} ({}%)", network.ssid, network.strength));
+            properties.insert("toggle-type".to_string(), owned(Value::from("checkmark")));
+            properties.insert("toggle-state".to_string(), owned(Value::from(i32::from(network.active))));
+            items.push((NETWORK_ID_BASE + index as i32, properties));
+        }
+        items.push((FOOTER_SEPARATOR_ID, separator_properties()));
+        items.push((QUIT_ID, label_properties("Quit")));
+        items
+    }
+
+    fn action_for(&self, id: i32) -> Option<TrayAction> {
+        match id {
+            SHOW_ID => Some(TrayAction::ShowWindow),
+            QUIT_ID => Some(TrayAction::Quit),
+            _ => {
+                let index = usize::try_from(id.checked_sub(NETWORK_ID_BASE)?).ok()?;
+                let menu = lock(&self.menu);
+                let network = menu.networks.get(index)?;
+                (!network.active).then(|| TrayAction::Connect(network.ssid.clone()))
+            }
+        }
+    }
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl DbusMenu {
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        _recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> (u32, Layout) {
+        let revision = lock(&self.menu).revision;
+        let mut items = self.items();
+        if let Some(index) = items.iter().position(|(id, _)| *id == parent_id) {
+            let (id, properties) = items.swap_remove(index);
+            return (revision, (id, properties, Vec::new()));
+        }
+
+        let mut root = Properties::new();
+        root.insert("children-display".to_string(), owned(Value::from("submenu")));
+        let children = items
+            .into_iter()
+            .map(|(id, properties)| owned(Value::from((id, properties, Vec::<OwnedValue>::new()))))
+            .collect();
+        (revision, (ROOT_ID, root, children))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, Properties)> {
+        self.items()
+            .into_iter()
+            .filter(|(id, _)| ids.is_empty() || ids.contains(id))
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: String) -> OwnedValue {
+        self.items()
+            .into_iter()
+            .find(|(item, _)| *item == id)
+            .and_then(|(_, mut properties)| properties.remove(&name))
+            .unwrap_or_else(|| owned(Value::from("")))
+    }
+
+    fn event(&self, id: i32, event_id: String, _data: OwnedValue, _timestamp: u32) {
+        if event_id != "clicked" {
+            return;
+        }
+        if let Some(action) = self.action_for(id) {
+            let _ = self.ui_tx.try_send(UiEvent::Tray(action));
+        }
+    }
+
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, data, timestamp) in events {
+            self.event(id, event_id, data, timestamp);
+        }
+        Vec::new()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[zbus(signal)]
+    async fn layout_updated(ctxt: &SignalContext<'_>, revision: u32, parent: i32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+fn owned(value: Value<'_>) -> OwnedValue {
+    // Menu properties never carry file descriptors, so the conversion cannot fail.
+    OwnedValue::try_from(value).expect("menu property without fds")
+}
+
+fn label_properties(label: &str) -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("label".to_string(), owned(Value::from(Str::from(label.to_string()))));
+    properties
+}
+
+fn separator_properties() -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("type".to_string(), owned(Value::from("separator")));
+    properties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock_nm::MockNetworkManager;
+    use crate::models::Network;
+
+    fn network(ssid: &str, strength: u8, is_saved: bool, active: bool) -> Network {
+        Network {
+            ssid: ssid.to_string(),
+            signal_icon: "network-wireless-signal-good-symbolic",
+            action: if active { NetworkAction::Disconnect } else { NetworkAction::Connect },
+            strength,
+            is_active: active,
+            is_saved,
+            is_secure: true,
+            security: "wpa2",
+            access_points: Vec::new(),
+        }
+    }
+
+    fn neighbourhood() -> AppState {
+        AppState {
+            wifi_enabled: true,
+            networks: vec![
+                network("Office", 90, true, false),
+                network("Stranger", 85, false, false),
+                network("Home", 70, true, true),
+                network("Cafe", 40, true, false),
+                network("Library", 35, true, false),
+                network("Gym", 30, true, false),
+                network("Airport", 10, true, false),
+            ],
+            out_of_range: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lists_strongest_saved_networks() {
+        let ssids: Vec<String> = quick_connect_networks(&neighbourhood())
+            .into_iter()
+            .map(|network| network.ssid)
+            .collect();
+        assert_eq!(ssids, ["Office", "Home", "Cafe", "Library", "Gym"]);
+    }
+
+    #[test]
+    fn menu_clicks_become_ui_actions() {
+        let Some(mock) = MockNetworkManager::start(Vec::new()) else {
+            eprintln!("dbus-daemon not available, skipping tray menu test");
+            return;
+        };
+        let (ui_tx, ui_rx) = async_channel::unbounded();
+        let builder = connection::Builder::address(mock.bus_address()).unwrap();
+        let tray = Tray::serve(builder, ui_tx).expect("serve tray");
+        tray.update(&neighbourhood());
+
+        let client = connection::Builder::address(mock.bus_address())
+            .unwrap()
+            .build()
+            .unwrap();
+        let unique_name = tray.connection.unique_name().unwrap().to_string();
+        let menu = Proxy::new(&client, unique_name.as_str(), MENU_PATH, "com.canonical.dbusmenu")
+            .unwrap();
+        let (revision, (_, _, children)): (u32, Layout) =
+            menu.call("GetLayout", &(0i32, -1i32, Vec::<String>::new())).unwrap();
+        assert_eq!(revision, 1);
+        assert_eq!(children.len(), 9);
+
+        let labels: Vec<(i32, Properties)> = menu
+            .call("GetGroupProperties", &(vec![NETWORK_ID_BASE], Vec::<String>::new()))
+            .unwrap();
+        let label = String::try_from(labels[0].1["label"].try_clone().unwrap()).unwrap();
+        assert_eq!(label, "Office (90%)");
+
+        let click = |id: i32| {
+            let data = OwnedValue::from(0i32);
+            let _: () = menu.call("Event", &(id, "clicked", data, 0u32)).unwrap();
+        };
+        click(NETWORK_ID_BASE + 1);
+        click(NETWORK_ID_BASE);
+        click(QUIT_ID);
+        let actions: Vec<TrayAction> = std::iter::from_fn(|| match ui_rx.try_recv() {
+            Ok(UiEvent::Tray(action)) => Some(action),
+            _ => None,
+        })
+        .collect();
+        assert_eq!(
+            actions,
+            [TrayAction::Connect("Office".to_string()), TrayAction::Quit]
+        );
+    }
+}
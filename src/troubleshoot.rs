@@ -0,0 +1,379 @@
+use crate::backend::{Backend, BackendError};
+use crate::friendly_error;
+use crate::models::{IpConfig, Network};
+use std::io;
+use std::net::ToSocketAddrs;
+use std::process::{Command, Stdio};
+
+/// Host NetworkManager's own connectivity check uses; resolving it says
+/// nothing about reaching it, which the portal step covers.
+const DNS_PROBE_HOST: &str = "nmcheck.gnome.org";
+const CONNECTIVITY_PORTAL: u32 = 2;
+
+/// One step of the "Why can't I connect?" analyzer, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    RadioEnabled,
+    DevicePresent,
+    NetworkVisible,
+    SecretsSaved,
+    AddressObtained,
+    GatewayReachable,
+    DnsResolves,
+    NoCaptivePortal,
+}
+
+pub const CHECKS: [Check; 8] = [
+    Check::RadioEnabled,
+    Check::DevicePresent,
+    Check::NetworkVisible,
+    Check::SecretsSaved,
+    Check::AddressObtained,
+    Check::GatewayReachable,
+    Check::DnsResolves,
+    Check::NoCaptivePortal,
+];
+
+impl Check {
+    pub fn label(self) -> &'static str {
+        match self {
+            Check::RadioEnabled => "Wi‑Fi radio is on",
+            Check::DevicePresent => "Wi‑Fi device is present",
+            Check::NetworkVisible => "Network is in range",
+            Check::SecretsSaved => "Password is saved",
+            Check::AddressObtained => "Got an IP address",
+            Check::GatewayReachable => "Router responds",
+            Check::DnsResolves => "Names resolve (DNS)",
+            Check::NoCaptivePortal => "No sign-in page in the way",
+        }
+    }
+
+    pub fn fix(self) -> &'static str {
+        match self {
+            Check::RadioEnabled => {
+                "Turn Wi‑Fi on with the switch in the header. If it is hard blocked, use the laptop's wireless key or switch, or enable Wi‑Fi in the BIOS."
+            }
+            Check::DevicePresent => {
+                "No Wi‑Fi adapter was found. Check that the driver is loaded (see Diagnostics) and that a USB adapter is plugged in."
+            }
+            Check::NetworkVisible => {
+                "Move closer to the access point, click Refresh to scan again, or use Connect to Hidden Network if it does not broadcast its name."
+            }
+            Check::SecretsSaved => "Connect to the network and enter its password when asked.",
+            Check::AddressObtained => {
+                "Connect to the network first. If it stays on \"Obtaining address\", restart the router or set a static address in the network details."
+            }
+            Check::GatewayReachable => {
+                "The router did not answer. Restart it, or check the gateway in the network details if you set a static address."
+            }
+            Check::DnsResolves => {
+                "The DNS servers did not answer. Set DNS servers such as 1.1.1.1 or 9.9.9.9 in the network details."
+            }
+            Check::NoCaptivePortal => {
+                "This network wants you to sign in. Open any web page in a browser to reach the sign-in page."
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StepResult {
+    pub check: Check,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl StepResult {
+    fn pass(check: Check, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(check: Check, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Checks that leave NetworkManager and talk to the network directly.
+pub trait Probes {
+    fn ping(&self, host: &str) -> io::Result<bool>;
+    fn resolve(&self, host: &str) -> bool;
+}
+
+pub struct SystemProbes;
+
+impl Probes for SystemProbes {
+    fn ping(&self, host: &str) -> io::Result<bool> {
+        Command::new("ping")
+            .args(["-c", "1", "-W", "2", host])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+    }
+
+    fn resolve(&self, host: &str) -> bool {
+        (host, 80)
+            .to_socket_addrs()
+            .is_ok_and(|mut addrs| addrs.next().is_some())
+    }
+}
+
+/// Runs the checks in order for `ssid`, calling `on_step` as each one
+/// finishes, and stops at the first failure.
+pub fn run(
+    backend: &impl Backend,
+    ssid: &str,
+    probes: &impl Probes,
+    mut on_step: impl FnMut(&StepResult),
+) -> Vec<StepResult> {
+    let mut results = Vec::new();
+    let mut context = Context::default();
+    for check in CHECKS {
+        let result = context.evaluate(check, backend, ssid, probes);
+        on_step(&result);
+        let passed = result.passed;
+        results.push(result);
+        if !passed {
+            break;
+        }
+    }
+    results
+}
+
+/// Facts gathered by earlier steps that later ones depend on.
+#[derive(Default)]
+struct Context {
+    connectivity: u32,
+    connectivity_checked: bool,
+    network: Option<Network>,
+    ip_config: Option<IpConfig>,
+}
+
+impl Context {
+    fn evaluate(
+        &mut self,
+        check: Check,
+        backend: &impl Backend,
+        ssid: &str,
+        probes: &impl Probes,
+    ) -> StepResult {
+        let unreadable = |err: BackendError| {
+            StepResult::fail(
+                check,
+                format!("Could not ask NetworkManager: {}", friendly_error(&err)),
+            )
+        };
+        match check {
+            Check::RadioEnabled => {
+                let nm = match backend.diagnostics() {
+                    Ok(nm) => nm,
+                    Err(err) => return unreadable(err),
+                };
+                self.connectivity = nm.connectivity;
+                self.connectivity_checked = nm.connectivity_check_enabled;
+                match (nm.wireless_hardware_enabled, nm.wireless_enabled) {
+                    (false, _) => StepResult::fail(check, "Hard blocked by a switch or the BIOS."),
+                    (true, false) => StepResult::fail(check, "Wi‑Fi is turned off."),
+                    (true, true) => StepResult::pass(check, "Unblocked."),
+                }
+            }
+            Check::DevicePresent => match backend.diagnostics() {
+                Ok(nm) => match nm.device {
+                    Some(device) => {
+                        StepResult::pass(check, format!("{} ({})", device.interface, device.driver))
+                    }
+                    None => StepResult::fail(check, "NetworkManager reports no Wi‑Fi device."),
+                },
+                Err(err) => unreadable(err),
+            },
+            Check::NetworkVisible => {
+                let state = match backend.load_state() {
+                    Ok(state) => state,
+                    Err(err) => return unreadable(err),
+                };
+                match state
+                    .networks
+                    .into_iter()
+                    .find(|network| network.ssid == ssid)
+                {
+                    Some(network) => {
+                        let detail = format!("Signal {}%.", network.strength);
+                        self.network = Some(network);
+                        StepResult::pass(check, detail)
+                    }
+                    None => {
+                        StepResult::fail(check, format!("{ssid} was not seen in the last scan."))
+                    }
+                }
+            }
+            Check::SecretsSaved => self.evaluate_secrets(backend, ssid),
+            Check::AddressObtained => {
+                if !self
+                    .network
+                    .as_ref()
+                    .is_some_and(|network| network.is_active)
+                {
+                    return StepResult::fail(check, format!("Not connected to {ssid}."));
+                }
+                match backend.ip_config() {
+                    Ok(Some(config)) => {
+                        let source = if config.dhcp { "from DHCP" } else { "static" };
+                        let detail = format!("{}/{} ({source}).", config.address, config.prefix);
+                        self.ip_config = Some(config);
+                        StepResult::pass(check, detail)
+                    }
+                    Ok(None) => StepResult::fail(check, "The device has no IPv4 address."),
+                    Err(err) => unreadable(err),
+                }
+            }
+            Check::GatewayReachable => {
+                let Some(gateway) = self
+                    .ip_config
+                    .as_ref()
+                    .and_then(|config| config.gateway.clone())
+                else {
+                    return StepResult::fail(check, "No default gateway was handed out.");
+                };
+                match probes.ping(&gateway) {
+                    Ok(true) => StepResult::pass(check, format!("{gateway} answered.")),
+                    Ok(false) => {
+                        StepResult::fail(check, format!("{gateway} did not answer a ping."))
+                    }
+                    Err(_) => StepResult::pass(
+                        check,
+                        format!("Skipped: ping is not available to reach {gateway}."),
+                    ),
+                }
+            }
+            Check::DnsResolves => {
+                let servers = self
+                    .ip_config
+                    .as_ref()
+                    .map(|config| config.dns_servers.join(", "))
+                    .filter(|servers| !servers.is_empty())
+                    .unwrap_or_else(|| "none set".to_string());
+                if probes.resolve(DNS_PROBE_HOST) {
+                    StepResult::pass(
+                        check,
+                        format!("Resolved {DNS_PROBE_HOST} (servers: {servers})."),
+                    )
+                } else {
+                    StepResult::fail(
+                        check,
+                        format!("Could not resolve {DNS_PROBE_HOST} (servers: {servers})."),
+                    )
+                }
+            }
+            Check::NoCaptivePortal => {
+                if !self.connectivity_checked {
+                    StepResult::pass(check, "Not checked: connectivity checking is disabled.")
+                } else if self.connectivity == CONNECTIVITY_PORTAL {
+                    StepResult::fail(check, "NetworkManager detected a captive portal.")
+                } else {
+                    StepResult::pass(check, "NetworkManager reports no portal.")
+                }
+            }
+        }
+    }
+
+    fn evaluate_secrets(&self, backend: &impl Backend, ssid: &str) -> StepResult {
+        let check = Check::SecretsSaved;
+        match &self.network {
+            Some(network) if !network.is_secure => {
+                StepResult::pass(check, "Open network, no password needed.")
+            }
+            Some(network) if !network.is_saved => {
+                StepResult::fail(check, format!("{ssid} has no saved profile."))
+            }
+            _ => match backend.get_saved_password(ssid) {
+                Ok(Some(secret)) if !secret.is_empty() => {
+                    StepResult::pass(check, "A password is stored.")
+                }
+                Ok(_) => StepResult::fail(check, "The saved profile has no password."),
+                // Reading secrets needs polkit; not being allowed to look is not a failure.
+                Err(_) => StepResult::pass(
+                    check,
+                    "Could not read the saved password; assuming it is there.",
+                ),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock_nm::{MockAccessPoint, MockNetworkManager};
+
+    struct FakeProbes {
+        gateway_answers: bool,
+    }
+
+    impl Probes for FakeProbes {
+        fn ping(&self, _host: &str) -> io::Result<bool> {
+            Ok(self.gateway_answers)
+        }
+
+        fn resolve(&self, _host: &str) -> bool {
+            true
+        }
+    }
+
+    fn failed_at(results: &[StepResult]) -> Option<Check> {
+        results
+            .iter()
+            .find(|result| !result.passed)
+            .map(|result| result.check)
+    }
+
+    #[test]
+    fn stops_at_first_failing_step() {
+        let access_points = vec![
+            MockAccessPoint::secured("Office", 90, "hunter22"),
+            MockAccessPoint::secured("Lab", 60, "s3cret"),
+        ];
+        let Some(mock) = MockNetworkManager::start(access_points) else {
+            eprintln!("dbus-daemon not available, skipping troubleshooting test");
+            return;
+        };
+        mock.add_saved("Office", Some("hunter22"));
+        let backend = mock.backend();
+        let probes = FakeProbes {
+            gateway_answers: true,
+        };
+
+        let missing = run(&backend, "Elsewhere", &probes, |_| {});
+        assert_eq!(failed_at(&missing), Some(Check::NetworkVisible));
+        assert_eq!(missing.len(), 3);
+
+        let unsaved = run(&backend, "Lab", &probes, |_| {});
+        assert_eq!(failed_at(&unsaved), Some(Check::SecretsSaved));
+
+        let idle = run(&backend, "Office", &probes, |_| {});
+        assert_eq!(failed_at(&idle), Some(Check::AddressObtained));
+
+        backend
+            .connect_network("Office", None, true, None)
+            .expect("connect");
+        let mut seen = 0;
+        let healthy = run(&backend, "Office", &probes, |_| seen += 1);
+        assert_eq!(failed_at(&healthy), None);
+        assert_eq!(seen, CHECKS.len());
+
+        let silent = FakeProbes {
+            gateway_answers: false,
+        };
+        let results = run(&backend, "Office", &silent, |_| {});
+        assert_eq!(failed_at(&results), Some(Check::GatewayReachable));
+        assert!(results.last().unwrap().detail.contains("192.168.1.1"));
+    }
+}
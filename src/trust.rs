@@ -0,0 +1,81 @@
+use crate::backend::{Backend, BackendResult};
+use crate::models::SettingValue;
+use serde::{Deserialize, Serialize};
+
+/// `connection.mdns`: NetworkManager's default, and off.
+const MDNS_DEFAULT: i32 = -1;
+const MDNS_NO: i32 = 0;
+
+/// How far the user trusts a network. Tagging one applies a set of
+/// privacy settings to its profile in one go.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkTrust {
+    /// Home or work: the real MAC address, the default firewall zone and mDNS.
+    Trusted,
+    /// Cafés and airports: a random MAC address on each connection, the
+    /// firewalld `public` zone, no mDNS and treated as metered.
+    Untrusted,
+}
+
+impl NetworkTrust {
+    pub const ALL: [NetworkTrust; 2] = [NetworkTrust::Trusted, NetworkTrust::Untrusted];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NetworkTrust::Trusted => "Trusted",
+            NetworkTrust::Untrusted => "Untrusted",
+        }
+    }
+
+    /// The profile keys this level sets, as (section, key, value).
+    fn settings(self) -> [(&'static str, &'static str, SettingValue); 3] {
+        let (mac, zone, mdns) = match self {
+            NetworkTrust::Trusted => ("permanent", "", MDNS_DEFAULT),
+            NetworkTrust::Untrusted => ("random", "public", MDNS_NO),
+        };
+        [
+            ("802-11-wireless", "cloned-mac-address", SettingValue::Text(mac.to_string())),
+            ("connection", "zone", SettingValue::Text(zone.to_string())),
+            ("connection", "mdns", SettingValue::Int32(mdns)),
+        ]
+    }
+}
+
+/// Writes the settings of `trust` to the profile of `ssid`. They take effect
+/// the next time the network connects.
+pub fn apply(backend: &impl Backend, ssid: &str, trust: NetworkTrust) -> BackendResult<()> {
+    for (section, key, value) in trust.settings() {
+        backend.set_raw_setting(ssid, section, key, &value)?;
+    }
+    backend.set_metered(ssid, trust == NetworkTrust::Untrusted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock_nm::{MockAccessPoint, MockNetworkManager};
+
+    #[test]
+    fn tags_write_the_privacy_settings() {
+        let Some(mock) = MockNetworkManager::start(vec![MockAccessPoint::open("Airport", 60)]) else {
+            eprintln!("dbus-daemon not available, skipping mocked NetworkManager test");
+            return;
+        };
+        mock.add_saved("Airport", None);
+        let backend = mock.backend();
+
+        apply(&backend, "Airport", NetworkTrust::Untrusted).expect("untrusted");
+        let raw = backend.raw_settings("Airport").expect("settings");
+        assert_eq!(raw["802-11-wireless"]["cloned-mac-address"].input.as_deref(), Some("random"));
+        assert_eq!(raw["connection"]["zone"].input.as_deref(), Some("public"));
+        assert_eq!(raw["connection"]["mdns"].input.as_deref(), Some("0"));
+        assert_eq!(raw["connection"]["metered"].input.as_deref(), Some("1"));
+
+        apply(&backend, "Airport", NetworkTrust::Trusted).expect("trusted");
+        let raw = backend.raw_settings("Airport").expect("settings");
+        assert_eq!(raw["802-11-wireless"]["cloned-mac-address"].input.as_deref(), Some("permanent"));
+        assert_eq!(raw["connection"]["zone"].input.as_deref(), Some(""));
+        assert_eq!(raw["connection"]["metered"].input.as_deref(), Some("0"));
+    }
+}
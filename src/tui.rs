@@ -0,0 +1,362 @@
+use crate::backend::nm::NetworkManagerBackend;
+use crate::backend::{Backend, BackendResult};
+use crate::connect_flow::{
+    NM_ACTIVE_CONNECTION_STATE_ACTIVATED, NM_ACTIVE_CONNECTION_STATE_DEACTIVATED,
+};
+use crate::friendly_error;
+use crate::models::Network;
+use crate::preferences::Preferences;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(250);
+/// How often the list is re-read so new scan results and drops show up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+const HELP: &str = "↑/↓ select  Enter connect  d disconnect  w Wi‑Fi on/off  r rescan  q quit";
+
+/// `yufi --tui`: the network list in a terminal, for machines without a compositor.
+pub fn run() -> i32 {
+    let backend = NetworkManagerBackend::new();
+    let mut app = TuiApp::default();
+    app.reload(&backend);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &backend, &mut app);
+    ratatui::restore();
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("yufi: {err}");
+            1
+        }
+    }
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    backend: &impl Backend,
+    app: &mut TuiApp,
+) -> io::Result<()> {
+    let mut last_refresh = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if event::poll(TICK)? {
+            let request = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key.code),
+                _ => None,
+            };
+            match request {
+                Some(Request::Quit) => return Ok(()),
+                Some(request) => {
+                    app.perform(backend, request);
+                    last_refresh = Instant::now();
+                }
+                None => {}
+            }
+        }
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            app.poll_activation(backend);
+            app.reload(backend);
+            last_refresh = Instant::now();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Request {
+    Connect {
+        ssid: String,
+        password: Option<String>,
+        is_saved: bool,
+    },
+    Disconnect(String),
+    ToggleWifi,
+    Rescan,
+    Quit,
+}
+
+struct PasswordPrompt {
+    ssid: String,
+    input: String,
+}
+
+#[derive(Default)]
+struct TuiApp {
+    wifi_enabled: bool,
+    networks: Vec<Network>,
+    list: ListState,
+    prompt: Option<PasswordPrompt>,
+    /// SSID and active connection path of a connect still in progress.
+    pending: Option<(String, String)>,
+    status: String,
+}
+
+impl TuiApp {
+    fn selected(&self) -> Option<&Network> {
+        self.list
+            .selected()
+            .and_then(|index| self.networks.get(index))
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Option<Request> {
+        if let Some(prompt) = self.prompt.as_mut() {
+            match code {
+                KeyCode::Esc => self.prompt = None,
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(ch) => prompt.input.push(ch),
+                KeyCode::Enter => {
+                    let prompt = self.prompt.take()?;
+                    return Some(Request::Connect {
+                        ssid: prompt.ssid,
+                        password: Some(prompt.input),
+                        is_saved: false,
+                    });
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(Request::Quit),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.list.select_previous();
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.list.select_next();
+                None
+            }
+            KeyCode::Char('w') => Some(Request::ToggleWifi),
+            KeyCode::Char('r') => Some(Request::Rescan),
+            KeyCode::Char('d') => self
+                .selected()
+                .filter(|network| network.is_active)
+                .map(|network| Request::Disconnect(network.ssid.clone())),
+            KeyCode::Enter => {
+                let network = self
+                    .selected()
+                    .filter(|network| !network.is_active)?
+                    .clone();
+                if network.is_saved || !needs_password(&network) {
+                    return Some(Request::Connect {
+                        ssid: network.ssid,
+                        password: None,
+                        is_saved: network.is_saved,
+                    });
+                }
+                self.prompt = Some(PasswordPrompt {
+                    ssid: network.ssid,
+                    input: String::new(),
+                });
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn perform(&mut self, backend: &impl Backend, request: Request) {
+        let result = match request {
+            Request::Connect {
+                ssid,
+                password,
+                is_saved,
+            } => {
+                let autoconnect = is_saved || Preferences::load().autoconnect_new;
+                backend
+                    .connect_network(&ssid, password.as_deref(), autoconnect, None)
+                    .map(|path| {
+                        self.status = format!("Connecting to {ssid}...");
+                        self.pending = path.map(|path| (ssid, path));
+                    })
+            }
+            Request::Disconnect(ssid) => backend.disconnect_network(&ssid).map(|()| {
+                self.status = format!("Disconnected from {ssid}.");
+            }),
+            Request::ToggleWifi => {
+                let enabled = !self.wifi_enabled;
+                backend.set_wifi_enabled(enabled).map(|()| {
+                    self.status = format!("Wi‑Fi turned {}.", if enabled { "on" } else { "off" });
+                })
+            }
+            Request::Rescan => backend.request_scan().map(|()| {
+                self.status = "Scanning...".to_string();
+            }),
+            Request::Quit => Ok(()),
+        };
+        self.report(result);
+        self.reload(backend);
+    }
+
+    /// Reports how a connect started by `perform` ended, since NetworkManager
+    /// only accepts the request up front.
+    fn poll_activation(&mut self, backend: &impl Backend) {
+        let Some((ssid, path)) = self.pending.take() else {
+            return;
+        };
+        match backend.active_connection_state(&path) {
+            Ok(NM_ACTIVE_CONNECTION_STATE_ACTIVATED) => {
+                self.status = format!("Connected to {ssid}.")
+            }
+            Ok(NM_ACTIVE_CONNECTION_STATE_DEACTIVATED) | Err(_) => {
+                self.status =
+                    format!("Could not connect to {ssid}. Check the password and try again.");
+            }
+            Ok(_) => self.pending = Some((ssid, path)),
+        }
+    }
+
+    fn reload(&mut self, backend: &impl Backend) {
+        let selected = self.selected().map(|network| network.ssid.clone());
+        let result = backend.load_state().map(|state| {
+            self.wifi_enabled = state.wifi_enabled;
+            self.networks = state.networks;
+        });
+        self.report(result);
+        let index = selected
+            .and_then(|ssid| {
+                self.networks
+                    .iter()
+                    .position(|network| network.ssid == ssid)
+            })
+            .or((!self.networks.is_empty()).then_some(0));
+        self.list.select(index);
+    }
+
+    fn report(&mut self, result: BackendResult<()>) {
+        if let Err(err) = result {
+            self.status = friendly_error(&err);
+        }
+    }
+}
+
+fn needs_password(network: &Network) -> bool {
+    network.is_secure && network.security != "owe"
+}
+
+fn network_line(network: &Network) -> String {
+    let marker = if network.is_active { "●" } else { " " };
+    let security = if network.is_secure {
+        network.security
+    } else {
+        "open"
+    };
+    let saved = if network.is_saved { "saved" } else { "" };
+    format!(
+        "{marker} {:<32} {:>3}%  {security:<5} {saved}",
+        network.ssid, network.strength
+    )
+}
+
+fn draw(frame: &mut Frame, app: &mut TuiApp) {
+    let [header, body, status, help] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let radio = if app.wifi_enabled { "on" } else { "off" };
+    frame.render_widget(Paragraph::new(format!("YuFi — Wi‑Fi {radio}")), header);
+
+    let block = Block::bordered().title(" Networks ");
+    if app.networks.is_empty() {
+        let empty = if app.wifi_enabled {
+            "No networks found."
+        } else {
+            "Wi‑Fi is off."
+        };
+        frame.render_widget(Paragraph::new(empty).block(block), body);
+    } else {
+        let items: Vec<ListItem> = app
+            .networks
+            .iter()
+            .map(|network| ListItem::new(network_line(network)))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, body, &mut app.list);
+    }
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), status);
+    frame.render_widget(
+        Paragraph::new(HELP).style(Style::default().add_modifier(Modifier::DIM)),
+        help,
+    );
+
+    if let Some(prompt) = &app.prompt {
+        let area = centered(frame.area(), 48, 3);
+        let masked = "•".repeat(prompt.input.chars().count());
+        let block = Block::bordered().title(format!(" Password for {} ", prompt.ssid));
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(masked).block(block), area);
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [row] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [cell] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(row);
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NetworkAction;
+
+    fn network(ssid: &str, is_saved: bool, is_active: bool) -> Network {
+        Network {
+            ssid: ssid.to_string(),
+            signal_icon: "network-wireless-signal-good-symbolic",
+            action: NetworkAction::None,
+            strength: 60,
+            is_active,
+            is_saved,
+            is_secure: true,
+            security: "wpa2",
+            access_points: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keys_select_connect_and_prompt_for_passwords() {
+        let mut app = TuiApp {
+            networks: vec![network("Home", true, true), network("Cafe", false, false)],
+            ..TuiApp::default()
+        };
+        app.list.select(Some(0));
+        assert_eq!(app.handle_key(KeyCode::Enter), None);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('d')),
+            Some(Request::Disconnect("Home".to_string()))
+        );
+
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.handle_key(KeyCode::Enter), None);
+        assert!(app.prompt.is_some());
+        assert_eq!(app.handle_key(KeyCode::Char('q')), None);
+        app.handle_key(KeyCode::Char('x'));
+        app.handle_key(KeyCode::Backspace);
+        assert_eq!(
+            app.handle_key(KeyCode::Enter),
+            Some(Request::Connect {
+                ssid: "Cafe".to_string(),
+                password: Some("q".to_string()),
+                is_saved: false,
+            })
+        );
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Some(Request::Quit));
+    }
+}
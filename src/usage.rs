@@ -0,0 +1,294 @@
+use crate::demo;
+use crate::models::{AppState, SavedProfile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Line-based file used before usage moved into the JSON data file.
+const LEGACY_FILE_NAME: &str = "usage";
+/// Sightings closer together than this are not written back, so a refresh every
+/// few seconds does not rewrite the file each time.
+const SEEN_RESOLUTION_SECS: u64 = 300;
+/// The month `relative_time` counts in.
+const MONTH_SECS: u64 = 2_592_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkUsage {
+    pub times_connected: u32,
+    pub last_connected: u64,
+    pub last_seen: u64,
+}
+
+/// Turns successive states into per-SSID counters NetworkManager does not keep.
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    active: Option<String>,
+    observed: bool,
+}
+
+impl UsageTracker {
+    /// Updates `history` from a freshly loaded state and returns true when
+    /// something worth saving changed. Only saved networks are tracked. A network
+    /// that is already active the first time the app looks is not counted as a
+    /// new connection.
+    pub fn observe(
+        &mut self,
+        history: &mut BTreeMap<String, NetworkUsage>,
+        state: &AppState,
+        now: u64,
+    ) -> bool {
+        let mut changed = false;
+        let active = state
+            .networks
+            .iter()
+            .find(|network| network.is_active)
+            .map(|network| network.ssid.clone());
+
+        for network in state.networks.iter().filter(|network| network.is_saved) {
+            let usage = history.entry(network.ssid.clone()).or_default();
+            if now.saturating_sub(usage.last_seen) >= SEEN_RESOLUTION_SECS {
+                usage.last_seen = now;
+                if network.is_active {
+                    usage.last_connected = now;
+                }
+                changed = true;
+            }
+        }
+
+        let newly_active = active
+            .as_ref()
+            .filter(|ssid| self.observed && self.active.as_ref() != Some(*ssid));
+        if let Some(ssid) = newly_active {
+            let usage = history.entry(ssid.clone()).or_default();
+            usage.times_connected = usage.times_connected.saturating_add(1);
+            usage.last_connected = now;
+            usage.last_seen = now;
+            changed = true;
+        }
+        self.active = active;
+        self.observed = true;
+        changed
+    }
+}
+
+pub fn load_legacy() -> BTreeMap<String, NetworkUsage> {
+    legacy_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| parse_legacy(&contents))
+        .unwrap_or_default()
+}
+
+pub fn remove_legacy() {
+    if let Some(path) = legacy_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn parse_legacy(contents: &str) -> BTreeMap<String, NetworkUsage> {
+    let mut history = BTreeMap::new();
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // The SSID goes last so that spaces inside it survive the split.
+        let mut fields = line.splitn(4, ' ');
+        let (Some(times), Some(connected), Some(seen), Some(ssid)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let (Ok(times_connected), Ok(last_connected), Ok(last_seen)) =
+            (times.parse(), connected.parse(), seen.parse())
+        else {
+            continue;
+        };
+        history.insert(
+            ssid.to_string(),
+            NetworkUsage {
+                times_connected,
+                last_connected,
+                last_seen,
+            },
+        );
+    }
+    history
+}
+
+/// A saved network offered for cleanup, with every profile path saved for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CleanupCandidate {
+    pub ssid: String,
+    pub paths: Vec<String>,
+    pub is_open: bool,
+    /// Newest of NetworkManager's timestamp and YuFi's history; 0 if never.
+    pub last_connected: u64,
+}
+
+/// Saved networks that are unencrypted or were not connected to in the last
+/// `months` months, least recently used first. A network counts as open when
+/// any of its profiles is.
+pub fn cleanup_candidates(
+    profiles: &[SavedProfile],
+    history: &BTreeMap<String, NetworkUsage>,
+    now: u64,
+    months: u32,
+) -> Vec<CleanupCandidate> {
+    let mut candidates: Vec<CleanupCandidate> = Vec::new();
+    for profile in profiles {
+        let recorded = history.get(&profile.ssid).map_or(0, |usage| usage.last_connected);
+        let last_connected = recorded.max(profile.timestamp);
+        match candidates.iter_mut().find(|candidate| candidate.ssid == profile.ssid) {
+            Some(candidate) => {
+                candidate.paths.push(profile.path.clone());
+                candidate.is_open |= profile.is_open;
+                candidate.last_connected = candidate.last_connected.max(last_connected);
+            }
+            None => candidates.push(CleanupCandidate {
+                ssid: profile.ssid.clone(),
+                paths: vec![profile.path.clone()],
+                is_open: profile.is_open,
+                last_connected,
+            }),
+        }
+    }
+    let cutoff = now.saturating_sub(u64::from(months) * MONTH_SECS);
+    candidates.retain(|candidate| candidate.is_open || candidate.last_connected < cutoff);
+    candidates.sort_by_key(|candidate| (candidate.last_connected, candidate.ssid.clone()));
+    candidates
+}
+
+/// Coarse "2 days ago" style description of a past timestamp.
+pub fn relative_time(then: u64, now: u64) -> String {
+    let elapsed = now.saturating_sub(then);
+    let (count, unit) = match elapsed {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (elapsed / 60, "minute"),
+        3_600..86_400 => (elapsed / 3_600, "hour"),
+        86_400..2_592_000 => (elapsed / 86_400, "day"),
+        2_592_000..31_536_000 => (elapsed / 2_592_000, "month"),
+        _ => (elapsed / 31_536_000, "year"),
+    };
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(root) = demo::root() {
+        return Some(root.join("data").join("yufi"));
+    }
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(base.join("yufi"))
+}
+
+fn legacy_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(LEGACY_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Network, NetworkAction};
+
+    fn state(networks: &[(&str, bool, bool)]) -> AppState {
+        AppState {
+            wifi_enabled: true,
+            networks: networks
+                .iter()
+                .map(|(ssid, is_saved, is_active)| Network {
+                    ssid: ssid.to_string(),
+                    signal_icon: "network-wireless-signal-good-symbolic",
+                    action: NetworkAction::None,
+                    strength: 50,
+                    is_active: *is_active,
+                    is_saved: *is_saved,
+                    is_secure: true,
+                    security: "wpa2",
+                    access_points: Vec::new(),
+                })
+                .collect(),
+            out_of_range: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn counts_connections_and_sightings() {
+        let mut tracker = UsageTracker::default();
+        let mut history = BTreeMap::new();
+        let home = [("Home", true, true), ("Cafe", false, false)];
+        assert!(tracker.observe(&mut history, &state(&home), 1_000));
+        assert_eq!(history["Home"].times_connected, 0);
+        assert_eq!(history["Home"].last_connected, 1_000);
+        assert!(!history.contains_key("Cafe"));
+
+        assert!(!tracker.observe(&mut history, &state(&[("Home", true, true)]), 1_010));
+        let idle = [("Home", true, false), ("Office", true, false)];
+        tracker.observe(&mut history, &state(&idle), 1_020);
+        let office = [("Home", true, false), ("Office", true, true)];
+        assert!(tracker.observe(&mut history, &state(&office), 1_030));
+        let office = history["Office"];
+        assert_eq!((office.times_connected, office.last_connected), (1, 1_030));
+    }
+
+    #[test]
+    fn parses_legacy_ssids_with_spaces() {
+        let history = parse_legacy("3 100 200 My Home Wi-Fi\nbroken line\n");
+        let usage = &history["My Home Wi-Fi"];
+        assert_eq!((usage.times_connected, usage.last_connected, usage.last_seen), (3, 100, 200));
+    }
+
+    #[test]
+    fn offers_open_and_long_unused_networks() {
+        let profile = |ssid: &str, timestamp, is_open| SavedProfile {
+            path: format!("/{ssid}/{timestamp}"),
+            id: ssid.to_string(),
+            ssid: ssid.to_string(),
+            timestamp,
+            priority: 0,
+            autoconnect: true,
+            is_open,
+            security: Default::default(),
+        };
+        let now = 24 * MONTH_SECS;
+        let profiles = [
+            profile("Home", now - 60, false),
+            profile("Airport", now - MONTH_SECS, true),
+            profile("Hotel", 0, false),
+            profile("Office", MONTH_SECS, false),
+            profile("Office", 2 * MONTH_SECS, false),
+            profile("Library", MONTH_SECS, false),
+        ];
+        let mut history = BTreeMap::new();
+        history.insert(
+            "Library".to_string(),
+            NetworkUsage {
+                last_connected: now - MONTH_SECS,
+                ..NetworkUsage::default()
+            },
+        );
+
+        let candidates = cleanup_candidates(&profiles, &history, now, 6);
+        let ssids: Vec<&str> = candidates.iter().map(|candidate| candidate.ssid.as_str()).collect();
+        assert_eq!(ssids, ["Hotel", "Office", "Airport"]);
+        assert_eq!(candidates[1].paths.len(), 2);
+        assert_eq!(candidates[1].last_connected, 2 * MONTH_SECS);
+        assert!(cleanup_candidates(&profiles, &history, now, 24).iter().all(|c| c.ssid != "Office"));
+    }
+
+    #[test]
+    fn describes_elapsed_time() {
+        assert_eq!(relative_time(100, 130), "just now");
+        assert_eq!(relative_time(0, 3_600), "1 hour ago");
+        assert_eq!(relative_time(0, 2 * 86_400 + 5), "2 days ago");
+        assert_eq!(relative_time(200, 100), "just now");
+    }
+}
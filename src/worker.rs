@@ -0,0 +1,507 @@
+use crate::backend::{Backend, BackendError};
+use crate::backend::nm::NetworkManagerBackend;
+use crate::backup;
+use crate::diagnostics::SessionDiagnostics;
+use crate::hooks::HookEnv;
+use crate::link;
+#[cfg(feature = "netlink")]
+use crate::netlink;
+use crate::models::{
+    AccessPointInfo, BandLock, EnterpriseCertificates, LinkStatus, Network, NetworkDetails, NmDiagnostics, RawSettings,
+    RestoredProfiles, SavedProfile, VisibleAccessPoint, VpnConnection, best_available,
+};
+use crate::trust::{self, NetworkTrust};
+use crate::{DialogId, UiEvent, password_error_message};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+pub enum Command {
+    LoadState,
+    /// Whether Wi‑Fi is on; answered on `reply` like `LoadDetails`.
+    WifiEnabled {
+        reply: async_channel::Sender<Result<bool, BackendError>>,
+    },
+    /// Answered on `reply` once every command sent before it has run.
+    Flush {
+        reply: async_channel::Sender<()>,
+    },
+    Scan,
+    ReadLastScan,
+    SetWifi(bool),
+    Connect {
+        ssid: String,
+        password: Option<String>,
+        from_password: bool,
+        autoconnect: bool,
+        band: Option<BandLock>,
+    },
+    ConnectHidden {
+        ssid: String,
+        password: Option<String>,
+        autoconnect: bool,
+    },
+    Disconnect(String),
+    RemoveProfile(String),
+    /// Reads the interface and address a network that came up is using, for
+    /// its hooks.
+    LookupHookEnv(String),
+    /// Reads the Wi‑Fi device's traffic counters for the monthly usage.
+    TrafficCounters,
+    /// Brings up the VPN profile `uuid` tied to the network `ssid`.
+    ActivateVpn {
+        ssid: String,
+        uuid: String,
+    },
+    /// Reads the state of the activation at `path`, in case it settled
+    /// before the window started following its signals.
+    ProbeActive(String),
+    /// Answered on `reply` rather than as a `UiEvent`, since only the details
+    /// dialog that asked is interested. The access point is read along with
+    /// the profile, and is None when the network is out of range.
+    LoadDetails {
+        ssid: String,
+        reply: async_channel::Sender<(Result<NetworkDetails, BackendError>, Option<AccessPointInfo>)>,
+    },
+    /// Answered on `reply` like `LoadDetails`; a failure is also shown in
+    /// `dialog` as a `UiEvent::DialogError`.
+    LoadPassword {
+        ssid: String,
+        dialog: DialogId,
+        reply: async_channel::Sender<Result<Option<String>, BackendError>>,
+    },
+    /// Answered on `reply` like `LoadDetails`.
+    LoadVpns {
+        reply: async_channel::Sender<Result<Vec<VpnConnection>, BackendError>>,
+    },
+    /// The best saved network in range, by `best_available`; answered on
+    /// `reply` like `LoadDetails`.
+    PickBest {
+        reply: async_channel::Sender<Result<Option<Network>, BackendError>>,
+    },
+    /// Writes `autoconnect-priority` to each network's profiles, as (SSID,
+    /// profile paths, priority), stopping at the first that fails; answered
+    /// on `reply` like `LoadDetails`, naming the network that failed.
+    SetPriorities {
+        priorities: Vec<(String, Vec<String>, i32)>,
+        reply: async_channel::Sender<Result<(), (String, BackendError)>>,
+    },
+    /// Every access point the last scan found; answered on `reply` like
+    /// `LoadDetails`.
+    LoadAccessPoints {
+        reply: async_channel::Sender<Result<Vec<VisibleAccessPoint>, BackendError>>,
+    },
+    /// The settings map of the profile of `ssid`; answered on `reply` like
+    /// `LoadDetails`.
+    LoadRawSettings {
+        ssid: String,
+        reply: async_channel::Sender<Result<RawSettings, BackendError>>,
+    },
+    /// Deletes the profiles at `paths`, stopping at the first that fails;
+    /// answered on `reply` like `LoadDetails`.
+    DeleteProfiles {
+        paths: Vec<String>,
+        reply: async_channel::Sender<Result<(), BackendError>>,
+    },
+    /// Saves a copy of the profile at `path` as `id`; answered on `reply`
+    /// like `LoadDetails` with the copy's path.
+    DuplicateProfile {
+        path: String,
+        id: String,
+        reply: async_channel::Sender<Result<String, BackendError>>,
+    },
+    /// Writes the certificates of the 802.1X profile of `ssid`, and the
+    /// private key's password when given; answered on `reply` like
+    /// `LoadDetails`.
+    SetCertificates {
+        ssid: String,
+        certificates: EnterpriseCertificates,
+        key_password: Option<String>,
+        reply: async_channel::Sender<Result<(), BackendError>>,
+    },
+    /// Keeps the profile of `ssid` to `user` on the current device, or opens
+    /// it up again with None; answered on `reply` like `LoadDetails`.
+    SetPersonal {
+        ssid: String,
+        user: Option<String>,
+        reply: async_channel::Sender<Result<(), BackendError>>,
+    },
+    /// Writes the settings of `trust` to the profile of `ssid`; answered on
+    /// `reply` like `LoadDetails`.
+    ApplyTrust {
+        ssid: String,
+        trust: NetworkTrust,
+        reply: async_channel::Sender<Result<(), BackendError>>,
+    },
+    /// Every saved Wi‑Fi profile; answered on `reply` like `LoadDetails`.
+    LoadProfiles {
+        reply: async_channel::Sender<Result<Vec<SavedProfile>, BackendError>>,
+    },
+    /// The radio link of the active connection; answered on `reply` like
+    /// `LoadDetails`.
+    LoadLink {
+        reply: async_channel::Sender<Result<LinkReading, BackendError>>,
+    },
+    /// What the diagnostics report is made of; answered on `reply` like
+    /// `LoadDetails`. Looking for agents walks every running process.
+    LoadDiagnostics {
+        reply: async_channel::Sender<DiagnosticsReading>,
+    },
+    /// Writes every Wi‑Fi profile to `path`, encrypted with `password`;
+    /// answered on `reply` like `LoadDetails`. The key derivation alone takes
+    /// about a second.
+    BackUpProfiles {
+        path: PathBuf,
+        password: String,
+        reply: async_channel::Sender<Result<(), BackupFailure>>,
+    },
+    /// Adds the profiles of the backup at `path` that are not saved yet;
+    /// answered on `reply` like `LoadDetails`.
+    RestoreProfiles {
+        path: PathBuf,
+        password: String,
+        reply: async_channel::Sender<Result<RestoredProfiles, BackupFailure>>,
+    },
+}
+
+/// Why a backup could not be written or restored.
+pub enum BackupFailure {
+    Backend(BackendError),
+    /// Reading or writing the file, or its encryption.
+    Archive(String),
+}
+
+impl BackupFailure {
+    fn archive(err: impl ToString) -> Self {
+        BackupFailure::Archive(err.to_string())
+    }
+}
+
+/// The radio link with what `iw` reports about it, for the details dialog.
+pub struct LinkReading {
+    pub link: LinkStatus,
+    /// None when the driver does not report it.
+    pub tx_power_dbm: Option<f64>,
+    /// None while not associated or when nl80211 can't be read.
+    #[cfg(feature = "netlink")]
+    pub station: Option<netlink::StationStats>,
+}
+
+/// NetworkManager's state and the session's, for the diagnostics report.
+pub struct DiagnosticsReading {
+    pub nm: Result<NmDiagnostics, BackendError>,
+    pub session: SessionDiagnostics,
+    /// The nl80211 lines for the Wi‑Fi device, empty without one.
+    #[cfg(feature = "netlink")]
+    pub netlink: String,
+}
+
+#[derive(Clone)]
+pub struct Worker {
+    commands: mpsc::Sender<Command>,
+}
+
+impl Worker {
+    pub fn spawn(ui_tx: async_channel::Sender<UiEvent>) -> Self {
+        let (commands, queue) = mpsc::channel::<Command>();
+        thread::Builder::new()
+            .name("yufi-backend".to_string())
+            .spawn(move || {
+                let backend = NetworkManagerBackend::new();
+                let mut pending = VecDeque::new();
+                while let Some(command) = next_command(&queue, &mut pending) {
+                    let event = superseded(&command, &pending).or_else(|| run(&backend, command));
+                    let Some(event) = event else {
+                        continue;
+                    };
+                    if ui_tx.send_blocking(event).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn backend worker");
+        Self { commands }
+    }
+
+    pub fn send(&self, command: Command) {
+        let _ = self.commands.send(command);
+    }
+}
+
+/// The next command to run. Everything sent meanwhile is gathered into
+/// `pending`, so a newer command can cancel an older one it conflicts with.
+fn next_command(queue: &mpsc::Receiver<Command>, pending: &mut VecDeque<Command>) -> Option<Command> {
+    if pending.is_empty() {
+        pending.push_back(queue.recv().ok()?);
+    }
+    pending.extend(queue.try_iter());
+    pending.pop_front()
+}
+
+fn activates(command: &Command) -> bool {
+    matches!(command, Command::Connect { .. } | Command::ConnectHidden { .. })
+}
+
+/// The answer for `command` if a later one in `pending` makes running it
+/// pointless: the device joins one network at a time, so only the last
+/// activation asked for is run, and likewise for switching the radio. A
+/// disconnect from the network, or turning Wi‑Fi off, also cancels an
+/// activation still waiting. Each cancelled command is still answered, so
+/// the window can tell "superseded" from a failure.
+fn superseded(command: &Command, pending: &VecDeque<Command>) -> Option<UiEvent> {
+    let replaces_activation = |ssid: &str| {
+        pending.iter().any(|later| {
+            activates(later)
+                || matches!(later, Command::SetWifi(false))
+                || matches!(later, Command::Disconnect(other) if other == ssid)
+        })
+    };
+    match command {
+        Command::SetWifi(enabled) => pending
+            .iter()
+            .any(|later| matches!(later, Command::SetWifi(_)))
+            .then_some(UiEvent::WifiSet {
+                enabled: *enabled,
+                result: Err(BackendError::Superseded),
+            }),
+        Command::Connect {
+            ssid, from_password, ..
+        } => replaces_activation(ssid).then(|| UiEvent::ConnectDone {
+            ssid: ssid.clone(),
+            result: Err(BackendError::Superseded),
+            from_password: *from_password,
+        }),
+        Command::ConnectHidden { ssid, .. } => replaces_activation(ssid).then(|| UiEvent::HiddenDone {
+            ssid: ssid.clone(),
+            result: Err(BackendError::Superseded),
+        }),
+        _ => None,
+    }
+}
+
+fn run<B: Backend>(backend: &B, command: Command) -> Option<UiEvent> {
+    let event = match command {
+        Command::LoadState => UiEvent::StateLoaded(backend.load_state()),
+        Command::Scan => UiEvent::ScanDone(backend.request_scan()),
+        Command::ReadLastScan => UiEvent::LastScan(backend.last_scan().ok().flatten()),
+        Command::SetWifi(enabled) => UiEvent::WifiSet {
+            enabled,
+            result: backend.set_wifi_enabled(enabled),
+        },
+        Command::Connect {
+            ssid,
+            password,
+            from_password,
+            autoconnect,
+            band,
+        } => {
+            let result = backend.connect_network(&ssid, password.as_deref(), autoconnect, band);
+            UiEvent::ConnectDone {
+                ssid,
+                result,
+                from_password,
+            }
+        }
+        Command::ConnectHidden {
+            ssid,
+            password,
+            autoconnect,
+        } => {
+            let result =
+                backend.connect_hidden(&ssid, "wpa-psk", password.as_deref(), autoconnect);
+            UiEvent::HiddenDone { ssid, result }
+        }
+        Command::Disconnect(ssid) => {
+            let result = backend.disconnect_network(&ssid);
+            UiEvent::DisconnectDone { ssid, result }
+        }
+        Command::RemoveProfile(ssid) => {
+            let result = backend.forget_network(&ssid);
+            UiEvent::CleanupResult { ssid, result }
+        }
+        Command::LookupHookEnv(ssid) => UiEvent::HookEnv(HookEnv::lookup(backend, &ssid)),
+        Command::TrafficCounters => UiEvent::TrafficCounters(backend.traffic_counters()),
+        Command::ActivateVpn { ssid, uuid } => UiEvent::VpnActivated {
+            result: backend.activate_vpn(&uuid),
+            ssid,
+        },
+        Command::ProbeActive(path) => UiEvent::ActiveState {
+            state: backend.active_connection_state(&path).unwrap_or_default(),
+            reason: 0,
+            path,
+        },
+        Command::LoadDetails { ssid, reply } => {
+            // The dialog may have been closed meanwhile; nobody is left to tell.
+            let details = backend.get_network_details(&ssid);
+            let _ = reply.send_blocking((details, backend.get_access_point_info(&ssid).ok()));
+            return None;
+        }
+        Command::LoadPassword { ssid, dialog, reply } => {
+            let result = backend.get_saved_password(&ssid);
+            let event = result.as_ref().err().map(|err| UiEvent::DialogError {
+                dialog,
+                message: password_error_message(err),
+            });
+            let _ = reply.send_blocking(result);
+            return event;
+        }
+        Command::LoadVpns { reply } => {
+            let _ = reply.send_blocking(backend.vpn_connections());
+            return None;
+        }
+        Command::PickBest { reply } => {
+            let best = backend.load_state().and_then(|state| {
+                let profiles = backend.saved_profiles()?;
+                Ok(best_available(&state, &profiles).cloned())
+            });
+            let _ = reply.send_blocking(best);
+            return None;
+        }
+        Command::SetPriorities { priorities, reply } => {
+            let result = priorities.iter().try_for_each(|(ssid, paths, priority)| {
+                paths
+                    .iter()
+                    .try_for_each(|path| backend.set_autoconnect_priority(path, *priority))
+                    .map_err(|err| (ssid.clone(), err))
+            });
+            let _ = reply.send_blocking(result);
+            return None;
+        }
+        Command::WifiEnabled { reply } => {
+            let _ = reply.send_blocking(backend.wifi_enabled());
+            return None;
+        }
+        Command::Flush { reply } => {
+            let _ = reply.send_blocking(());
+            return None;
+        }
+        Command::DuplicateProfile { path, id, reply } => {
+            let _ = reply.send_blocking(backend.duplicate_profile(&path, &id));
+            return None;
+        }
+        Command::SetCertificates {
+            ssid,
+            certificates,
+            key_password,
+            reply,
+        } => {
+            let _ = reply.send_blocking(backend.set_certificates(&ssid, &certificates, key_password.as_deref()));
+            return None;
+        }
+        Command::SetPersonal { ssid, user, reply } => {
+            let _ = reply.send_blocking(backend.set_personal(&ssid, user.as_deref()));
+            return None;
+        }
+        Command::ApplyTrust { ssid, trust, reply } => {
+            let _ = reply.send_blocking(trust::apply(backend, &ssid, trust));
+            return None;
+        }
+        Command::LoadAccessPoints { reply } => {
+            let _ = reply.send_blocking(backend.list_access_points());
+            return None;
+        }
+        Command::LoadRawSettings { ssid, reply } => {
+            let _ = reply.send_blocking(backend.raw_settings(&ssid));
+            return None;
+        }
+        Command::DeleteProfiles { paths, reply } => {
+            let result = paths.iter().try_for_each(|path| backend.delete_profile(path));
+            let _ = reply.send_blocking(result);
+            return None;
+        }
+        Command::LoadProfiles { reply } => {
+            let _ = reply.send_blocking(backend.saved_profiles());
+            return None;
+        }
+        Command::LoadLink { reply } => {
+            let reading = backend.link_status().map(|link| LinkReading {
+                tx_power_dbm: link::tx_power_dbm(&link.interface),
+                #[cfg(feature = "netlink")]
+                station: netlink::station_stats(&link.interface).ok().flatten(),
+                link,
+            });
+            let _ = reply.send_blocking(reading);
+            return None;
+        }
+        Command::LoadDiagnostics { reply } => {
+            let nm = backend.diagnostics();
+            #[cfg(feature = "netlink")]
+            let netlink = match nm.as_ref().ok().and_then(|nm| nm.device.as_ref()) {
+                Some(device) => netlink::report(&device.interface),
+                None => String::new(),
+            };
+            let _ = reply.send_blocking(DiagnosticsReading {
+                nm,
+                session: SessionDiagnostics::collect(),
+                #[cfg(feature = "netlink")]
+                netlink,
+            });
+            return None;
+        }
+        Command::BackUpProfiles { path, password, reply } => {
+            let written = backend
+                .backup_profiles()
+                .map_err(BackupFailure::Backend)
+                .and_then(|plain| backup::seal(&plain, &password).map_err(BackupFailure::archive))
+                .and_then(|archive| fs::write(&path, archive).map_err(BackupFailure::archive));
+            let _ = reply.send_blocking(written);
+            return None;
+        }
+        Command::RestoreProfiles { path, password, reply } => {
+            let restored = fs::read(&path)
+                .map_err(|err| BackupFailure::Archive(format!("Failed to read {}: {err}", path.display())))
+                .and_then(|archive| backup::open(&archive, &password).map_err(BackupFailure::archive))
+                .and_then(|plain| backend.restore_profiles(&plain).map_err(BackupFailure::Backend));
+            let _ = reply.send_blocking(restored);
+            return None;
+        }
+    };
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect(ssid: &str) -> Command {
+        Command::Connect {
+            ssid: ssid.to_string(),
+            password: None,
+            from_password: false,
+            autoconnect: true,
+            band: None,
+        }
+    }
+
+    fn is_superseded(event: Option<UiEvent>) -> bool {
+        match event {
+            Some(UiEvent::ConnectDone { result, .. }) | Some(UiEvent::HiddenDone { result, .. }) => {
+                matches!(result, Err(BackendError::Superseded))
+            }
+            Some(UiEvent::WifiSet { result, .. }) => matches!(result, Err(BackendError::Superseded)),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn newer_conflicting_commands_cancel_waiting_ones() {
+        let pending = VecDeque::from([connect("Cafe")]);
+        assert!(is_superseded(superseded(&connect("Home"), &pending)));
+
+        let pending = VecDeque::from([Command::Disconnect("Home".to_string())]);
+        assert!(is_superseded(superseded(&connect("Home"), &pending)));
+        assert!(superseded(&connect("Cafe"), &pending).is_none());
+
+        let pending = VecDeque::from([Command::SetWifi(false)]);
+        assert!(is_superseded(superseded(&Command::SetWifi(true), &pending)));
+        assert!(is_superseded(superseded(&connect("Home"), &pending)));
+
+        // Scans and lookups conflict with nothing.
+        let pending = VecDeque::from([Command::Scan, Command::LoadState]);
+        assert!(superseded(&connect("Home"), &pending).is_none());
+        assert!(superseded(&Command::SetWifi(true), &pending).is_none());
+        assert!(superseded(&Command::Disconnect("Home".to_string()), &VecDeque::from([connect("Cafe")])).is_none());
+    }
+}
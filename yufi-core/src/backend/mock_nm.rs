@@ -0,0 +1,1256 @@
+use crate::backend::nm::{boottime_ms, NetworkManagerBackend};
+use crate::backend::secret_agent::{AGENT_INTERFACE, AGENT_PATH};
+use crate::backend::SCAN_COOLDOWN;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard};
+use zbus::blocking::Connection;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Str};
+use zbus::message::Header;
+use zbus::{interface, DBusError, ObjectServer, SignalContext};
+
+const DEVICE_PATH: &str = "/org/freedesktop/NetworkManager/Devices/1";
+pub const DEVICE_MAC: &str = "02:00:00:00:AA:01";
+/// The Wi‑Fi device's burned-in address; `DEVICE_MAC` is a randomized one.
+pub const PERMANENT_MAC: &str = "00:1B:21:00:AA:01";
+const ETHERNET_PATH: &str = "/org/freedesktop/NetworkManager/Devices/2";
+pub const ETHERNET_MAC: &str = "02:00:00:00:EE:01";
+pub const NM_VERSION: &str = "1.46.0";
+/// Seconds since boot reported as every access point's last sighting.
+pub const MOCK_LAST_SEEN: i32 = 4_200;
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const AGENT_MANAGER_PATH: &str = "/org/freedesktop/NetworkManager/AgentManager";
+const IP4_CONFIG_PATH: &str = "/org/freedesktop/NetworkManager/IP4Config/1";
+const DHCP4_CONFIG_PATH: &str = "/org/freedesktop/NetworkManager/DHCP4Config/1";
+pub const LEASED_ADDRESS: &str = "192.168.1.50";
+pub const GATEWAY: &str = "192.168.1.1";
+
+const STATE_ACTIVATED: u32 = 2;
+const STATE_DEACTIVATED: u32 = 4;
+
+type SettingsMap = HashMap<String, HashMap<String, OwnedValue>>;
+
+#[derive(Clone)]
+pub struct MockAccessPoint {
+    pub ssid: &'static str,
+    pub strength: u8,
+    pub psk: Option<&'static str>,
+    pub rsn_flags: u32,
+    pub frequency: u32,
+}
+
+impl MockAccessPoint {
+    pub fn open(ssid: &'static str, strength: u8) -> Self {
+        Self {
+            ssid,
+            strength,
+            psk: None,
+            rsn_flags: 0,
+            frequency: 5180,
+        }
+    }
+
+    pub fn secured(ssid: &'static str, strength: u8, psk: &'static str) -> Self {
+        Self {
+            ssid,
+            strength,
+            psk: Some(psk),
+            rsn_flags: 0x188,
+            frequency: 5180,
+        }
+    }
+
+    pub fn sae(ssid: &'static str, strength: u8, psk: &'static str) -> Self {
+        Self {
+            ssid,
+            strength,
+            psk: Some(psk),
+            rsn_flags: 0x488,
+            frequency: 5180,
+        }
+    }
+
+    pub fn owe(ssid: &'static str, strength: u8) -> Self {
+        Self {
+            ssid,
+            strength,
+            psk: None,
+            rsn_flags: 0x888,
+            frequency: 5180,
+        }
+    }
+
+    pub fn on(mut self, frequency: u32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+}
+
+struct MockConnection {
+    path: String,
+    ssid: String,
+    settings: SettingsMap,
+    /// Goes up with every change, like `VersionId`.
+    version: u64,
+}
+
+struct MockActive {
+    path: String,
+    connection: String,
+    access_point: String,
+    state: u32,
+    /// VPNs come up next to the Wi‑Fi connection instead of on the device.
+    vpn: bool,
+}
+
+#[derive(Default)]
+struct MockState {
+    wireless_enabled: bool,
+    access_points: Vec<(String, MockAccessPoint)>,
+    connections: Vec<MockConnection>,
+    active: Vec<MockActive>,
+    scans: u32,
+    /// `CLOCK_BOOTTIME` milliseconds of the last scan.
+    last_scan_ms: Option<u64>,
+    denied: bool,
+    wifi_unplugged: bool,
+    /// Connectivity checks hit a sign-in page.
+    portal: bool,
+    /// Saving drops `address-data` and `dns-data`, like NetworkManager before them.
+    legacy_ipv4: bool,
+    /// Bus name of the registered secret agent.
+    agent: Option<String>,
+    /// Someone else edits the next profile whose `VersionId` is read.
+    edit_after_read: bool,
+    /// Checkpoint path and the saved profiles it can restore.
+    checkpoints: Vec<(String, Vec<(String, SettingsMap)>)>,
+    reapplied: u32,
+    reloads: u32,
+    next_id: u32,
+    /// Bytes received and sent, counted only while statistics are on.
+    traffic: (u64, u64),
+    statistics_refresh_ms: u32,
+}
+
+impl MockState {
+    fn next_id(&mut self) -> u32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn take_checkpoint(&mut self, path: &str) -> Result<Vec<(String, SettingsMap)>, MockError> {
+        let index = self
+            .checkpoints
+            .iter()
+            .position(|(checkpoint, _)| checkpoint == path)
+            .ok_or_else(|| MockError::UnknownCheckpoint(path.to_string()))?;
+        Ok(self.checkpoints.remove(index).1)
+    }
+
+    fn access_point(&self, path: &str) -> Option<&MockAccessPoint> {
+        self.access_points
+            .iter()
+            .find(|(ap_path, _)| ap_path == path)
+            .map(|(_, ap)| ap)
+    }
+
+    fn access_point_for_ssid(&self, ssid: &str) -> Option<(&str, &MockAccessPoint)> {
+        self.access_points
+            .iter()
+            .filter(|(_, ap)| ap.ssid == ssid)
+            .max_by_key(|(_, ap)| ap.strength)
+            .map(|(path, ap)| (path.as_str(), ap))
+    }
+
+    fn device_active(&self) -> Option<&MockActive> {
+        self.active.iter().rev().find(|active| !active.vpn)
+    }
+
+    fn activate(&mut self, connection: &str, specific: &str) -> Result<String, MockError> {
+        if self.connections.iter().any(|c| c.path == connection && is_vpn(&c.settings)) {
+            let path = format!("/org/freedesktop/NetworkManager/ActiveConnection/{}", self.next_id());
+            self.active.push(MockActive {
+                path: path.clone(),
+                connection: connection.to_string(),
+                access_point: "/".to_string(),
+                state: STATE_ACTIVATED,
+                vpn: true,
+            });
+            return Ok(path);
+        }
+        let profile = self
+            .connections
+            .iter()
+            .find(|c| c.path == connection)
+            .ok_or_else(|| MockError::UnknownConnection(connection.to_string()))?;
+        let (ap_path, ap) = match self.access_point(specific) {
+            Some(ap) => (specific, ap),
+            None => self
+                .access_point_for_ssid(&profile.ssid)
+                .ok_or_else(|| MockError::UnknownConnection(profile.ssid.clone()))?,
+        };
+        let stored_psk = profile
+            .settings
+            .get("802-11-wireless-security")
+            .and_then(|sec| sec.get("psk"))
+            .and_then(|value| String::try_from(value.try_clone().ok()?).ok());
+        let state = match (ap.psk, stored_psk.as_deref()) {
+            (None, _) => STATE_ACTIVATED,
+            (Some(_), None) => {
+                return Err(MockError::NoSecrets(
+                    "No agents were available for this request.".to_string(),
+                ))
+            }
+            (Some(expected), Some(given)) if expected == given => STATE_ACTIVATED,
+            (Some(_), Some(_)) => STATE_DEACTIVATED,
+        };
+        let ap_path = ap_path.to_string();
+
+        self.active.clear();
+        let path = format!("/org/freedesktop/NetworkManager/ActiveConnection/{}", self.next_id());
+        self.active.push(MockActive {
+            path: path.clone(),
+            connection: connection.to_string(),
+            access_point: ap_path,
+            state,
+            vpn: false,
+        });
+        Ok(path)
+    }
+}
+
+#[derive(DBusError, Debug)]
+#[zbus(prefix = "org.freedesktop.NetworkManager.AgentManager")]
+enum MockError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NoSecrets(String),
+    UnknownConnection(String),
+    UnknownCheckpoint(String),
+    VersionIdMismatch(String),
+}
+
+#[derive(DBusError, Debug)]
+#[zbus(prefix = "org.freedesktop.NetworkManager.Device")]
+enum MockDeviceError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NotAllowed(String),
+}
+
+type Shared = Arc<Mutex<MockState>>;
+
+/// Keys `GetSettings` leaves out and only `GetSecrets` hands over.
+const SECRETS: &[&str] = &["psk", "wep-key0", "password", "private-key-password"];
+
+fn is_secret(key: &str) -> bool {
+    SECRETS.contains(&key)
+}
+
+fn lock(state: &Shared) -> MutexGuard<'_, MockState> {
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn copy_settings(settings: &SettingsMap) -> SettingsMap {
+    settings
+        .iter()
+        .map(|(section, values)| {
+            let values = values
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.try_clone().ok()?)))
+                .collect();
+            (section.clone(), values)
+        })
+        .collect()
+}
+
+fn object_path(path: &str) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(path.to_string()).expect("valid object path")
+}
+
+fn is_vpn(settings: &SettingsMap) -> bool {
+    settings
+        .get("connection")
+        .and_then(|connection| connection.get("type"))
+        .and_then(|value| String::try_from(value.try_clone().ok()?).ok())
+        .is_some_and(|kind| kind == "vpn" || kind == "wireguard")
+}
+
+fn ssid_of(settings: &SettingsMap) -> Option<String> {
+    let value = settings.get("802-11-wireless")?.get("ssid")?;
+    let bytes: Vec<u8> = Vec::try_from(value.try_clone().ok()?).ok()?;
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+struct NetworkManager {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager")]
+impl NetworkManager {
+    #[zbus(property)]
+    fn wireless_enabled(&self) -> bool {
+        lock(&self.state).wireless_enabled
+    }
+
+    #[zbus(property)]
+    fn set_wireless_enabled(&mut self, enabled: bool) {
+        let mut state = lock(&self.state);
+        state.wireless_enabled = enabled;
+        if !enabled {
+            state.active.clear();
+        }
+    }
+
+    #[zbus(property)]
+    fn wireless_hardware_enabled(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> String {
+        NM_VERSION.to_string()
+    }
+
+    #[zbus(property)]
+    fn connectivity(&self) -> u32 {
+        let state = lock(&self.state);
+        match (state.active.is_empty(), state.portal) {
+            (true, _) => 1,
+            (false, true) => 2,
+            (false, false) => 4,
+        }
+    }
+
+    #[zbus(property)]
+    fn connectivity_check_enabled(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn active_connections(&self) -> Vec<OwnedObjectPath> {
+        lock(&self.state)
+            .active
+            .iter()
+            .map(|active| object_path(&active.path))
+            .collect()
+    }
+
+    fn get_devices(&self) -> Vec<OwnedObjectPath> {
+        if lock(&self.state).wifi_unplugged {
+            vec![object_path(ETHERNET_PATH)]
+        } else {
+            vec![object_path(DEVICE_PATH), object_path(ETHERNET_PATH)]
+        }
+    }
+
+    fn checkpoint_create(
+        &self,
+        _devices: Vec<OwnedObjectPath>,
+        _rollback_timeout: u32,
+        _flags: u32,
+    ) -> OwnedObjectPath {
+        let mut state = lock(&self.state);
+        let path = format!("/org/freedesktop/NetworkManager/Checkpoint/{}", state.next_id());
+        let snapshot = state
+            .connections
+            .iter()
+            .map(|connection| (connection.path.clone(), copy_settings(&connection.settings)))
+            .collect();
+        state.checkpoints.push((path.clone(), snapshot));
+        object_path(&path)
+    }
+
+    fn checkpoint_destroy(&self, checkpoint: OwnedObjectPath) -> Result<(), MockError> {
+        lock(&self.state).take_checkpoint(checkpoint.as_str()).map(|_| ())
+    }
+
+    fn checkpoint_rollback(
+        &self,
+        checkpoint: OwnedObjectPath,
+    ) -> Result<HashMap<OwnedObjectPath, u32>, MockError> {
+        let mut state = lock(&self.state);
+        let snapshot = state.take_checkpoint(checkpoint.as_str())?;
+        for (path, settings) in snapshot {
+            if let Some(connection) = state.connections.iter_mut().find(|c| c.path == path) {
+                connection.settings = settings;
+            }
+        }
+        Ok(HashMap::from([(object_path(DEVICE_PATH), 0)]))
+    }
+
+    fn reload(&self, _flags: u32) {
+        lock(&self.state).reloads += 1;
+    }
+
+    #[zbus(signal)]
+    async fn device_removed(context: &SignalContext<'_>, device: OwnedObjectPath) -> zbus::Result<()>;
+
+    async fn activate_connection(
+        &self,
+        connection: OwnedObjectPath,
+        _device: OwnedObjectPath,
+        specific_object: OwnedObjectPath,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<OwnedObjectPath, MockError> {
+        let path = lock(&self.state).activate(connection.as_str(), specific_object.as_str())?;
+        register_active(server, &self.state, &path).await?;
+        Ok(object_path(&path))
+    }
+
+    async fn add_and_activate_connection(
+        &self,
+        settings: SettingsMap,
+        _device: OwnedObjectPath,
+        specific_object: OwnedObjectPath,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<(OwnedObjectPath, OwnedObjectPath), MockError> {
+        let connection = add_connection(server, &self.state, settings).await?;
+        let path = lock(&self.state).activate(&connection, specific_object.as_str())?;
+        register_active(server, &self.state, &path).await?;
+        Ok((object_path(&connection), object_path(&path)))
+    }
+
+    fn deactivate_connection(&self, active: OwnedObjectPath) {
+        lock(&self.state)
+            .active
+            .retain(|current| current.path != active.as_str());
+    }
+}
+
+async fn add_connection(
+    server: &ObjectServer,
+    state: &Shared,
+    settings: SettingsMap,
+) -> Result<String, MockError> {
+    let ssid = ssid_of(&settings).unwrap_or_default();
+    let path = {
+        let mut state = lock(state);
+        let path = format!("{SETTINGS_PATH}/{}", state.next_id());
+        state.connections.push(MockConnection {
+            path: path.clone(),
+            ssid,
+            settings,
+            version: 1,
+        });
+        path
+    };
+    server
+        .at(
+            path.as_str(),
+            SettingsConnection {
+                state: state.clone(),
+                path: path.clone(),
+            },
+        )
+        .await?;
+    Ok(path)
+}
+
+async fn register_active(server: &ObjectServer, state: &Shared, path: &str) -> Result<(), MockError> {
+    server
+        .at(
+            path,
+            ActiveConnection {
+                state: state.clone(),
+                path: path.to_string(),
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+struct Device {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Device")]
+impl Device {
+    #[zbus(property)]
+    fn device_type(&self) -> u32 {
+        2
+    }
+
+    #[zbus(property)]
+    fn interface(&self) -> String {
+        "wlan0".to_string()
+    }
+
+    #[zbus(property)]
+    fn driver(&self) -> String {
+        "mac80211_hwsim".to_string()
+    }
+
+    #[zbus(property)]
+    fn driver_version(&self) -> String {
+        "6.8.0".to_string()
+    }
+
+    #[zbus(property)]
+    fn firmware_version(&self) -> String {
+        "N/A".to_string()
+    }
+
+    #[zbus(property)]
+    fn hw_address(&self) -> String {
+        DEVICE_MAC.to_string()
+    }
+
+    fn reapply(&self, _connection: SettingsMap, _version_id: u64, _flags: u32) {
+        lock(&self.state).reapplied += 1;
+    }
+
+    #[zbus(property)]
+    fn state(&self) -> u32 {
+        let state = lock(&self.state);
+        if state.device_active().is_some() { 100 } else { 30 }
+    }
+
+    #[zbus(property)]
+    fn active_connection(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(state.device_active().map_or("/", |active| active.path.as_str()))
+    }
+
+    #[zbus(property)]
+    fn ip4_config(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(state.device_active().map_or("/", |_| IP4_CONFIG_PATH))
+    }
+
+    #[zbus(property)]
+    fn dhcp4_config(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(state.device_active().map_or("/", |_| DHCP4_CONFIG_PATH))
+    }
+
+    /// `NM_METERED_YES` when the active profile is marked metered, otherwise
+    /// `NM_METERED_GUESS_NO`.
+    #[zbus(property)]
+    fn metered(&self) -> u32 {
+        let state = lock(&self.state);
+        let marked = state
+            .device_active()
+            .and_then(|active| state.connections.iter().find(|c| c.path == active.connection))
+            .and_then(|connection| connection.settings.get("connection")?.get("metered"))
+            .and_then(|value| i32::try_from(value).ok())
+            == Some(1);
+        if marked { 1 } else { 4 }
+    }
+}
+
+struct Statistics {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Device.Statistics")]
+impl Statistics {
+    #[zbus(property)]
+    fn refresh_rate_ms(&self) -> u32 {
+        lock(&self.state).statistics_refresh_ms
+    }
+
+    #[zbus(property)]
+    fn set_refresh_rate_ms(&mut self, refresh_rate_ms: u32) {
+        lock(&self.state).statistics_refresh_ms = refresh_rate_ms;
+    }
+
+    #[zbus(property)]
+    fn rx_bytes(&self) -> u64 {
+        lock(&self.state).traffic.0
+    }
+
+    #[zbus(property)]
+    fn tx_bytes(&self) -> u64 {
+        lock(&self.state).traffic.1
+    }
+}
+
+/// A wired port with no cable, so device listings see more than Wi‑Fi.
+struct EthernetDevice;
+
+#[interface(name = "org.freedesktop.NetworkManager.Device")]
+impl EthernetDevice {
+    #[zbus(property)]
+    fn device_type(&self) -> u32 {
+        1
+    }
+
+    #[zbus(property)]
+    fn interface(&self) -> String {
+        "eth0".to_string()
+    }
+
+    #[zbus(property)]
+    fn driver(&self) -> String {
+        "e1000e".to_string()
+    }
+
+    #[zbus(property)]
+    fn hw_address(&self) -> String {
+        ETHERNET_MAC.to_string()
+    }
+
+    #[zbus(property)]
+    fn state(&self) -> u32 {
+        20
+    }
+}
+
+struct Ip4Config;
+
+#[interface(name = "org.freedesktop.NetworkManager.IP4Config")]
+impl Ip4Config {
+    #[zbus(property)]
+    fn address_data(&self) -> Vec<HashMap<String, OwnedValue>> {
+        vec![HashMap::from([
+            ("address".to_string(), OwnedValue::from(Str::from(LEASED_ADDRESS))),
+            ("prefix".to_string(), OwnedValue::from(24u32)),
+        ])]
+    }
+
+    #[zbus(property)]
+    fn gateway(&self) -> String {
+        GATEWAY.to_string()
+    }
+
+    #[zbus(property)]
+    fn nameserver_data(&self) -> Vec<HashMap<String, OwnedValue>> {
+        vec![HashMap::from([(
+            "address".to_string(),
+            OwnedValue::from(Str::from(GATEWAY)),
+        )])]
+    }
+}
+
+struct Dhcp4Config;
+
+#[interface(name = "org.freedesktop.NetworkManager.DHCP4Config")]
+impl Dhcp4Config {
+    #[zbus(property)]
+    fn options(&self) -> HashMap<String, OwnedValue> {
+        [
+            ("dhcp_server_identifier", GATEWAY),
+            ("dhcp_lease_time", "86400"),
+            ("domain_name", "lan"),
+            ("ntp_servers", "192.168.1.1 192.168.1.2"),
+            ("ip_address", LEASED_ADDRESS),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), OwnedValue::from(Str::from(value))))
+        .collect()
+    }
+}
+
+struct Wireless {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Device.Wireless")]
+impl Wireless {
+    fn get_access_points(&self) -> Vec<OwnedObjectPath> {
+        lock(&self.state)
+            .access_points
+            .iter()
+            .map(|(path, _)| object_path(path))
+            .collect()
+    }
+
+    fn request_scan(&self, _options: HashMap<String, OwnedValue>) -> Result<(), MockDeviceError> {
+        let mut state = lock(&self.state);
+        if state.denied {
+            let denied = zbus::fdo::Error::AccessDenied("Not authorized to request a scan".to_string());
+            return Err(MockDeviceError::ZBus(denied.into()));
+        }
+        let now = boottime_ms().unwrap_or_default();
+        let cooldown = SCAN_COOLDOWN.as_millis() as u64;
+        if state.last_scan_ms.is_some_and(|last| now.saturating_sub(last) < cooldown) {
+            return Err(MockDeviceError::NotAllowed(
+                "Scanning not allowed immediately following previous scan".to_string(),
+            ));
+        }
+        state.scans += 1;
+        state.last_scan_ms = Some(now);
+        Ok(())
+    }
+
+    #[zbus(signal)]
+    async fn access_point_added(context: &SignalContext<'_>, access_point: OwnedObjectPath) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn last_scan(&self) -> i64 {
+        lock(&self.state)
+            .last_scan_ms
+            .and_then(|last| i64::try_from(last).ok())
+            .unwrap_or(-1)
+    }
+
+    #[zbus(property)]
+    fn hw_address(&self) -> String {
+        DEVICE_MAC.to_string()
+    }
+
+    #[zbus(property)]
+    fn perm_hw_address(&self) -> String {
+        PERMANENT_MAC.to_string()
+    }
+
+    #[zbus(property)]
+    fn bitrate(&self) -> u32 {
+        let state = lock(&self.state);
+        match state.device_active() {
+            Some(active) if active.state == STATE_ACTIVATED => 433_300,
+            _ => 0,
+        }
+    }
+
+    #[zbus(property)]
+    fn active_access_point(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(
+            state
+                .device_active()
+                .filter(|active| active.state == STATE_ACTIVATED)
+                .map_or("/", |active| active.access_point.as_str()),
+        )
+    }
+}
+
+struct AccessPoint {
+    ap: MockAccessPoint,
+    bssid: String,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.AccessPoint")]
+impl AccessPoint {
+    #[zbus(property)]
+    fn ssid(&self) -> Vec<u8> {
+        self.ap.ssid.as_bytes().to_vec()
+    }
+
+    #[zbus(property)]
+    fn strength(&self) -> u8 {
+        self.ap.strength
+    }
+
+    #[zbus(property)]
+    fn flags(&self) -> u32 {
+        u32::from(self.ap.rsn_flags != 0)
+    }
+
+    #[zbus(property)]
+    fn wpa_flags(&self) -> u32 {
+        0
+    }
+
+    #[zbus(property)]
+    fn rsn_flags(&self) -> u32 {
+        self.ap.rsn_flags
+    }
+
+    #[zbus(property)]
+    fn frequency(&self) -> u32 {
+        self.ap.frequency
+    }
+
+    #[zbus(property)]
+    fn hw_address(&self) -> String {
+        self.bssid.clone()
+    }
+
+    #[zbus(property)]
+    fn max_bitrate(&self) -> u32 {
+        866_700
+    }
+
+    #[zbus(property)]
+    fn last_seen(&self) -> i32 {
+        MOCK_LAST_SEEN
+    }
+}
+
+struct AgentManager {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.AgentManager")]
+impl AgentManager {
+    fn register(&self, _identifier: String, #[zbus(header)] header: Header<'_>) {
+        lock(&self.state).agent = header.sender().map(|sender| sender.to_string());
+    }
+
+    fn unregister(&self) {
+        lock(&self.state).agent = None;
+    }
+}
+
+struct Settings {
+    state: Shared,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Settings")]
+impl Settings {
+    fn list_connections(&self) -> Vec<OwnedObjectPath> {
+        lock(&self.state)
+            .connections
+            .iter()
+            .map(|connection| object_path(&connection.path))
+            .collect()
+    }
+
+    async fn add_connection(
+        &self,
+        settings: SettingsMap,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<OwnedObjectPath, MockError> {
+        let path = add_connection(server, &self.state, settings).await?;
+        Ok(object_path(&path))
+    }
+}
+
+struct SettingsConnection {
+    state: Shared,
+    path: String,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Settings.Connection")]
+impl SettingsConnection {
+    #[zbus(property)]
+    fn version_id(&self) -> u64 {
+        let mut state = lock(&self.state);
+        let edit_after_read = std::mem::take(&mut state.edit_after_read);
+        let Some(connection) = state.connections.iter_mut().find(|c| c.path == self.path) else {
+            return 0;
+        };
+        let version = connection.version;
+        if edit_after_read {
+            connection.version += 1;
+        }
+        version
+    }
+
+    fn get_settings(&self) -> Result<SettingsMap, MockError> {
+        let state = lock(&self.state);
+        let connection = state
+            .connections
+            .iter()
+            .find(|c| c.path == self.path)
+            .ok_or_else(|| MockError::UnknownConnection(self.path.clone()))?;
+        let mut settings = SettingsMap::new();
+        for (section, values) in &connection.settings {
+            let mut copy = HashMap::new();
+            for (key, value) in values {
+                // GetSettings never returns secrets.
+                if is_secret(key) {
+                    continue;
+                }
+                copy.insert(key.clone(), value.try_clone().map_err(zbus::Error::from)?);
+            }
+            settings.insert(section.clone(), copy);
+        }
+        Ok(settings)
+    }
+
+    fn get_secrets(&self, setting: String) -> Result<SettingsMap, MockError> {
+        let state = lock(&self.state);
+        let connection = state
+            .connections
+            .iter()
+            .find(|c| c.path == self.path)
+            .ok_or_else(|| MockError::UnknownConnection(self.path.clone()))?;
+        let mut secrets = SettingsMap::new();
+        let mut section = HashMap::new();
+        for (key, value) in connection.settings.get(&setting).into_iter().flatten() {
+            if is_secret(key) {
+                section.insert(key.clone(), value.try_clone().map_err(zbus::Error::from)?);
+            }
+        }
+        if !section.is_empty() {
+            secrets.insert(setting, section);
+        }
+        Ok(secrets)
+    }
+
+    /// Like NetworkManager, keeps the stored secrets when the update carries
+    /// none at all, since `GetSettings` never returns them, but drops every
+    /// one left out once it carries any. Refuses a `version-id` that is not
+    /// the current one.
+    fn update2(
+        &self,
+        mut settings: SettingsMap,
+        _flags: u32,
+        args: HashMap<String, OwnedValue>,
+    ) -> Result<HashMap<String, OwnedValue>, MockError> {
+        let mut state = lock(&self.state);
+        if let (true, Some(ipv4)) = (state.legacy_ipv4, settings.get_mut("ipv4")) {
+            ipv4.remove("address-data");
+            ipv4.remove("dns-data");
+        }
+        let connection = state
+            .connections
+            .iter_mut()
+            .find(|c| c.path == self.path)
+            .ok_or_else(|| MockError::UnknownConnection(self.path.clone()))?;
+        if !settings.values().flat_map(|values| values.keys()).any(|key| is_secret(key)) {
+            for (section, values) in &connection.settings {
+                let Some(update) = settings.get_mut(section) else {
+                    continue;
+                };
+                for (key, value) in values.iter().filter(|(key, _)| is_secret(key)) {
+                    update.insert(key.clone(), value.try_clone().map_err(zbus::Error::from)?);
+                }
+            }
+        }
+        let expected = args.get("version-id").and_then(|version| u64::try_from(version).ok());
+        if expected.is_some_and(|version| version != connection.version) {
+            return Err(MockError::VersionIdMismatch(self.path.clone()));
+        }
+        connection.settings = settings;
+        connection.version += 1;
+        Ok(HashMap::new())
+    }
+
+    async fn delete(&self, #[zbus(object_server)] server: &ObjectServer) -> Result<(), MockError> {
+        {
+            let mut state = lock(&self.state);
+            state.connections.retain(|c| c.path != self.path);
+            state.active.retain(|active| active.connection != self.path);
+        }
+        server
+            .remove::<SettingsConnection, _>(self.path.as_str())
+            .await?;
+        Ok(())
+    }
+}
+
+struct ActiveConnection {
+    state: Shared,
+    path: String,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.Connection.Active")]
+impl ActiveConnection {
+    #[zbus(property)]
+    fn state(&self) -> u32 {
+        lock(&self.state)
+            .active
+            .iter()
+            .find(|active| active.path == self.path)
+            .map_or(STATE_DEACTIVATED, |active| active.state)
+    }
+
+    #[zbus(property)]
+    fn connection(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(
+            state
+                .active
+                .iter()
+                .find(|active| active.path == self.path)
+                .map_or("/", |active| active.connection.as_str()),
+        )
+    }
+
+    #[zbus(property)]
+    fn specific_object(&self) -> OwnedObjectPath {
+        let state = lock(&self.state);
+        object_path(
+            state
+                .active
+                .iter()
+                .find(|active| active.path == self.path)
+                .map_or("/", |active| active.access_point.as_str()),
+        )
+    }
+}
+
+pub struct MockNetworkManager {
+    daemon: Child,
+    address: String,
+    state: Shared,
+    service: Connection,
+}
+
+impl MockNetworkManager {
+    /// Starts a private dbus-daemon with a scripted NetworkManager on it.
+    /// Returns `None` when `dbus-daemon` is not installed so tests can skip.
+    pub fn start(access_points: Vec<MockAccessPoint>) -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        let stdout = daemon.stdout.take()?;
+        BufReader::new(stdout).read_line(&mut address).ok()?;
+        let address = address.trim().to_string();
+
+        let mut state = MockState {
+            wireless_enabled: true,
+            ..MockState::default()
+        };
+        for ap in access_points {
+            let path = format!("/org/freedesktop/NetworkManager/AccessPoint/{}", state.next_id());
+            state.access_points.push((path, ap));
+        }
+        let access_points = state.access_points.clone();
+        let state = Arc::new(Mutex::new(state));
+
+        let mut builder = zbus::blocking::connection::Builder::address(address.as_str())
+            .ok()?
+            .name("org.freedesktop.NetworkManager")
+            .ok()?
+            .serve_at(
+                "/org/freedesktop/NetworkManager",
+                NetworkManager {
+                    state: state.clone(),
+                },
+            )
+            .ok()?
+            .serve_at(DEVICE_PATH, Device { state: state.clone() })
+            .ok()?
+            .serve_at(DEVICE_PATH, Wireless { state: state.clone() })
+            .ok()?
+            .serve_at(DEVICE_PATH, Statistics { state: state.clone() })
+            .ok()?
+            .serve_at(SETTINGS_PATH, Settings { state: state.clone() })
+            .ok()?
+            .serve_at(AGENT_MANAGER_PATH, AgentManager { state: state.clone() })
+            .ok()?
+            .serve_at(IP4_CONFIG_PATH, Ip4Config)
+            .ok()?
+            .serve_at(DHCP4_CONFIG_PATH, Dhcp4Config)
+            .ok()?
+            .serve_at(ETHERNET_PATH, EthernetDevice)
+            .ok()?;
+        for (index, (path, ap)) in access_points.into_iter().enumerate() {
+            let bssid = format!("02:00:00:00:00:{:02X}", index + 1);
+            builder = builder.serve_at(path, AccessPoint { ap, bssid }).ok()?;
+        }
+        let service = builder.build().ok()?;
+
+        Some(Self {
+            daemon,
+            address,
+            state,
+            service,
+        })
+    }
+
+    pub fn bus_address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn backend(&self) -> NetworkManagerBackend {
+        NetworkManagerBackend::with_bus_address(&self.address)
+    }
+
+    pub fn add_saved(&self, ssid: &str, psk: Option<&str>) {
+        let mut settings = SettingsMap::new();
+        let mut connection = HashMap::new();
+        connection.insert("id".to_string(), OwnedValue::from(Str::from(ssid)));
+        connection.insert("autoconnect".to_string(), OwnedValue::from(true));
+        settings.insert("connection".to_string(), connection);
+        let mut wireless = HashMap::new();
+        wireless.insert(
+            "ssid".to_string(),
+            OwnedValue::try_from(zbus::zvariant::Array::from(ssid.as_bytes().to_vec()))
+                .expect("ssid value"),
+        );
+        settings.insert("802-11-wireless".to_string(), wireless);
+        if let Some(psk) = psk {
+            let mut security = HashMap::new();
+            security.insert("key-mgmt".to_string(), OwnedValue::from(Str::from("wpa-psk")));
+            security.insert("psk".to_string(), OwnedValue::from(Str::from(psk)));
+            settings.insert("802-11-wireless-security".to_string(), security);
+        }
+        let server = self.service.object_server();
+        zbus::block_on(add_connection(server.inner(), &self.state, settings))
+            .expect("add saved connection");
+    }
+
+    /// Adds an OpenVPN profile, which is not tied to any device.
+    pub fn add_vpn(&self, id: &str, uuid: &str) {
+        let mut settings = SettingsMap::new();
+        let mut connection = HashMap::new();
+        connection.insert("id".to_string(), OwnedValue::from(Str::from(id)));
+        connection.insert("uuid".to_string(), OwnedValue::from(Str::from(uuid)));
+        connection.insert("type".to_string(), OwnedValue::from(Str::from("vpn")));
+        settings.insert("connection".to_string(), connection);
+        let mut vpn = HashMap::new();
+        vpn.insert(
+            "service-type".to_string(),
+            OwnedValue::from(Str::from("org.freedesktop.NetworkManager.openvpn")),
+        );
+        settings.insert("vpn".to_string(), vpn);
+        let server = self.service.object_server();
+        zbus::block_on(add_connection(server.inner(), &self.state, settings)).expect("add VPN connection");
+    }
+
+    /// Simulates the connectivity check landing on a captive portal, or
+    /// getting through once signed in.
+    pub fn set_portal(&self, portal: bool) {
+        lock(&self.state).portal = portal;
+        let nm = self
+            .service
+            .object_server()
+            .interface::<_, NetworkManager>("/org/freedesktop/NetworkManager")
+            .expect("NetworkManager interface");
+        zbus::block_on(nm.get().connectivity_changed(nm.signal_context()))
+            .expect("emit Connectivity");
+    }
+
+    /// Simulates pulling out the USB Wi‑Fi adapter.
+    pub fn unplug_wifi(&self) {
+        lock(&self.state).wifi_unplugged = true;
+        let nm = self
+            .service
+            .object_server()
+            .interface::<_, NetworkManager>("/org/freedesktop/NetworkManager")
+            .expect("NetworkManager interface");
+        zbus::block_on(NetworkManager::device_removed(
+            nm.signal_context(),
+            object_path(DEVICE_PATH),
+        ))
+        .expect("emit DeviceRemoved");
+    }
+
+    /// Behaves like NetworkManager before `address-data` and `dns-data`, which
+    /// only keeps the packed `addresses` and `dns`.
+    pub fn emulate_legacy_ipv4(&self) {
+        lock(&self.state).legacy_ipv4 = true;
+    }
+
+    /// Changes the next profile read for an edit before it is written back.
+    pub fn edit_after_read(&self) {
+        lock(&self.state).edit_after_read = true;
+    }
+
+    /// Simulates a scan turning up `ap`.
+    pub fn add_access_point(&self, ap: MockAccessPoint) {
+        let (path, bssid) = {
+            let mut state = lock(&self.state);
+            let path = format!("/org/freedesktop/NetworkManager/AccessPoint/{}", state.next_id());
+            state.access_points.push((path.clone(), ap.clone()));
+            (path, format!("02:00:00:00:00:{:02X}", state.access_points.len()))
+        };
+        let server = self.service.object_server();
+        server.at(path.as_str(), AccessPoint { ap, bssid }).expect("serve access point");
+        let wireless = server
+            .interface::<_, Wireless>(DEVICE_PATH)
+            .expect("Wireless interface");
+        zbus::block_on(Wireless::access_point_added(wireless.signal_context(), object_path(&path)))
+            .expect("emit AccessPointAdded");
+    }
+
+    /// Asks the registered secret agent for the 802.1X secrets of the profile
+    /// of `ssid`, as NetworkManager does once an activation needs them. Blocks
+    /// until the agent answers.
+    pub fn request_secrets(&self, ssid: &str, flags: u32) -> zbus::Result<SettingsMap> {
+        let (agent, path, settings) = {
+            let state = lock(&self.state);
+            let agent = state.agent.clone().ok_or_else(|| zbus::Error::Failure("No agents".to_string()))?;
+            let connection = state
+                .connections
+                .iter()
+                .find(|c| c.ssid == ssid)
+                .ok_or_else(|| zbus::Error::Failure(format!("No profile for {ssid}")))?;
+            (agent, object_path(&connection.path), copy_settings(&connection.settings))
+        };
+        let reply = self.service.call_method(
+            Some(agent.as_str()),
+            AGENT_PATH,
+            Some(AGENT_INTERFACE),
+            "GetSecrets",
+            &(settings, path, "802-1x", Vec::<String>::new(), flags),
+        )?;
+        reply.body().deserialize()
+    }
+
+    /// Tells the secret agent the secrets of `ssid` are no longer needed.
+    pub fn cancel_secrets(&self, ssid: &str) {
+        let path = lock(&self.state).connections.iter().find(|c| c.ssid == ssid).map(|c| object_path(&c.path));
+        if let (Some(agent), Some(path)) = (lock(&self.state).agent.clone(), path) {
+            let _ = self.service.call_method(
+                Some(agent.as_str()),
+                AGENT_PATH,
+                Some(AGENT_INTERFACE),
+                "CancelGetSecrets",
+                &(path, "802-1x"),
+            );
+        }
+    }
+
+    pub fn deny_access(&self) {
+        lock(&self.state).denied = true;
+    }
+
+    pub fn scan_count(&self) -> u32 {
+        lock(&self.state).scans
+    }
+
+    pub fn saved_ssids(&self) -> Vec<String> {
+        lock(&self.state)
+            .connections
+            .iter()
+            .map(|connection| connection.ssid.clone())
+            .collect()
+    }
+
+    pub fn set_priority(&self, ssid: &str, priority: i32) {
+        let mut state = lock(&self.state);
+        if let Some(connection) = state.connections.iter_mut().find(|c| c.ssid == ssid) {
+            connection
+                .settings
+                .entry("connection".to_string())
+                .or_default()
+                .insert("autoconnect-priority".to_string(), OwnedValue::from(priority));
+            connection.version += 1;
+        }
+    }
+
+    pub fn saved_settings(&self, ssid: &str) -> Option<SettingsMap> {
+        let state = lock(&self.state);
+        let connection = state.connections.iter().find(|c| c.ssid == ssid)?;
+        Some(copy_settings(&connection.settings))
+    }
+
+    /// Moves bytes over the device. Like NetworkManager, the counters only
+    /// advance once a client turned statistics on.
+    pub fn add_traffic(&self, rx_bytes: u64, tx_bytes: u64) {
+        let mut state = lock(&self.state);
+        if state.statistics_refresh_ms > 0 {
+            state.traffic.0 += rx_bytes;
+            state.traffic.1 += tx_bytes;
+        }
+    }
+
+    pub fn checkpoint_count(&self) -> usize {
+        lock(&self.state).checkpoints.len()
+    }
+
+    pub fn reapply_count(&self) -> u32 {
+        lock(&self.state).reapplied
+    }
+
+    pub fn reload_count(&self) -> u32 {
+        lock(&self.state).reloads
+    }
+
+    pub fn active_state(&self, path: &str) -> Option<u32> {
+        lock(&self.state)
+            .active
+            .iter()
+            .find(|active| active.path == path)
+            .map(|active| active.state)
+    }
+}
+
+impl Drop for MockNetworkManager {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
@@ -0,0 +1,187 @@
+pub mod nm;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock_nm;
+pub mod secret_agent;
+#[cfg(test)]
+mod tests;
+
+use crate::models::{
+    AccessPointInfo, AppState, BandLock, DeviceInfo, DhcpLeases, EnterpriseCertificates, ImportedProfile, IpConfig, LinkStatus, Network, NetworkDetails,
+    NmDiagnostics,
+    RawSettings, RestoredProfiles, SavedProfile, SettingValue, TrafficCounters, VisibleAccessPoint, VpnConnection,
+};
+use secret_agent::SecretRequests;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum BackendError {
+    Unavailable(String),
+    PermissionDenied(String),
+    /// NetworkManager is running but has no Wi‑Fi device, e.g. after the only
+    /// USB adapter was unplugged.
+    NoWifiDevice,
+    /// The device refused the request in its current state
+    /// (`Device.NotAllowed`), typically a scan asked for less than
+    /// `SCAN_COOLDOWN` after the previous one.
+    NotAllowed(String),
+    /// A request dropped unrun because a newer one made it pointless, e.g.
+    /// connecting to one network and then another before the first started.
+    Superseded,
+}
+
+/// How long NetworkManager refuses new scan requests after a scan.
+pub const SCAN_COOLDOWN: Duration = Duration::from_secs(10);
+
+pub type BackendResult<T> = Result<T, BackendError>;
+
+#[derive(Clone, Debug)]
+pub enum BackendEvent {
+    WifiEnabledChanged(bool),
+    NetworkStateChanged,
+    ActiveConnectionsChanged,
+    /// NetworkManager's `Connectivity`, e.g. `NM_CONNECTIVITY_PORTAL` once a
+    /// sign-in page is found.
+    ConnectivityChanged(u32),
+    WirelessDeviceChanged,
+    DeviceStateChanged {
+        state: u32,
+        reason: u32,
+    },
+    DeviceAdded,
+    DeviceRemoved,
+    /// A scan turned up a new radio, as a network of one access point; None
+    /// if it could not be read and the list needs a full refresh.
+    AccessPointAdded(Option<Network>),
+    AccessPointRemoved,
+    ActiveConnectionStateChanged {
+        path: String,
+        state: u32,
+        reason: u32,
+    },
+}
+
+pub type EventStream = mpsc::Receiver<BackendEvent>;
+
+pub trait Backend {
+    fn load_state(&self) -> BackendResult<AppState>;
+    /// Whether the Wi‑Fi radio is on, without reading the networks.
+    fn wifi_enabled(&self) -> BackendResult<bool>;
+    fn set_wifi_enabled(&self, enabled: bool) -> BackendResult<()>;
+    fn request_scan(&self) -> BackendResult<()>;
+    /// How long ago the Wi‑Fi device last scanned; `None` if it never has.
+    fn last_scan(&self) -> BackendResult<Option<Duration>>;
+    /// Activates the saved profile for `ssid`, or saves a new one. With `band`,
+    /// a radio on that band is picked for this activation only; the profile
+    /// is left free to use either band.
+    fn connect_network(
+        &self,
+        ssid: &str,
+        password: Option<&str>,
+        autoconnect: bool,
+        band: Option<BandLock>,
+    ) -> BackendResult<Option<String>>;
+    fn disconnect_network(&self, ssid: &str) -> BackendResult<()>;
+    fn renew_lease(&self, ssid: &str) -> BackendResult<String>;
+    fn connect_hidden(
+        &self,
+        ssid: &str,
+        security: &str,
+        password: Option<&str>,
+        autoconnect: bool,
+    ) -> BackendResult<Option<String>>;
+    fn get_network_details(&self, ssid: &str) -> BackendResult<NetworkDetails>;
+    fn get_access_point_info(&self, ssid: &str) -> BackendResult<AccessPointInfo>;
+    fn list_access_points(&self) -> BackendResult<Vec<VisibleAccessPoint>>;
+    fn set_ip_dns(
+        &self,
+        ssid: &str,
+        ip: Option<&str>,
+        prefix: Option<u32>,
+        gateway: Option<&str>,
+        dns: Option<Vec<String>>,
+    ) -> BackendResult<()>;
+    fn get_saved_password(&self, ssid: &str) -> BackendResult<Option<String>>;
+    /// The profile's full settings map, for inspecting what NetworkManager stored.
+    fn raw_settings(&self, ssid: &str) -> BackendResult<RawSettings>;
+    /// Sets one key of the profile, adding it or its section if missing, for
+    /// settings YuFi has no dedicated controls for.
+    fn set_raw_setting(&self, ssid: &str, section: &str, key: &str, value: &SettingValue) -> BackendResult<()>;
+    fn set_autoreconnect(&self, ssid: &str, enabled: bool) -> BackendResult<()>;
+    /// Sets the certificates of an 802.1X profile. Entries still as
+    /// `get_network_details` read them are kept as stored and None removes one;
+    /// `key_password` replaces the private key's password when given.
+    fn set_certificates(
+        &self,
+        ssid: &str,
+        certificates: &EnterpriseCertificates,
+        key_password: Option<&str>,
+    ) -> BackendResult<()>;
+    /// Removes `connection.interface-name`, so the profile works on any Wi‑Fi device.
+    fn clear_interface_binding(&self, ssid: &str) -> BackendResult<()>;
+    /// With `user`, keeps the profile to that account on the current Wi‑Fi
+    /// device (`connection.permissions` and `interface-name`), so it neither
+    /// connects for other accounts nor through other adapters. None opens it
+    /// up to everyone again.
+    fn set_personal(&self, ssid: &str, user: Option<&str>) -> BackendResult<()>;
+    /// Marks the profile as metered, or hands the decision back to NetworkManager.
+    fn set_metered(&self, ssid: &str, metered: bool) -> BackendResult<()>;
+    /// Sets `dns-priority` for both IPv4 and IPv6. Lower values win; a negative
+    /// value excludes the servers of every connection with a higher one.
+    fn set_dns_priority(&self, ssid: &str, priority: i32) -> BackendResult<()>;
+    /// Has the profile pick its own 169.254.x.x address instead of asking a
+    /// DHCP server, for plugging straight into a printer or camera, or hands
+    /// IPv4 back to DHCP.
+    fn set_link_local(&self, ssid: &str, link_local: bool) -> BackendResult<()>;
+    /// Points the profile at the proxy auto-config script at `pac_url`, or
+    /// turns its proxy off and drops the script when it is empty.
+    fn set_proxy(&self, ssid: &str, pac_url: &str) -> BackendResult<()>;
+    fn forget_network(&self, ssid: &str) -> BackendResult<()>;
+    fn saved_profiles(&self) -> BackendResult<Vec<SavedProfile>>;
+    /// VPN and WireGuard profiles, sorted by name.
+    fn vpn_connections(&self) -> BackendResult<Vec<VpnConnection>>;
+    /// Brings up the VPN profile with `uuid` and returns its name, or None
+    /// when it is already up.
+    fn activate_vpn(&self, uuid: &str) -> BackendResult<Option<String>>;
+    fn delete_profile(&self, path: &str) -> BackendResult<()>;
+    /// Sets `connection.autoconnect-priority` of the profile at `path`. Higher
+    /// values are tried first when several saved networks are in range.
+    fn set_autoconnect_priority(&self, path: &str, priority: i32) -> BackendResult<()>;
+    /// Saves `profile` without connecting to it and returns its settings path.
+    fn add_profile(&self, profile: &ImportedProfile) -> BackendResult<String>;
+    /// Saves a copy of the profile at `path`, secrets included, as `id` with a
+    /// new UUID and returns its settings path. The copy does not autoconnect,
+    /// so the original stays the one NetworkManager picks.
+    fn duplicate_profile(&self, path: &str, id: &str) -> BackendResult<String>;
+    /// Every Wi‑Fi profile with whatever secrets NetworkManager hands out,
+    /// D-Bus encoded (`aa{sa{sv}}`) for `restore_profiles`. Not keyfiles: the
+    /// settings go back through `AddConnection` exactly as read, with no
+    /// keyfile writer in between to drop keys it doesn't know, and restoring
+    /// needs no access to `/etc/NetworkManager`.
+    fn backup_profiles(&self) -> BackendResult<Vec<u8>>;
+    /// Adds the profiles of a `backup_profiles` blob that are not saved yet.
+    fn restore_profiles(&self, backup: &[u8]) -> BackendResult<RestoredProfiles>;
+    /// Byte counters of the Wi‑Fi device. NetworkManager only counts once a
+    /// client asks for statistics, so the first call turns them on and may read zero.
+    fn traffic_counters(&self) -> BackendResult<TrafficCounters>;
+    fn link_status(&self) -> BackendResult<LinkStatus>;
+    fn active_connection_state(&self, path: &str) -> BackendResult<u32>;
+    fn diagnostics(&self) -> BackendResult<NmDiagnostics>;
+    fn ip_config(&self) -> BackendResult<Option<IpConfig>>;
+    fn dhcp_leases(&self) -> BackendResult<DhcpLeases>;
+    fn list_devices(&self) -> BackendResult<Vec<DeviceInfo>>;
+    /// Snapshots the Wi‑Fi device and its profile. NetworkManager restores the
+    /// snapshot by itself after `rollback_timeout_secs` unless it is destroyed first.
+    fn checkpoint_create(&self, rollback_timeout_secs: u32) -> BackendResult<String>;
+    fn checkpoint_destroy(&self, checkpoint: &str) -> BackendResult<()>;
+    fn checkpoint_rollback(&self, checkpoint: &str) -> BackendResult<()>;
+    /// Applies the saved profile to the running Wi‑Fi connection without reconnecting.
+    fn reapply_connection(&self) -> BackendResult<()>;
+    /// Makes NetworkManager re-read its configuration files.
+    fn reload_config(&self) -> BackendResult<()>;
+    fn subscribe(&self) -> BackendResult<EventStream>;
+    /// Registers as a secret agent, so NetworkManager asks for the sign-in of
+    /// an 802.1X network it lacks one for instead of timing out. The requests
+    /// come in for as long as the backend lives.
+    fn register_secret_agent(&self) -> BackendResult<SecretRequests>;
+}
@@ -0,0 +1,2445 @@
+use crate::backend::secret_agent::{self, SecretRequests};
+use crate::backend::{
+    Backend, BackendError, BackendEvent, BackendResult, EventStream,
+};
+use crate::models::{
+    AccessPoint, AccessPointInfo, AppState, BandLock, DeviceInfo, DeviceKind, DhcpLease, DhcpLeases, EMBEDDED_CERTIFICATE,
+    EnterpriseCertificates, ImportedProfile, IpConfig, LinkStatus, Network, NetworkAction,
+    NetworkDetails, NmDiagnostics, ProfileSecurity, RawSettings, RestoredProfiles, RawValue, SavedProfile, SettingKind, SettingValue, TrafficCounters,
+    VisibleAccessPoint, VpnConnection, WifiDeviceInfo, sort_networks,
+};
+use crate::profile;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use zbus::blocking::{Connection, MessageIterator, Proxy};
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{self, Array, ObjectPath, OwnedObjectPath, OwnedValue, Str};
+use zbus::{MatchRule, Message};
+
+/// Bus that `NetworkManagerBackend::new()` talks to instead of the system bus,
+/// set once by `use_bus_address`.
+#[cfg(any(test, feature = "mock"))]
+static DEFAULT_BUS_ADDRESS: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Points every backend created from now on at the bus at `address`, such as
+/// the one `MockNetworkManager` serves. Only the first call has an effect.
+#[cfg(any(test, feature = "mock"))]
+pub fn use_bus_address(address: &str) {
+    let _ = DEFAULT_BUS_ADDRESS.set(address.to_string());
+}
+
+#[cfg(any(test, feature = "mock"))]
+fn default_bus_address() -> Option<String> {
+    DEFAULT_BUS_ADDRESS.get().cloned()
+}
+
+#[cfg(not(any(test, feature = "mock")))]
+fn default_bus_address() -> Option<String> {
+    None
+}
+
+pub struct NetworkManagerBackend {
+    bus_address: Option<String>,
+    connection: RefCell<Option<Connection>>,
+}
+
+impl Default for NetworkManagerBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkManagerBackend {
+    pub fn new() -> Self {
+        Self {
+            bus_address: default_bus_address(),
+            connection: RefCell::new(None),
+        }
+    }
+
+    #[cfg(any(test, feature = "mock"))]
+    pub fn with_bus_address(address: &str) -> Self {
+        Self {
+            bus_address: Some(address.to_string()),
+            connection: RefCell::new(None),
+        }
+    }
+
+    fn bus(&self) -> BackendResult<Connection> {
+        if let Some(conn) = self.connection.borrow().as_ref() {
+            return Ok(conn.clone());
+        }
+        let conn = match &self.bus_address {
+            Some(address) => zbus::blocking::connection::Builder::address(address.as_str())
+                .and_then(|builder| builder.build())
+                .map_err(dbus_error)?,
+            None => system_bus()?,
+        };
+        *self.connection.borrow_mut() = Some(conn.clone());
+        Ok(conn)
+    }
+}
+
+impl Backend for NetworkManagerBackend {
+    fn load_state(&self) -> BackendResult<AppState> {
+        let _span = profile::span("load_state");
+        let conn = profile::measure("load_state: connect to the bus", || self.bus())?;
+        let nm = nm_proxy(&conn)?;
+
+        let wifi_enabled: bool = profile::measure("load_state: WirelessEnabled", || {
+            nm.get_property("WirelessEnabled")
+        })
+        .map_err(dbus_error)?;
+
+        let wifi_device = profile::measure("load_state: find the Wi-Fi device", || first_wifi_device(&conn, &nm))?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+        let saved = profile::measure("load_state: saved profiles", || match nm_settings_proxy(&conn) {
+            Ok(settings) => saved_wifi_profiles(&conn, &settings).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        });
+
+        let active_ap: OwnedObjectPath = profile::measure("load_state: ActiveAccessPoint", || {
+            wireless.get_property("ActiveAccessPoint")
+        })
+        .map_err(dbus_error)?;
+        let (active_specific_ap, active_ok) = profile::measure("load_state: active connection", || {
+            active_connection_info_for_device(&conn, &wifi_device)
+        })?;
+
+        let ap_paths: Vec<OwnedObjectPath> = profile::measure("load_state: GetAccessPoints", || {
+            wireless.call("GetAccessPoints", &())
+        })
+        .map_err(dbus_error)?;
+
+        let mut best_by_ssid: HashMap<String, (u8, bool, &'static str, &'static str)> = HashMap::new();
+        let mut radios_by_ssid: HashMap<String, Vec<AccessPoint>> = HashMap::new();
+
+        let fetched = profile::measure("load_state: access points", || fetch_access_points(&conn, &ap_paths))?;
+        for (ap_path, fetched) in ap_paths.iter().zip(fetched) {
+            let Some((ssid, access_point)) = fetched else {
+                continue;
+            };
+            let strength = access_point.strength;
+            let security =
+                security_keyword(access_point.flags, access_point.wpa_flags, access_point.rsn_flags);
+            radios_by_ssid.entry(ssid.clone()).or_default().push(access_point);
+
+            let is_active = if active_ok {
+                if let Some(active_ap) = active_specific_ap.as_ref() {
+                    ap_path == active_ap
+                } else if active_ap.as_str() != "/" {
+                    *ap_path == active_ap
+                } else {
+                    false
+                }
+            } else {
+                false
+            };
+            let icon = icon_for_strength(strength);
+
+            match best_by_ssid.get(&ssid) {
+                Some((best_strength, best_active, _best_icon, _best_security)) => {
+                    if (is_active && !best_active) || strength > *best_strength {
+                        best_by_ssid.insert(ssid, (strength, is_active, icon, security));
+                    }
+                }
+                None => {
+                    best_by_ssid.insert(ssid, (strength, is_active, icon, security));
+                }
+            }
+        }
+
+        let mut networks: Vec<Network> = best_by_ssid
+            .into_iter()
+            .map(|(ssid, (strength, is_active, icon, security))| {
+                let is_saved = saved.contains_key(&ssid);
+                let mut access_points = radios_by_ssid.remove(&ssid).unwrap_or_default();
+                access_points.sort_by_key(|ap| std::cmp::Reverse(ap.strength));
+                Network {
+                    ssid,
+                    signal_icon: icon,
+                    action: if !wifi_enabled {
+                    NetworkAction::None
+                } else if is_active {
+                    NetworkAction::Disconnect
+                } else {
+                    NetworkAction::Connect
+                    },
+                    strength,
+                    is_active,
+                    is_saved,
+                    is_secure: security != "open",
+                    security,
+                    access_points,
+            }})
+            .collect();
+
+        sort_networks(&mut networks);
+
+        let mut out_of_range: Vec<Network> = saved
+            .into_iter()
+            .filter(|(ssid, _)| wifi_enabled && !networks.iter().any(|network| network.ssid == *ssid))
+            .map(|(ssid, security)| Network {
+                ssid,
+                signal_icon: "network-wireless-offline-symbolic",
+                action: NetworkAction::None,
+                strength: 0,
+                is_active: false,
+                is_saved: true,
+                is_secure: security != "open",
+                security,
+                access_points: Vec::new(),
+            })
+            .collect();
+        out_of_range.sort_by(|a, b| a.ssid.cmp(&b.ssid));
+
+        Ok(AppState {
+            wifi_enabled,
+            networks,
+            out_of_range,
+        })
+    }
+
+    fn wifi_enabled(&self) -> BackendResult<bool> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        nm.get_property("WirelessEnabled").map_err(dbus_error)
+    }
+
+    fn set_wifi_enabled(&self, _enabled: bool) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        nm.set_property("WirelessEnabled", &_enabled)
+            .map_err(dbus_error)
+    }
+
+    fn request_scan(&self) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+        let options: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wireless
+            .call("RequestScan", &(options))
+            .map_err(dbus_error)
+    }
+
+    fn last_scan(&self) -> BackendResult<Option<Duration>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+        let last_scan: i64 = wireless.get_property("LastScan").map_err(dbus_error)?;
+        let Ok(last_scan) = u64::try_from(last_scan) else {
+            return Ok(None);
+        };
+        let now = boottime_ms().ok_or_else(|| BackendError::Unavailable("Cannot read the system uptime".to_string()))?;
+        Ok(Some(Duration::from_millis(now.saturating_sub(last_scan))))
+    }
+
+    fn connect_network(
+        &self,
+        _ssid: &str,
+        _password: Option<&str>,
+        autoconnect: bool,
+        band: Option<BandLock>,
+    ) -> BackendResult<Option<String>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+
+        // The access point is the activation's specific object, so `band` holds
+        // for this connection only and the profile stays free to roam.
+        let (ap_path, _ap_strength) = find_ap_for_ssid(&conn, &wireless, _ssid, band)?;
+        let key_mgmt = key_mgmt_for_ap(&conn, &ap_path)?;
+
+        let settings = nm_settings_proxy(&conn)?;
+        if let Some(connection_path) = find_connection_for_ssid(&conn, &settings, _ssid)? {
+            let active_path: OwnedObjectPath = nm
+                .call(
+                    "ActivateConnection",
+                    &(connection_path, wifi_device.clone(), ap_path),
+                )
+                .map_err(dbus_error)?;
+            return Ok(Some(active_path.as_str().to_string()));
+        }
+
+        let mut connection: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
+        let mut con_section = HashMap::new();
+        con_section.insert("type".to_string(), ov_str("802-11-wireless"));
+        con_section.insert("id".to_string(), ov_str(_ssid));
+        con_section.insert("autoconnect".to_string(), OwnedValue::from(autoconnect));
+        connection.insert("connection".to_string(), con_section);
+
+        let mut wifi_section = HashMap::new();
+        wifi_section.insert("ssid".to_string(), ov_bytes(_ssid.as_bytes().to_vec())?);
+        wifi_section.insert("mode".to_string(), ov_str("infrastructure"));
+        connection.insert("802-11-wireless".to_string(), wifi_section);
+
+        match (key_mgmt, _password) {
+            (Some("owe"), _) => {
+                let mut sec_section = HashMap::new();
+                sec_section.insert("key-mgmt".to_string(), ov_str("owe"));
+                connection.insert("802-11-wireless-security".to_string(), sec_section);
+            }
+            (key_mgmt, Some(password)) => {
+                let mut sec_section = HashMap::new();
+                sec_section.insert("key-mgmt".to_string(), ov_str(key_mgmt.unwrap_or("wpa-psk")));
+                sec_section.insert("psk".to_string(), ov_str(password));
+                connection.insert("802-11-wireless-security".to_string(), sec_section);
+            }
+            (_, None) => {}
+        }
+
+        let (_, active_path): (OwnedObjectPath, OwnedObjectPath) = nm
+            .call(
+                "AddAndActivateConnection",
+                &(connection, wifi_device.clone(), ap_path),
+            )
+            .map_err(dbus_error)?;
+
+        Ok(Some(active_path.as_str().to_string()))
+    }
+
+    fn disconnect_network(&self, ssid: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let active_path = find_active_connection_for_ssid(&conn, &nm, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("No active connection".to_string()))?;
+        let _: () = nm
+            .call("DeactivateConnection", &(active_path))
+            .map_err(dbus_error)?;
+        Ok(())
+    }
+
+    fn renew_lease(&self, ssid: &str) -> BackendResult<String> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let active_path = find_active_connection_for_ssid(&conn, &nm, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("No active connection".to_string()))?;
+        let active = Proxy::new(
+            &conn,
+            nm_consts::BUS_NAME,
+            active_path.as_str(),
+            nm_consts::ACTIVE_CONNECTION_INTERFACE,
+        )
+        .map_err(dbus_error)?;
+        let connection_path: OwnedObjectPath =
+            active.get_property("Connection").map_err(dbus_error)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let ap = OwnedObjectPath::try_from("/").map_err(dbus_error)?;
+
+        // Re-activating an active profile makes NetworkManager tear down the
+        // IP configuration and start a fresh DHCP transaction.
+        let new_path: OwnedObjectPath = nm
+            .call("ActivateConnection", &(connection_path, wifi_device, ap))
+            .map_err(dbus_error)?;
+        Ok(new_path.as_str().to_string())
+    }
+
+    fn connect_hidden(
+        &self,
+        ssid: &str,
+        _security: &str,
+        password: Option<&str>,
+        autoconnect: bool,
+    ) -> BackendResult<Option<String>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+
+        let settings = nm_settings_proxy(&conn)?;
+        if let Some(connection_path) = find_connection_for_ssid(&conn, &settings, ssid)? {
+            let ap = OwnedObjectPath::try_from("/")
+                .map_err(dbus_error)?;
+            let active_path: OwnedObjectPath = nm
+                .call("ActivateConnection", &(connection_path, wifi_device, ap))
+                .map_err(dbus_error)?;
+            return Ok(Some(active_path.as_str().to_string()));
+        }
+
+        let mut connection: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
+        let mut con_section = HashMap::new();
+        con_section.insert("type".to_string(), ov_str("802-11-wireless"));
+        con_section.insert("id".to_string(), ov_str(ssid));
+        con_section.insert("autoconnect".to_string(), OwnedValue::from(autoconnect));
+        connection.insert("connection".to_string(), con_section);
+
+        let mut wifi_section = HashMap::new();
+        wifi_section.insert("ssid".to_string(), ov_bytes(ssid.as_bytes().to_vec())?);
+        wifi_section.insert("mode".to_string(), ov_str("infrastructure"));
+        wifi_section.insert("hidden".to_string(), OwnedValue::from(true));
+        connection.insert("802-11-wireless".to_string(), wifi_section);
+
+        if let Some(password) = password {
+            let mut sec_section = HashMap::new();
+            sec_section.insert("key-mgmt".to_string(), ov_str("wpa-psk"));
+            sec_section.insert("psk".to_string(), ov_str(password));
+            connection.insert("802-11-wireless-security".to_string(), sec_section);
+        }
+
+        let ap_path = OwnedObjectPath::try_from("/")
+            .map_err(dbus_error)?;
+        let (_, active_path): (OwnedObjectPath, OwnedObjectPath) = nm
+            .call("AddAndActivateConnection", &(connection, wifi_device.clone(), ap_path))
+            .map_err(dbus_error)?;
+
+        Ok(Some(active_path.as_str().to_string()))
+    }
+
+    fn get_access_point_info(&self, ssid: &str) -> BackendResult<AccessPointInfo> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+        let (ap_path, _strength) = find_ap_for_ssid(&conn, &wireless, ssid, None)?;
+
+        let ap = ap_proxy(&conn, &ap_path)?;
+        let flags: u32 = ap.get_property("Flags").map_err(dbus_error)?;
+        let wpa_flags: u32 = ap.get_property("WpaFlags").map_err(dbus_error)?;
+        let rsn_flags: u32 = ap.get_property("RsnFlags").map_err(dbus_error)?;
+        let frequency_mhz: u32 = ap.get_property("Frequency").map_err(dbus_error)?;
+        let max_bitrate_kbps: u32 = ap.get_property("MaxBitrate").map_err(dbus_error)?;
+
+        let mut key_mgmt = Vec::new();
+        for (sec_flags, generation) in [(wpa_flags, "WPA"), (rsn_flags, "WPA2")] {
+            if sec_flags & NM_802_11_AP_SEC_KEY_MGMT_PSK != 0 {
+                key_mgmt.push(if generation == "WPA" { "WPA-PSK" } else { "WPA2-PSK" });
+            }
+            if sec_flags & NM_802_11_AP_SEC_KEY_MGMT_802_1X != 0 {
+                key_mgmt.push(if generation == "WPA" { "WPA-EAP" } else { "WPA2-EAP" });
+            }
+        }
+        if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_SAE != 0 {
+            key_mgmt.push("WPA3-SAE");
+        }
+        if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_OWE != 0 {
+            key_mgmt.push("OWE");
+        }
+        if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_EAP_SUITE_B_192 != 0 {
+            key_mgmt.push("WPA3-EAP 192-bit");
+        }
+        if key_mgmt.is_empty() && flags & NM_802_11_AP_FLAGS_PRIVACY != 0 {
+            key_mgmt.push("WEP");
+        }
+
+        let ciphers = wpa_flags | rsn_flags;
+        Ok(AccessPointInfo {
+            key_mgmt,
+            pairwise: cipher_names(ciphers),
+            group: cipher_names(ciphers >> 4),
+            wps: flags & NM_802_11_AP_FLAGS_WPS != 0,
+            frequency_mhz,
+            max_bitrate_kbps,
+            generation: wifi_generation(frequency_mhz, max_bitrate_kbps),
+        })
+    }
+
+    fn list_access_points(&self) -> BackendResult<Vec<VisibleAccessPoint>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let wireless = wireless_proxy(&conn, &wifi_device)?;
+        let ap_paths: Vec<OwnedObjectPath> = wireless
+            .call("GetAccessPoints", &())
+            .map_err(dbus_error)?;
+
+        let mut access_points = Vec::new();
+        for ap_path in ap_paths {
+            let ap = ap_proxy(&conn, &ap_path)?;
+            let ssid_bytes: Vec<u8> = ap.get_property("Ssid").map_err(dbus_error)?;
+            let access_point = access_point_from_proxy(&ap)?;
+            access_points.push(VisibleAccessPoint {
+                ssid: ssid_text(&ssid_bytes),
+                bssid: access_point.bssid,
+                frequency_mhz: access_point.frequency_mhz,
+                strength: access_point.strength,
+            });
+        }
+        Ok(access_points)
+    }
+
+    fn get_network_details(&self, ssid: &str) -> BackendResult<NetworkDetails> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        let settings_map = connection_settings(&conn, &connection_path)?;
+
+        let mut details = NetworkDetails::default();
+
+        if let Some(connection) = settings_map.get("connection") {
+            if let Some(value) = connection.get("autoconnect") {
+                if let Ok(flag) = owned_value_to_bool(value) {
+                    details.auto_reconnect = Some(flag);
+                }
+            }
+            let text = |key| connection.get(key).and_then(|value| owned_value_to_string(value).ok());
+            details.uuid = text("uuid");
+            details.interface_name = text("interface-name").filter(|name| !name.is_empty());
+            details.permitted_users = connection
+                .get("permissions")
+                .and_then(|value| Vec::<String>::try_from(value.try_clone().ok()?).ok())
+                .map(|permissions| permissions.iter().filter_map(|entry| permitted_user(entry)).collect())
+                .unwrap_or_default();
+        }
+
+        if let Some(ipv4) = settings_map.get("ipv4") {
+            if let Some(value) = ipv4.get("address-data") {
+                if let Some((addr, prefix)) = first_address_from_value(value) {
+                    details.ip_address = Some(addr);
+                    details.prefix = Some(prefix);
+                }
+            }
+            if let Some(value) = ipv4.get("gateway") {
+                if let Ok(gateway) = owned_value_to_string(value) {
+                    details.gateway = Some(gateway);
+                }
+            }
+            if let Some(value) = ipv4.get("dns-data") {
+                details.dns_servers = dns_from_value(value);
+            }
+            // NetworkManager before address-data and dns-data only has the packed forms.
+            let packed = ipv4
+                .get("addresses")
+                .filter(|_| details.ip_address.is_none())
+                .and_then(first_packed_address);
+            if let Some((addr, prefix, gateway)) = packed {
+                details.ip_address = Some(addr);
+                details.prefix = Some(prefix);
+                details.gateway = details.gateway.take().or(gateway);
+            }
+            if details.dns_servers.is_empty() {
+                details.dns_servers = ipv4.get("dns").map(packed_dns).unwrap_or_default();
+            }
+            details.dns_priority = ipv4
+                .get("dns-priority")
+                .and_then(|value| i32::try_from(value.try_clone().ok()?).ok());
+            details.link_local = ipv4
+                .get("method")
+                .and_then(|value| owned_value_to_string(value).ok())
+                .is_some_and(|method| method == NM_IPV4_METHOD_LINK_LOCAL);
+        }
+
+        if let Some(proxy) = settings_map.get("proxy") {
+            let method = proxy
+                .get("method")
+                .and_then(|value| i32::try_from(value.try_clone().ok()?).ok());
+            details.proxy_pac_url = proxy
+                .get("pac-url")
+                .and_then(|value| owned_value_to_string(value).ok())
+                .filter(|_| method == Some(NM_PROXY_METHOD_AUTO));
+        }
+
+        details.certificates = settings_map.get("802-1x").map(enterprise_certificates);
+
+        // Hardware addresses are informational; a missing device shouldn't hide saved settings.
+        if let Ok((device_mac, bssid)) = hardware_addresses(&conn, ssid) {
+            details.device_mac = Some(device_mac);
+            details.bssid = bssid;
+        }
+
+        Ok(details)
+    }
+
+    fn set_ip_dns(
+        &self,
+        ssid: &str,
+        ip: Option<&str>,
+        prefix: Option<u32>,
+        gateway: Option<&str>,
+        dns: Option<Vec<String>>,
+    ) -> BackendResult<()> {
+        if ip.is_none() && dns.is_none() && gateway.is_none() {
+            return Ok(());
+        }
+
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let ipv4 = settings_map
+                .entry("ipv4".to_string())
+                .or_insert_with(HashMap::new);
+
+            let mut set_manual = false;
+
+            if let Some(ip) = ip {
+                let (address, default_prefix) = parse_ip_prefix(ip);
+                let prefix = prefix.unwrap_or(default_prefix);
+                ipv4.insert("method".to_string(), ov_str("manual"));
+                let mut addr = HashMap::new();
+                addr.insert("address".to_string(), ov_str(&address));
+                addr.insert("prefix".to_string(), OwnedValue::from(prefix));
+                let address_data = vec![addr];
+                ipv4.insert("address-data".to_string(), ov_array_dict(address_data)?);
+                set_manual = true;
+            }
+
+            if let Some(gateway) = gateway {
+                ipv4.insert("gateway".to_string(), ov_str(gateway));
+                set_manual = true;
+            }
+
+            if let Some(dns_list) = dns {
+                let mut dns_data = Vec::new();
+                for dns in dns_list {
+                    if dns.trim().is_empty() {
+                        continue;
+                    }
+                    let mut dns_entry = HashMap::new();
+                    dns_entry.insert("address".to_string(), ov_str(dns.trim()));
+                    dns_data.push(dns_entry);
+                }
+                if !dns_data.is_empty() {
+                    ipv4.insert("dns-data".to_string(), ov_array_dict(dns_data)?);
+                    ipv4.insert("ignore-auto-dns".to_string(), OwnedValue::from(true));
+                    set_manual = true;
+                }
+            }
+
+            if set_manual {
+                ipv4.insert("method".to_string(), ov_str("manual"));
+                pack_ipv4_settings(ipv4)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn get_saved_password(&self, _ssid: &str) -> BackendResult<Option<String>> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, _ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        let connection_proxy = connection_proxy(&conn, &connection_path)?;
+        let secrets: HashMap<String, HashMap<String, OwnedValue>> = connection_proxy
+            .call("GetSecrets", &("802-11-wireless-security",))
+            .map_err(dbus_error)?;
+
+        let sec = match secrets.get("802-11-wireless-security") {
+            Some(section) => section,
+            None => return Ok(None),
+        };
+
+        if let Some(value) = sec.get("psk") {
+            return owned_value_to_string(value).map(Some);
+        }
+        if let Some(value) = sec.get("wep-key0") {
+            return owned_value_to_string(value).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn raw_settings(&self, ssid: &str) -> BackendResult<RawSettings> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        let settings_map = connection_settings(&conn, &connection_path)?;
+        Ok(settings_map
+            .into_iter()
+            .map(|(section, values)| {
+                let values = values
+                    .iter()
+                    .map(|(key, value)| {
+                        let raw = RawValue {
+                            signature: value.value_signature().to_string(),
+                            text: value.to_string(),
+                            input: editor_input(value),
+                        };
+                        (key.clone(), raw)
+                    })
+                    .collect();
+                (section, values)
+            })
+            .collect())
+    }
+
+    fn set_raw_setting(&self, ssid: &str, section: &str, key: &str, value: &SettingValue) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let value = match value {
+                SettingValue::Text(text) => ov_str(text),
+                SettingValue::Boolean(flag) => OwnedValue::from(*flag),
+                SettingValue::Int32(number) => OwnedValue::from(*number),
+                SettingValue::UInt32(number) => OwnedValue::from(*number),
+                SettingValue::Int64(number) => OwnedValue::from(*number),
+                SettingValue::UInt64(number) => OwnedValue::from(*number),
+                SettingValue::Bytes(bytes) => ov_bytes(bytes.clone())?,
+                SettingValue::TextList(items) => OwnedValue::try_from(Array::from(items.clone())).map_err(dbus_error)?,
+            };
+            settings_map
+                .entry(section.to_string())
+                .or_insert_with(HashMap::new)
+                .insert(key.to_string(), value);
+            Ok(())
+        })
+    }
+
+    fn set_certificates(
+        &self,
+        ssid: &str,
+        certificates: &EnterpriseCertificates,
+        key_password: Option<&str>,
+    ) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let dot1x = settings_map
+                .get_mut("802-1x")
+                .ok_or_else(|| BackendError::Unavailable(format!("{ssid} does not use 802.1X")))?;
+            let stored = enterprise_certificates(dot1x);
+            for (key, wanted, current) in [
+                ("ca-cert", &certificates.ca_cert, &stored.ca_cert),
+                ("client-cert", &certificates.client_cert, &stored.client_cert),
+                ("private-key", &certificates.private_key, &stored.private_key),
+            ] {
+                match wanted {
+                    Some(wanted) if current.as_ref() == Some(wanted) => {}
+                    Some(wanted) => {
+                        dot1x.insert(key.to_string(), ov_bytes(certificate_value(wanted))?);
+                    }
+                    None => {
+                        dot1x.remove(key);
+                    }
+                }
+            }
+            if certificates.ca_path.is_none() {
+                dot1x.remove("ca-path");
+            }
+            dot1x.insert("system-ca-certs".to_string(), OwnedValue::from(certificates.system_ca_certs));
+            if let Some(password) = key_password {
+                dot1x.insert("private-key-password".to_string(), ov_str(password));
+            }
+            Ok(())
+        })
+    }
+
+    fn set_autoreconnect(&self, _ssid: &str, _enabled: bool) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, _ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let connection = settings_map
+                .entry("connection".to_string())
+                .or_insert_with(HashMap::new);
+            connection.insert("autoconnect".to_string(), OwnedValue::from(_enabled));
+            Ok(())
+        })
+    }
+
+    fn clear_interface_binding(&self, ssid: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            if let Some(connection) = settings_map.get_mut("connection") {
+                connection.remove("interface-name");
+            }
+            Ok(())
+        })
+    }
+
+    fn set_personal(&self, ssid: &str, user: Option<&str>) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let connection = settings_map
+                .entry("connection".to_string())
+                .or_insert_with(HashMap::new);
+            match user {
+                Some(user) => {
+                    let nm = nm_proxy(&conn)?;
+                    let device = first_wifi_device(&conn, &nm)?;
+                    let interface: String = device_proxy(&conn, &device)?
+                        .get_property("Interface")
+                        .map_err(dbus_error)?;
+                    // The last field is reserved and left empty.
+                    let permissions = OwnedValue::try_from(Array::from(vec![format!("user:{user}:")])).map_err(dbus_error)?;
+                    connection.insert("permissions".to_string(), permissions);
+                    connection.insert("interface-name".to_string(), ov_str(&interface));
+                }
+                None => {
+                    connection.remove("permissions");
+                    connection.remove("interface-name");
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn set_metered(&self, ssid: &str, metered: bool) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let connection = settings_map
+                .entry("connection".to_string())
+                .or_insert_with(HashMap::new);
+            let value = if metered { NM_METERED_YES } else { NM_METERED_UNKNOWN };
+            connection.insert("metered".to_string(), OwnedValue::from(value));
+            Ok(())
+        })
+    }
+
+    fn set_dns_priority(&self, ssid: &str, priority: i32) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            for family in ["ipv4", "ipv6"] {
+                settings_map
+                    .entry(family.to_string())
+                    .or_insert_with(HashMap::new)
+                    .insert("dns-priority".to_string(), OwnedValue::from(priority));
+            }
+            Ok(())
+        })
+    }
+
+    fn set_link_local(&self, ssid: &str, link_local: bool) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let ipv4 = settings_map.entry("ipv4".to_string()).or_default();
+            if link_local {
+                // NetworkManager refuses static addresses and a gateway with link-local.
+                for key in ["address-data", "addresses", "gateway"] {
+                    ipv4.remove(key);
+                }
+                ipv4.insert("method".to_string(), ov_str(NM_IPV4_METHOD_LINK_LOCAL));
+            } else {
+                ipv4.insert("method".to_string(), ov_str("auto"));
+            }
+            Ok(())
+        })
+    }
+
+    fn set_proxy(&self, ssid: &str, pac_url: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        edit_connection(&conn, &connection_path, |settings_map| {
+            let proxy = settings_map.entry("proxy".to_string()).or_default();
+            if pac_url.is_empty() {
+                // NetworkManager refuses a PAC script without the auto method.
+                proxy.remove("pac-url");
+                proxy.insert("method".to_string(), OwnedValue::from(NM_PROXY_METHOD_NONE));
+            } else {
+                proxy.insert("method".to_string(), OwnedValue::from(NM_PROXY_METHOD_AUTO));
+                proxy.insert("pac-url".to_string(), ov_str(pac_url));
+            }
+            Ok(())
+        })
+    }
+
+    fn forget_network(&self, ssid: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let nm = nm_proxy(&conn)?;
+        if let Ok(Some(active_path)) = find_active_connection_for_ssid(&conn, &nm, ssid) {
+            let _: () = nm
+                .call("DeactivateConnection", &(active_path))
+                .map_err(dbus_error)?;
+        }
+        let connection_path = find_connection_for_ssid(&conn, &settings, ssid)?
+            .ok_or_else(|| BackendError::Unavailable("Connection not found".to_string()))?;
+
+        let connection = connection_proxy(&conn, &connection_path)?;
+        let _: () = connection
+            .call("Delete", &())
+            .map_err(dbus_error)?;
+        Ok(())
+    }
+
+    fn saved_profiles(&self) -> BackendResult<Vec<SavedProfile>> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connections: Vec<OwnedObjectPath> = settings
+            .call("ListConnections", &())
+            .map_err(dbus_error)?;
+
+        let mut profiles = Vec::new();
+        for path in connections {
+            let settings_map = connection_settings(&conn, &path)?;
+            let Some(ssid) = settings_map
+                .get("802-11-wireless")
+                .and_then(|wireless| wireless.get("ssid"))
+                .and_then(ssid_from_value)
+            else {
+                continue;
+            };
+            let connection = settings_map.get("connection");
+            let id = connection
+                .and_then(|connection| connection.get("id"))
+                .and_then(|value| owned_value_to_string(value).ok())
+                .unwrap_or_else(|| ssid.clone());
+            let timestamp = connection
+                .and_then(|connection| connection.get("timestamp"))
+                .and_then(|value| u64::try_from(value.try_clone().ok()?).ok())
+                .unwrap_or(0);
+            let priority = connection
+                .and_then(|connection| connection.get("autoconnect-priority"))
+                .and_then(|value| i32::try_from(value.try_clone().ok()?).ok())
+                .unwrap_or(0);
+            let autoconnect = connection
+                .and_then(|connection| connection.get("autoconnect"))
+                .and_then(|value| owned_value_to_bool(value).ok())
+                .unwrap_or(true);
+            let is_open = !settings_map.contains_key("802-11-wireless-security");
+            let security = audited_security(&settings_map);
+            profiles.push(SavedProfile {
+                path: path.as_str().to_string(),
+                id,
+                ssid,
+                timestamp,
+                priority,
+                autoconnect,
+                is_open,
+                security,
+            });
+        }
+        Ok(profiles)
+    }
+
+    fn vpn_connections(&self) -> BackendResult<Vec<VpnConnection>> {
+        let conn = self.bus()?;
+        let active = active_connection_profiles(&conn)?;
+        let mut vpns: Vec<VpnConnection> = vpn_profiles(&conn)?
+            .into_iter()
+            .map(|(path, id, uuid)| VpnConnection {
+                active: active.contains(&path),
+                id,
+                uuid,
+            })
+            .collect();
+        vpns.sort_by_key(|vpn| vpn.id.to_lowercase());
+        Ok(vpns)
+    }
+
+    fn activate_vpn(&self, uuid: &str) -> BackendResult<Option<String>> {
+        let conn = self.bus()?;
+        let (path, id, _) = vpn_profiles(&conn)?
+            .into_iter()
+            .find(|(_, _, candidate)| candidate == uuid)
+            .ok_or_else(|| BackendError::Unavailable("The VPN profile no longer exists".to_string()))?;
+        if active_connection_profiles(&conn)?.contains(&path) {
+            return Ok(None);
+        }
+        let root = OwnedObjectPath::try_from("/").map_err(dbus_error)?;
+        let _: OwnedObjectPath = nm_proxy(&conn)?
+            .call("ActivateConnection", &(path, root.clone(), root))
+            .map_err(dbus_error)?;
+        Ok(Some(id))
+    }
+
+    fn add_profile(&self, profile: &ImportedProfile) -> BackendResult<String> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+
+        let mut connection: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
+        let mut con_section = HashMap::new();
+        con_section.insert("type".to_string(), ov_str("802-11-wireless"));
+        con_section.insert("id".to_string(), ov_str(&profile.id));
+        con_section.insert("autoconnect".to_string(), OwnedValue::from(profile.autoconnect));
+        connection.insert("connection".to_string(), con_section);
+
+        let mut wifi_section = HashMap::new();
+        wifi_section.insert("ssid".to_string(), ov_bytes(profile.ssid.as_bytes().to_vec())?);
+        wifi_section.insert("mode".to_string(), ov_str("infrastructure"));
+        wifi_section.insert("hidden".to_string(), OwnedValue::from(profile.hidden));
+        connection.insert("802-11-wireless".to_string(), wifi_section);
+
+        if let Some(key_mgmt) = profile.key_mgmt.as_deref() {
+            let mut sec_section = HashMap::new();
+            sec_section.insert("key-mgmt".to_string(), ov_str(key_mgmt));
+            if let Some(password) = profile.password.as_deref() {
+                let key = if key_mgmt == "none" { "wep-key0" } else { "psk" };
+                sec_section.insert(key.to_string(), ov_str(password));
+            }
+            connection.insert("802-11-wireless-security".to_string(), sec_section);
+        }
+
+        let path: OwnedObjectPath = settings
+            .call("AddConnection", &(connection,))
+            .map_err(dbus_error)?;
+        Ok(path.as_str().to_string())
+    }
+
+    fn duplicate_profile(&self, path: &str, id: &str) -> BackendResult<String> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let path = OwnedObjectPath::try_from(path).map_err(dbus_error)?;
+
+        let mut copy = connection_settings(&conn, &path)?;
+        merge_secrets(&conn, &path, &mut copy)?;
+        let connection = copy.entry("connection".to_string()).or_insert_with(HashMap::new);
+        // NetworkManager fills in a new UUID; the timestamp starts over.
+        connection.remove("uuid");
+        connection.remove("timestamp");
+        connection.insert("id".to_string(), ov_str(id));
+        connection.insert("autoconnect".to_string(), OwnedValue::from(false));
+
+        let copy_path: OwnedObjectPath = settings
+            .call("AddConnection", &(copy,))
+            .map_err(dbus_error)?;
+        Ok(copy_path.as_str().to_string())
+    }
+
+    fn delete_profile(&self, path: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let path = OwnedObjectPath::try_from(path).map_err(dbus_error)?;
+        let connection = connection_proxy(&conn, &path)?;
+        let _: () = connection.call("Delete", &()).map_err(dbus_error)?;
+        Ok(())
+    }
+
+    fn set_autoconnect_priority(&self, path: &str, priority: i32) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let path = OwnedObjectPath::try_from(path).map_err(dbus_error)?;
+        edit_connection(&conn, &path, |settings_map| {
+            settings_map
+                .entry("connection".to_string())
+                .or_insert_with(HashMap::new)
+                .insert("autoconnect-priority".to_string(), OwnedValue::from(priority));
+            Ok(())
+        })
+    }
+
+    fn backup_profiles(&self) -> BackendResult<Vec<u8>> {
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connections: Vec<OwnedObjectPath> = settings
+            .call("ListConnections", &())
+            .map_err(dbus_error)?;
+
+        let mut profiles = Vec::new();
+        for path in connections {
+            let mut settings_map = connection_settings(&conn, &path)?;
+            if !settings_map.contains_key("802-11-wireless") {
+                continue;
+            }
+            // A profile whose secrets are not handed out is still worth keeping.
+            merge_secrets(&conn, &path, &mut settings_map)?;
+            profiles.push(settings_map);
+        }
+        let encoded = zvariant::to_bytes(backup_context(), &profiles)
+            .map_err(|err| BackendError::Unavailable(err.to_string()))?;
+        Ok(encoded.to_vec())
+    }
+
+    fn restore_profiles(&self, backup: &[u8]) -> BackendResult<RestoredProfiles> {
+        let data = zvariant::serialized::Data::new(backup, backup_context());
+        let profiles: Vec<HashMap<String, HashMap<String, OwnedValue>>> = data
+            .deserialize()
+            .map(|(profiles, _)| profiles)
+            .map_err(|_| BackendError::Unavailable("Not a YuFi profile backup".to_string()))?;
+
+        let conn = self.bus()?;
+        let settings = nm_settings_proxy(&conn)?;
+        let connections: Vec<OwnedObjectPath> = settings
+            .call("ListConnections", &())
+            .map_err(dbus_error)?;
+        let mut saved = HashSet::new();
+        for path in connections {
+            saved.insert(profile_identity(&connection_settings(&conn, &path)?).0);
+        }
+
+        let mut restored = RestoredProfiles::default();
+        for mut profile in profiles {
+            let (identity, name) = profile_identity(&profile);
+            if !saved.insert(identity) {
+                restored.skipped.push(name);
+                continue;
+            }
+            // Bindings to the old machine's adapter would keep the profile
+            // from ever matching a device on the new one.
+            if let Some(connection) = profile.get_mut("connection") {
+                connection.remove("interface-name");
+            }
+            if let Some(wireless) = profile.get_mut("802-11-wireless") {
+                wireless.remove("mac-address");
+            }
+            let _: OwnedObjectPath = settings
+                .call("AddConnection", &(profile,))
+                .map_err(dbus_error)?;
+            restored.added.push(name);
+        }
+        Ok(restored)
+    }
+
+    fn traffic_counters(&self) -> BackendResult<TrafficCounters> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let device_path = first_wifi_device(&conn, &nm)?;
+        let statistics = Proxy::new(
+            &conn,
+            nm_consts::BUS_NAME,
+            device_path.as_str(),
+            nm_consts::STATISTICS_INTERFACE,
+        )
+        .map_err(dbus_error)?;
+        let refresh_ms: u32 = statistics.get_property("RefreshRateMs").map_err(dbus_error)?;
+        if refresh_ms == 0 {
+            statistics
+                .set_property("RefreshRateMs", STATISTICS_REFRESH_MS)
+                .map_err(dbus_error)?;
+        }
+        let metered: u32 = device_proxy(&conn, &device_path)?
+            .get_property("Metered")
+            .map_err(dbus_error)?;
+        Ok(TrafficCounters {
+            rx_bytes: statistics.get_property("RxBytes").map_err(dbus_error)?,
+            tx_bytes: statistics.get_property("TxBytes").map_err(dbus_error)?,
+            metered: matches!(metered, NM_DEVICE_METERED_YES | NM_DEVICE_METERED_GUESS_YES),
+        })
+    }
+
+    fn link_status(&self) -> BackendResult<LinkStatus> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let device_path = first_wifi_device(&conn, &nm)?;
+        let interface: String = device_proxy(&conn, &device_path)?
+            .get_property("Interface")
+            .map_err(dbus_error)?;
+        let bitrate_kbps: u32 = wireless_proxy(&conn, &device_path)?
+            .get_property("Bitrate")
+            .map_err(dbus_error)?;
+        Ok(LinkStatus {
+            interface,
+            bitrate_kbps,
+        })
+    }
+
+    fn active_connection_state(&self, path: &str) -> BackendResult<u32> {
+        let conn = self.bus()?;
+        let active_proxy = Proxy::new(
+            &conn,
+            nm_consts::BUS_NAME,
+            path,
+            nm_consts::ACTIVE_CONNECTION_INTERFACE,
+        )
+        .map_err(dbus_error)?;
+        active_proxy
+            .get_property("State")
+            .map_err(dbus_error)
+    }
+
+    fn diagnostics(&self) -> BackendResult<NmDiagnostics> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        // A missing Wi‑Fi device is itself a finding, so report it instead of failing.
+        let device = match first_wifi_device(&conn, &nm) {
+            Ok(path) => {
+                let device = device_proxy(&conn, &path)?;
+                let wireless = wireless_proxy(&conn, &path)?;
+                Some(WifiDeviceInfo {
+                    interface: device.get_property("Interface").map_err(dbus_error)?,
+                    driver: device.get_property("Driver").map_err(dbus_error)?,
+                    driver_version: device.get_property("DriverVersion").map_err(dbus_error)?,
+                    firmware_version: device.get_property("FirmwareVersion").map_err(dbus_error)?,
+                    mac: wireless.get_property("HwAddress").map_err(dbus_error)?,
+                    permanent_mac: wireless.get_property("PermHwAddress").unwrap_or_default(),
+                })
+            }
+            Err(_) => None,
+        };
+        Ok(NmDiagnostics {
+            version: nm.get_property("Version").map_err(dbus_error)?,
+            wireless_enabled: nm.get_property("WirelessEnabled").map_err(dbus_error)?,
+            wireless_hardware_enabled: nm
+                .get_property("WirelessHardwareEnabled")
+                .map_err(dbus_error)?,
+            connectivity: nm.get_property("Connectivity").map_err(dbus_error)?,
+            connectivity_check_enabled: nm
+                .get_property("ConnectivityCheckEnabled")
+                .unwrap_or(false),
+            device,
+            devices: self.list_devices().unwrap_or_default(),
+        })
+    }
+
+    fn list_devices(&self) -> BackendResult<Vec<DeviceInfo>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let paths: Vec<OwnedObjectPath> = nm.call("GetDevices", &()).map_err(dbus_error)?;
+        let mut devices = Vec::new();
+        for path in paths {
+            let device = device_proxy(&conn, &path)?;
+            let device_type: u32 = device.get_property("DeviceType").map_err(dbus_error)?;
+            devices.push(DeviceInfo {
+                path: path.as_str().to_string(),
+                interface: device.get_property("Interface").map_err(dbus_error)?,
+                kind: DeviceKind::from_nm(device_type),
+                state: device.get_property("State").map_err(dbus_error)?,
+                driver: device.get_property("Driver").unwrap_or_default(),
+                // Device.HwAddress only exists since NetworkManager 1.24.
+                mac: device.get_property("HwAddress").unwrap_or_default(),
+            });
+        }
+        Ok(devices)
+    }
+
+    fn checkpoint_create(&self, rollback_timeout_secs: u32) -> BackendResult<String> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let checkpoint: OwnedObjectPath = nm
+            .call(
+                "CheckpointCreate",
+                &(vec![wifi_device], rollback_timeout_secs, NM_CHECKPOINT_CREATE_FLAG_NONE),
+            )
+            .map_err(dbus_error)?;
+        Ok(checkpoint.as_str().to_string())
+    }
+
+    fn checkpoint_destroy(&self, checkpoint: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let checkpoint = ObjectPath::try_from(checkpoint).map_err(dbus_error)?;
+        nm.call("CheckpointDestroy", &(checkpoint,)).map_err(dbus_error)
+    }
+
+    fn checkpoint_rollback(&self, checkpoint: &str) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let checkpoint = ObjectPath::try_from(checkpoint).map_err(dbus_error)?;
+        // The reply maps each device to a result code; a failed device is not worth more
+        // than the error NetworkManager would already have returned.
+        let _results: HashMap<OwnedObjectPath, u32> = nm
+            .call("CheckpointRollback", &(checkpoint,))
+            .map_err(dbus_error)?;
+        Ok(())
+    }
+
+    fn reapply_connection(&self) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let device = device_proxy(&conn, &wifi_device)?;
+        // Empty settings and version 0 mean "reapply the profile as currently saved".
+        let settings: HashMap<String, HashMap<String, OwnedValue>> = HashMap::new();
+        device
+            .call("Reapply", &(settings, 0u64, 0u32))
+            .map_err(dbus_error)
+    }
+
+    fn reload_config(&self) -> BackendResult<()> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        nm.call("Reload", &(NM_MANAGER_RELOAD_FLAG_CONF,))
+            .map_err(dbus_error)
+    }
+
+    fn ip_config(&self) -> BackendResult<Option<IpConfig>> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let device = device_proxy(&conn, &wifi_device)?;
+        let ip4_path: OwnedObjectPath = device.get_property("Ip4Config").map_err(dbus_error)?;
+        if ip4_path.as_str() == "/" {
+            return Ok(None);
+        }
+        let ip4 = Proxy::new(
+            &conn,
+            nm_consts::BUS_NAME,
+            ip4_path.as_str(),
+            nm_consts::IP4_CONFIG_INTERFACE,
+        )
+        .map_err(dbus_error)?;
+        let address_data: OwnedValue = ip4.get_property("AddressData").map_err(dbus_error)?;
+        let Some((address, prefix)) = first_address_from_value(&address_data) else {
+            return Ok(None);
+        };
+        let gateway: String = ip4.get_property("Gateway").unwrap_or_default();
+        let dns_servers = ip4
+            .get_property::<OwnedValue>("NameserverData")
+            .map(|value| dns_from_value(&value))
+            .unwrap_or_default();
+        let dhcp4_path: OwnedObjectPath = device.get_property("Dhcp4Config").map_err(dbus_error)?;
+        Ok(Some(IpConfig {
+            address,
+            prefix,
+            gateway: (!gateway.is_empty()).then_some(gateway),
+            dns_servers,
+            dhcp: dhcp4_path.as_str() != "/",
+        }))
+    }
+
+    fn dhcp_leases(&self) -> BackendResult<DhcpLeases> {
+        let conn = self.bus()?;
+        let nm = nm_proxy(&conn)?;
+        let wifi_device = first_wifi_device(&conn, &nm)?;
+        let device = device_proxy(&conn, &wifi_device)?;
+        let lease = |property: &str, interface: &'static str| -> BackendResult<Option<DhcpLease>> {
+            // Dhcp6Config is missing on devices that never ran IPv6; treat that like "/".
+            let Ok(path) = device.get_property::<OwnedObjectPath>(property) else {
+                return Ok(None);
+            };
+            if path.as_str() == "/" {
+                return Ok(None);
+            }
+            let config = Proxy::new(&conn, nm_consts::BUS_NAME, path.as_str(), interface)
+                .map_err(dbus_error)?;
+            let options: HashMap<String, OwnedValue> =
+                config.get_property("Options").map_err(dbus_error)?;
+            let options = options
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), owned_value_to_string(value).ok()?)))
+                .collect();
+            Ok(Some(DhcpLease { options }))
+        };
+        Ok(DhcpLeases {
+            ipv4: lease("Dhcp4Config", nm_consts::DHCP4_CONFIG_INTERFACE)?,
+            ipv6: lease("Dhcp6Config", nm_consts::DHCP6_CONFIG_INTERFACE)?,
+        })
+    }
+
+    fn subscribe(&self) -> BackendResult<EventStream> {
+        let conn = self.bus()?;
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender(nm_consts::BUS_NAME)
+            .map_err(dbus_error)?
+            .build();
+        let signals = MessageIterator::for_match_rule(rule, &conn, Some(64))
+            .map_err(dbus_error)?;
+        let mut wifi_devices = wifi_device_paths(&conn)?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for message in signals {
+                let Ok(message) = message else { continue };
+                for event in events_from_signal(&conn, &message, &mut wifi_devices) {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    fn register_secret_agent(&self) -> BackendResult<SecretRequests> {
+        secret_agent::register(&self.bus()?)
+    }
+}
+
+pub mod nm_consts {
+    pub const BUS_NAME: &str = "org.freedesktop.NetworkManager";
+    pub const OBJECT_PATH: &str = "/org/freedesktop/NetworkManager";
+    pub const NM_INTERFACE: &str = "org.freedesktop.NetworkManager";
+    pub const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+    pub const ACTIVE_CONNECTION_INTERFACE: &str =
+        "org.freedesktop.NetworkManager.Connection.Active";
+    pub const DEVICE_INTERFACE: &str = "org.freedesktop.NetworkManager.Device";
+    pub const WIFI_DEVICE_INTERFACE: &str = "org.freedesktop.NetworkManager.Device.Wireless";
+    pub const STATISTICS_INTERFACE: &str = "org.freedesktop.NetworkManager.Device.Statistics";
+    pub const AP_INTERFACE: &str = "org.freedesktop.NetworkManager.AccessPoint";
+    pub const IP4_CONFIG_INTERFACE: &str = "org.freedesktop.NetworkManager.IP4Config";
+    pub const DHCP4_CONFIG_INTERFACE: &str = "org.freedesktop.NetworkManager.DHCP4Config";
+    pub const DHCP6_CONFIG_INTERFACE: &str = "org.freedesktop.NetworkManager.DHCP6Config";
+    pub const SETTINGS_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings";
+    pub const CONNECTION_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+}
+
+pub mod polkit {
+    pub const NETWORK_CONTROL: &str = "org.freedesktop.NetworkManager.network-control";
+    pub const ENABLE_DISABLE_WIFI: &str = "org.freedesktop.NetworkManager.enable-disable-wifi";
+    pub const WIFI_SCAN: &str = "org.freedesktop.NetworkManager.wifi.scan";
+    pub const SETTINGS_MODIFY_SYSTEM: &str = "org.freedesktop.NetworkManager.settings.modify.system";
+}
+
+const NM_DEVICE_NOT_ALLOWED: &str = "org.freedesktop.NetworkManager.Device.NotAllowed";
+const PERMISSION_ERRORS: &[&str] = &[
+    "org.freedesktop.DBus.Error.AccessDenied",
+    "org.freedesktop.NetworkManager.PermissionDenied",
+    "org.freedesktop.NetworkManager.Settings.PermissionDenied",
+    "org.freedesktop.NetworkManager.Settings.Connection.PermissionDenied",
+    "org.freedesktop.NetworkManager.AgentManager.PermissionDenied",
+    "org.freedesktop.PolicyKit1.Error.NotAuthorized",
+];
+
+const NM_DEVICE_TYPE_WIFI: u32 = 2;
+const NM_CHECKPOINT_CREATE_FLAG_NONE: u32 = 0;
+const NM_MANAGER_RELOAD_FLAG_CONF: u32 = 0x1;
+/// Profile settings that can hold secrets.
+const SECRET_SETTINGS: &[&str] = &["802-11-wireless-security", "802-1x"];
+/// Keeps a profile on disk or in memory, wherever it already is.
+const NM_SETTINGS_UPDATE2_FLAG_NONE: u32 = 0;
+const NM_IPV4_METHOD_LINK_LOCAL: &str = "link-local";
+/// `proxy.method`: no proxy, or the proxy auto-config script at `pac-url`.
+const NM_PROXY_METHOD_NONE: i32 = 0;
+const NM_PROXY_METHOD_AUTO: i32 = 1;
+const NM_METERED_UNKNOWN: i32 = 0;
+const NM_METERED_YES: i32 = 1;
+/// `Device.Metered` is unsigned, unlike the profile setting.
+const NM_DEVICE_METERED_YES: u32 = 1;
+const NM_DEVICE_METERED_GUESS_YES: u32 = 3;
+/// How often NetworkManager refreshes the byte counters once asked to.
+const STATISTICS_REFRESH_MS: u32 = 5000;
+/// Most threads `load_state` reads access point properties on at once.
+const AP_FETCH_THREADS: usize = 8;
+
+const NM_802_11_AP_FLAGS_PRIVACY: u32 = 0x1;
+const NM_802_11_AP_FLAGS_WPS: u32 = 0x2;
+const NM_802_11_AP_SEC_PAIR_WEP40: u32 = 0x1;
+const NM_802_11_AP_SEC_PAIR_WEP104: u32 = 0x2;
+const NM_802_11_AP_SEC_PAIR_TKIP: u32 = 0x4;
+const NM_802_11_AP_SEC_PAIR_CCMP: u32 = 0x8;
+const NM_802_11_AP_SEC_KEY_MGMT_PSK: u32 = 0x100;
+const NM_802_11_AP_SEC_KEY_MGMT_802_1X: u32 = 0x200;
+const NM_802_11_AP_SEC_KEY_MGMT_SAE: u32 = 0x400;
+const NM_802_11_AP_SEC_KEY_MGMT_OWE: u32 = 0x800;
+const NM_802_11_AP_SEC_KEY_MGMT_EAP_SUITE_B_192: u32 = 0x2000;
+
+pub(crate) fn dbus_error(err: impl Into<zbus::Error>) -> BackendError {
+    let err: zbus::Error = err.into();
+    let message = err.to_string();
+    if matches!(&err, zbus::Error::MethodError(name, ..) if name.as_str() == NM_DEVICE_NOT_ALLOWED) {
+        BackendError::NotAllowed(message)
+    } else if PERMISSION_ERRORS.iter().any(|name| message.contains(name)) {
+        BackendError::PermissionDenied(message)
+    } else {
+        BackendError::Unavailable(message)
+    }
+}
+
+/// `CLOCK_BOOTTIME` in milliseconds, the clock of `LastScan` and `LastSeen`.
+/// `/proc/uptime` reads the same clock, so no libc binding is needed.
+pub(crate) fn boottime_ms() -> Option<u64> {
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    let seconds: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    Some((seconds * 1000.0) as u64)
+}
+
+fn system_bus() -> BackendResult<Connection> {
+    Connection::system().map_err(dbus_error)
+}
+
+fn nm_proxy(conn: &Connection) -> BackendResult<Proxy<'_>> {
+    Proxy::new(conn, nm_consts::BUS_NAME, nm_consts::OBJECT_PATH, "org.freedesktop.NetworkManager")
+        .map_err(dbus_error)
+}
+
+fn device_proxy<'a>(
+    conn: &'a Connection,
+    path: &'a OwnedObjectPath,
+) -> BackendResult<Proxy<'a>> {
+    Proxy::new(conn, nm_consts::BUS_NAME, path.as_str(), nm_consts::DEVICE_INTERFACE)
+        .map_err(dbus_error)
+}
+
+fn wireless_proxy<'a>(
+    conn: &'a Connection,
+    path: &'a OwnedObjectPath,
+) -> BackendResult<Proxy<'a>> {
+    Proxy::new(conn, nm_consts::BUS_NAME, path.as_str(), nm_consts::WIFI_DEVICE_INTERFACE)
+        .map_err(dbus_error)
+}
+
+fn ap_proxy<'a>(conn: &'a Connection, path: &'a OwnedObjectPath) -> BackendResult<Proxy<'a>> {
+    Proxy::new(conn, nm_consts::BUS_NAME, path.as_str(), nm_consts::AP_INTERFACE)
+        .map_err(dbus_error)
+}
+
+/// Reads the SSID and properties of every access point in `paths`, a few at
+/// a time, since each read is a D-Bus round trip that would otherwise wait for
+/// the previous one. Hidden networks come back as None; the order is kept.
+fn fetch_access_points(
+    conn: &Connection,
+    paths: &[OwnedObjectPath],
+) -> BackendResult<Vec<Option<(String, AccessPoint)>>> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let per_thread = paths.len().div_ceil(AP_FETCH_THREADS);
+    thread::scope(|scope| {
+        let workers: Vec<_> = paths
+            .chunks(per_thread)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| fetch_access_point(conn, path))
+                        .collect::<BackendResult<Vec<_>>>()
+                })
+            })
+            .collect();
+        let mut fetched = Vec::with_capacity(paths.len());
+        for worker in workers {
+            let chunk = worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+            fetched.extend(chunk);
+        }
+        Ok(fetched)
+    })
+}
+
+fn fetch_access_point(conn: &Connection, path: &OwnedObjectPath) -> BackendResult<Option<(String, AccessPoint)>> {
+    let ap_proxy = ap_proxy(conn, path)?;
+    let ssid_bytes: Vec<u8> = profile::measure("load_state: access point Ssid", || {
+        ap_proxy.get_property("Ssid")
+    })
+    .map_err(dbus_error)?;
+    let ssid = ssid_text(&ssid_bytes);
+    if ssid.trim().is_empty() {
+        return Ok(None);
+    }
+    let access_point = profile::measure("load_state: access point properties", || {
+        access_point_from_proxy(&ap_proxy)
+    })?;
+    Ok(Some((ssid, access_point)))
+}
+
+/// Returns the Wi‑Fi interface MAC and, when it is associated with `ssid`, the BSSID
+/// of the access point it is connected to.
+fn hardware_addresses(conn: &Connection, ssid: &str) -> BackendResult<(String, Option<String>)> {
+    let nm = nm_proxy(conn)?;
+    let wifi_device = first_wifi_device(conn, &nm)?;
+    let wireless = wireless_proxy(conn, &wifi_device)?;
+    let device_mac: String = wireless.get_property("HwAddress").map_err(dbus_error)?;
+    let active_ap: OwnedObjectPath = wireless
+        .get_property("ActiveAccessPoint")
+        .map_err(dbus_error)?;
+    if active_ap.as_str() == "/" {
+        return Ok((device_mac, None));
+    }
+
+    let ap = ap_proxy(conn, &active_ap)?;
+    let ssid_bytes: Vec<u8> = ap.get_property("Ssid").map_err(dbus_error)?;
+    if ssid_text(&ssid_bytes) != ssid {
+        return Ok((device_mac, None));
+    }
+    let bssid: String = ap.get_property("HwAddress").map_err(dbus_error)?;
+    Ok((device_mac, Some(bssid)))
+}
+
+/// `access_point` as a network of its own, for showing it as soon as a scan
+/// finds it. The next full read groups it with the other radios of `ssid`.
+fn network_from_access_point(ssid: String, access_point: AccessPoint) -> Network {
+    let security = security_keyword(access_point.flags, access_point.wpa_flags, access_point.rsn_flags);
+    Network {
+        ssid,
+        signal_icon: icon_for_strength(access_point.strength),
+        action: NetworkAction::Connect,
+        strength: access_point.strength,
+        is_active: false,
+        is_saved: false,
+        is_secure: security != "open",
+        security,
+        access_points: vec![access_point],
+    }
+}
+
+/// Reads the properties of one AccessPoint object.
+fn access_point_from_proxy(ap: &Proxy<'_>) -> BackendResult<AccessPoint> {
+    // LastSeen is -1 for an AP that was never found by a scan; older daemons lack it.
+    let last_seen: i32 = ap.get_property("LastSeen").unwrap_or(-1);
+    Ok(AccessPoint {
+        bssid: ap.get_property("HwAddress").map_err(dbus_error)?,
+        frequency_mhz: ap.get_property("Frequency").map_err(dbus_error)?,
+        strength: ap.get_property("Strength").map_err(dbus_error)?,
+        max_bitrate_kbps: ap.get_property("MaxBitrate").map_err(dbus_error)?,
+        flags: ap.get_property("Flags").map_err(dbus_error)?,
+        wpa_flags: ap.get_property("WpaFlags").map_err(dbus_error)?,
+        rsn_flags: ap.get_property("RsnFlags").map_err(dbus_error)?,
+        last_seen: u32::try_from(last_seen).ok(),
+    })
+}
+
+/// Coarse security keyword for an access point, used for display and the `sec:` search filter.
+fn security_keyword(flags: u32, wpa_flags: u32, rsn_flags: u32) -> &'static str {
+    let enterprise = NM_802_11_AP_SEC_KEY_MGMT_802_1X | NM_802_11_AP_SEC_KEY_MGMT_EAP_SUITE_B_192;
+    if (wpa_flags | rsn_flags) & enterprise != 0 {
+        "enterprise"
+    } else if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_SAE != 0 {
+        "wpa3"
+    } else if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_OWE != 0 {
+        "owe"
+    } else if rsn_flags != 0 {
+        "wpa2"
+    } else if wpa_flags != 0 {
+        "wpa"
+    } else if flags & NM_802_11_AP_FLAGS_PRIVACY != 0 {
+        "wep"
+    } else {
+        "open"
+    }
+}
+
+fn nm_settings_proxy(conn: &Connection) -> BackendResult<Proxy<'_>> {
+    Proxy::new(
+        conn,
+        nm_consts::BUS_NAME,
+        "/org/freedesktop/NetworkManager/Settings",
+        nm_consts::SETTINGS_INTERFACE,
+    )
+    .map_err(dbus_error)
+}
+
+fn connection_proxy<'a>(
+    conn: &'a Connection,
+    path: &'a OwnedObjectPath,
+) -> BackendResult<Proxy<'a>> {
+    Proxy::new(
+        conn,
+        nm_consts::BUS_NAME,
+        path.as_str(),
+        nm_consts::CONNECTION_INTERFACE,
+    )
+    .map_err(dbus_error)
+}
+
+fn first_wifi_device(conn: &Connection, nm: &Proxy<'_>) -> BackendResult<OwnedObjectPath> {
+    let devices: Vec<OwnedObjectPath> = nm
+        .call("GetDevices", &())
+        .map_err(dbus_error)?;
+
+    for path in devices {
+        let device_type: u32 = {
+            let device = device_proxy(conn, &path)?;
+            device
+                .get_property("DeviceType")
+                .map_err(dbus_error)?
+        };
+        if device_type == NM_DEVICE_TYPE_WIFI {
+            return Ok(path);
+        }
+    }
+
+    Err(BackendError::NoWifiDevice)
+}
+
+fn wifi_device_paths(conn: &Connection) -> BackendResult<HashSet<String>> {
+    let nm = nm_proxy(conn)?;
+    let devices: Vec<OwnedObjectPath> = nm
+        .call("GetDevices", &())
+        .map_err(dbus_error)?;
+    Ok(devices
+        .into_iter()
+        .filter(|path| is_wifi_device(conn, path))
+        .map(|path| path.as_str().to_string())
+        .collect())
+}
+
+fn is_wifi_device(conn: &Connection, path: &OwnedObjectPath) -> bool {
+    device_proxy(conn, path)
+        .and_then(|device| {
+            device
+                .get_property::<u32>("DeviceType")
+                .map_err(dbus_error)
+        })
+        .is_ok_and(|device_type| device_type == NM_DEVICE_TYPE_WIFI)
+}
+
+fn events_from_signal(
+    conn: &Connection,
+    message: &Message,
+    wifi_devices: &mut HashSet<String>,
+) -> Vec<BackendEvent> {
+    let header = message.header();
+    let (Some(interface), Some(member), Some(path)) =
+        (header.interface(), header.member(), header.path())
+    else {
+        return Vec::new();
+    };
+    let path = path.as_str().to_string();
+    let body = message.body();
+
+    match (interface.as_str(), member.as_str()) {
+        (nm_consts::PROPERTIES_INTERFACE, "PropertiesChanged") => {
+            let Ok((iface, changed, _invalidated)) =
+                body.deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+            else {
+                return Vec::new();
+            };
+            properties_changed_events(&path, &iface, &changed, wifi_devices)
+        }
+        (nm_consts::NM_INTERFACE, "StateChanged") => vec![BackendEvent::NetworkStateChanged],
+        (nm_consts::NM_INTERFACE, "DeviceAdded") => {
+            let Ok(device) = body.deserialize::<OwnedObjectPath>() else {
+                return Vec::new();
+            };
+            if is_wifi_device(conn, &device) {
+                wifi_devices.insert(device.as_str().to_string());
+            }
+            vec![BackendEvent::DeviceAdded]
+        }
+        (nm_consts::NM_INTERFACE, "DeviceRemoved") => {
+            let Ok(device) = body.deserialize::<OwnedObjectPath>() else {
+                return Vec::new();
+            };
+            wifi_devices.remove(device.as_str());
+            vec![BackendEvent::DeviceRemoved]
+        }
+        (nm_consts::DEVICE_INTERFACE, "StateChanged") if wifi_devices.contains(&path) => body
+            .deserialize::<(u32, u32, u32)>()
+            .map(|(state, _old, reason)| vec![BackendEvent::DeviceStateChanged { state, reason }])
+            .unwrap_or_default(),
+        (nm_consts::WIFI_DEVICE_INTERFACE, "AccessPointAdded") => {
+            let Ok(access_point) = body.deserialize::<OwnedObjectPath>() else {
+                return vec![BackendEvent::AccessPointAdded(None)];
+            };
+            // Hidden networks are not listed, so there is nothing to show.
+            match fetch_access_point(conn, &access_point) {
+                Ok(Some((ssid, access_point))) => {
+                    vec![BackendEvent::AccessPointAdded(Some(network_from_access_point(ssid, access_point)))]
+                }
+                Ok(None) => Vec::new(),
+                Err(_) => vec![BackendEvent::AccessPointAdded(None)],
+            }
+        }
+        (nm_consts::WIFI_DEVICE_INTERFACE, "AccessPointRemoved") => {
+            vec![BackendEvent::AccessPointRemoved]
+        }
+        (nm_consts::ACTIVE_CONNECTION_INTERFACE, "StateChanged") => body
+            .deserialize::<(u32, u32)>()
+            .map(|(state, reason)| {
+                vec![BackendEvent::ActiveConnectionStateChanged {
+                    path,
+                    state,
+                    reason,
+                }]
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn properties_changed_events(
+    path: &str,
+    iface: &str,
+    changed: &HashMap<String, OwnedValue>,
+    wifi_devices: &HashSet<String>,
+) -> Vec<BackendEvent> {
+    let mut events = Vec::new();
+    match iface {
+        nm_consts::NM_INTERFACE => {
+            if let Some(enabled) = changed
+                .get("WirelessEnabled")
+                .and_then(|value| owned_value_to_bool(value).ok())
+            {
+                events.push(BackendEvent::WifiEnabledChanged(enabled));
+            }
+            if changed.contains_key("ActiveConnections") || changed.contains_key("PrimaryConnection") {
+                events.push(BackendEvent::ActiveConnectionsChanged);
+            }
+            if let Some(connectivity) = changed
+                .get("Connectivity")
+                .and_then(|value| owned_value_to_u32(value).ok())
+            {
+                events.push(BackendEvent::ConnectivityChanged(connectivity));
+            }
+        }
+        nm_consts::DEVICE_INTERFACE | nm_consts::WIFI_DEVICE_INTERFACE
+            if wifi_devices.contains(path)
+                && (changed.contains_key("ActiveAccessPoint")
+                    || changed.contains_key("ActiveConnection")
+                    || changed.contains_key("LastScan")) =>
+        {
+            events.push(BackendEvent::WirelessDeviceChanged);
+        }
+        _ => {}
+    }
+    events
+}
+
+fn icon_for_strength(strength: u8) -> &'static str {
+    match strength {
+        0..=20 => "network-wireless-signal-none",
+        21..=40 => "network-wireless-signal-weak",
+        41..=60 => "network-wireless-signal-ok",
+        61..=80 => "network-wireless-signal-good",
+        _ => "network-wireless-signal-excellent",
+    }
+}
+
+/// Reads what the security audit checks from a profile's settings map.
+fn audited_security(settings: &HashMap<String, HashMap<String, OwnedValue>>) -> ProfileSecurity {
+    let security = settings.get("802-11-wireless-security");
+    let get = |key: &str| security.and_then(|section| section.get(key));
+    let list = |key: &str| {
+        get(key)
+            .and_then(|value| Vec::<String>::try_from(value.try_clone().ok()?).ok())
+            .unwrap_or_default()
+    };
+    let key_mgmt = get("key-mgmt").and_then(|value| owned_value_to_string(value).ok());
+    let flags_key = if key_mgmt.as_deref() == Some("none") { "wep-key-flags" } else { "psk-flags" };
+    let verifies_server = settings
+        .get("802-1x")
+        .is_some_and(|dot1x| enterprise_certificates(dot1x).verifies_server());
+    ProfileSecurity {
+        key_mgmt,
+        proto: list("proto"),
+        pairwise: list("pairwise"),
+        secret_flags: get(flags_key)
+            .and_then(|value| owned_value_to_u32(value).ok())
+            .unwrap_or(0),
+        verifies_server,
+    }
+}
+
+fn enterprise_certificates(dot1x: &HashMap<String, OwnedValue>) -> EnterpriseCertificates {
+    let certificate = |key: &str| {
+        let bytes = Vec::<u8>::try_from(dot1x.get(key)?.try_clone().ok()?).ok()?;
+        certificate_path(&bytes)
+    };
+    EnterpriseCertificates {
+        ca_cert: certificate("ca-cert"),
+        ca_path: dot1x
+            .get("ca-path")
+            .and_then(|value| owned_value_to_string(value).ok())
+            .filter(|path| !path.is_empty()),
+        system_ca_certs: dot1x
+            .get("system-ca-certs")
+            .is_some_and(|value| owned_value_to_bool(value).unwrap_or(false)),
+        client_cert: certificate("client-cert"),
+        private_key: certificate("private-key"),
+    }
+}
+
+/// NetworkManager stores certificates as `file://` paths or `pkcs11:` URIs
+/// ending in a NUL byte, or as the certificate itself.
+fn certificate_path(bytes: &[u8]) -> Option<String> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(bytes.strip_suffix(&[0]).unwrap_or(bytes));
+    if let Some(path) = text.strip_prefix("file://") {
+        return Some(path.to_string());
+    }
+    if text.starts_with("pkcs11:") {
+        return Some(text.into_owned());
+    }
+    Some(EMBEDDED_CERTIFICATE.to_string())
+}
+
+fn certificate_value(path: &str) -> Vec<u8> {
+    let mut value = if path.starts_with("pkcs11:") {
+        path.as_bytes().to_vec()
+    } else {
+        format!("file://{path}").into_bytes()
+    };
+    value.push(0);
+    value
+}
+
+/// Encoding of `backup_profiles` blobs.
+fn backup_context() -> zvariant::serialized::Context {
+    zvariant::serialized::Context::new_dbus(zvariant::LE, 0)
+}
+
+/// What tells profiles apart when restoring: the UUID, or the name for a
+/// profile without one. Returned with the name.
+fn profile_identity(settings: &HashMap<String, HashMap<String, OwnedValue>>) -> (String, String) {
+    let field = |key: &str| {
+        settings
+            .get("connection")
+            .and_then(|connection| connection.get(key))
+            .and_then(|value| owned_value_to_string(value).ok())
+    };
+    let name = field("id").unwrap_or_default();
+    (field("uuid").unwrap_or_else(|| name.clone()), name)
+}
+
+/// The account of a `connection.permissions` entry, `user:NAME:`.
+fn permitted_user(entry: &str) -> Option<String> {
+    let name = entry.strip_prefix("user:")?;
+    Some(name.strip_suffix(':').unwrap_or(name).to_string())
+}
+
+fn ov_str(value: &str) -> OwnedValue {
+    OwnedValue::from(Str::from(value))
+}
+
+fn ov_bytes(bytes: Vec<u8>) -> BackendResult<OwnedValue> {
+    OwnedValue::try_from(Array::from(bytes))
+        .map_err(dbus_error)
+}
+
+/// `value` in the form `SettingKind::parse` reads back, if it is of a kind it knows.
+fn editor_input(value: &OwnedValue) -> Option<String> {
+    let kind = SettingKind::from_signature(value.value_signature().as_str())?;
+    let owned = value.try_clone().ok()?;
+    Some(match kind {
+        SettingKind::Text => String::try_from(owned).ok()?,
+        SettingKind::Boolean => bool::try_from(owned).ok()?.to_string(),
+        SettingKind::Int32 => i32::try_from(owned).ok()?.to_string(),
+        SettingKind::UInt32 => u32::try_from(owned).ok()?.to_string(),
+        SettingKind::Int64 => i64::try_from(owned).ok()?.to_string(),
+        SettingKind::UInt64 => u64::try_from(owned).ok()?.to_string(),
+        SettingKind::Bytes => String::from_utf8(Vec::<u8>::try_from(owned).ok()?).ok()?,
+        SettingKind::TextList => Vec::<String>::try_from(owned).ok()?.join(", "),
+    })
+}
+
+fn ov_array_dict(value: Vec<HashMap<String, OwnedValue>>) -> BackendResult<OwnedValue> {
+    OwnedValue::try_from(Array::from(value)).map_err(dbus_error)
+}
+
+fn owned_value_to_string(value: &OwnedValue) -> BackendResult<String> {
+    let owned = value
+        .try_clone()
+        .map_err(dbus_error)?;
+    String::try_from(owned).map_err(dbus_error)
+}
+
+fn owned_value_to_bool(value: &OwnedValue) -> BackendResult<bool> {
+    let owned = value
+        .try_clone()
+        .map_err(dbus_error)?;
+    bool::try_from(owned).map_err(dbus_error)
+}
+
+fn owned_value_to_u32(value: &OwnedValue) -> BackendResult<u32> {
+    let owned = value
+        .try_clone()
+        .map_err(dbus_error)?;
+    u32::try_from(owned).map_err(dbus_error)
+}
+
+fn value_to_vec_dict(
+    value: &OwnedValue,
+) -> Option<Vec<HashMap<String, OwnedValue>>> {
+    let owned = value.try_clone().ok()?;
+    Vec::<HashMap<String, OwnedValue>>::try_from(owned).ok()
+}
+
+fn first_address_from_value(value: &OwnedValue) -> Option<(String, u32)> {
+    let dicts = value_to_vec_dict(value)?;
+    let first = dicts.into_iter().next()?;
+    let address = first.get("address")?;
+    let prefix = first.get("prefix")?;
+    let addr = owned_value_to_string(address).ok()?;
+    let pre = owned_value_to_u32(prefix).ok()?;
+    Some((addr, pre))
+}
+
+fn dns_from_value(value: &OwnedValue) -> Vec<String> {
+    let Some(dicts) = value_to_vec_dict(value) else {
+        return Vec::new();
+    };
+    dicts
+        .into_iter()
+        .filter_map(|dict| dict.get("address").and_then(|v| owned_value_to_string(v).ok()))
+        .collect()
+}
+
+/// An IPv4 address as the deprecated `addresses` and `dns` settings carry it:
+/// the octets in memory order, read as a native `u32`.
+fn unpack_ipv4(packed: u32) -> String {
+    Ipv4Addr::from(packed.to_ne_bytes()).to_string()
+}
+
+fn pack_ipv4(address: &str) -> Option<u32> {
+    address.parse::<Ipv4Addr>().ok().map(|address| u32::from_ne_bytes(address.octets()))
+}
+
+/// Address, prefix and gateway of the first `[address, prefix, gateway]` entry.
+fn first_packed_address(value: &OwnedValue) -> Option<(String, u32, Option<String>)> {
+    let entries = Vec::<Vec<u32>>::try_from(value.try_clone().ok()?).ok()?;
+    let &[address, prefix, gateway] = entries.first()?.as_slice() else {
+        return None;
+    };
+    Some((unpack_ipv4(address), prefix, (gateway != 0).then(|| unpack_ipv4(gateway))))
+}
+
+fn packed_dns(value: &OwnedValue) -> Vec<String> {
+    let Some(servers) = value.try_clone().ok().and_then(|value| Vec::<u32>::try_from(value).ok()) else {
+        return Vec::new();
+    };
+    servers.into_iter().map(unpack_ipv4).collect()
+}
+
+/// Mirrors `address-data`, `gateway` and `dns-data` into the packed `addresses`
+/// and `dns`. Older NetworkManager only reads those, and a newer one sent both
+/// may go by the packed copy, so it must not be left stale.
+fn pack_ipv4_settings(ipv4: &mut HashMap<String, OwnedValue>) -> BackendResult<()> {
+    let gateway = ipv4
+        .get("gateway")
+        .and_then(|value| owned_value_to_string(value).ok())
+        .and_then(|gateway| pack_ipv4(&gateway))
+        .unwrap_or(0);
+    let addresses: Vec<Vec<u32>> = ipv4
+        .get("address-data")
+        .and_then(value_to_vec_dict)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let address = pack_ipv4(&owned_value_to_string(entry.get("address")?).ok()?)?;
+            let prefix = owned_value_to_u32(entry.get("prefix")?).ok()?;
+            Some(vec![address, prefix, if index == 0 { gateway } else { 0 }])
+        })
+        .collect();
+    let dns: Vec<u32> = ipv4
+        .get("dns-data")
+        .map(dns_from_value)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|server| pack_ipv4(server))
+        .collect();
+    if !addresses.is_empty() {
+        ipv4.insert("addresses".to_string(), OwnedValue::try_from(Array::from(addresses)).map_err(dbus_error)?);
+    }
+    if !dns.is_empty() {
+        ipv4.insert("dns".to_string(), OwnedValue::try_from(Array::from(dns)).map_err(dbus_error)?);
+    }
+    Ok(())
+}
+
+fn parse_ip_prefix(input: &str) -> (String, u32) {
+    if let Some((addr, prefix)) = input.split_once('/') {
+        if let Ok(prefix) = prefix.parse::<u32>() {
+            return (addr.to_string(), prefix);
+        }
+    }
+    (input.to_string(), 24)
+}
+
+fn connection_settings(
+    conn: &Connection,
+    path: &OwnedObjectPath,
+) -> BackendResult<HashMap<String, HashMap<String, OwnedValue>>> {
+    let proxy = connection_proxy(conn, path)?;
+    proxy
+        .call("GetSettings", &())
+        .map_err(dbus_error)
+}
+
+/// Adds the secrets of the profile at `path`, which `GetSettings` leaves out,
+/// to its `settings`. Secrets in a keyring that is locked, or owned by
+/// another user, are not handed out and stay left out.
+fn merge_secrets(
+    conn: &Connection,
+    path: &OwnedObjectPath,
+    settings: &mut HashMap<String, HashMap<String, OwnedValue>>,
+) -> BackendResult<()> {
+    let proxy = connection_proxy(conn, path)?;
+    for section in SECRET_SETTINGS {
+        if !settings.contains_key(*section) {
+            continue;
+        }
+        let secrets: Result<HashMap<String, HashMap<String, OwnedValue>>, _> = proxy.call("GetSecrets", &(section,));
+        for (section, values) in secrets.into_iter().flatten() {
+            settings.entry(section).or_default().extend(values);
+        }
+    }
+    Ok(())
+}
+
+/// Reads the profile at `path`, lets `edit` change it and writes it back.
+/// `Update2` leaves an in-memory profile in memory, where `Update` would
+/// save it to disk. From NetworkManager 1.44 on the write also carries the
+/// version that was read, so a change made elsewhere in between is refused
+/// rather than silently undone.
+fn edit_connection(
+    conn: &Connection,
+    path: &OwnedObjectPath,
+    edit: impl FnOnce(&mut HashMap<String, HashMap<String, OwnedValue>>) -> BackendResult<()>,
+) -> BackendResult<()> {
+    let proxy = connection_proxy(conn, path)?;
+    let version: Option<u64> = proxy.get_property("VersionId").ok();
+    let mut settings: HashMap<String, HashMap<String, OwnedValue>> = proxy
+        .call("GetSettings", &())
+        .map_err(dbus_error)?;
+    // NetworkManager keeps the stored secrets only if the update carries none,
+    // so any edit that sets one would drop the others.
+    merge_secrets(conn, path, &mut settings)?;
+    edit(&mut settings)?;
+
+    let mut args: HashMap<&str, OwnedValue> = HashMap::new();
+    if let Some(version) = version {
+        args.insert("version-id", OwnedValue::from(version));
+    }
+    let _: HashMap<String, OwnedValue> = proxy
+        .call("Update2", &(settings, NM_SETTINGS_UPDATE2_FLAG_NONE, args))
+        .map_err(dbus_error)?;
+    Ok(())
+}
+
+pub(crate) fn ssid_from_value(value: &OwnedValue) -> Option<String> {
+    let owned = value.try_clone().ok()?;
+    let bytes: Vec<u8> = Vec::try_from(owned).ok()?;
+    let ssid = ssid_text(&bytes);
+    if ssid.trim().is_empty() {
+        None
+    } else {
+        Some(ssid)
+    }
+}
+
+/// An SSID as text, exactly as broadcast. Surrounding spaces are kept: "Home "
+/// is another network than "Home", and often one posing as it.
+fn ssid_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn find_ap_for_ssid(
+    conn: &Connection,
+    wireless: &Proxy<'_>,
+    ssid: &str,
+    band: Option<BandLock>,
+) -> BackendResult<(OwnedObjectPath, u8)> {
+    let ap_paths: Vec<OwnedObjectPath> = wireless
+        .call("GetAccessPoints", &())
+        .map_err(dbus_error)?;
+
+    let mut best: Option<(OwnedObjectPath, u8)> = None;
+    for ap_path in ap_paths {
+        let (current_ssid, strength) = {
+            let ap = ap_proxy(conn, &ap_path)?;
+            let ssid_bytes: Vec<u8> = ap
+                .get_property("Ssid")
+                .map_err(dbus_error)?;
+            let current_ssid = ssid_text(&ssid_bytes);
+            let strength: u8 = ap
+                .get_property("Strength")
+                .map_err(dbus_error)?;
+            (current_ssid, strength)
+        };
+
+        if current_ssid != ssid {
+            continue;
+        }
+        if let Some(band) = band {
+            let frequency: u32 = ap_proxy(conn, &ap_path)?
+                .get_property("Frequency")
+                .map_err(dbus_error)?;
+            if BandLock::for_frequency(frequency) != Some(band) {
+                continue;
+            }
+        }
+        match &best {
+            Some((_, best_strength)) if *best_strength >= strength => {}
+            _ => best = Some((ap_path, strength)),
+        }
+    }
+
+    best.ok_or_else(|| BackendError::Unavailable("SSID not found".to_string()))
+}
+
+fn cipher_names(flags: u32) -> Vec<&'static str> {
+    [
+        (NM_802_11_AP_SEC_PAIR_WEP40, "WEP-40"),
+        (NM_802_11_AP_SEC_PAIR_WEP104, "WEP-104"),
+        (NM_802_11_AP_SEC_PAIR_TKIP, "TKIP"),
+        (NM_802_11_AP_SEC_PAIR_CCMP, "CCMP"),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| flags & bit != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+// NetworkManager does not expose HT/VHT/HE capabilities, so the generation is
+// estimated from the band and the advertised maximum bitrate.
+fn wifi_generation(frequency_mhz: u32, max_bitrate_kbps: u32) -> &'static str {
+    match frequency_mhz {
+        5925.. => "Wi‑Fi 6E",
+        4900.. if max_bitrate_kbps > 600_000 => "Wi‑Fi 5 or newer",
+        4900.. if max_bitrate_kbps > 54_000 => "Wi‑Fi 4",
+        4900.. => "802.11a",
+        _ if max_bitrate_kbps > 300_000 => "Wi‑Fi 6",
+        _ if max_bitrate_kbps > 54_000 => "Wi‑Fi 4",
+        _ => "802.11b/g",
+    }
+}
+
+fn key_mgmt_for_ap(conn: &Connection, ap_path: &OwnedObjectPath) -> BackendResult<Option<&'static str>> {
+    let ap = ap_proxy(conn, ap_path)?;
+    let flags: u32 = ap.get_property("Flags").map_err(dbus_error)?;
+    let wpa_flags: u32 = ap.get_property("WpaFlags").map_err(dbus_error)?;
+    let rsn_flags: u32 = ap.get_property("RsnFlags").map_err(dbus_error)?;
+    Ok(key_mgmt_for_flags(flags, wpa_flags, rsn_flags))
+}
+
+fn key_mgmt_for_flags(flags: u32, wpa_flags: u32, rsn_flags: u32) -> Option<&'static str> {
+    let key_mgmt = wpa_flags | rsn_flags;
+    if key_mgmt & NM_802_11_AP_SEC_KEY_MGMT_PSK != 0 {
+        Some("wpa-psk")
+    } else if key_mgmt & NM_802_11_AP_SEC_KEY_MGMT_SAE != 0 {
+        Some("sae")
+    } else if key_mgmt & NM_802_11_AP_SEC_KEY_MGMT_OWE != 0 {
+        Some("owe")
+    } else if flags & NM_802_11_AP_FLAGS_PRIVACY != 0 || key_mgmt != 0 {
+        Some("wpa-psk")
+    } else {
+        None
+    }
+}
+
+fn find_connection_for_ssid(
+    conn: &Connection,
+    settings: &Proxy<'_>,
+    ssid: &str,
+) -> BackendResult<Option<OwnedObjectPath>> {
+    let connections: Vec<OwnedObjectPath> = settings
+        .call("ListConnections", &())
+        .map_err(dbus_error)?;
+
+    for path in connections {
+        let is_match = {
+            let connection_proxy = Proxy::new(
+                conn,
+                nm_consts::BUS_NAME,
+                path.as_str(),
+                nm_consts::CONNECTION_INTERFACE,
+            )
+            .map_err(dbus_error)?;
+
+            let settings_map: HashMap<String, HashMap<String, OwnedValue>> = connection_proxy
+                .call("GetSettings", &())
+                .map_err(dbus_error)?;
+
+            if let Some(wireless) = settings_map.get("802-11-wireless") {
+                if let Some(ssid_value) = wireless.get("ssid") {
+                    if let Some(current_ssid) = ssid_from_value(ssid_value) {
+                        current_ssid == ssid
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                }
+            } else {
+                false
+            }
+        };
+
+        if is_match {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Saved Wi‑Fi profiles by SSID, with the security keyword their key-mgmt implies.
+fn saved_wifi_profiles(
+    conn: &Connection,
+    settings: &Proxy<'_>,
+) -> BackendResult<HashMap<String, &'static str>> {
+    let connections: Vec<OwnedObjectPath> = settings
+        .call("ListConnections", &())
+        .map_err(dbus_error)?;
+
+    let mut profiles = HashMap::new();
+    for path in connections {
+        let connection_proxy = Proxy::new(
+            conn,
+            nm_consts::BUS_NAME,
+            path.as_str(),
+            nm_consts::CONNECTION_INTERFACE,
+        )
+        .map_err(dbus_error)?;
+
+        let settings_map: HashMap<String, HashMap<String, OwnedValue>> = connection_proxy
+            .call("GetSettings", &())
+            .map_err(dbus_error)?;
+
+        let Some(ssid) = settings_map
+            .get("802-11-wireless")
+            .and_then(|wireless| wireless.get("ssid"))
+            .and_then(ssid_from_value)
+        else {
+            continue;
+        };
+        let key_mgmt = settings_map
+            .get("802-11-wireless-security")
+            .and_then(|security| security.get("key-mgmt"))
+            .and_then(|value| owned_value_to_string(value).ok());
+        profiles.insert(ssid, profile_security(key_mgmt.as_deref()));
+    }
+
+    Ok(profiles)
+}
+
+fn profile_security(key_mgmt: Option<&str>) -> &'static str {
+    match key_mgmt {
+        None => "open",
+        Some("sae") => "wpa3",
+        Some("owe") => "owe",
+        Some("none" | "ieee8021x") => "wep",
+        Some("wpa-eap" | "wpa-eap-suite-b-192") => "enterprise",
+        Some(_) => "wpa2",
+    }
+}
+
+/// Path, name and UUID of every VPN and WireGuard profile.
+fn vpn_profiles(conn: &Connection) -> BackendResult<Vec<(OwnedObjectPath, String, String)>> {
+    let settings = nm_settings_proxy(conn)?;
+    let connections: Vec<OwnedObjectPath> = settings
+        .call("ListConnections", &())
+        .map_err(dbus_error)?;
+    let mut vpns = Vec::new();
+    for path in connections {
+        let settings_map = connection_settings(conn, &path)?;
+        let Some(connection) = settings_map.get("connection") else {
+            continue;
+        };
+        let field = |key: &str| connection.get(key).and_then(|value| owned_value_to_string(value).ok());
+        if !matches!(field("type").as_deref(), Some("vpn" | "wireguard")) {
+            continue;
+        }
+        let (Some(id), Some(uuid)) = (field("id"), field("uuid")) else {
+            continue;
+        };
+        vpns.push((path, id, uuid));
+    }
+    Ok(vpns)
+}
+
+/// The settings profiles of the connections that are up.
+fn active_connection_profiles(conn: &Connection) -> BackendResult<HashSet<OwnedObjectPath>> {
+    let active: Vec<OwnedObjectPath> = nm_proxy(conn)?
+        .get_property("ActiveConnections")
+        .map_err(dbus_error)?;
+    let mut profiles = HashSet::new();
+    for path in active {
+        let active_proxy = Proxy::new(
+            conn,
+            nm_consts::BUS_NAME,
+            path.as_str(),
+            "org.freedesktop.NetworkManager.Connection.Active",
+        )
+        .map_err(dbus_error)?;
+        // A connection that went down meanwhile is simply not up.
+        if let Ok(profile) = active_proxy.get_property::<OwnedObjectPath>("Connection") {
+            profiles.insert(profile);
+        }
+    }
+    Ok(profiles)
+}
+
+fn find_active_connection_for_ssid(
+    conn: &Connection,
+    nm: &Proxy<'_>,
+    ssid: &str,
+) -> BackendResult<Option<OwnedObjectPath>> {
+    let active: Vec<OwnedObjectPath> = nm
+        .get_property("ActiveConnections")
+        .map_err(dbus_error)?;
+
+    for path in active {
+        let is_match = {
+            let active_proxy = Proxy::new(
+                conn,
+                nm_consts::BUS_NAME,
+                path.as_str(),
+                "org.freedesktop.NetworkManager.Connection.Active",
+            )
+            .map_err(dbus_error)?;
+
+            let connection: OwnedObjectPath = active_proxy
+                .get_property("Connection")
+                .map_err(dbus_error)?;
+
+            let settings_proxy = Proxy::new(
+                conn,
+                nm_consts::BUS_NAME,
+                connection.as_str(),
+                nm_consts::CONNECTION_INTERFACE,
+            )
+            .map_err(dbus_error)?;
+
+            let settings_map: HashMap<String, HashMap<String, OwnedValue>> = settings_proxy
+                .call("GetSettings", &())
+                .map_err(dbus_error)?;
+
+            if let Some(wireless) = settings_map.get("802-11-wireless") {
+                if let Some(ssid_value) = wireless.get("ssid") {
+                    if let Some(current_ssid) = ssid_from_value(ssid_value) {
+                        current_ssid == ssid
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                }
+            } else {
+                false
+            }
+        };
+
+        if is_match {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
+
+fn active_connection_info_for_device(
+    conn: &Connection,
+    device_path: &OwnedObjectPath,
+) -> BackendResult<(Option<OwnedObjectPath>, bool)> {
+    let device = device_proxy(conn, device_path)?;
+    let active: OwnedObjectPath = device
+        .get_property("ActiveConnection")
+        .map_err(dbus_error)?;
+
+    if active.as_str() == "/" {
+        return Ok((None, false));
+    }
+
+    let active_proxy = Proxy::new(
+        conn,
+        nm_consts::BUS_NAME,
+        active.as_str(),
+        "org.freedesktop.NetworkManager.Connection.Active",
+    )
+    .map_err(dbus_error)?;
+
+    let state: u32 = active_proxy
+        .get_property("State")
+        .map_err(dbus_error)?;
+    let activated = state == 2;
+    if !activated {
+        return Ok((None, false));
+    }
+
+    let specific: OwnedObjectPath = active_proxy
+        .get_property("SpecificObject")
+        .map_err(dbus_error)?;
+
+    if specific.as_str() == "/" {
+        Ok((None, true))
+    } else {
+        Ok((Some(specific), true))
+    }
+}
@@ -0,0 +1,153 @@
+//! NetworkManager's secret agent interface. While an 802.1X network
+//! activates, NetworkManager asks registered agents for the username and
+//! password it lacks; without one the attempt sits in NEED_AUTH until it
+//! times out.
+
+use crate::backend::nm::{dbus_error, nm_consts, ssid_from_value};
+use crate::backend::BackendResult;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Str};
+use zbus::{interface, DBusError};
+
+pub(crate) const AGENT_PATH: &str = "/org/freedesktop/NetworkManager/SecretAgent";
+pub(crate) const AGENT_INTERFACE: &str = "org.freedesktop.NetworkManager.SecretAgent";
+const AGENT_MANAGER_PATH: &str = "/org/freedesktop/NetworkManager/AgentManager";
+const AGENT_MANAGER_INTERFACE: &str = "org.freedesktop.NetworkManager.AgentManager";
+const IDENTIFIER: &str = "com.yufi.app";
+
+pub(crate) const NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION: u32 = 0x1;
+pub(crate) const NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW: u32 = 0x2;
+const DOT1X: &str = "802-1x";
+
+type SettingsMap = HashMap<String, HashMap<String, OwnedValue>>;
+type Reply = async_channel::Sender<Option<Credentials>>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credentials {
+    pub identity: String,
+    pub password: String,
+}
+
+/// NetworkManager asking for the sign-in of an 802.1X network.
+#[derive(Debug)]
+pub struct SecretRequest {
+    pub ssid: String,
+    /// The username saved in the profile, if any.
+    pub identity: Option<String>,
+    /// The credentials NetworkManager had were turned down.
+    pub retry: bool,
+    reply: Reply,
+}
+
+impl SecretRequest {
+    /// Hands `credentials` to NetworkManager; None gives up on the connection.
+    pub fn answer(&self, credentials: Option<Credentials>) {
+        let _ = self.reply.try_send(credentials);
+    }
+
+    /// Whether the request was answered, or NetworkManager stopped waiting.
+    pub fn is_closed(&self) -> bool {
+        self.reply.is_closed()
+    }
+}
+
+pub type SecretRequests = async_channel::Receiver<SecretRequest>;
+
+#[derive(DBusError, Debug)]
+#[zbus(prefix = "org.freedesktop.NetworkManager.SecretAgent")]
+enum AgentError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NoSecrets(String),
+    UserCanceled(String),
+}
+
+struct SecretAgent {
+    requests: async_channel::Sender<SecretRequest>,
+    /// Requests still waiting for an answer, by profile path and setting.
+    pending: Arc<Mutex<HashMap<(String, String), Reply>>>,
+}
+
+fn lock(pending: &Mutex<HashMap<(String, String), Reply>>) -> MutexGuard<'_, HashMap<(String, String), Reply>> {
+    pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn text(value: Option<&OwnedValue>) -> Option<String> {
+    let text = String::try_from(value?.try_clone().ok()?).ok()?;
+    (!text.is_empty()).then_some(text)
+}
+
+#[interface(name = "org.freedesktop.NetworkManager.SecretAgent")]
+impl SecretAgent {
+    /// Only 802.1X sign-ins the user started are asked for; anything else is
+    /// left to the next agent, or to the password YuFi sends when connecting.
+    async fn get_secrets(
+        &self,
+        connection: SettingsMap,
+        connection_path: OwnedObjectPath,
+        setting_name: String,
+        _hints: Vec<String>,
+        flags: u32,
+    ) -> Result<SettingsMap, AgentError> {
+        if setting_name != DOT1X || flags & NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION == 0 {
+            return Err(AgentError::NoSecrets(format!("YuFi does not provide {setting_name} secrets")));
+        }
+        let ssid = connection
+            .get("802-11-wireless")
+            .and_then(|wireless| wireless.get("ssid"))
+            .and_then(ssid_from_value)
+            .or_else(|| text(connection.get("connection").and_then(|section| section.get("id"))))
+            .unwrap_or_default();
+        let identity = text(connection.get(DOT1X).and_then(|dot1x| dot1x.get("identity")));
+
+        let (reply, answer) = async_channel::bounded(1);
+        let key = (connection_path.to_string(), setting_name);
+        lock(&self.pending).insert(key.clone(), reply.clone());
+        let request = SecretRequest {
+            ssid,
+            identity,
+            retry: flags & NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW != 0,
+            reply,
+        };
+        let credentials = match self.requests.send(request).await {
+            Ok(()) => answer.recv().await.ok().flatten(),
+            Err(_) => None,
+        };
+        lock(&self.pending).remove(&key);
+
+        let credentials = credentials.ok_or_else(|| AgentError::UserCanceled("The sign-in was cancelled".to_string()))?;
+        let mut dot1x = HashMap::new();
+        dot1x.insert("identity".to_string(), OwnedValue::from(Str::from(credentials.identity)));
+        dot1x.insert("password".to_string(), OwnedValue::from(Str::from(credentials.password)));
+        Ok(HashMap::from([(DOT1X.to_string(), dot1x)]))
+    }
+
+    fn cancel_get_secrets(&self, connection_path: OwnedObjectPath, setting_name: String) {
+        if let Some(reply) = lock(&self.pending).remove(&(connection_path.to_string(), setting_name)) {
+            let _ = reply.try_send(None);
+        }
+    }
+
+    /// NetworkManager keeps the secrets in the profile; only ones flagged as
+    /// agent-owned would be handed here, and YuFi stores none of its own.
+    fn save_secrets(&self, _connection: SettingsMap, _connection_path: OwnedObjectPath) {}
+
+    fn delete_secrets(&self, _connection: SettingsMap, _connection_path: OwnedObjectPath) {}
+}
+
+/// Serves the agent on `conn` and registers it with NetworkManager, which
+/// forgets it once `conn` closes.
+pub(crate) fn register(conn: &Connection) -> BackendResult<SecretRequests> {
+    let (requests, receiver) = async_channel::unbounded();
+    let agent = SecretAgent {
+        requests,
+        pending: Arc::default(),
+    };
+    conn.object_server().at(AGENT_PATH, agent).map_err(dbus_error)?;
+    let manager = Proxy::new(conn, nm_consts::BUS_NAME, AGENT_MANAGER_PATH, AGENT_MANAGER_INTERFACE)
+        .map_err(dbus_error)?;
+    let _: () = manager.call("Register", &(IDENTIFIER,)).map_err(dbus_error)?;
+    Ok(receiver)
+}
@@ -0,0 +1,1051 @@
+use crate::backend::mock_nm::{
+    DEVICE_MAC, ETHERNET_MAC, GATEWAY, LEASED_ADDRESS, MOCK_LAST_SEEN, MockAccessPoint, MockNetworkManager,
+    NM_VERSION, PERMANENT_MAC,
+};
+use crate::backend::secret_agent::{
+    Credentials, NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION, NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW,
+};
+use crate::backend::{Backend, BackendError, BackendEvent, SCAN_COOLDOWN};
+use crate::models::{
+    AccessPoint, AuditFinding, BandLock, DeviceKind, EMBEDDED_CERTIFICATE, EnterpriseCertificates, ImportedProfile, Network, NetworkAction, SettingKind, SettingValue, WeakSecurity,
+    audit_profile, best_available, copy_name, duplicate_profile_groups, ranked_priorities,
+};
+use std::time::Duration;
+
+fn start(access_points: Vec<MockAccessPoint>) -> Option<MockNetworkManager> {
+    let mock = MockNetworkManager::start(access_points);
+    if mock.is_none() {
+        eprintln!("dbus-daemon not available, skipping mocked NetworkManager test");
+    }
+    mock
+}
+
+fn neighbourhood() -> Vec<MockAccessPoint> {
+    vec![
+        MockAccessPoint::secured("Home", 70, "correct horse"),
+        MockAccessPoint::secured("Home", 40, "correct horse"),
+        MockAccessPoint::open("Cafe", 55),
+        MockAccessPoint::secured("Office", 90, "hunter22"),
+    ]
+}
+
+#[test]
+fn scan_requests_and_lists_networks() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    backend.request_scan().expect("scan");
+    assert_eq!(mock.scan_count(), 1);
+
+    let state = backend.load_state().expect("state");
+    assert!(state.wifi_enabled);
+    let ssids: Vec<&str> = state.networks.iter().map(|n| n.ssid.as_str()).collect();
+    assert_eq!(ssids, ["Office", "Home", "Cafe"]);
+
+    let home = &state.networks[1];
+    assert_eq!(home.strength, 70);
+    assert!(home.is_secure);
+    assert!(!home.is_saved);
+    assert!(matches!(home.action, NetworkAction::Connect));
+
+    assert!(state.networks[0].is_saved);
+    assert!(!state.networks[2].is_secure);
+
+    assert_eq!(home.security, "wpa2");
+    assert_eq!(home.access_points.len(), 2);
+    assert!(home.access_points.iter().all(|ap| ap.frequency_mhz == 5180));
+    assert_eq!(state.networks[2].security, "open");
+}
+
+#[test]
+fn saved_networks_out_of_range_are_listed_separately() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_saved("Holiday Flat", Some("sunshine"));
+    mock.add_saved("Airport Free", None);
+    let backend = mock.backend();
+
+    let state = backend.load_state().expect("state");
+    let away: Vec<(&str, &str)> = state
+        .out_of_range
+        .iter()
+        .map(|network| (network.ssid.as_str(), network.security))
+        .collect();
+    assert_eq!(away, [("Airport Free", "open"), ("Holiday Flat", "wpa2")]);
+    assert!(state.out_of_range.iter().all(|network| {
+        network.is_saved && matches!(network.action, NetworkAction::None)
+    }));
+
+    backend.set_wifi_enabled(false).expect("radio off");
+    assert!(backend.load_state().expect("state").out_of_range.is_empty());
+}
+
+#[test]
+fn connect_with_password_activates_new_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    let active = backend
+        .connect_network("Home", Some("correct horse"), true, None)
+        .expect("connect")
+        .expect("active connection path");
+    assert_eq!(mock.active_state(&active), Some(2));
+    assert_eq!(mock.saved_ssids(), ["Home"]);
+
+    let state = backend.load_state().expect("state");
+    let home = state.networks.iter().find(|n| n.ssid == "Home").unwrap();
+    assert!(home.is_active);
+    assert!(home.is_saved);
+    assert!(matches!(home.action, NetworkAction::Disconnect));
+
+    backend.disconnect_network("Home").expect("disconnect");
+    let state = backend.load_state().expect("state");
+    assert!(state.networks.iter().all(|n| !n.is_active));
+}
+
+#[test]
+fn connect_reuses_saved_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    let active = backend
+        .connect_network("Office", None, true, None)
+        .expect("connect")
+        .expect("active connection path");
+    assert_eq!(mock.active_state(&active), Some(2));
+    assert_eq!(mock.saved_ssids(), ["Office"]);
+}
+
+#[test]
+fn band_rows_pick_a_radio_without_locking_the_profile() {
+    let Some(mock) = start(vec![
+        MockAccessPoint::secured("Home", 70, "correct horse").on(2437),
+        MockAccessPoint::secured("Home", 40, "correct horse").on(5180),
+        MockAccessPoint::secured("Office", 90, "hunter22").on(2412),
+    ]) else {
+        return;
+    };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+    let locked = |ssid| mock.saved_settings(ssid).expect("profile")["802-11-wireless"].contains_key("band");
+
+    backend
+        .connect_network("Home", Some("correct horse"), true, Some(BandLock::Five))
+        .expect("connect");
+    let details = backend.get_network_details("Home").expect("details");
+    assert_eq!(details.bssid.as_deref(), Some("02:00:00:00:00:02"));
+    assert!(!locked("Home"));
+
+    backend
+        .connect_network("Office", None, true, Some(BandLock::TwoPointFour))
+        .expect("connect");
+    assert!(!locked("Office"));
+}
+
+#[test]
+fn wrong_password_deactivates_connection() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    let active = backend
+        .connect_network("Home", Some("wrong"), true, None)
+        .expect("activation is accepted")
+        .expect("active connection path");
+    assert_eq!(mock.active_state(&active), Some(4));
+
+    let state = backend.load_state().expect("state");
+    assert!(state.networks.iter().all(|n| !n.is_active));
+}
+
+#[test]
+fn missing_secrets_are_reported() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    let err = backend.connect_network("Home", None, true, None).unwrap_err();
+    let BackendError::Unavailable(message) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert!(message.contains("NoSecrets"), "{message}");
+}
+
+#[test]
+fn edits_keep_other_settings_and_do_not_undo_changes_made_meanwhile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    let zone = SettingValue::Text("work".to_string());
+    backend.set_raw_setting("Office", "connection", "zone", &zone).expect("zone");
+    backend.set_autoreconnect("Office", false).expect("autoconnect");
+    backend
+        .set_ip_dns("Office", None, None, None, Some(vec!["9.9.9.9".to_string()]))
+        .expect("dns");
+    let raw = backend.raw_settings("Office").expect("settings");
+    assert_eq!(raw["connection"]["zone"].input.as_deref(), Some("work"));
+    assert_eq!(raw["connection"]["autoconnect"].input.as_deref(), Some("false"));
+    assert_eq!(backend.get_saved_password("Office").unwrap().as_deref(), Some("hunter22"));
+
+    mock.edit_after_read();
+    let err = backend.set_autoreconnect("Office", true).unwrap_err();
+    let BackendError::Unavailable(message) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert!(message.contains("VersionIdMismatch"), "{message}");
+    let raw = backend.raw_settings("Office").expect("settings");
+    assert_eq!(raw["connection"]["autoconnect"].input.as_deref(), Some("false"));
+}
+
+#[test]
+fn secret_agent_asks_for_enterprise_sign_ins() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", None);
+    let backend = mock.backend();
+    let identity = SettingValue::Text("alice".to_string());
+    backend.set_raw_setting("Office", "802-1x", "identity", &identity).expect("identity");
+    let requests = backend.register_secret_agent().expect("agent");
+    let interactive = NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION;
+
+    let err = mock.request_secrets("Office", 0).unwrap_err();
+    assert!(err.to_string().contains("NoSecrets"), "{err}");
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let request = requests.recv_blocking().expect("request");
+            assert_eq!(request.ssid, "Office");
+            assert_eq!(request.identity.as_deref(), Some("alice"));
+            assert!(request.retry);
+            request.answer(Some(Credentials {
+                identity: "alice".to_string(),
+                password: "s3cret".to_string(),
+            }));
+        });
+        let secrets = mock
+            .request_secrets("Office", interactive | NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW)
+            .expect("secrets");
+        let text = |key: &str| String::try_from(secrets["802-1x"][key].try_clone().unwrap()).unwrap();
+        assert_eq!((text("identity"), text("password")), ("alice".to_string(), "s3cret".to_string()));
+    });
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let request = requests.recv_blocking().expect("request");
+            mock.cancel_secrets("Office");
+            while !request.is_closed() {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        });
+        let err = mock.request_secrets("Office", interactive).unwrap_err();
+        assert!(err.to_string().contains("UserCanceled"), "{err}");
+    });
+}
+
+#[test]
+fn renew_lease_reactivates_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    assert!(backend.renew_lease("Cafe").is_err());
+    let first = backend
+        .connect_network("Cafe", None, true, None)
+        .expect("connect")
+        .expect("active connection path");
+    let renewed = backend.renew_lease("Cafe").expect("renew");
+    assert_ne!(first, renewed);
+    assert_eq!(mock.active_state(&renewed), Some(2));
+    assert_eq!(mock.saved_ssids(), ["Cafe"]);
+}
+
+#[test]
+fn forget_removes_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    backend.forget_network("Cafe").expect("forget");
+    assert!(mock.saved_ssids().is_empty());
+
+    let state = backend.load_state().expect("state");
+    let cafe = state.networks.iter().find(|n| n.ssid == "Cafe").unwrap();
+    assert!(!cafe.is_saved);
+    assert!(!cafe.is_active);
+
+    assert!(backend.forget_network("Cafe").is_err());
+}
+
+#[test]
+fn autoreconnect_is_written_to_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    backend.set_autoreconnect("Cafe", false).expect("update");
+    let settings = mock.saved_settings("Cafe").expect("profile");
+    let autoconnect = settings["connection"]["autoconnect"].try_clone().unwrap();
+    assert!(!bool::try_from(autoconnect).unwrap());
+    assert_eq!(backend.get_network_details("Cafe").unwrap().auto_reconnect, Some(false));
+}
+
+#[test]
+fn imported_profile_is_saved_without_connecting() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+    let profile = ImportedProfile {
+        id: "Office (imported)".to_string(),
+        ssid: "Office".to_string(),
+        key_mgmt: Some("wpa-psk".to_string()),
+        password: Some("hunter22".to_string()),
+        hidden: true,
+        autoconnect: false,
+    };
+
+    backend.add_profile(&profile).expect("import");
+    assert_eq!(mock.saved_ssids(), ["Office"]);
+    assert_eq!(backend.get_saved_password("Office").unwrap().as_deref(), Some("hunter22"));
+    let settings = mock.saved_settings("Office").expect("profile");
+    assert!(bool::try_from(settings["802-11-wireless"]["hidden"].try_clone().unwrap()).unwrap());
+    let office = backend.load_state().unwrap();
+    let office = office.networks.iter().find(|n| n.ssid == "Office").unwrap();
+    assert!(office.is_saved && !office.is_active);
+}
+
+#[test]
+fn raw_settings_show_every_key_but_secrets() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    let raw = backend.raw_settings("Office").expect("settings");
+    let id = &raw["connection"]["id"];
+    assert_eq!((id.signature.as_str(), id.text.as_str()), ("s", "\"Office\""));
+    assert_eq!(raw["connection"]["autoconnect"].text, "true");
+    assert_eq!(raw["802-11-wireless"]["ssid"].signature, "ay");
+    assert_eq!(raw["802-11-wireless-security"]["key-mgmt"].text, "\"wpa-psk\"");
+    assert!(!raw["802-11-wireless-security"].contains_key("psk"));
+    assert!(backend.raw_settings("Cafe").is_err());
+}
+
+#[test]
+fn raw_settings_are_parsed_by_type_and_written() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    assert!(SettingKind::UInt32.parse("-1").is_err());
+    assert!(SettingKind::Boolean.parse("maybe").is_err());
+    assert_eq!(SettingKind::Boolean.parse(" Yes "), Ok(SettingValue::Boolean(true)));
+    let search = SettingKind::TextList.parse("lan, home.arpa,").unwrap();
+    assert_eq!(search, SettingValue::TextList(vec!["lan".to_string(), "home.arpa".to_string()]));
+
+    let retries = SettingKind::Int32.parse("3").unwrap();
+    backend.set_raw_setting("Office", "connection", "autoconnect-retries", &retries).expect("retries");
+    backend.set_raw_setting("Office", "ipv4", "dns-search", &search).expect("dns-search");
+    let raw = backend.raw_settings("Office").expect("settings");
+    assert_eq!(raw["connection"]["autoconnect-retries"].input.as_deref(), Some("3"));
+    assert_eq!(raw["ipv4"]["dns-search"].input.as_deref(), Some("lan, home.arpa"));
+    assert_eq!(raw["802-11-wireless"]["ssid"].input.as_deref(), Some("Office"));
+    assert_eq!(raw["connection"]["id"].input.as_deref(), Some("Office"));
+}
+
+#[test]
+fn interface_binding_is_shown_and_cleared() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+    let uuid = SettingValue::Text("0b5a5f3e-8f0a-4d47-9a52-7e1f3c1d2b6a".to_string());
+    backend.set_raw_setting("Office", "connection", "uuid", &uuid).expect("uuid");
+    let binding = SettingValue::Text("wlx00c0ca000001".to_string());
+    backend.set_raw_setting("Office", "connection", "interface-name", &binding).expect("bind");
+
+    let details = backend.get_network_details("Office").expect("details");
+    assert_eq!(details.uuid.as_deref(), Some("0b5a5f3e-8f0a-4d47-9a52-7e1f3c1d2b6a"));
+    assert_eq!(details.interface_name.as_deref(), Some("wlx00c0ca000001"));
+
+    backend.clear_interface_binding("Office").expect("clear");
+    let details = backend.get_network_details("Office").expect("details");
+    assert_eq!(details.interface_name, None);
+    assert!(details.uuid.is_some());
+}
+
+#[test]
+fn personal_profiles_are_limited_to_one_account_and_device() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    backend.set_personal("Office", Some("alex")).expect("personal");
+    let raw = backend.raw_settings("Office").expect("settings");
+    assert_eq!(raw["connection"]["permissions"].input.as_deref(), Some("user:alex:"));
+    let details = backend.get_network_details("Office").expect("details");
+    assert_eq!(details.permitted_users, vec!["alex".to_string()]);
+    assert!(details.interface_name.is_some());
+
+    backend.set_personal("Office", None).expect("shared");
+    let details = backend.get_network_details("Office").expect("details");
+    assert!(details.permitted_users.is_empty());
+    assert_eq!(details.interface_name, None);
+}
+
+#[test]
+fn traffic_counters_turn_on_statistics_and_report_metered() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    mock.add_traffic(1_000, 100);
+    assert_eq!(backend.traffic_counters().expect("counters").rx_bytes, 0);
+
+    mock.add_traffic(5_000, 700);
+    backend.set_metered("Cafe", true).expect("metered");
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let counters = backend.traffic_counters().expect("counters");
+    assert_eq!((counters.rx_bytes, counters.tx_bytes), (5_000, 700));
+    assert!(counters.metered);
+}
+
+#[test]
+fn link_status_reports_bitrate_only_while_connected() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+    let idle = backend.link_status().expect("link");
+    assert_eq!((idle.interface.as_str(), idle.bitrate_kbps), ("wlan0", 0));
+
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    assert_eq!(backend.link_status().expect("link").bitrate_kbps, 433_300);
+}
+
+#[test]
+fn metered_is_written_to_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    let metered = || {
+        let settings = mock.saved_settings("Cafe").expect("profile");
+        i32::try_from(settings["connection"]["metered"].try_clone().unwrap()).unwrap()
+    };
+
+    backend.set_metered("Cafe", true).expect("metered");
+    assert_eq!(metered(), 1);
+    backend.set_metered("Cafe", false).expect("unmetered");
+    assert_eq!(metered(), 0);
+    assert!(backend.set_metered("Elsewhere", true).is_err());
+}
+
+#[test]
+fn dns_priority_is_written_for_both_families() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    assert_eq!(backend.get_network_details("Cafe").unwrap().dns_priority, None);
+
+    backend.set_dns_priority("Cafe", -10).expect("priority");
+    let settings = mock.saved_settings("Cafe").expect("profile");
+    for family in ["ipv4", "ipv6"] {
+        let priority = settings[family]["dns-priority"].try_clone().unwrap();
+        assert_eq!(i32::try_from(priority).unwrap(), -10);
+    }
+    assert_eq!(backend.get_network_details("Cafe").unwrap().dns_priority, Some(-10));
+}
+
+#[test]
+fn proxy_round_trips_through_the_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    let pac_url = || backend.get_network_details("Cafe").expect("details").proxy_pac_url;
+    assert_eq!(pac_url(), None);
+
+    backend.set_proxy("Cafe", "http://wpad.cafe/wpad.dat").expect("proxy on");
+    assert_eq!(pac_url().as_deref(), Some("http://wpad.cafe/wpad.dat"));
+
+    backend.set_proxy("Cafe", "").expect("proxy off");
+    assert_eq!(pac_url(), None);
+    let settings = mock.saved_settings("Cafe").expect("profile");
+    assert!(!settings["proxy"].contains_key("pac-url"));
+}
+
+#[test]
+fn checkpoint_rolls_back_manual_addressing() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let has_ipv4 = || mock.saved_settings("Cafe").expect("profile").contains_key("ipv4");
+
+    let checkpoint = backend.checkpoint_create(60).expect("checkpoint");
+    backend
+        .set_ip_dns("Cafe", Some("10.0.0.5"), Some(24), Some("10.0.0.1"), None)
+        .expect("manual addressing");
+    backend.reapply_connection().expect("reapply");
+    assert_eq!(mock.reapply_count(), 1);
+    assert!(has_ipv4());
+
+    backend.checkpoint_rollback(&checkpoint).expect("rollback");
+    assert!(!has_ipv4());
+    assert_eq!(mock.checkpoint_count(), 0);
+
+    let checkpoint = backend.checkpoint_create(60).expect("checkpoint");
+    backend.checkpoint_destroy(&checkpoint).expect("keep changes");
+    assert_eq!(mock.checkpoint_count(), 0);
+    assert!(backend.checkpoint_rollback(&checkpoint).is_err());
+}
+
+#[test]
+fn manual_addressing_round_trips_on_older_networkmanager() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    mock.emulate_legacy_ipv4();
+    let backend = mock.backend();
+
+    let dns = Some(vec!["9.9.9.9".to_string(), "1.1.1.1".to_string()]);
+    backend
+        .set_ip_dns("Cafe", Some("10.0.0.5"), Some(24), Some("10.0.0.1"), dns)
+        .expect("manual addressing");
+    let ipv4 = &mock.saved_settings("Cafe").expect("profile")["ipv4"];
+    assert!(!ipv4.contains_key("address-data"));
+
+    let details = backend.get_network_details("Cafe").expect("details");
+    assert_eq!(details.ip_address.as_deref(), Some("10.0.0.5"));
+    assert_eq!(details.prefix, Some(24));
+    assert_eq!(details.gateway.as_deref(), Some("10.0.0.1"));
+    assert_eq!(details.dns_servers, vec!["9.9.9.9".to_string(), "1.1.1.1".to_string()]);
+}
+
+#[test]
+fn link_local_replaces_manual_addressing_and_back() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+    backend
+        .set_ip_dns("Cafe", Some("10.0.0.5"), Some(24), Some("10.0.0.1"), None)
+        .expect("manual addressing");
+
+    backend.set_link_local("Cafe", true).expect("link-local");
+    let details = backend.get_network_details("Cafe").expect("details");
+    assert!(details.link_local);
+    assert_eq!(details.ip_address, None);
+    assert_eq!(details.gateway, None);
+
+    backend.set_link_local("Cafe", false).expect("dhcp");
+    let raw = backend.raw_settings("Cafe").expect("settings");
+    assert_eq!(raw["ipv4"]["method"].input.as_deref(), Some("auto"));
+    assert!(!backend.get_network_details("Cafe").expect("details").link_local);
+}
+
+#[test]
+fn details_include_device_mac_and_active_bssid() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let details = backend.get_network_details("Cafe").expect("details");
+    assert_eq!(details.device_mac.as_deref(), Some(DEVICE_MAC));
+    assert_eq!(details.bssid.as_deref(), Some("02:00:00:00:00:03"));
+
+    let other = backend.get_network_details("Office").expect("details");
+    assert_eq!(other.device_mac.as_deref(), Some(DEVICE_MAC));
+    assert_eq!(other.bssid, None);
+}
+
+#[test]
+fn diagnostics_report_version_device_and_connectivity() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    let report = backend.diagnostics().expect("diagnostics");
+    assert_eq!(report.version, NM_VERSION);
+    assert!(report.wireless_enabled && report.wireless_hardware_enabled);
+    assert_eq!(report.connectivity, 1);
+    let device = report.device.expect("wifi device");
+    assert_eq!(device.interface, "wlan0");
+    assert_eq!(device.driver, "mac80211_hwsim");
+    assert_eq!((device.mac.as_str(), device.permanent_mac.as_str()), (DEVICE_MAC, PERMANENT_MAC));
+    assert!(device.mac_randomized());
+
+    backend.reload_config().expect("reload");
+    assert_eq!(mock.reload_count(), 1);
+
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    assert_eq!(backend.diagnostics().unwrap().connectivity, 4);
+}
+
+#[test]
+fn lists_devices_of_every_type() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    let devices = backend.list_devices().expect("devices");
+    assert_eq!(devices.len(), 2);
+    let wifi = devices.iter().find(|device| device.kind == DeviceKind::Wifi).unwrap();
+    assert_eq!((wifi.interface.as_str(), wifi.mac.as_str()), ("wlan0", DEVICE_MAC));
+    assert_eq!(wifi.state_label(), "disconnected");
+    let wired = devices.iter().find(|device| device.kind == DeviceKind::Ethernet).unwrap();
+    assert_eq!((wired.driver.as_str(), wired.mac.as_str()), ("e1000e", ETHERNET_MAC));
+    assert_eq!(wired.state_label(), "unavailable");
+
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let devices = backend.diagnostics().expect("diagnostics").devices;
+    assert!(devices.iter().any(|device| device.interface == "wlan0" && device.state == 100));
+}
+
+#[test]
+fn ip_config_follows_active_connection() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    assert!(backend.ip_config().expect("ip config").is_none());
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let config = backend.ip_config().unwrap().expect("address after connect");
+    assert_eq!((config.address.as_str(), config.prefix), (LEASED_ADDRESS, 24));
+    assert_eq!(config.gateway.as_deref(), Some(GATEWAY));
+    assert_eq!(config.dns_servers, vec![GATEWAY.to_string()]);
+    assert!(config.dhcp);
+}
+
+#[test]
+fn dhcp_lease_is_read_while_connected() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    assert!(backend.dhcp_leases().expect("leases").ipv4.is_none());
+    backend.connect_network("Cafe", None, true, None).expect("connect");
+    let leases = backend.dhcp_leases().expect("leases");
+    let lease = leases.ipv4.expect("lease after connect");
+    assert_eq!(lease.server(), Some(GATEWAY));
+    assert_eq!(lease.lease_time_secs(), Some(86_400));
+    assert_eq!(lease.domain(), Some("lan"));
+    assert_eq!(lease.ntp_servers(), ["192.168.1.1", "192.168.1.2"]);
+    assert!(leases.ipv6.is_none());
+}
+
+#[test]
+fn subscription_reports_wifi_toggle() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    let events = backend.subscribe().expect("subscribe");
+    backend.set_wifi_enabled(false).expect("disable wifi");
+    let toggled = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(2)).ok())
+        .any(|event| matches!(event, BackendEvent::WifiEnabledChanged(false)));
+    assert!(toggled);
+}
+
+#[test]
+fn subscription_reports_captive_portal() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+    backend.connect_network("Office", None, true, None).expect("connect");
+
+    let events = backend.subscribe().expect("subscribe");
+    mock.set_portal(true);
+    let portal = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(2)).ok())
+        .any(|event| matches!(event, BackendEvent::ConnectivityChanged(2)));
+    assert!(portal);
+}
+
+#[test]
+fn access_points_show_up_as_a_scan_finds_them() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Library", None);
+    let backend = mock.backend();
+    let mut state = backend.load_state().expect("state");
+    assert!(state.out_of_range.iter().any(|network| network.ssid == "Library"));
+
+    let events = backend.subscribe().expect("subscribe");
+    mock.add_access_point(MockAccessPoint::open("Library", 95));
+    let found = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(2)).ok())
+        .find_map(|event| match event {
+            BackendEvent::AccessPointAdded(network) => network,
+            _ => None,
+        })
+        .expect("new access point");
+    assert_eq!(found.ssid, "Library");
+    assert_eq!(found.security, "open");
+
+    state.add_found(found);
+    assert_eq!(state.networks[0].ssid, "Library");
+    assert!(state.networks[0].is_saved);
+    assert_eq!(state.networks[0].access_points.len(), 1);
+    assert!(!state.out_of_range.iter().any(|network| network.ssid == "Library"));
+}
+
+#[test]
+fn unplugging_the_adapter_is_reported() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+    assert!(backend.load_state().is_ok());
+
+    let events = backend.subscribe().expect("subscribe");
+    mock.unplug_wifi();
+    let removed = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(2)).ok())
+        .any(|event| matches!(event, BackendEvent::DeviceRemoved));
+    assert!(removed);
+    assert!(matches!(backend.load_state(), Err(BackendError::NoWifiDevice)));
+}
+
+#[test]
+fn scans_are_rate_limited_after_the_last_one() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    assert_eq!(backend.last_scan().expect("last scan"), None);
+    backend.request_scan().expect("scan");
+    let age = backend.last_scan().expect("last scan").expect("scanned");
+    assert!(age < SCAN_COOLDOWN, "{age:?}");
+    let err = backend.request_scan().unwrap_err();
+    assert!(matches!(err, BackendError::NotAllowed(_)), "{err:?}");
+    assert_eq!(mock.scan_count(), 1);
+}
+
+#[test]
+fn access_denied_maps_to_permission_error() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.deny_access();
+    let backend = mock.backend();
+
+    let err = backend.request_scan().unwrap_err();
+    assert!(matches!(err, BackendError::PermissionDenied(_)), "{err:?}");
+    assert_eq!(mock.scan_count(), 0);
+}
+
+#[test]
+fn duplicate_profiles_are_grouped_and_deleted() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_saved("Cafe", None);
+    let backend = mock.backend();
+
+    let profiles = backend.saved_profiles().expect("profiles");
+    assert_eq!(profiles.len(), 3);
+    let groups = duplicate_profile_groups(profiles);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].len(), 2);
+    assert!(groups[0].iter().all(|profile| profile.ssid == "Cafe"));
+
+    backend.delete_profile(&groups[0][1].path).expect("delete extra");
+    let mut saved = mock.saved_ssids();
+    saved.sort();
+    assert_eq!(saved, ["Cafe", "Office"]);
+    assert!(duplicate_profile_groups(backend.saved_profiles().unwrap()).is_empty());
+}
+
+#[test]
+fn duplicated_profile_keeps_secrets_under_a_new_name() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    let original = backend.saved_profiles().expect("profiles").remove(0);
+    let name = copy_name(&original.id, std::slice::from_ref(&original));
+    assert_eq!(name, "Office (copy)");
+    let copy_path = backend.duplicate_profile(&original.path, &name).expect("duplicate");
+    let profiles = backend.saved_profiles().expect("profiles");
+    assert_eq!(copy_name("Office", &profiles), "Office (copy 2)");
+    let copy = profiles.iter().find(|profile| profile.path == copy_path).expect("copy");
+    assert_eq!((copy.id.as_str(), copy.ssid.as_str(), copy.autoconnect), ("Office (copy)", "Office", false));
+
+    backend.delete_profile(&original.path).expect("delete original");
+    assert_eq!(backend.get_saved_password("Office").unwrap().as_deref(), Some("hunter22"));
+}
+
+#[test]
+fn best_available_respects_priority_and_autoconnect() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+
+    let pick = |backend: &crate::backend::nm::NetworkManagerBackend| {
+        let state = backend.load_state().expect("state");
+        let profiles = backend.saved_profiles().expect("profiles");
+        best_available(&state, &profiles).map(|network| network.ssid.clone())
+    };
+    assert_eq!(pick(&backend).as_deref(), Some("Office"));
+
+    mock.set_priority("Cafe", 10);
+    assert_eq!(pick(&backend).as_deref(), Some("Cafe"));
+
+    backend.set_autoreconnect("Cafe", false).expect("update");
+    assert_eq!(pick(&backend).as_deref(), Some("Office"));
+}
+
+#[test]
+fn priority_order_is_written_to_every_profile() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_saved("Cafe", None);
+    mock.add_saved("Home", Some("correct horse"));
+    let backend = mock.backend();
+
+    let order = ["Cafe", "Home", "Office"];
+    let profiles = backend.saved_profiles().expect("profiles");
+    for (ssid, priority) in order.iter().zip(ranked_priorities(order.len())) {
+        let profile = profiles.iter().find(|profile| profile.ssid == *ssid).unwrap();
+        backend.set_autoconnect_priority(&profile.path, priority).expect("priority");
+    }
+
+    let profiles = backend.saved_profiles().expect("profiles");
+    let priority = |ssid| profiles.iter().find(|profile| profile.ssid == ssid).unwrap().priority;
+    assert_eq!((priority("Cafe"), priority("Home"), priority("Office")), (3, 2, 1));
+    let state = backend.load_state().expect("state");
+    assert_eq!(best_available(&state, &profiles).map(|network| network.ssid.as_str()), Some("Cafe"));
+    assert_eq!(backend.get_saved_password("Office").unwrap().as_deref(), Some("hunter22"));
+}
+
+#[test]
+fn security_audit_flags_weak_and_careless_profiles() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Cafe", None);
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_saved("Home", Some("correct horse"));
+    let backend = mock.backend();
+    let set = |ssid, section, key, value: SettingValue| {
+        backend.set_raw_setting(ssid, section, key, &value).expect("set");
+    };
+    set("Home", "802-11-wireless-security", "psk-flags", SettingValue::UInt32(1));
+    set("Office", "802-11-wireless-security", "proto", SettingValue::TextList(vec!["wpa".to_string()]));
+
+    let findings = |ssid| {
+        let profiles = backend.saved_profiles().expect("profiles");
+        audit_profile(profiles.iter().find(|profile| profile.ssid == ssid).unwrap())
+    };
+    assert_eq!(findings("Cafe"), [AuditFinding::AutoconnectOpen]);
+    assert_eq!(
+        findings("Office"),
+        [AuditFinding::Weak(WeakSecurity::LegacyWpa), AuditFinding::PlaintextSecret]
+    );
+    assert!(findings("Home").is_empty());
+
+    set("Home", "802-11-wireless-security", "key-mgmt", SettingValue::Text("wpa-eap".to_string()));
+    set("Home", "802-1x", "eap", SettingValue::TextList(vec!["peap".to_string()]));
+    assert_eq!(findings("Home"), [AuditFinding::NoCaCertificate]);
+    set("Home", "802-1x", "system-ca-certs", SettingValue::Boolean(true));
+    assert!(findings("Home").is_empty());
+}
+
+#[test]
+fn enterprise_certificates_are_read_and_replaced() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    let backend = mock.backend();
+    assert_eq!(backend.get_network_details("Office").expect("details").certificates, None);
+    assert!(backend.set_certificates("Office", &EnterpriseCertificates::default(), None).is_err());
+
+    let password = SettingValue::Text("eap secret".to_string());
+    backend.set_raw_setting("Office", "802-1x", "password", &password).expect("password");
+    assert_eq!(backend.get_saved_password("Office").unwrap().as_deref(), Some("hunter22"));
+    let embedded = SettingValue::Bytes(b"-----BEGIN CERTIFICATE-----".to_vec());
+    backend.set_raw_setting("Office", "802-1x", "ca-cert", &embedded).expect("ca-cert");
+    let certificates = backend.get_network_details("Office").expect("details").certificates.expect("802.1X");
+    assert_eq!(certificates.ca_cert.as_deref(), Some(EMBEDDED_CERTIFICATE));
+    assert!(certificates.verifies_server());
+
+    let chosen = EnterpriseCertificates {
+        client_cert: Some("/home/me/.certs/me.pem".to_string()),
+        private_key: Some("/home/me/.certs/me.key".to_string()),
+        ..certificates
+    };
+    backend.set_certificates("Office", &chosen, Some("key secret")).expect("set");
+    assert_eq!(
+        backend.get_network_details("Office").expect("details").certificates.as_ref(),
+        Some(&chosen)
+    );
+    let dot1x = &mock.saved_settings("Office").expect("profile")["802-1x"];
+    let bytes = |key: &str| Vec::<u8>::try_from(dot1x[key].try_clone().unwrap()).unwrap();
+    assert_eq!(bytes("ca-cert"), b"-----BEGIN CERTIFICATE-----");
+    assert_eq!(bytes("client-cert"), b"file:///home/me/.certs/me.pem\0");
+    assert_eq!(String::try_from(dot1x["private-key-password"].try_clone().unwrap()).unwrap(), "key secret");
+
+    assert_eq!(String::try_from(dot1x["password"].try_clone().unwrap()).unwrap(), "eap secret");
+
+    let skipped = EnterpriseCertificates {
+        ca_cert: None,
+        ..chosen
+    };
+    backend.set_certificates("Office", &skipped, None).expect("clear");
+    let certificates = backend.get_network_details("Office").expect("details").certificates.unwrap();
+    assert!(!certificates.verifies_server());
+}
+
+#[test]
+fn vpns_are_listed_and_activated_once() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_vpn("Work VPN", "6c1f4a1e-1d2b-4c8e-9a3f-0e5b7d9c2a11");
+    mock.add_vpn("ahoy", "0f3e2d1c-4b5a-4978-8695-a4b3c2d1e0f9");
+    let backend = mock.backend();
+    backend.connect_network("Office", None, true, None).expect("connect");
+
+    let vpns = backend.vpn_connections().expect("vpns");
+    let names: Vec<&str> = vpns.iter().map(|vpn| vpn.id.as_str()).collect();
+    assert_eq!(names, ["ahoy", "Work VPN"]);
+    assert!(vpns.iter().all(|vpn| !vpn.active));
+
+    let uuid = "6c1f4a1e-1d2b-4c8e-9a3f-0e5b7d9c2a11";
+    assert_eq!(backend.activate_vpn(uuid).expect("activate").as_deref(), Some("Work VPN"));
+    assert_eq!(backend.activate_vpn(uuid).expect("already up"), None);
+    assert!(backend.vpn_connections().expect("vpns")[1].active);
+    assert!(backend.activate_vpn("missing").is_err());
+
+    let state = backend.load_state().expect("state");
+    assert!(state.networks.iter().any(|network| network.ssid == "Office" && network.is_active));
+}
+
+#[test]
+fn backup_restores_forgotten_profiles_with_their_passwords() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    mock.add_saved("Office", Some("hunter22"));
+    mock.add_saved("Home", Some("correct horse"));
+    let backend = mock.backend();
+    let backup = backend.backup_profiles().expect("backup");
+
+    backend.forget_network("Office").expect("forget");
+    assert!(mock.saved_settings("Office").is_none());
+    let restored = backend.restore_profiles(&backup).expect("restore");
+    assert_eq!(restored.added, ["Office"]);
+    assert_eq!(restored.skipped, ["Home"]);
+    assert_eq!(backend.get_saved_password("Office").expect("password").as_deref(), Some("hunter22"));
+
+    assert!(backend.restore_profiles(b"not a backup").is_err());
+}
+
+#[test]
+fn new_profile_respects_autoconnect_choice() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    backend.connect_network("Cafe", None, false, None).expect("connect");
+    let settings = mock.saved_settings("Cafe").expect("profile");
+    let autoconnect = settings["connection"]["autoconnect"].try_clone().unwrap();
+    assert!(!bool::try_from(autoconnect).unwrap());
+}
+
+#[test]
+fn wpa3_and_owe_profiles_use_matching_key_mgmt() {
+    let Some(mock) = start(vec![
+        MockAccessPoint::sae("Modern", 80, "sae secret"),
+        MockAccessPoint::owe("Airport", 60),
+    ]) else {
+        return;
+    };
+    let backend = mock.backend();
+
+    let key_mgmt = |ssid: &str| {
+        let settings = mock.saved_settings(ssid).expect("profile");
+        let value = settings["802-11-wireless-security"]["key-mgmt"].try_clone().unwrap();
+        String::try_from(value).unwrap()
+    };
+
+    let active = backend
+        .connect_network("Modern", Some("sae secret"), true, None)
+        .expect("connect")
+        .expect("active connection path");
+    assert_eq!(mock.active_state(&active), Some(2));
+    assert_eq!(key_mgmt("Modern"), "sae");
+
+    backend.connect_network("Airport", None, true, None).expect("connect");
+    assert_eq!(key_mgmt("Airport"), "owe");
+}
+
+#[test]
+fn access_point_info_decodes_security_flags() {
+    let Some(mock) = start(neighbourhood()) else { return };
+    let backend = mock.backend();
+
+    let info = backend.get_access_point_info("Home").expect("info");
+    assert_eq!(info.key_mgmt, ["WPA2-PSK"]);
+    assert_eq!(info.pairwise, ["CCMP"]);
+    assert_eq!(info.group, ["CCMP"]);
+    assert!(!info.wps);
+    assert_eq!(info.frequency_mhz, 5180);
+    assert_eq!(info.generation, "Wi‑Fi 5 or newer");
+
+    let open = backend.get_access_point_info("Cafe").expect("info");
+    assert!(open.key_mgmt.is_empty());
+}
+
+#[test]
+fn lists_every_bssid_with_frequency() {
+    let Some(mock) = start(vec![
+        MockAccessPoint::secured("Home", 70, "correct horse").on(2437),
+        MockAccessPoint::secured("Home", 40, "correct horse"),
+        MockAccessPoint::open("Cafe", 55).on(2412),
+    ]) else {
+        return;
+    };
+    let backend = mock.backend();
+
+    let aps = backend.list_access_points().expect("access points");
+    assert_eq!(aps.len(), 3);
+    assert_eq!(aps[0].ssid, "Home");
+    assert_eq!(aps[0].frequency_mhz, 2437);
+    assert_eq!(aps[1].frequency_mhz, 5180);
+    assert_ne!(aps[0].bssid, aps[1].bssid);
+}
+
+#[test]
+fn load_state_carries_every_access_point() {
+    let Some(mock) = start(vec![
+        MockAccessPoint::secured("Home", 40, "correct horse").on(2437),
+        MockAccessPoint::secured("Home", 70, "correct horse"),
+        MockAccessPoint::open("Cafe", 55).on(2412),
+    ]) else {
+        return;
+    };
+    let state = mock.backend().load_state().expect("state");
+
+    let home = state.networks.iter().find(|network| network.ssid == "Home").unwrap();
+    assert_eq!(home.access_points.len(), 2);
+    let strongest = &home.access_points[0];
+    assert_eq!((strongest.strength, strongest.frequency_mhz), (70, 5180));
+    assert_eq!(home.access_points[1].frequency_mhz, 2437);
+    assert_eq!(strongest.max_bitrate_kbps, 866_700);
+    assert_ne!(strongest.rsn_flags, 0);
+    assert_eq!(strongest.last_seen, Some(MOCK_LAST_SEEN as u32));
+
+    let cafe = state.networks.iter().find(|network| network.ssid == "Cafe").unwrap();
+    assert_eq!(cafe.access_points[0].flags, 0);
+}
+
+#[test]
+fn weak_security_needs_every_radio_to_be_weak() {
+    let radio = |flags, wpa_flags, rsn_flags| AccessPoint {
+        flags,
+        wpa_flags,
+        rsn_flags,
+        ..AccessPoint::default()
+    };
+    assert_eq!(radio(0x1, 0, 0).weak_security(), Some(WeakSecurity::Wep));
+    assert_eq!(radio(0x1, 0x144, 0).weak_security(), Some(WeakSecurity::LegacyWpa));
+    assert_eq!(radio(0x1, 0, 0x144).weak_security(), Some(WeakSecurity::LegacyWpa));
+    assert_eq!(radio(0x1, 0x144, 0x188).weak_security(), None);
+    assert_eq!(radio(0, 0, 0).weak_security(), None);
+
+    let mut network = Network {
+        ssid: "Legacy".to_string(),
+        signal_icon: "network-wireless-signal-good-symbolic",
+        action: NetworkAction::Connect,
+        strength: 70,
+        is_active: false,
+        is_saved: false,
+        is_secure: true,
+        security: "wpa",
+        access_points: vec![radio(0x1, 0x144, 0), radio(0x1, 0, 0)],
+    };
+    assert_eq!(network.weak_security(), Some(WeakSecurity::Wep));
+    network.access_points.push(radio(0x1, 0, 0x188));
+    assert_eq!(network.weak_security(), None);
+    network.access_points.clear();
+    assert_eq!(network.weak_security(), None);
+}
@@ -0,0 +1,6 @@
+//! NetworkManager access shared by the YuFi frontends. Nothing in here
+//! depends on GTK.
+
+pub mod backend;
+pub mod models;
+pub mod profile;
@@ -0,0 +1,707 @@
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub enum NetworkAction {
+    None,
+    Connect,
+    Disconnect,
+    /// Wi‑Fi is off: turn it on, then connect to this saved network.
+    EnableWifiAndConnect,
+}
+
+#[derive(Clone, Debug)]
+pub struct Network {
+    /// As broadcast, surrounding spaces included, so a lookalike of a saved
+    /// network is never merged into it.
+    pub ssid: String,
+    pub signal_icon: &'static str,
+    pub action: NetworkAction,
+    pub strength: u8,
+    pub is_active: bool,
+    pub is_saved: bool,
+    pub is_secure: bool,
+    pub security: &'static str,
+    /// Every radio broadcasting this SSID, strongest first.
+    pub access_points: Vec<AccessPoint>,
+}
+
+/// One band of a dual-band network, listed as its own row. These are the bands
+/// of `802-11-wireless.band`, which has no 6 GHz.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BandLock {
+    TwoPointFour,
+    Five,
+}
+
+impl BandLock {
+    pub fn label(self) -> &'static str {
+        match self {
+            BandLock::TwoPointFour => "2.4 GHz",
+            BandLock::Five => "5 GHz",
+        }
+    }
+
+    pub fn for_frequency(frequency_mhz: u32) -> Option<Self> {
+        match frequency_mhz {
+            2400..=2500 => Some(BandLock::TwoPointFour),
+            4900..=5925 => Some(BandLock::Five),
+            _ => None,
+        }
+    }
+}
+
+/// One BSS as seen by the last scan. The flag words are NetworkManager's raw
+/// `NM80211ApFlags` / `NM80211ApSecurityFlags` values.
+#[derive(Clone, Debug, Default)]
+pub struct AccessPoint {
+    pub bssid: String,
+    pub frequency_mhz: u32,
+    pub strength: u8,
+    pub max_bitrate_kbps: u32,
+    pub flags: u32,
+    pub wpa_flags: u32,
+    pub rsn_flags: u32,
+    /// Seconds since boot (CLOCK_BOOTTIME) of the last sighting, if any.
+    pub last_seen: Option<u32>,
+}
+
+const NM_802_11_AP_FLAGS_PRIVACY: u32 = 0x1;
+const NM_802_11_AP_SEC_PAIR_CCMP: u32 = 0x8;
+const NM_802_11_AP_SEC_KEY_MGMT_SAE: u32 = 0x400;
+const NM_802_11_AP_SEC_KEY_MGMT_OWE: u32 = 0x800;
+
+/// Security that many current devices refuse or handle badly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeakSecurity {
+    Wep,
+    /// WPA1, or WPA2 with TKIP as the only cipher.
+    LegacyWpa,
+}
+
+impl WeakSecurity {
+    pub fn label(self) -> &'static str {
+        match self {
+            WeakSecurity::Wep => "WEP",
+            WeakSecurity::LegacyWpa => "WPA/TKIP",
+        }
+    }
+
+    pub fn explanation(self) -> &'static str {
+        match self {
+            WeakSecurity::Wep => {
+                "This network only offers WEP, which can be cracked in minutes. Many phones and recent Linux releases no longer connect to it. Switch the router to WPA2 or WPA3 if you can."
+            }
+            WeakSecurity::LegacyWpa => {
+                "This network only offers WPA with the TKIP cipher, which is deprecated. Newer devices and drivers often refuse it or connect at reduced speed. Switch the router to WPA2 (AES) or WPA3 if you can."
+            }
+        }
+    }
+}
+
+impl AccessPoint {
+    /// `None` for open networks and anything offering AES (CCMP), WPA3 or OWE.
+    pub fn weak_security(&self) -> Option<WeakSecurity> {
+        let modern =
+            NM_802_11_AP_SEC_PAIR_CCMP | NM_802_11_AP_SEC_KEY_MGMT_SAE | NM_802_11_AP_SEC_KEY_MGMT_OWE;
+        if self.rsn_flags & modern != 0 {
+            None
+        } else if self.wpa_flags | self.rsn_flags != 0 {
+            Some(WeakSecurity::LegacyWpa)
+        } else if self.flags & NM_802_11_AP_FLAGS_PRIVACY != 0 {
+            Some(WeakSecurity::Wep)
+        } else {
+            None
+        }
+    }
+}
+
+impl Network {
+    /// Flags the network only when every radio broadcasting it is weak, since
+    /// clients pick the better BSS on their own.
+    pub fn weak_security(&self) -> Option<WeakSecurity> {
+        self.access_points
+            .iter()
+            .map(AccessPoint::weak_security)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AppState {
+    pub wifi_enabled: bool,
+    pub networks: Vec<Network>,
+    /// Saved networks the last scan did not see, by name. Empty while Wi‑Fi is off.
+    pub out_of_range: Vec<Network>,
+}
+
+impl AppState {
+    /// Folds in a network a running scan just turned up, so it shows before
+    /// the next full read. `found` holds the one new access point and knows
+    /// nothing of saved profiles; an out-of-range saved network of the same
+    /// name tells it is saved.
+    pub fn add_found(&mut self, mut found: Network) {
+        let Some(access_point) = found.access_points.pop().filter(|_| self.wifi_enabled) else {
+            return;
+        };
+        match self.networks.iter_mut().find(|network| network.ssid == found.ssid) {
+            Some(network) => {
+                network.access_points.retain(|ap| ap.bssid != access_point.bssid);
+                if access_point.strength > network.strength {
+                    network.strength = access_point.strength;
+                    network.signal_icon = found.signal_icon;
+                }
+                network.access_points.push(access_point);
+                network.access_points.sort_by_key(|ap| std::cmp::Reverse(ap.strength));
+            }
+            None => {
+                if let Some(index) = self.out_of_range.iter().position(|network| network.ssid == found.ssid) {
+                    self.out_of_range.remove(index);
+                    found.is_saved = true;
+                }
+                found.access_points.push(access_point);
+                self.networks.push(found);
+            }
+        }
+        sort_networks(&mut self.networks);
+    }
+}
+
+/// The active network first, then by signal and name.
+pub fn sort_networks(networks: &mut [Network]) {
+    networks.sort_by(|a, b| {
+        b.is_active
+            .cmp(&a.is_active)
+            .then_with(|| b.strength.cmp(&a.strength))
+            .then_with(|| a.ssid.cmp(&b.ssid))
+    });
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NetworkDetails {
+    pub ip_address: Option<String>,
+    pub prefix: Option<u32>,
+    pub gateway: Option<String>,
+    pub dns_servers: Vec<String>,
+    /// `ipv4.dns-priority`; 0 means NetworkManager's default.
+    pub dns_priority: Option<i32>,
+    /// `ipv4.method` is `link-local`.
+    pub link_local: bool,
+    pub auto_reconnect: Option<bool>,
+    pub device_mac: Option<String>,
+    pub bssid: Option<String>,
+    /// `connection.uuid`, which `nmcli` and scripts refer to the profile by.
+    pub uuid: Option<String>,
+    /// `connection.interface-name`: the profile only activates on this device.
+    pub interface_name: Option<String>,
+    /// `connection.permissions`: the accounts that may use the profile; empty
+    /// for every account.
+    pub permitted_users: Vec<String>,
+    /// Set on 802.1X profiles.
+    pub certificates: Option<EnterpriseCertificates>,
+    /// `proxy.pac-url`, set when `proxy.method` is auto: the proxy
+    /// auto-config script the profile uses.
+    pub proxy_pac_url: Option<String>,
+}
+
+/// The certificates an 802.1X profile names, each a file path, a `pkcs11:`
+/// URI or `EMBEDDED_CERTIFICATE`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnterpriseCertificates {
+    /// `ca-cert`: the CA the authentication server's certificate must chain to.
+    pub ca_cert: Option<String>,
+    /// `ca-path`: a directory of CA certificates.
+    pub ca_path: Option<String>,
+    /// `system-ca-certs`: trust the CAs the system trusts.
+    pub system_ca_certs: bool,
+    /// `client-cert` and `private-key`, for logging in with a certificate (EAP-TLS).
+    pub client_cert: Option<String>,
+    pub private_key: Option<String>,
+}
+
+/// Stands for a certificate stored in the profile itself rather than in a file.
+pub const EMBEDDED_CERTIFICATE: &str = "(stored in the profile)";
+
+impl EnterpriseCertificates {
+    /// Whether the server's certificate is checked against some CA; without
+    /// that any server can pose as the network.
+    pub fn verifies_server(&self) -> bool {
+        self.ca_cert.is_some() || self.ca_path.is_some() || self.system_ca_certs
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AccessPointInfo {
+    pub key_mgmt: Vec<&'static str>,
+    pub pairwise: Vec<&'static str>,
+    pub group: Vec<&'static str>,
+    pub wps: bool,
+    pub frequency_mhz: u32,
+    pub max_bitrate_kbps: u32,
+    pub generation: &'static str,
+}
+
+/// What a DHCP server handed out, as NetworkManager's `DHCP4Config` /
+/// `DHCP6Config` `Options`. Option names depend on the DHCP client in use, so
+/// the accessors try the common spellings and `options` keeps everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DhcpLease {
+    pub options: BTreeMap<String, String>,
+}
+
+impl DhcpLease {
+    pub fn server(&self) -> Option<&str> {
+        self.first(&["dhcp_server_identifier", "dhcp6_server_id"])
+    }
+
+    pub fn lease_time_secs(&self) -> Option<u64> {
+        self.first(&["dhcp_lease_time", "max_life"])?.parse().ok()
+    }
+
+    pub fn domain(&self) -> Option<&str> {
+        self.first(&["domain_name", "domain_search", "dhcp6_domain_search"])
+    }
+
+    pub fn ntp_servers(&self) -> Vec<&str> {
+        self.first(&["ntp_servers", "dhcp6_ntp_servers", "dhcp6_sntp_servers"])
+            .map(|servers| servers.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    fn first(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter()
+            .find_map(|key| self.options.get(*key))
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Leases of the active Wi‑Fi connection; `None` where that family was not
+/// configured through DHCP.
+#[derive(Clone, Debug, Default)]
+pub struct DhcpLeases {
+    pub ipv4: Option<DhcpLease>,
+    pub ipv6: Option<DhcpLease>,
+}
+
+/// One key of a saved profile as NetworkManager stores it: its D-Bus type
+/// signature and its value in GVariant text form, e.g. `("u", "uint32 600")`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawValue {
+    pub signature: String,
+    pub text: String,
+    /// The value as the settings editor takes it, for the types it can write.
+    pub input: Option<String>,
+}
+
+/// The setting types the advanced editor can write, by D-Bus signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingKind {
+    Text,
+    Boolean,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Bytes,
+    TextList,
+}
+
+impl SettingKind {
+    pub const ALL: [SettingKind; 8] = [
+        SettingKind::Text,
+        SettingKind::Boolean,
+        SettingKind::Int32,
+        SettingKind::UInt32,
+        SettingKind::Int64,
+        SettingKind::UInt64,
+        SettingKind::Bytes,
+        SettingKind::TextList,
+    ];
+
+    pub fn signature(self) -> &'static str {
+        match self {
+            SettingKind::Text => "s",
+            SettingKind::Boolean => "b",
+            SettingKind::Int32 => "i",
+            SettingKind::UInt32 => "u",
+            SettingKind::Int64 => "x",
+            SettingKind::UInt64 => "t",
+            SettingKind::Bytes => "ay",
+            SettingKind::TextList => "as",
+        }
+    }
+
+    pub fn from_signature(signature: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.signature() == signature)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingKind::Text => "Text",
+            SettingKind::Boolean => "Boolean",
+            SettingKind::Int32 => "Integer",
+            SettingKind::UInt32 => "Unsigned integer",
+            SettingKind::Int64 => "64-bit integer",
+            SettingKind::UInt64 => "64-bit unsigned integer",
+            SettingKind::Bytes => "Bytes (as text)",
+            SettingKind::TextList => "Text list",
+        }
+    }
+
+    /// Placeholder for the value entry.
+    pub fn hint(self) -> &'static str {
+        match self {
+            SettingKind::Text | SettingKind::Bytes => "Value",
+            SettingKind::Boolean => "true or false",
+            SettingKind::Int32 | SettingKind::Int64 => "Whole number",
+            SettingKind::UInt32 | SettingKind::UInt64 => "Whole number, 0 or more",
+            SettingKind::TextList => "Comma-separated values",
+        }
+    }
+
+    /// Reads `input` as a value of this type, or says why it is not one.
+    pub fn parse(self, input: &str) -> Result<SettingValue, String> {
+        let trimmed = input.trim();
+        let number = |what: &str| format!("{trimmed:?} is not {what}");
+        Ok(match self {
+            SettingKind::Text => SettingValue::Text(input.to_string()),
+            SettingKind::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => SettingValue::Boolean(true),
+                "false" | "no" | "0" => SettingValue::Boolean(false),
+                _ => return Err(format!("{trimmed:?} is not true or false")),
+            },
+            SettingKind::Int32 => SettingValue::Int32(trimmed.parse().map_err(|_| number("a 32-bit integer"))?),
+            SettingKind::UInt32 => {
+                SettingValue::UInt32(trimmed.parse().map_err(|_| number("a 32-bit unsigned integer"))?)
+            }
+            SettingKind::Int64 => SettingValue::Int64(trimmed.parse().map_err(|_| number("a 64-bit integer"))?),
+            SettingKind::UInt64 => {
+                SettingValue::UInt64(trimmed.parse().map_err(|_| number("a 64-bit unsigned integer"))?)
+            }
+            SettingKind::Bytes => SettingValue::Bytes(input.as_bytes().to_vec()),
+            SettingKind::TextList => SettingValue::TextList(
+                input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+        })
+    }
+}
+
+/// A value written by the advanced settings editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingValue {
+    Text(String),
+    Boolean(bool),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Bytes(Vec<u8>),
+    TextList(Vec<String>),
+}
+
+/// Every section and key of a saved profile (`GetSettings`), without secrets.
+pub type RawSettings = BTreeMap<String, BTreeMap<String, RawValue>>;
+
+/// Addressing currently applied to the Wi‑Fi device, as opposed to the
+/// configured values in `NetworkDetails`.
+#[derive(Clone, Debug, Default)]
+pub struct IpConfig {
+    pub address: String,
+    pub prefix: u32,
+    pub gateway: Option<String>,
+    pub dns_servers: Vec<String>,
+    pub dhcp: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceKind {
+    Ethernet,
+    Wifi,
+    Loopback,
+    Other(u32),
+}
+
+impl DeviceKind {
+    /// Maps NetworkManager's `NMDeviceType`.
+    pub fn from_nm(device_type: u32) -> Self {
+        match device_type {
+            1 => DeviceKind::Ethernet,
+            2 => DeviceKind::Wifi,
+            32 => DeviceKind::Loopback,
+            other => DeviceKind::Other(other),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DeviceKind::Ethernet => "Ethernet",
+            DeviceKind::Wifi => "Wi‑Fi",
+            DeviceKind::Loopback => "Loopback",
+            DeviceKind::Other(_) => "Other",
+        }
+    }
+}
+
+/// A VPN or WireGuard profile, which a Wi‑Fi network can bring up on connect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VpnConnection {
+    pub id: String,
+    pub uuid: String,
+    pub active: bool,
+}
+
+/// A network interface NetworkManager knows about, of any type.
+#[derive(Clone, Debug)]
+pub struct DeviceInfo {
+    pub path: String,
+    pub interface: String,
+    pub kind: DeviceKind,
+    /// Raw `NMDeviceState`; see `state_label`.
+    pub state: u32,
+    pub driver: String,
+    pub mac: String,
+}
+
+impl DeviceInfo {
+    pub fn state_label(&self) -> &'static str {
+        match self.state {
+            10 => "unmanaged",
+            20 => "unavailable",
+            30 => "disconnected",
+            40..=90 => "connecting",
+            100 => "connected",
+            110 => "disconnecting",
+            120 => "failed",
+            _ => "unknown",
+        }
+    }
+}
+
+/// What NetworkManager reports about itself and the Wi‑Fi device, for the
+/// diagnostics report.
+#[derive(Clone, Debug, Default)]
+pub struct NmDiagnostics {
+    pub version: String,
+    pub wireless_enabled: bool,
+    pub wireless_hardware_enabled: bool,
+    pub connectivity: u32,
+    pub connectivity_check_enabled: bool,
+    pub device: Option<WifiDeviceInfo>,
+    pub devices: Vec<DeviceInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WifiDeviceInfo {
+    pub interface: String,
+    pub driver: String,
+    pub driver_version: String,
+    pub firmware_version: String,
+    /// Address the radio is using right now, which is also the one probe
+    /// requests go out with.
+    pub mac: String,
+    /// Burned-in address; empty if the driver does not report one.
+    pub permanent_mac: String,
+}
+
+impl WifiDeviceInfo {
+    pub fn mac_randomized(&self) -> bool {
+        !self.permanent_mac.is_empty() && !self.mac.eq_ignore_ascii_case(&self.permanent_mac)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VisibleAccessPoint {
+    pub ssid: String,
+    pub bssid: String,
+    pub frequency_mhz: u32,
+    pub strength: u8,
+}
+
+/// A Wi‑Fi profile read from a file, ready to be added to NetworkManager.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportedProfile {
+    pub id: String,
+    pub ssid: String,
+    /// NetworkManager `key-mgmt`: `wpa-psk`, `sae`, `owe`, or `none` for WEP.
+    /// `None` is an open network.
+    pub key_mgmt: Option<String>,
+    pub password: Option<String>,
+    pub hidden: bool,
+    pub autoconnect: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct SavedProfile {
+    pub path: String,
+    pub id: String,
+    pub ssid: String,
+    pub timestamp: u64,
+    pub priority: i32,
+    pub autoconnect: bool,
+    /// No `802-11-wireless-security` section: the network is unencrypted.
+    pub is_open: bool,
+    pub security: ProfileSecurity,
+}
+
+/// What restoring a backup did, by profile name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestoredProfiles {
+    pub added: Vec<String>,
+    /// Already saved on this machine, so left alone.
+    pub skipped: Vec<String>,
+}
+
+/// Byte counters of the Wi‑Fi device from NetworkManager's `Device.Statistics`.
+/// They count from when the device came up, across connections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrafficCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Whether the current connection is metered, set by the user or guessed.
+    pub metered: bool,
+}
+
+/// The radio link of the Wi‑Fi device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkStatus {
+    pub interface: String,
+    /// Rate of the frames the device sent last, 0 while not connected. It
+    /// follows the signal and is not the speed of the internet connection.
+    pub bitrate_kbps: u32,
+}
+
+/// The parts of a profile's security settings the audit looks at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileSecurity {
+    /// `key-mgmt`: `wpa-psk`, `sae`, `owe`, `wpa-eap`, `ieee8021x` or `none` (WEP).
+    pub key_mgmt: Option<String>,
+    /// `proto`, e.g. `["wpa"]` for WPA1 only; empty allows every version.
+    pub proto: Vec<String>,
+    /// `pairwise` ciphers; empty allows every cipher.
+    pub pairwise: Vec<String>,
+    /// `psk-flags`, or `wep-key-flags` for WEP. 0 means NetworkManager keeps the
+    /// secret itself, in plain text under `/etc/NetworkManager/system-connections`.
+    pub secret_flags: u32,
+    /// Set on 802.1X profiles with `ca-cert`, `ca-path` or `system-ca-certs`.
+    pub verifies_server: bool,
+}
+
+/// Something the security audit flags in a saved profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditFinding {
+    Weak(WeakSecurity),
+    PlaintextSecret,
+    AutoconnectOpen,
+    NoCaCertificate,
+}
+
+impl AuditFinding {
+    pub fn label(self) -> &'static str {
+        match self {
+            AuditFinding::Weak(WeakSecurity::Wep) => "Uses WEP",
+            AuditFinding::Weak(WeakSecurity::LegacyWpa) => "Allows WPA1 or TKIP",
+            AuditFinding::PlaintextSecret => "Password stored in plain text",
+            AuditFinding::AutoconnectOpen => "Joins an open network automatically",
+            AuditFinding::NoCaCertificate => "Does not verify the server",
+        }
+    }
+
+    pub fn advice(self) -> &'static str {
+        match self {
+            AuditFinding::Weak(weak) => weak.explanation(),
+            AuditFinding::PlaintextSecret => {
+                "NetworkManager keeps this password in a file only root can read. Storing it in your keyring instead keeps it off the disk in clear text."
+            }
+            AuditFinding::AutoconnectOpen => {
+                "Anyone can set up an unencrypted network with this name. Turn off auto-reconnect so YuFi only joins it when you ask."
+            }
+            AuditFinding::NoCaCertificate => {
+                "Without a CA certificate any server can pose as this network and collect your login. Set a CA certificate or use the system's certificates."
+            }
+        }
+    }
+}
+
+/// Everything the audit flags in `profile`, most serious first.
+pub fn audit_profile(profile: &SavedProfile) -> Vec<AuditFinding> {
+    let security = &profile.security;
+    let key_mgmt = security.key_mgmt.as_deref();
+    let mut findings = Vec::new();
+    if matches!(key_mgmt, Some("none" | "ieee8021x")) {
+        findings.push(AuditFinding::Weak(WeakSecurity::Wep));
+    } else if key_mgmt.is_some_and(|key_mgmt| key_mgmt.starts_with("wpa-"))
+        && (security.proto == ["wpa"] || security.pairwise == ["tkip"])
+    {
+        findings.push(AuditFinding::Weak(WeakSecurity::LegacyWpa));
+    }
+    if matches!(key_mgmt, Some("wpa-eap" | "ieee8021x")) && !security.verifies_server {
+        findings.push(AuditFinding::NoCaCertificate);
+    }
+    if matches!(key_mgmt, Some("none" | "wpa-psk" | "sae")) && security.secret_flags == 0 {
+        findings.push(AuditFinding::PlaintextSecret);
+    }
+    if profile.is_open && profile.autoconnect {
+        findings.push(AuditFinding::AutoconnectOpen);
+    }
+    findings
+}
+
+/// Picks the in-range saved network NetworkManager would prefer: highest
+/// autoconnect-priority first, then the strongest signal. Profiles with
+/// autoconnect turned off are skipped, as NetworkManager itself would. The
+/// result may be the network that is already active.
+pub fn best_available<'a>(state: &'a AppState, profiles: &[SavedProfile]) -> Option<&'a Network> {
+    state
+        .networks
+        .iter()
+        .filter_map(|network| {
+            let priority = profiles
+                .iter()
+                .filter(|profile| profile.ssid == network.ssid && profile.autoconnect)
+                .map(|profile| profile.priority)
+                .max()?;
+            Some((priority, network))
+        })
+        .max_by_key(|(priority, network)| (*priority, network.strength))
+        .map(|(_, network)| network)
+}
+
+/// `autoconnect-priority` values for networks ordered most preferred first.
+/// The last one gets 1 and each one above it one more, so the ordered networks
+/// all win over profiles left at the default of 0. NetworkManager caps the
+/// value at 999.
+pub fn ranked_priorities(count: usize) -> Vec<i32> {
+    (0..count).map(|rank| (count - rank).min(999) as i32).collect()
+}
+
+/// A name for a copy of the profile `id` that no profile has yet:
+/// `"Cafe (copy)"`, then `"Cafe (copy 2)"` and so on.
+pub fn copy_name(id: &str, profiles: &[SavedProfile]) -> String {
+    (1..)
+        .map(|n| if n == 1 { format!("{id} (copy)") } else { format!("{id} (copy {n})") })
+        .find(|name| profiles.iter().all(|profile| profile.id != *name))
+        .unwrap_or_default()
+}
+
+pub fn duplicate_profile_groups(profiles: Vec<SavedProfile>) -> Vec<Vec<SavedProfile>> {
+    let mut groups: Vec<Vec<SavedProfile>> = Vec::new();
+    for profile in profiles {
+        match groups.iter_mut().find(|group| group[0].ssid == profile.ssid) {
+            Some(group) => group.push(profile),
+            None => groups.push(vec![profile]),
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    for group in &mut groups {
+        group.sort_by_key(|profile| std::cmp::Reverse(profile.timestamp));
+    }
+    groups.sort_by(|a, b| a[0].ssid.cmp(&b[0].ssid));
+    groups
+}
@@ -0,0 +1,127 @@
+//! Opt-in timings for `yufi --profile`. Until `enable` is called nothing is
+//! measured, so the calls can stay in hot paths.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<BTreeMap<&'static str, Timing>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Timing {
+    calls: u32,
+    total: Duration,
+    max: Duration,
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `call` and adds its duration to `label` in the summary.
+pub fn measure<T>(label: &'static str, call: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return call();
+    }
+    let started = Instant::now();
+    let result = call();
+    record(label, started.elapsed());
+    result
+}
+
+/// Times the rest of the enclosing scope. Unlike `measure`, which only adds
+/// to the summary, a span also logs each time it ends.
+pub fn span(label: &'static str) -> Span {
+    Span {
+        label,
+        started: enabled().then(Instant::now),
+    }
+}
+
+pub struct Span {
+    label: &'static str,
+    started: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(started) = self.started {
+            let elapsed = started.elapsed();
+            record(self.label, elapsed);
+            eprintln!("yufi: {} took {}", self.label, millis(elapsed));
+        }
+    }
+}
+
+fn record(label: &'static str, elapsed: Duration) {
+    let mut timings = TIMINGS.lock().unwrap_or_else(PoisonError::into_inner);
+    let timing = timings.entry(label).or_default();
+    timing.calls += 1;
+    timing.total += elapsed;
+    timing.max = timing.max.max(elapsed);
+}
+
+/// Everything measured so far, slowest in total first.
+pub fn summary() -> String {
+    format_summary(&TIMINGS.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+fn format_summary(timings: &BTreeMap<&'static str, Timing>) -> String {
+    let mut rows: Vec<_> = timings.iter().collect();
+    rows.sort_by_key(|(label, timing)| (std::cmp::Reverse(timing.total), **label));
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0).max("What".len());
+    let mut summary = format!("{:width$}  {:>6}  {:>10}  {:>10}  {:>10}\n", "What", "Calls", "Total", "Average", "Slowest");
+    for (label, timing) in rows {
+        summary.push_str(&format!(
+            "{label:width$}  {:>6}  {:>10}  {:>10}  {:>10}\n",
+            timing.calls,
+            millis(timing.total),
+            millis(timing.total / timing.calls.max(1)),
+            millis(timing.max)
+        ));
+    }
+    summary
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_lists_slowest_first_with_averages() {
+        let mut timings = BTreeMap::new();
+        timings.insert(
+            "load_state: Ssid",
+            Timing {
+                calls: 4,
+                total: Duration::from_millis(10),
+                max: Duration::from_millis(4),
+            },
+        );
+        timings.insert(
+            "load_state",
+            Timing {
+                calls: 1,
+                total: Duration::from_millis(30),
+                max: Duration::from_millis(30),
+            },
+        );
+        let summary = format_summary(&timings);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("What"));
+        assert!(lines[1].starts_with("load_state "));
+        assert!(lines[2].ends_with("4    10.00 ms     2.50 ms     4.00 ms"), "{}", lines[2]);
+        assert_eq!(format_summary(&BTreeMap::new()).lines().count(), 1);
+    }
+}